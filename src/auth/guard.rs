@@ -0,0 +1,303 @@
+use std::{ collections::HashSet, future::Future };
+
+use async_graphql::Context;
+
+use crate::{
+    auth::AuthUser,
+    context::ContextExtensions,
+    models::role::Role,
+    AppError,
+    DbClient,
+    Repository,
+};
+
+/// Permission required to hard-delete a user record
+pub const PERMANENTLY_DELETE_USER: &str = "user:permanently_delete";
+
+/// Permission required to terminate a user
+pub const TERMINATE_USER: &str = "user:terminate";
+
+/// Permission required to set another user's password
+pub const SET_USER_PASSWORD: &str = "user:set_password";
+
+/// Permission required to clear a user's login lockout or record a login on their behalf
+pub const MANAGE_USER_LOGINS: &str = "user:manage_logins";
+
+/// Permission required to download the asset register as CSV
+pub const EXPORT_ASSETS: &str = "asset:export";
+
+/// Permission required to read the audit trail
+pub const VIEW_AUDIT_EVENTS: &str = "audit:view";
+
+/// Permission required to follow work order status changes
+pub const VIEW_WORK_ORDERS: &str = "work_order:view";
+
+/// Permission required to rate the vendor a work order was hired out to
+pub const RATE_VENDORS: &str = "vendor:rate";
+
+/// Permission required for vendor upkeep such as recomputing ratings
+pub const MANAGE_VENDORS: &str = "vendor:manage";
+
+/// Permission required to scrape `/metrics`
+pub const VIEW_METRICS: &str = "metrics:view";
+
+/// Permission required to grant or revoke permissions on any role
+pub const MANAGE_ROLES: &str = "role:manage";
+
+/// Additionally required to grant or revoke permissions on a system role
+pub const MANAGE_SYSTEM_ROLES: &str = "role:manage_system";
+
+/// Ensures the authenticated caller holds a permission
+///
+/// Roles from the caller's token are loaded via `Repository` and the
+/// permission is looked up on each role and, transitively, on its
+/// `parent_role_id` chain. Inactive or expired roles grant nothing.
+///
+/// # Arguments
+///
+/// * `ctx` - GraphQL context carrying the `AuthUser` and db client
+/// * `permission_id` - Permission that must be granted
+///
+/// # Returns
+///
+/// The caller's `AuthUser`, `AppError::Unauthorized` for anonymous requests,
+/// or `AppError::Forbidden` when none of the caller's roles grant the permission
+pub async fn require_permission(
+    ctx: &Context<'_>,
+    permission_id: &str
+) -> Result<AuthUser, AppError> {
+    let auth_user = ctx
+        .auth_user()
+        .cloned()
+        .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))?;
+
+    let db_client = ctx
+        .data::<DbClient>()
+        .map_err(|_| {
+            AppError::InternalServerError("Database client not available".to_string())
+        })?;
+    let repo = ctx.repository(db_client);
+
+    ensure_permission(&repo, &auth_user, permission_id).await?;
+
+    Ok(auth_user)
+}
+
+/// Ensures an already-authenticated user holds a permission
+///
+/// Used by plain HTTP routes that have no GraphQL context; `require_permission`
+/// delegates here after resolving the caller.
+///
+/// # Returns
+///
+/// `Ok(())` if granted, `AppError::Forbidden` when none of the user's roles grant the permission
+pub async fn ensure_permission(
+    repo: &Repository,
+    auth_user: &AuthUser,
+    permission_id: &str
+) -> Result<(), AppError> {
+    let granted = roles_grant_permission(auth_user.roles.clone(), permission_id, |ids| {
+        repo.batch_get::<Role>(ids)
+    }).await?;
+
+    if granted {
+        Ok(())
+    } else {
+        Err(
+            AppError::Forbidden(
+                format!("User {} lacks permission {}", auth_user.user_id, permission_id)
+            )
+        )
+    }
+}
+
+/// Checks whether any of `role_ids`, or the roles they inherit from, grant a permission
+async fn roles_grant_permission<F, Fut>(
+    role_ids: Vec<String>,
+    permission_id: &str,
+    load_roles: F
+) -> Result<bool, AppError>
+    where F: FnMut(Vec<String>) -> Fut, Fut: Future<Output = Result<Vec<Role>, AppError>>
+{
+    walk_role_hierarchy(role_ids, load_roles, |role| role.has_permission(permission_id)).await
+}
+
+/// Permission ids granted by `role_ids` and the roles they inherit from
+///
+/// Ids are deduplicated and ordered by first appearance, so a role's own
+/// permissions precede those inherited from its parents.
+pub(crate) async fn effective_permission_ids<F, Fut>(
+    role_ids: Vec<String>,
+    load_roles: F
+) -> Result<Vec<String>, AppError>
+    where F: FnMut(Vec<String>) -> Fut, Fut: Future<Output = Result<Vec<Role>, AppError>>
+{
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut permission_ids = Vec::new();
+
+    walk_role_hierarchy(role_ids, load_roles, |role| {
+        for permission_id in &role.permission_ids {
+            if seen.insert(permission_id.clone()) {
+                permission_ids.push(permission_id.clone());
+            }
+        }
+        false
+    }).await?;
+
+    Ok(permission_ids)
+}
+
+/// Walks the role hierarchy breadth-first, fetching each level with `load_roles`
+///
+/// `visit` is called for every usable role and stops the walk by returning `true`.
+/// Inactive or expired roles are skipped along with their parents. Each role is
+/// visited at most once, so cycles in `parent_role_id` terminate.
+///
+/// # Returns
+///
+/// `true` if `visit` stopped the walk, `false` once the hierarchy is exhausted
+async fn walk_role_hierarchy<F, Fut, V>(
+    role_ids: Vec<String>,
+    mut load_roles: F,
+    mut visit: V
+) -> Result<bool, AppError>
+    where
+        F: FnMut(Vec<String>) -> Fut,
+        Fut: Future<Output = Result<Vec<Role>, AppError>>,
+        V: FnMut(&Role) -> bool
+{
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut frontier: Vec<String> = role_ids
+        .into_iter()
+        .filter(|id| visited.insert(id.clone()))
+        .collect();
+
+    while !frontier.is_empty() {
+        let roles = load_roles(frontier).await?;
+        frontier = Vec::new();
+
+        for role in roles.iter().filter(|role| role.is_usable()) {
+            if visit(role) {
+                return Ok(true);
+            }
+
+            if let Some(parent_id) = &role.parent_role_id
+                && visited.insert(parent_id.clone())
+            {
+                frontier.push(parent_id.clone());
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn role(id: &str, permission_ids: &[&str], parent_role_id: Option<&str>) -> Role {
+        Role::new(
+            id.to_string(),
+            id.to_string(),
+            None,
+            "custom".to_string(),
+            false,
+            permission_ids
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            parent_role_id.map(|p| p.to_string()),
+            0,
+            true,
+            None,
+            None,
+            None
+        ).unwrap()
+    }
+
+    async fn check(roles: &HashMap<String, Role>, role_ids: &[&str], permission_id: &str) -> bool {
+        roles_grant_permission(
+            role_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+            permission_id,
+            |ids| {
+                let found = ids
+                    .iter()
+                    .filter_map(|id| roles.get(id).cloned())
+                    .collect();
+                async move { Ok(found) }
+            }
+        ).await.unwrap()
+    }
+
+    fn hierarchy() -> HashMap<String, Role> {
+        [
+            role("admin", &[PERMANENTLY_DELETE_USER], None),
+            role("manager", &[TERMINATE_USER], Some("admin")),
+            role("technician", &[], None),
+            role("loop-a", &[], Some("loop-b")),
+            role("loop-b", &[], Some("loop-a")),
+        ]
+            .into_iter()
+            .map(|r| (r.id.clone(), r))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_direct_permission_is_granted() {
+        assert!(check(&hierarchy(), &["manager"], TERMINATE_USER).await);
+    }
+
+    #[tokio::test]
+    async fn test_permission_is_inherited_from_parent_role() {
+        assert!(check(&hierarchy(), &["manager"], PERMANENTLY_DELETE_USER).await);
+    }
+
+    #[tokio::test]
+    async fn test_user_without_permission_is_rejected() {
+        assert!(!check(&hierarchy(), &["technician"], PERMANENTLY_DELETE_USER).await);
+        assert!(!check(&hierarchy(), &[], TERMINATE_USER).await);
+    }
+
+    #[tokio::test]
+    async fn test_inactive_role_grants_nothing() {
+        let mut roles = hierarchy();
+        roles.get_mut("admin").unwrap().active = false;
+
+        assert!(!check(&roles, &["manager"], PERMANENTLY_DELETE_USER).await);
+    }
+
+    #[tokio::test]
+    async fn test_parent_cycle_terminates() {
+        assert!(!check(&hierarchy(), &["loop-a"], TERMINATE_USER).await);
+    }
+
+    #[tokio::test]
+    async fn test_effective_permissions_include_inherited_once() {
+        let roles: HashMap<String, Role> = [
+            role("admin", &[PERMANENTLY_DELETE_USER, EXPORT_ASSETS], None),
+            role("manager", &[TERMINATE_USER, EXPORT_ASSETS], Some("admin")),
+            role("supervisor", &[EXPORT_ASSETS], Some("manager")),
+        ]
+            .into_iter()
+            .map(|r| (r.id.clone(), r))
+            .collect();
+
+        let permission_ids = effective_permission_ids(
+            vec!["supervisor".to_string(), "manager".to_string()],
+            |ids| {
+                let found = ids
+                    .iter()
+                    .filter_map(|id| roles.get(id).cloned())
+                    .collect();
+                async move { Ok(found) }
+            }
+        ).await.unwrap();
+
+        assert_eq!(permission_ids, vec![EXPORT_ASSETS, TERMINATE_USER, PERMANENTLY_DELETE_USER]);
+    }
+}
@@ -1,56 +1,69 @@
-use std::{ env, time::{ SystemTime, UNIX_EPOCH } };
+use std::time::{ SystemTime, UNIX_EPOCH };
 
-use ore_dock_cmms_lambda::AppError;
+use crate::{ config::AuthConfig, AppError };
 use serde::{ Deserialize, Serialize };
-use jsonwebtoken::{ decode, encode, DecodingKey, EncodingKey, Header, Validation };
+use jsonwebtoken::{ decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String, // user ID
     pub email: String,
+    #[serde(default)]
+    pub roles: Vec<String>, // role IDs
     pub exp: usize,
 }
 
-// Create jwt from user id and email
-pub fn create_token(user_id: &str, email: &str) -> Result<String, AppError> {
-    // Load secret from ENV
-    let jwt_secret = env
-        ::var("JWT_SECRET")
-        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
-    let secret_as_bytes = jwt_secret.as_bytes();
+/// Identity of the caller, extracted from a verified bearer token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthUser {
+    pub user_id: String,
+    pub roles: Vec<String>,
+}
+
+impl From<Claims> for AuthUser {
+    fn from(claims: Claims) -> Self {
+        Self {
+            user_id: claims.sub,
+            roles: claims.roles,
+        }
+    }
+}
 
+// Create HS256 jwt for a user, expiring after `auth_config.token_expiry` seconds
+pub fn create_token(
+    user_id: &str,
+    email: &str,
+    roles: Vec<String>,
+    auth_config: &AuthConfig
+) -> Result<String, AppError> {
     let expiration =
         (
             SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .map_err(|e| AppError::InternalServerError(e.to_string()))?
                 .as_secs() as usize
-        ) +
-        24 * 3600;
+        ) + (auth_config.token_expiry as usize);
 
     let claims = Claims {
         sub: user_id.to_string(),
         email: email.to_string(),
+        roles,
         exp: expiration,
     };
 
-    encode(&Header::default(), &claims, &EncodingKey::from_secret(secret_as_bytes)).map_err(|e|
-        AppError::Unauthorized(e.to_string())
-    )
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(auth_config.jwt_secret.as_bytes())
+    ).map_err(|e| AppError::Unauthorized(e.to_string()))
 }
 
-// Validate token against jwt secret
-pub fn validate_token(token: &str) -> Result<Claims, AppError> {
-    // Load secret from ENV
-    let jwt_secret = env
-        ::var("JWT_SECRET")
-        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
-    let secret_as_bytes = jwt_secret.as_bytes();
-
+// Validate HS256 signature and expiry against jwt secret
+pub fn validate_token(token: &str, jwt_secret: &str) -> Result<Claims, AppError> {
     let token_data = decode::<Claims>(
         token,
-        &DecodingKey::from_secret(secret_as_bytes),
-        &Validation::default()
+        &DecodingKey::from_secret(jwt_secret.as_bytes()),
+        &Validation::new(Algorithm::HS256)
     ).map_err(|e| AppError::Unauthorized(e.to_string()))?;
 
     Ok(token_data.claims)
@@ -1,34 +1,146 @@
 use axum::{
   body::Body,
+  extract::State,
   http::{ header::AUTHORIZATION, HeaderMap, Request },
   middleware::Next,
   response::Response,
 };
-use ore_dock_cmms_lambda::AppError;
+use tracing::debug;
 
+use crate::config::AuthConfig;
 
-use super::jwt::validate_token;
+use super::jwt::{ validate_token, AuthUser };
 
- async fn auth_middleware<B>(
-  headers: HeaderMap,
-  request: Request<Body>,
+/// Extract and verify the `Authorization: Bearer` token from request headers
+///
+/// # Arguments
+///
+/// * `headers` - Incoming request headers
+/// * `jwt_secret` - HS256 secret the token must be signed with
+///
+/// # Returns
+///
+/// `Some(AuthUser)` for a valid, unexpired token; `None` otherwise
+pub fn auth_user_from_headers(headers: &HeaderMap, jwt_secret: &str) -> Option<AuthUser> {
+  let auth_header = headers.get(AUTHORIZATION)?.to_str().ok()?;
+
+  let Some(token) = auth_header.strip_prefix("Bearer ") else {
+    debug!("Ignoring authorization header with invalid token format");
+    return None;
+  };
+
+  match validate_token(token.trim(), jwt_secret) {
+    Ok(claims) => Some(claims.into()),
+    Err(e) => {
+      debug!("Rejected bearer token: {}", e);
+      None
+    }
+  }
+}
+
+/// Attach the caller's `AuthUser` to the request extensions when a valid token is present.
+///
+/// Requests without a valid token are still passed through so individual
+/// resolvers can decide whether anonymous access is allowed.
+pub async fn auth_middleware(
+  State(auth_config): State<AuthConfig>,
+  mut request: Request<Body>,
   next: Next
-) -> Result<Response, AppError> {
-  let auth_header = headers
-      .get(AUTHORIZATION)
-      .and_then(|value| value.to_str().ok())
-      .ok_or_else(|| AppError::Unauthorized("No authorization header".into()))?;
+) -> Response {
+  if let Some(auth_user) = auth_user_from_headers(request.headers(), &auth_config.jwt_secret) {
+    request.extensions_mut().insert(auth_user);
+  }
+
+  next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::auth::jwt::{ create_token, Claims };
+  use axum::http::HeaderValue;
+  use jsonwebtoken::{ encode, Algorithm, EncodingKey, Header };
+  use std::time::{ SystemTime, UNIX_EPOCH };
+
+  const SECRET: &str = "test-secret";
+
+  fn now() -> usize {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as usize
+  }
+
+  fn sign(claims: &Claims, secret: &str) -> String {
+    encode(
+      &Header::new(Algorithm::HS256),
+      claims,
+      &EncodingKey::from_secret(secret.as_bytes())
+    ).unwrap()
+  }
 
-  if !auth_header.starts_with("Bearer ") {
-      return Err(AppError::Unauthorized("Invalid token format".into()));
+  fn bearer(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {}", token)).unwrap());
+    headers
   }
 
-  let token = &auth_header[7..];
+  fn claims(exp: usize) -> Claims {
+    Claims {
+      sub: "user-1".to_string(),
+      email: "user@example.com".to_string(),
+      roles: vec!["role-admin".to_string()],
+      exp,
+    }
+  }
+
+  #[test]
+  fn test_hand_signed_token_yields_auth_user() {
+    let token = sign(&claims(now() + 3600), SECRET);
+
+    let auth_user = auth_user_from_headers(&bearer(&token), SECRET);
+
+    assert_eq!(
+      auth_user,
+      Some(AuthUser {
+        user_id: "user-1".to_string(),
+        roles: vec!["role-admin".to_string()],
+      })
+    );
+  }
+
+  #[test]
+  fn test_expired_token_is_rejected() {
+    let token = sign(&claims(now() - 3600), SECRET);
+
+    assert_eq!(auth_user_from_headers(&bearer(&token), SECRET), None);
+  }
+
+  #[test]
+  fn test_token_signed_with_other_secret_is_rejected() {
+    let token = sign(&claims(now() + 3600), "other-secret");
 
-  let claims = validate_token(token)?;
+    assert_eq!(auth_user_from_headers(&bearer(&token), SECRET), None);
+  }
 
-  let mut request = request;
-  request.extensions_mut().insert(claims);
+  #[test]
+  fn test_missing_or_malformed_header_is_anonymous() {
+    assert_eq!(auth_user_from_headers(&HeaderMap::new(), SECRET), None);
 
-  Ok(next.run(request).await)
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+    assert_eq!(auth_user_from_headers(&headers, SECRET), None);
+  }
+
+  #[test]
+  fn test_create_token_round_trips() {
+    let auth_config = AuthConfig {
+      jwt_secret: SECRET.to_string(),
+      token_expiry: 60,
+      max_attempts: 5,
+      lockout_duration_minutes: 15,
+    };
+    let token = create_token("user-2", "two@example.com", vec![], &auth_config).unwrap();
+
+    let auth_user = auth_user_from_headers(&bearer(&token), SECRET).unwrap();
+    assert_eq!(auth_user.user_id, "user-2");
+    assert!(auth_user.roles.is_empty());
+  }
 }
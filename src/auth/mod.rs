@@ -1,2 +1,6 @@
 pub mod middleware;
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
+pub mod guard;
+
+pub use jwt::AuthUser;
+pub use guard::require_permission;
@@ -1,20 +1,66 @@
 use serde::Deserialize;
 
+use crate::{
+    cors::OriginMatcher,
+    rate_limit::parse_trusted_proxies,
+    models::{
+        validation::calling_code,
+        work_order::{ sla_hours_for, WorkOrderPriority, MAX_SLA_HOURS },
+    },
+    AppError,
+};
+
+/// Placeholder secret shipped in `Config::default`, never acceptable outside dev
+const DEFAULT_JWT_SECRET: &str = "default-secret-change-in-production";
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub database: DatabaseConfig,
     pub graphql: GraphQLConfig,
     pub auth: AuthConfig,
     pub aws: AwsConfig,
+    pub s3: S3Config,
     pub environment: String,
     pub allow_origins: String,
     pub log_level: String,
+    #[serde(default = "default_phone_region")]
+    pub default_phone_region: String, // ISO 3166 alpha-2, used to normalize national phone numbers
+    #[serde(default)]
+    pub webhook: Option<WebhookConfig>, // outbound event delivery, disabled when unset
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub sla: SlaConfig,
+}
+
+fn default_phone_region() -> String {
+    "US".to_string()
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct DatabaseConfig {
     pub region: String,
     pub endpoint: Option<String>, // For local DynamoDB
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32, // retries after the first attempt of each DynamoDB call
+    #[serde(default = "default_operation_timeout_ms")]
+    pub operation_timeout_ms: u64, // upper bound on a DynamoDB call, retries included
+    #[serde(default = "default_max_scan_items")]
+    pub max_scan_items: usize, // items an unfiltered table scan may read before it is refused
+    #[serde(default)]
+    pub table_prefix: Option<String>, // e.g. "staging" reads and writes `staging_Assets`
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_operation_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_max_scan_items() -> usize {
+    10_000
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -29,6 +75,114 @@ pub struct GraphQLConfig {
 pub struct AuthConfig {
     pub jwt_secret: String,
     pub token_expiry: u64, // seconds
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: i32, // consecutive failed logins before the account locks
+    #[serde(default = "default_lockout_duration_minutes")]
+    pub lockout_duration_minutes: i64,
+}
+
+fn default_max_attempts() -> i32 {
+    5
+}
+
+fn default_lockout_duration_minutes() -> i64 {
+    15
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub secret: String, // HMAC-SHA256 key for the X-Signature header
+    pub events: Vec<String>, // e.g. "work_order.completed"; unlisted events are not sent
+}
+
+/// Per-client token bucket applied to `/graphql`
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(default = "default_requests_per_minute")]
+    pub requests_per_minute: u32, // sustained rate the bucket refills at
+    #[serde(default = "default_burst")]
+    pub burst: u32, // bucket capacity, i.e. requests allowed back to back
+    #[serde(default)]
+    pub trusted_proxies: String, // comma-separated proxy IPs whose X-Forwarded-For is honoured
+}
+
+fn default_requests_per_minute() -> u32 {
+    120
+}
+
+fn default_burst() -> u32 {
+    30
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: default_requests_per_minute(),
+            burst: default_burst(),
+            trusted_proxies: String::new(),
+        }
+    }
+}
+
+/// Hours a work order of each priority has to be resolved in, counted from creation
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlaConfig {
+    #[serde(default = "default_emergency_sla_hours")]
+    pub emergency_hours: i64,
+    #[serde(default = "default_urgent_sla_hours")]
+    pub urgent_hours: i64,
+    #[serde(default = "default_high_sla_hours")]
+    pub high_hours: i64,
+    #[serde(default = "default_normal_sla_hours")]
+    pub normal_hours: i64,
+    #[serde(default = "default_low_sla_hours")]
+    pub low_hours: i64,
+}
+
+fn default_emergency_sla_hours() -> i64 {
+    sla_hours_for(WorkOrderPriority::Emergency)
+}
+
+fn default_urgent_sla_hours() -> i64 {
+    sla_hours_for(WorkOrderPriority::Urgent)
+}
+
+fn default_high_sla_hours() -> i64 {
+    sla_hours_for(WorkOrderPriority::High)
+}
+
+fn default_normal_sla_hours() -> i64 {
+    sla_hours_for(WorkOrderPriority::Normal)
+}
+
+fn default_low_sla_hours() -> i64 {
+    sla_hours_for(WorkOrderPriority::Low)
+}
+
+impl SlaConfig {
+    /// Resolution window for `priority`
+    pub fn hours_for(&self, priority: WorkOrderPriority) -> i64 {
+        match priority {
+            WorkOrderPriority::Emergency => self.emergency_hours,
+            WorkOrderPriority::Urgent => self.urgent_hours,
+            WorkOrderPriority::High => self.high_hours,
+            WorkOrderPriority::Normal => self.normal_hours,
+            WorkOrderPriority::Low => self.low_hours,
+        }
+    }
+}
+
+impl Default for SlaConfig {
+    fn default() -> Self {
+        Self {
+            emergency_hours: default_emergency_sla_hours(),
+            urgent_hours: default_urgent_sla_hours(),
+            high_hours: default_high_sla_hours(),
+            normal_hours: default_normal_sla_hours(),
+            low_hours: default_low_sla_hours(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -38,12 +192,123 @@ pub struct AwsConfig {
     pub secret_access_key: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3Config {
+    pub bucket: String, // Bucket holding asset documentation
+}
+
 impl Config {
     pub fn from_env() -> Result<Self, crate::AppError> {
         envy::from_env().map_err(|e| {
             crate::AppError::ConfigError(format!("Failed to load config from environment: {}", e))
         })
     }
+
+    /// Rejects configurations that are unsafe or unusable
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the config is usable, `AppError::ConfigError` describing the first problem otherwise
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.environment != "dev" && self.auth.jwt_secret == DEFAULT_JWT_SECRET {
+            return Err(
+                AppError::ConfigError(
+                    format!("Default jwt_secret is not allowed in {} environment", self.environment)
+                )
+            );
+        }
+
+        if self.auth.token_expiry == 0 {
+            return Err(AppError::ConfigError("token_expiry must be greater than 0".to_string()));
+        }
+
+        if self.auth.max_attempts <= 0 || self.auth.lockout_duration_minutes <= 0 {
+            return Err(
+                AppError::ConfigError(
+                    "max_attempts and lockout_duration_minutes must be greater than 0".to_string()
+                )
+            );
+        }
+
+        if !self.graphql.playground && self.allow_origins.trim().is_empty() {
+            return Err(
+                AppError::ConfigError(
+                    "allow_origins must be set when the GraphQL playground is disabled".to_string()
+                )
+            );
+        }
+
+        // Checked with the playground on too, so a bad pattern fails before production
+        OriginMatcher::parse(&self.allow_origins)?;
+
+        if self.database.region.trim().is_empty() {
+            return Err(AppError::ConfigError("database.region cannot be empty".to_string()));
+        }
+
+        if self.database.operation_timeout_ms == 0 {
+            return Err(
+                AppError::ConfigError("database.operation_timeout_ms must be greater than 0".to_string())
+            );
+        }
+
+        if self.database.max_scan_items == 0 {
+            return Err(
+                AppError::ConfigError("database.max_scan_items must be greater than 0".to_string())
+            );
+        }
+
+        if self.rate_limit.requests_per_minute == 0 || self.rate_limit.burst == 0 {
+            return Err(
+                AppError::ConfigError(
+                    "rate_limit.requests_per_minute and rate_limit.burst must be greater than 0"
+                        .to_string()
+                )
+            );
+        }
+
+        parse_trusted_proxies(&self.rate_limit.trusted_proxies)?;
+
+        let sla = &self.sla;
+        let sla_hours = [
+            sla.emergency_hours,
+            sla.urgent_hours,
+            sla.high_hours,
+            sla.normal_hours,
+            sla.low_hours,
+        ];
+        if sla_hours.iter().any(|hours| *hours <= 0) {
+            return Err(AppError::ConfigError("sla hours must be greater than 0".to_string()));
+        }
+        if sla_hours.iter().any(|hours| *hours > MAX_SLA_HOURS) {
+            return Err(
+                AppError::ConfigError(format!("sla hours must be at most {}", MAX_SLA_HOURS))
+            );
+        }
+
+        if self.aws.region.trim().is_empty() {
+            return Err(AppError::ConfigError("aws.region cannot be empty".to_string()));
+        }
+
+        if calling_code(&self.default_phone_region).is_none() {
+            return Err(
+                AppError::ConfigError(
+                    format!("Unsupported default_phone_region {}", self.default_phone_region)
+                )
+            );
+        }
+
+        if let Some(webhook) = &self.webhook {
+            if !(webhook.url.starts_with("https://") || webhook.url.starts_with("http://")) {
+                return Err(AppError::ConfigError("webhook.url must be an http(s) URL".to_string()));
+            }
+
+            if webhook.secret.trim().is_empty() {
+                return Err(AppError::ConfigError("webhook.secret cannot be empty".to_string()));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for Config {
@@ -52,6 +317,10 @@ impl Default for Config {
             database: DatabaseConfig {
                 region: "us-east-2".to_string(),
                 endpoint: Some("http://localhost:8000".to_string()),
+                max_retries: default_max_retries(),
+                operation_timeout_ms: default_operation_timeout_ms(),
+                max_scan_items: default_max_scan_items(),
+                table_prefix: None,
             },
             graphql: GraphQLConfig {
                 playground: true,
@@ -60,17 +329,215 @@ impl Default for Config {
                 depth_limit: Some(10),
             },
             auth: AuthConfig {
-                jwt_secret: "default-secret-change-in-production".to_string(),
+                jwt_secret: DEFAULT_JWT_SECRET.to_string(),
                 token_expiry: 3600, // 1 hour
+                max_attempts: default_max_attempts(),
+                lockout_duration_minutes: default_lockout_duration_minutes(),
             },
             aws: AwsConfig {
                 region: "us-east-2".to_string(),
                 access_key_id: None,
                 secret_access_key: None,
             },
+            s3: S3Config {
+                bucket: std::env::var("AWS_S3_BUCKET_NAME").unwrap_or_default(),
+            },
             environment: "dev".to_string(),
             allow_origins: "".to_string(),
             log_level: "error".to_string(),
+            default_phone_region: default_phone_region(),
+            webhook: None,
+            rate_limit: RateLimitConfig::default(),
+            sla: SlaConfig::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_config_error(config: &Config, needle: &str) {
+        match config.validate() {
+            Err(AppError::ConfigError(msg)) => assert!(msg.contains(needle), "{}", msg),
+            other => panic!("expected ConfigError containing {:?}, got {:?}", needle, other),
         }
     }
+
+    #[test]
+    fn test_default_config_is_valid_in_dev() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_default_jwt_secret_rejected_outside_dev() {
+        let mut config = Config {
+            environment: "prod".to_string(),
+            ..Config::default()
+        };
+
+        assert_config_error(&config, "jwt_secret");
+
+        config.auth.jwt_secret = "a-real-secret".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_non_positive_sla_hours_rejected() {
+        let mut config = Config::default();
+        config.sla.normal_hours = 0;
+
+        assert_config_error(&config, "sla hours");
+    }
+
+    #[test]
+    fn test_sla_hours_over_a_year_rejected() {
+        let mut config = Config::default();
+        config.sla.low_hours = MAX_SLA_HOURS + 1;
+
+        assert_config_error(&config, "sla hours must be at most");
+    }
+
+    #[test]
+    fn test_zero_token_expiry_rejected() {
+        let mut config = Config::default();
+        config.auth.token_expiry = 0;
+
+        assert_config_error(&config, "token_expiry");
+    }
+
+    #[test]
+    fn test_non_positive_lockout_settings_rejected() {
+        let mut config = Config::default();
+        config.auth.max_attempts = 0;
+        assert_config_error(&config, "max_attempts");
+
+        let mut config = Config::default();
+        config.auth.lockout_duration_minutes = -1;
+        assert_config_error(&config, "lockout_duration_minutes");
+    }
+
+    #[test]
+    fn test_empty_allow_origins_rejected_without_playground() {
+        let mut config = Config::default();
+        config.graphql.playground = false;
+
+        assert_config_error(&config, "allow_origins");
+
+        config.allow_origins = "https://app.example.com".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_malformed_allow_origins_rejected_without_playground() {
+        let mut config = Config::default();
+        config.graphql.playground = false;
+        config.allow_origins = "https://app.example.com,*.com".to_string();
+
+        assert_config_error(&config, "Invalid allow_origins entry *.com");
+
+        config.allow_origins = "https://app.example.com,*.example.com".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_malformed_allow_origins_rejected_with_playground() {
+        let mut config = Config::default();
+        config.graphql.playground = true;
+        config.allow_origins = "*.com".to_string();
+
+        assert_config_error(&config, "Invalid allow_origins entry *.com");
+    }
+
+    #[test]
+    fn test_malformed_trusted_proxies_rejected() {
+        let mut config = Config::default();
+        config.rate_limit.trusted_proxies = "10.0.0.1, load-balancer".to_string();
+
+        assert_config_error(&config, "Invalid rate_limit.trusted_proxies entry load-balancer");
+
+        config.rate_limit.trusted_proxies = "10.0.0.1, ::1".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_empty_regions_rejected() {
+        let mut config = Config::default();
+        config.database.region = " ".to_string();
+        assert_config_error(&config, "database.region");
+
+        let mut config = Config::default();
+        config.aws.region = String::new();
+        assert_config_error(&config, "aws.region");
+    }
+
+    #[test]
+    fn test_zero_operation_timeout_rejected() {
+        let mut config = Config::default();
+        config.database.operation_timeout_ms = 0;
+
+        assert_config_error(&config, "operation_timeout_ms");
+    }
+
+    #[test]
+    fn test_zero_max_scan_items_rejected() {
+        let mut config = Config::default();
+        config.database.max_scan_items = 0;
+
+        assert_config_error(&config, "max_scan_items");
+    }
+
+    #[test]
+    fn test_zero_rate_limit_rejected() {
+        let mut config = Config::default();
+        config.rate_limit.burst = 0;
+
+        assert_config_error(&config, "rate_limit");
+    }
+
+    #[test]
+    fn test_webhook_requires_url_and_secret() {
+        let mut config = Config {
+            webhook: Some(WebhookConfig {
+                url: "parts.example.com/hooks".to_string(),
+                secret: "s3cret".to_string(),
+                events: vec!["work_order.completed".to_string()],
+            }),
+            ..Config::default()
+        };
+        assert_config_error(&config, "webhook.url");
+
+        let webhook = config.webhook.as_mut().unwrap();
+        webhook.url = "https://parts.example.com/hooks".to_string();
+        webhook.secret = String::new();
+        assert_config_error(&config, "webhook.secret");
+    }
+
+    #[test]
+    fn test_phone_region_defaults_when_unset() {
+        let config: Config = serde_json::from_value(
+            serde_json::json!({
+                "database": { "region": "us-east-2" },
+                "graphql": { "playground": true, "introspection": true },
+                "auth": { "jwt_secret": "secret", "token_expiry": 3600 },
+                "aws": { "region": "us-east-2" },
+                "s3": { "bucket": "docs" },
+                "environment": "dev",
+                "allow_origins": "",
+                "log_level": "info"
+            })
+        ).unwrap();
+
+        assert_eq!(config.default_phone_region, "US");
+    }
+
+    #[test]
+    fn test_unknown_phone_region_rejected() {
+        let config = Config {
+            default_phone_region: "ZZ".to_string(),
+            ..Config::default()
+        };
+
+        assert_config_error(&config, "default_phone_region");
+    }
 }
@@ -3,7 +3,7 @@ use aws_sdk_dynamodb::Client;
 use aws_sdk_s3::Client as S3Client;
 use std::sync::Arc;
 
-use crate::{ config::Config, AppError };
+use crate::{ auth::AuthUser, config::Config, request_id::RequestId, AppError, Repository };
 
 #[derive(Clone)]
 pub struct AppContext {
@@ -27,6 +27,10 @@ pub trait ContextExtensions {
     fn db_client(&self) -> Result<&Client, AppError>;
     fn config(&self) -> Result<&Config, AppError>;
     fn s3_client(&self) -> Result<&S3Client, AppError>;
+    fn auth_user(&self) -> Option<&AuthUser>;
+    fn s3_bucket(&self) -> Result<&str, AppError>;
+    fn request_id(&self) -> Option<&str>;
+    fn repository(&self, db_client: &Client) -> Repository;
 }
 
 impl<'a> ContextExtensions for Context<'a> {
@@ -47,4 +51,35 @@ impl<'a> ContextExtensions for Context<'a> {
             AppError::InternalServerError("AWS S3 client not available in context".to_string())
         })
     }
+
+    // None when the request carried no valid bearer token
+    fn auth_user(&self) -> Option<&AuthUser> {
+        self.data_opt::<AuthUser>()
+    }
+
+    fn s3_bucket(&self) -> Result<&str, AppError> {
+        let app_context = self.data::<AppContext>().map_err(|_| {
+            AppError::InternalServerError("App context not available in context".to_string())
+        })?;
+
+        let bucket = app_context.config.s3.bucket.as_str();
+        if bucket.is_empty() {
+            return Err(AppError::ConfigError("S3 bucket is not configured".to_string()));
+        }
+
+        Ok(bucket)
+    }
+
+    // Correlation id of the HTTP request this operation belongs to
+    fn request_id(&self) -> Option<&str> {
+        self.data_opt::<RequestId>().map(|id| id.as_str())
+    }
+
+    // Repository over `db_client` using the schema's database config, if it has one
+    fn repository(&self, db_client: &Client) -> Repository {
+        match self.data_opt::<Config>() {
+            Some(config) => Repository::from_config(db_client.clone(), &config.database),
+            None => Repository::new(db_client.clone()),
+        }
+    }
 }
@@ -0,0 +1,165 @@
+use crate::AppError;
+
+/// One `allow_origins` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OriginPattern {
+    /// A full origin such as `https://app.example.com`
+    Exact(String),
+    /// `*.example.com` or `https://*.example.com`, matching any subdomain of `suffix`
+    Subdomain {
+        scheme: Option<String>,
+        suffix: String,
+    },
+}
+
+impl OriginPattern {
+    fn parse(pattern: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::ConfigError(format!("Invalid allow_origins entry {}", pattern));
+
+        let lowered = pattern.to_ascii_lowercase();
+        let (scheme, host) = match lowered.split_once("://") {
+            Some((scheme, host)) => (Some(scheme), host),
+            None => (None, lowered.as_str()),
+        };
+
+        if scheme.is_some_and(|scheme| scheme != "http" && scheme != "https") {
+            return Err(invalid());
+        }
+
+        match host.strip_prefix("*.") {
+            Some(suffix) => {
+                // A bare top-level domain such as `*.com` would admit any site
+                if !is_valid_host(suffix) || !suffix.contains('.') {
+                    return Err(invalid());
+                }
+                Ok(OriginPattern::Subdomain {
+                    scheme: scheme.map(str::to_string),
+                    suffix: suffix.to_string(),
+                })
+            }
+            None if scheme.is_some() && is_valid_host(host) => Ok(OriginPattern::Exact(lowered)),
+            None => Err(invalid()),
+        }
+    }
+
+    fn matches(&self, origin: &str) -> bool {
+        match self {
+            OriginPattern::Exact(expected) => origin == expected,
+            OriginPattern::Subdomain { scheme, suffix } => {
+                let Some((origin_scheme, host)) = origin.split_once("://") else {
+                    return false;
+                };
+                if scheme.as_deref().is_some_and(|scheme| scheme != origin_scheme) {
+                    return false;
+                }
+
+                match host.strip_suffix(suffix.as_str()) {
+                    Some(subdomain) => subdomain.len() > 1 && subdomain.ends_with('.'),
+                    None => false,
+                }
+            }
+        }
+    }
+}
+
+/// Whether `host` is a hostname with an optional port and no path, query or userinfo
+fn is_valid_host(host: &str) -> bool {
+    let (name, port) = match host.rsplit_once(':') {
+        Some((name, port)) => (name, Some(port)),
+        None => (host, None),
+    };
+
+    if port.is_some_and(|port| port.parse::<u16>().is_err()) {
+        return false;
+    }
+
+    !name.is_empty() &&
+        name.split('.').all(|label| {
+            !label.is_empty() &&
+                !label.starts_with('-') &&
+                !label.ends_with('-') &&
+                label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Matches request `Origin` headers against the comma-separated `allow_origins` setting
+///
+/// Entries are either exact origins or `*.`-prefixed subdomain wildcards, which match
+/// any subdomain of the remainder but not the bare domain itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginMatcher {
+    patterns: Vec<OriginPattern>,
+}
+
+impl OriginMatcher {
+    /// Parses `allow_origins`, ignoring blank entries
+    ///
+    /// # Errors
+    ///
+    /// `AppError::ConfigError` naming the first malformed entry
+    pub fn parse(allow_origins: &str) -> Result<Self, AppError> {
+        let patterns = allow_origins
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(OriginPattern::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// Whether a request from `origin` may be served
+    pub fn matches(&self, origin: &str) -> bool {
+        let origin = origin.to_ascii_lowercase();
+        self.patterns.iter().any(|pattern| pattern.matches(&origin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_matches_subdomains_only() {
+        let matcher = OriginMatcher::parse("*.example.com").unwrap();
+
+        assert!(matcher.matches("https://a.example.com"));
+        assert!(matcher.matches("http://admin.eu.example.com"));
+        assert!(!matcher.matches("https://example.com"));
+        assert!(!matcher.matches("https://example.com.evil.com"));
+        assert!(!matcher.matches("https://evilexample.com"));
+    }
+
+    #[test]
+    fn test_exact_and_scheme_qualified_entries() {
+        let matcher = OriginMatcher::parse(
+            "https://app.example.com, https://*.example.org, http://localhost:3000"
+        ).unwrap();
+
+        assert!(matcher.matches("https://app.example.com"));
+        assert!(matcher.matches("HTTPS://App.Example.com"));
+        assert!(!matcher.matches("http://app.example.com"));
+        assert!(matcher.matches("https://a.example.org"));
+        assert!(!matcher.matches("http://a.example.org"));
+        assert!(matcher.matches("http://localhost:3000"));
+        assert!(!matcher.matches("http://localhost:3001"));
+    }
+
+    #[test]
+    fn test_malformed_entries_rejected() {
+        for entry in [
+            "example.com",
+            "ftp://app.example.com",
+            "https://*.com",
+            "https://app.*.example.com",
+            "https://app.example.com/path",
+            "https://app.example.com:99999",
+            "*.",
+        ] {
+            match OriginMatcher::parse(entry) {
+                Err(AppError::ConfigError(msg)) => assert!(msg.contains(entry), "{}", msg),
+                other => panic!("expected {} to be rejected, got {:?}", entry, other),
+            }
+        }
+    }
+}
@@ -19,7 +19,7 @@ use aws_sdk_dynamodb::{
 };
 
 use crate::error::AppError;
-use super::common::build;
+use super::common::{ build, prefixed_table_name, wait_until_active };
 
 /// Creates the Assets table for managing physical assets.
 ///
@@ -34,11 +34,12 @@ use super::common::build;
 /// * MaintenanceFrequencyIndex: Find assets by maintenance frequency
 pub async fn create_assets_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Assets";
+    let table_name = prefixed_table_name(table_prefix, "Assets");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -100,7 +101,7 @@ pub async fn create_assets_table(
 
     // Define GSI 1: Type Index
     let gsi1_pk = build(
-        KeySchemaElement::builder().attribute_name("type_id").key_type(KeyType::Hash).build(),
+        KeySchemaElement::builder().attribute_name("asset_type_id").key_type(KeyType::Hash).build(),
         "Failed to build Type GSI PK"
     )?;
 
@@ -185,7 +186,7 @@ pub async fn create_assets_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("Assets")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_asset_type_id)
@@ -206,6 +207,7 @@ pub async fn create_assets_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Assets table created: {:?}", response);
     Ok(())
 }
@@ -213,11 +215,12 @@ pub async fn create_assets_table(
 /// Creates the AssetTypes table for categorizing assets.
 pub async fn create_asset_types_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "AssetTypes";
+    let table_name = prefixed_table_name(table_prefix, "AssetTypes");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -240,7 +243,7 @@ pub async fn create_asset_types_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("AssetTypes")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .key_schema(ks_id)
@@ -251,6 +254,7 @@ pub async fn create_asset_types_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("AssetTypes table created: {:?}", response);
     Ok(())
 }
@@ -258,11 +262,12 @@ pub async fn create_asset_types_table(
 /// Creates the Locations table for asset placement tracking.
 pub async fn create_locations_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Locations";
+    let table_name = prefixed_table_name(table_prefix, "Locations");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -284,6 +289,14 @@ pub async fn create_locations_table(
         "Failed to build location_type_id attribute definition"
     )?;
 
+    let ad_parent_location_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("parent_location_id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build parent_location_id attribute definition"
+    )?;
+
     // Define key schema
     let ks_id = build(
         KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
@@ -308,15 +321,35 @@ pub async fn create_locations_table(
         "Failed to build LocationTypeIndex GSI"
     )?;
 
+    // Define GSI 2: Parent Location Index (root locations have no parent and are not indexed)
+    let gsi2_pk = build(
+        KeySchemaElement::builder()
+            .attribute_name("parent_location_id")
+            .key_type(KeyType::Hash)
+            .build(),
+        "Failed to build ParentLocation GSI PK"
+    )?;
+
+    let gsi2 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name("ParentLocationIndex")
+            .key_schema(gsi2_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build ParentLocationIndex GSI"
+    )?;
+
     // Create the table
     let response = client
         .create_table()
-        .table_name("Locations")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_location_type_id)
+        .attribute_definitions(ad_parent_location_id)
         .key_schema(ks_id)
         .global_secondary_indexes(gsi1)
+        .global_secondary_indexes(gsi2)
         .send().await
         .map_err(|e|
             AppError::DatabaseError(
@@ -324,6 +357,7 @@ pub async fn create_locations_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Locations table created: {:?}", response);
     Ok(())
 }
@@ -331,11 +365,12 @@ pub async fn create_locations_table(
 /// Creates the LocationTypes table.
 pub async fn create_location_types_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "LocationTypes";
+    let table_name = prefixed_table_name(table_prefix, "LocationTypes");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -358,7 +393,7 @@ pub async fn create_location_types_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("LocationTypes")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .key_schema(ks_id)
@@ -369,6 +404,7 @@ pub async fn create_location_types_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("LocationTypes table created: {:?}", response);
     Ok(())
 }
@@ -376,11 +412,12 @@ pub async fn create_location_types_table(
 /// Creates the Manufacturers table.
 pub async fn create_manufacturers_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Manufacturers";
+    let table_name = prefixed_table_name(table_prefix, "Manufacturers");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -403,7 +440,7 @@ pub async fn create_manufacturers_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("Manufacturers")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .key_schema(ks_id)
@@ -414,6 +451,7 @@ pub async fn create_manufacturers_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Manufacturers table created: {:?}", response);
     Ok(())
 }
@@ -421,11 +459,12 @@ pub async fn create_manufacturers_table(
 /// Creates the MaintenanceSchedules table.
 pub async fn create_maintenance_schedules_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "MaintenanceSchedules";
+    let table_name = prefixed_table_name(table_prefix, "MaintenanceSchedules");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -447,6 +486,14 @@ pub async fn create_maintenance_schedules_table(
         "Failed to build asset_id attribute definition"
     )?;
 
+    let ad_due_partition = build(
+        AttributeDefinition::builder()
+            .attribute_name("due_partition")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build due_partition attribute definition"
+    )?;
+
     let ad_next_due_date = build(
         AttributeDefinition::builder()
             .attribute_name("next_due_date")
@@ -476,16 +523,22 @@ pub async fn create_maintenance_schedules_table(
         "Failed to build AssetIndex GSI"
     )?;
 
-    // Define GSI 2: Due Date Index
+    // Define GSI 2: Due Date Index (constant partition, RFC3339 sort key for range queries)
     let gsi2_pk = build(
-        KeySchemaElement::builder().attribute_name("next_due_date").key_type(KeyType::Hash).build(),
+        KeySchemaElement::builder().attribute_name("due_partition").key_type(KeyType::Hash).build(),
         "Failed to build DueDate GSI PK"
     )?;
 
+    let gsi2_sk = build(
+        KeySchemaElement::builder().attribute_name("next_due_date").key_type(KeyType::Range).build(),
+        "Failed to build DueDate GSI SK"
+    )?;
+
     let gsi2 = build(
         GlobalSecondaryIndex::builder()
             .index_name("DueDateIndex")
             .key_schema(gsi2_pk)
+            .key_schema(gsi2_sk)
             .projection(Projection::builder().projection_type(ProjectionType::All).build())
             .build(),
         "Failed to build DueDateIndex GSI"
@@ -494,10 +547,11 @@ pub async fn create_maintenance_schedules_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("MaintenanceSchedules")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_asset_id)
+        .attribute_definitions(ad_due_partition)
         .attribute_definitions(ad_next_due_date)
         .key_schema(ks_id)
         .global_secondary_indexes(gsi1)
@@ -509,6 +563,7 @@ pub async fn create_maintenance_schedules_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("MaintenanceSchedules table created: {:?}", response);
     Ok(())
 }
@@ -516,11 +571,12 @@ pub async fn create_maintenance_schedules_table(
 /// Creates the WorkOrders table.
 pub async fn create_work_orders_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "WorkOrders";
+    let table_name = prefixed_table_name(table_prefix, "WorkOrders");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -544,10 +600,10 @@ pub async fn create_work_orders_table(
 
     let ad_assigned_to = build(
         AttributeDefinition::builder()
-            .attribute_name("assigned_to")
+            .attribute_name("assigned_technician_id")
             .attribute_type(ScalarAttributeType::S)
             .build(),
-        "Failed to build assigned_to attribute definition"
+        "Failed to build assigned_technician_id attribute definition"
     )?;
 
     let ad_status = build(
@@ -566,6 +622,22 @@ pub async fn create_work_orders_table(
         "Failed to build priority attribute definition"
     )?;
 
+    let ad_vendor_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("vendor_id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build vendor_id attribute definition"
+    )?;
+
+    let ad_completed_date = build(
+        AttributeDefinition::builder()
+            .attribute_name("completed_date")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build completed_date attribute definition"
+    )?;
+
     // Define key schema
     let ks_id = build(
         KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
@@ -589,7 +661,7 @@ pub async fn create_work_orders_table(
 
     // Define GSI 2: Assigned To Index
     let gsi2_pk = build(
-        KeySchemaElement::builder().attribute_name("assigned_to").key_type(KeyType::Hash).build(),
+        KeySchemaElement::builder().attribute_name("assigned_technician_id").key_type(KeyType::Hash).build(),
         "Failed to build AssignedTo GSI PK"
     )?;
 
@@ -632,21 +704,45 @@ pub async fn create_work_orders_table(
         "Failed to build PriorityIndex GSI"
     )?;
 
+    // Define GSI 5: Vendor Index, holding only completed work orders hired out to a vendor
+    let gsi5_pk = build(
+        KeySchemaElement::builder().attribute_name("vendor_id").key_type(KeyType::Hash).build(),
+        "Failed to build Vendor GSI PK"
+    )?;
+
+    let gsi5_sk = build(
+        KeySchemaElement::builder().attribute_name("completed_date").key_type(KeyType::Range).build(),
+        "Failed to build Vendor GSI SK"
+    )?;
+
+    let gsi5 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name("VendorIndex")
+            .key_schema(gsi5_pk)
+            .key_schema(gsi5_sk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build VendorIndex GSI"
+    )?;
+
     // Create the table
     let response = client
         .create_table()
-        .table_name("WorkOrders")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_asset_id)
         .attribute_definitions(ad_assigned_to)
         .attribute_definitions(ad_status)
         .attribute_definitions(ad_priority)
+        .attribute_definitions(ad_vendor_id)
+        .attribute_definitions(ad_completed_date)
         .key_schema(ks_id)
         .global_secondary_indexes(gsi1)
         .global_secondary_indexes(gsi2)
         .global_secondary_indexes(gsi3)
         .global_secondary_indexes(gsi4)
+        .global_secondary_indexes(gsi5)
         .send().await
         .map_err(|e|
             AppError::DatabaseError(
@@ -654,6 +750,7 @@ pub async fn create_work_orders_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("WorkOrders table created: {:?}", response);
     Ok(())
 }
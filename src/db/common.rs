@@ -4,8 +4,26 @@
 //! all table creation modules.
 
 use core::fmt;
+use std::{ collections::HashMap, time::Duration };
+
+use aws_sdk_dynamodb::{
+    Client,
+    types::{ AttributeValue, IndexStatus, TableDescription, TableStatus },
+};
+use chrono::{ DateTime, SecondsFormat, Utc };
+use tokio::time::{ sleep, Instant };
+
 use crate::error::AppError;
 
+/// Longest `wait_until_active` polls a table before giving up
+const TABLE_ACTIVE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Delay before the second `describe_table` call; doubled after each poll
+const INITIAL_POLL_DELAY: Duration = Duration::from_millis(200);
+
+/// Upper bound on the delay between polls
+const MAX_POLL_DELAY: Duration = Duration::from_secs(5);
+
 /// Helper function to simplify error handling during DynamoDB resource creation.
 ///
 /// This function wraps the builder pattern results with proper error context.
@@ -28,3 +46,219 @@ pub fn build<T, E>(builder_result: Result<T, E>, context: &str) -> Result<T, App
 {
     builder_result.map_err(|e| AppError::DatabaseError(format!("{}: {:?}", context, e.to_string())))
 }
+
+/// Waits for a newly created table and all of its GSIs to become ACTIVE.
+///
+/// `create_table` returns while the table is still `CREATING` and its indexes are
+/// backfilling, so queries issued straight after startup can fail. This polls
+/// `describe_table` with exponential backoff until everything is active.
+///
+/// # Arguments
+///
+/// * `client` - DynamoDB client for AWS API operations
+/// * `table_name` - Name of the table to wait for
+///
+/// # Returns
+///
+/// * `Result<(), AppError>` - Success, or a DatabaseError if the table is not active within the timeout
+pub async fn wait_until_active(client: &Client, table_name: &str) -> Result<(), AppError> {
+    let deadline = Instant::now() + TABLE_ACTIVE_TIMEOUT;
+    let mut delay = INITIAL_POLL_DELAY;
+
+    loop {
+        match client.describe_table().table_name(table_name).send().await {
+            Ok(output) if output.table().is_some_and(is_table_active) => {
+                return Ok(());
+            }
+            Ok(_) => {}
+            // A just-created table can briefly be invisible to describe_table
+            Err(e) if e.as_service_error().is_some_and(|se| se.is_resource_not_found_exception()) => {}
+            Err(e) => {
+                return Err(
+                    AppError::DatabaseError(
+                        format!("Failed to describe {} table: {:?}", table_name, e.to_string())
+                    )
+                );
+            }
+        }
+
+        if Instant::now() + delay > deadline {
+            return Err(
+                AppError::DatabaseError(
+                    format!(
+                        "Timed out after {}s waiting for {} table to become ACTIVE",
+                        TABLE_ACTIVE_TIMEOUT.as_secs(),
+                        table_name
+                    )
+                )
+            );
+        }
+
+        sleep(delay).await;
+        delay = (delay * 2).min(MAX_POLL_DELAY);
+    }
+}
+
+/// Reads a boolean attribute from a DynamoDB item.
+///
+/// Items written before an attribute existed, or holding a non-boolean value for it,
+/// read as `default`, so every caller states the default it relies on.
+///
+/// # Arguments
+///
+/// * `item` - DynamoDB item to read from
+/// * `key` - Attribute name
+/// * `default` - Value used when the attribute is missing or not a boolean
+///
+/// # Returns
+///
+/// * `bool` - The stored value, or `default`
+pub fn get_bool(item: &HashMap<String, AttributeValue>, key: &str, default: bool) -> bool {
+    item.get(key)
+        .and_then(|v| v.as_bool().ok())
+        .copied()
+        .unwrap_or(default)
+}
+
+/// Formats a timestamp in the single form every model stores.
+///
+/// RFC3339 in UTC with a `Z` suffix and a fixed nine fractional digits, e.g.
+/// `2026-10-15T08:30:00.000000000Z`. Every value has the same width, so lexical
+/// order matches chronological order and the strings can be used as GSI sort keys.
+///
+/// # Arguments
+///
+/// * `dt` - Timestamp to format
+///
+/// # Returns
+///
+/// * `String` - The canonical stored form
+pub fn to_db_timestamp(dt: &DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+/// Parses a timestamp read from a DynamoDB item.
+///
+/// Accepts the `to_db_timestamp` form and any other RFC3339 value, plus the
+/// `2026-10-15 08:30:00 UTC` form older items were written with.
+///
+/// # Arguments
+///
+/// * `s` - Stored attribute value
+///
+/// # Returns
+///
+/// * `Option<DateTime<Utc>>` - The timestamp, or `None` if `s` is not a timestamp
+pub fn from_db_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| s.parse::<DateTime<Utc>>().ok())
+}
+
+/// Physical name of a table once the deployment's table prefix is applied.
+///
+/// Environments sharing one AWS account are kept apart by prefixing every table,
+/// e.g. `staging` turns `Assets` into `staging_Assets`.
+///
+/// # Arguments
+///
+/// * `table_prefix` - `DatabaseConfig.table_prefix`, if any
+/// * `table_name` - Unprefixed table name
+///
+/// # Returns
+///
+/// * `String` - The table name to send to DynamoDB
+pub fn prefixed_table_name(table_prefix: Option<&str>, table_name: &str) -> String {
+    match table_prefix {
+        Some(prefix) if !prefix.is_empty() => format!("{}_{}", prefix, table_name),
+        _ => table_name.to_string(),
+    }
+}
+
+/// Whether a table and every one of its global secondary indexes is ACTIVE
+fn is_table_active(table: &TableDescription) -> bool {
+    table.table_status() == Some(&TableStatus::Active) &&
+        table
+            .global_secondary_indexes()
+            .iter()
+            .all(|gsi| gsi.index_status() == Some(&IndexStatus::Active))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_dynamodb::types::GlobalSecondaryIndexDescription;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_prefixed_table_name() {
+        assert_eq!(prefixed_table_name(Some("staging"), "Assets"), "staging_Assets");
+        assert_eq!(prefixed_table_name(Some(""), "Assets"), "Assets");
+        assert_eq!(prefixed_table_name(None, "Assets"), "Assets");
+    }
+
+    fn table(status: TableStatus, index_statuses: &[IndexStatus]) -> TableDescription {
+        let mut builder = TableDescription::builder().table_status(status);
+        for index_status in index_statuses {
+            builder = builder.global_secondary_indexes(
+                GlobalSecondaryIndexDescription::builder().index_status(index_status.clone()).build()
+            );
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_table_is_active_only_once_every_index_is() {
+        assert!(is_table_active(&table(TableStatus::Active, &[])));
+        assert!(is_table_active(&table(TableStatus::Active, &[IndexStatus::Active])));
+        assert!(!is_table_active(&table(TableStatus::Creating, &[])));
+        assert!(
+            !is_table_active(&table(TableStatus::Active, &[IndexStatus::Active, IndexStatus::Creating]))
+        );
+    }
+
+    #[test]
+    fn test_get_bool_falls_back_to_default() {
+        let mut item = HashMap::new();
+        item.insert("flag".to_string(), AttributeValue::Bool(false));
+        item.insert("text".to_string(), AttributeValue::S("true".to_string()));
+
+        assert!(!get_bool(&item, "flag", true));
+        assert!(get_bool(&item, "missing", true));
+        assert!(!get_bool(&item, "missing", false));
+        assert!(get_bool(&item, "text", true));
+    }
+
+    #[test]
+    fn test_db_timestamp_round_trips() {
+        let dt = Utc.with_ymd_and_hms(2026, 10, 15, 8, 30, 0).unwrap() +
+            chrono::Duration::nanoseconds(123_456_789);
+
+        let stored = to_db_timestamp(&dt);
+
+        assert_eq!(stored, "2026-10-15T08:30:00.123456789Z");
+        assert_eq!(from_db_timestamp(&stored), Some(dt));
+        assert_eq!(from_db_timestamp(&dt.to_string()), Some(dt));
+        assert_eq!(from_db_timestamp("not a date"), None);
+    }
+
+    #[test]
+    fn test_db_timestamps_sort_lexically_by_time() {
+        let base = Utc.with_ymd_and_hms(2026, 1, 9, 23, 59, 59).unwrap();
+        let times = [
+            base,
+            base + chrono::Duration::nanoseconds(5),
+            base + chrono::Duration::milliseconds(500),
+            base + chrono::Duration::seconds(1),
+            base + chrono::Duration::days(1),
+            base + chrono::Duration::days(400),
+        ];
+
+        let stored: Vec<String> = times.iter().map(to_db_timestamp).collect();
+        let mut sorted = stored.clone();
+        sorted.sort();
+
+        assert_eq!(stored, sorted);
+    }
+}
@@ -1,10 +1,32 @@
 use aws_config::{ meta::region::RegionProviderChain, BehaviorVersion };
-use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::{
+    config::{ retry::RetryConfig, timeout::TimeoutConfig, Builder },
+    Client,
+};
 use dotenvy::dotenv;
 use tracing::info;
-use std::env;
+use std::{ env, time::Duration };
 
-use crate::error::AppError;
+use crate::{ config::DatabaseConfig, error::AppError };
+
+/// Applies the configured retry and timeout policy to a DynamoDB client config
+///
+/// `operation_timeout_ms` bounds a whole call including its retries, so a slow
+/// or unreachable endpoint fails the resolver instead of hanging it.
+///
+/// # Arguments
+///
+/// * `builder` - DynamoDB client config under construction
+/// * `database` - Retry and timeout settings
+pub fn apply_database_config(builder: Builder, database: &DatabaseConfig) -> Builder {
+    builder
+        .retry_config(RetryConfig::standard().with_max_attempts(database.max_retries + 1))
+        .timeout_config(
+            TimeoutConfig::builder()
+                .operation_timeout(Duration::from_millis(database.operation_timeout_ms))
+                .build()
+        )
+}
 
 pub async fn setup_local_client() -> Result<Client, AppError> {
     dotenv().ok();
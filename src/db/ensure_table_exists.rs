@@ -3,10 +3,14 @@
 //! This module provides the main entry point for ensuring all database tables exist.
 //! It imports and calls the modular table creation functions from the specialized modules.
 
-use aws_sdk_dynamodb::Client;
+use std::{ future::Future, pin::Pin, time::Instant };
+
+use aws_sdk_dynamodb::{ operation::list_tables::ListTablesOutput, Client };
+use futures_util::future::join_all;
 use crate::error::AppError;
 
 use super::{
+    common::prefixed_table_name,
     user_tables,
     asset_tables,
     notification_tables,
@@ -15,73 +19,158 @@ use super::{
     misc_tables,
 };
 
+/// DynamoDB caps how many tables may be in the CREATING state at once
+const MAX_CONCURRENT_CREATES: usize = 10;
+
+/// A pending call to one of the per-table creation functions
+type TableCreation<'a> = Pin<Box<dyn Future<Output = Result<(), AppError>> + Send + 'a>>;
+
+/// Lists every table in the account, following `last_evaluated_table_name` past the
+/// 100 names DynamoDB returns per page
+async fn list_all_tables(client: &Client) -> Result<ListTablesOutput, AppError> {
+    let mut table_names = Vec::new();
+    let mut start_table_name = None;
+
+    loop {
+        let page = client
+            .list_tables()
+            .set_exclusive_start_table_name(start_table_name)
+            .send().await
+            .map_err(|e|
+                AppError::DatabaseError(
+                    format!("Failed to retrieve tables list from db client: {:?}", e.to_string())
+                )
+            )?;
+
+        table_names.extend(page.table_names().iter().cloned());
+
+        start_table_name = page.last_evaluated_table_name;
+        if start_table_name.is_none() {
+            break;
+        }
+    }
+
+    Ok(ListTablesOutput::builder().set_table_names(Some(table_names)).build())
+}
+
 /// Main function to ensure all required DynamoDB tables exist.
 ///
-/// This function orchestrates the creation of all tables across different functional areas
-/// by calling the specialized table creation functions from their respective modules.
+/// The table list is fetched once and shared by every creation function, each of
+/// which returns early if its table already exists. Missing tables are created
+/// concurrently, `MAX_CONCURRENT_CREATES` at a time, to keep cold starts short.
 ///
 /// # Arguments
 ///
 /// * `client` - DynamoDB client for AWS API operations
+/// * `table_prefix` - Prefix applied to every table name, see `prefixed_table_name`
 ///
 /// # Returns
 ///
-/// * `Result<(), AppError>` - Success or a database error with context
-pub async fn ensure_all_tables_exist(client: &Client) -> Result<(), AppError> {
+/// * `Result<(), AppError>` - Success or the first database error, in table order
+pub async fn ensure_all_tables_exist(
+    client: &Client,
+    table_prefix: Option<&str>
+) -> Result<(), AppError> {
+    let started = Instant::now();
+
     // Get all existing tables once to avoid multiple API calls
-    let tables = client
-        .list_tables()
-        .send().await
-        .map_err(|e|
-            AppError::DatabaseError(
-                format!("Failed to retrieve tables list from db client: {:?}", e.to_string())
-            )
-        )?;
+    let tables = list_all_tables(client).await?;
 
     println!("Starting table creation process...");
 
-    // Create user and access management tables
-    println!("Creating user and access management tables...");
-    user_tables::create_pantry_system_table(&tables, client).await?;
-    user_tables::create_users_table(&tables, client).await?;
-    user_tables::create_pantry_access_table(&tables, client).await?;
-
-    // Create asset management tables
-    println!("Creating asset management tables...");
-    asset_tables::create_assets_table(&tables, client).await?;
-    asset_tables::create_asset_types_table(&tables, client).await?;
-    asset_tables::create_locations_table(&tables, client).await?;
-    asset_tables::create_location_types_table(&tables, client).await?;
-    asset_tables::create_manufacturers_table(&tables, client).await?;
-    asset_tables::create_maintenance_schedules_table(&tables, client).await?;
-    asset_tables::create_work_orders_table(&tables, client).await?;
-
-    // Create notification system tables
-    println!("Creating notification system tables...");
-    notification_tables::create_notifications_table(&tables, client).await?;
-    notification_tables::create_notification_templates_table(&tables, client).await?;
-    notification_tables::create_notification_delivery_logs_table(&tables, client).await?;
-    notification_tables::create_user_notification_preferences_table(&tables, client).await?;
-
-    // Create security and permissions tables
-    println!("Creating security and permissions tables...");
-    security_tables::create_roles_table(&tables, client).await?;
-    security_tables::create_user_roles_table(&tables, client).await?;
-    security_tables::create_permissions_table(&tables, client).await?;
-    security_tables::create_permission_logs_table(&tables, client).await?;
-    security_tables::create_role_hierarchy_table(&tables, client).await?;
-    security_tables::create_temp_role_elevation_table(&tables, client).await?;
-
-    // Create vendor management tables
-    println!("Creating vendor management tables...");
-    vendor_tables::create_vendors_table(&tables, client).await?;
-    vendor_tables::create_vendor_categories_table(&tables, client).await?;
-
-    // Create miscellaneous tables
-    println!("Creating miscellaneous system tables...");
-    misc_tables::create_user_preferences_table(&tables, client).await?;
-    misc_tables::create_tasks_table(&tables, client).await?;
-
-    println!("All tables created successfully!");
+    let creations: Vec<(&str, TableCreation)> = vec![
+        // User and access management tables
+        ("PantrySystem", Box::pin(user_tables::create_pantry_system_table(&tables, client, table_prefix))),
+        ("Users", Box::pin(user_tables::create_users_table(&tables, client, table_prefix))),
+        ("UserCredentials", Box::pin(user_tables::create_user_credentials_table(&tables, client, table_prefix))),
+        ("PantryAccess", Box::pin(user_tables::create_pantry_access_table(&tables, client, table_prefix))),
+        // Asset management tables
+        ("Assets", Box::pin(asset_tables::create_assets_table(&tables, client, table_prefix))),
+        ("AssetTypes", Box::pin(asset_tables::create_asset_types_table(&tables, client, table_prefix))),
+        ("Locations", Box::pin(asset_tables::create_locations_table(&tables, client, table_prefix))),
+        ("LocationTypes", Box::pin(asset_tables::create_location_types_table(&tables, client, table_prefix))),
+        ("Manufacturers", Box::pin(asset_tables::create_manufacturers_table(&tables, client, table_prefix))),
+        (
+            "MaintenanceSchedules",
+            Box::pin(asset_tables::create_maintenance_schedules_table(&tables, client, table_prefix)),
+        ),
+        ("WorkOrders", Box::pin(asset_tables::create_work_orders_table(&tables, client, table_prefix))),
+        // Notification system tables
+        (
+            "Notifications",
+            Box::pin(notification_tables::create_notifications_table(&tables, client, table_prefix)),
+        ),
+        (
+            "NotificationTemplates",
+            Box::pin(notification_tables::create_notification_templates_table(&tables, client, table_prefix)),
+        ),
+        (
+            "NotificationDeliveryLogs",
+            Box::pin(notification_tables::create_notification_delivery_logs_table(&tables, client, table_prefix)),
+        ),
+        (
+            "UserNotificationPreferences",
+            Box::pin(
+                notification_tables::create_user_notification_preferences_table(&tables, client, table_prefix)
+            ),
+        ),
+        // Security and permissions tables
+        ("Roles", Box::pin(security_tables::create_roles_table(&tables, client, table_prefix))),
+        ("UserRoles", Box::pin(security_tables::create_user_roles_table(&tables, client, table_prefix))),
+        ("Permissions", Box::pin(security_tables::create_permissions_table(&tables, client, table_prefix))),
+        (
+            "PermissionLogs",
+            Box::pin(security_tables::create_permission_logs_table(&tables, client, table_prefix)),
+        ),
+        (
+            "RoleHierarchy",
+            Box::pin(security_tables::create_role_hierarchy_table(&tables, client, table_prefix)),
+        ),
+        (
+            "TempRoleElevation",
+            Box::pin(security_tables::create_temp_role_elevation_table(&tables, client, table_prefix)),
+        ),
+        ("AuditEvents", Box::pin(security_tables::create_audit_events_table(&tables, client, table_prefix))),
+        // Vendor management tables
+        ("Vendors", Box::pin(vendor_tables::create_vendors_table(&tables, client, table_prefix))),
+        (
+            "VendorCategories",
+            Box::pin(vendor_tables::create_vendor_categories_table(&tables, client, table_prefix)),
+        ),
+        // Miscellaneous tables
+        ("UserPreferences", Box::pin(misc_tables::create_user_preferences_table(&tables, client, table_prefix))),
+        ("Tasks", Box::pin(misc_tables::create_tasks_table(&tables, client, table_prefix))),
+        ("IdempotencyKeys", Box::pin(misc_tables::create_idempotency_keys_table(&tables, client, table_prefix))),
+        ("Counters", Box::pin(misc_tables::create_counters_table(&tables, client, table_prefix))),
+    ];
+
+    let (present, missing): (Vec<_>, Vec<_>) = creations
+        .iter()
+        .map(|(name, _)| prefixed_table_name(table_prefix, name))
+        .partition(|name| tables.table_names().contains(name));
+
+    println!("Tables already present: {:?}", present);
+    println!("Tables to create: {:?}", missing);
+
+    let mut first_error = None;
+    let mut pending = creations.into_iter().peekable();
+
+    // Later batches are not started once one has failed
+    while first_error.is_none() && pending.peek().is_some() {
+        let (names, batch): (Vec<_>, Vec<_>) = pending.by_ref().take(MAX_CONCURRENT_CREATES).unzip();
+
+        for (name, result) in names.into_iter().zip(join_all(batch).await) {
+            if let Err(e) = result {
+                eprintln!("Failed to ensure table {}: {}", name, e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    if let Some(e) = first_error {
+        return Err(e);
+    }
+
+    println!("All tables created successfully in {:?}!", started.elapsed());
     Ok(())
 }
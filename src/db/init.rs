@@ -9,7 +9,10 @@ use aws_sdk_dynamodb::Client;
 use crate::error::AppError;
 use super::ensure_table_exists;
 
-pub async fn ensure_tables_exist(client: &Client) -> Result<(), AppError> {
+pub async fn ensure_tables_exist(
+    client: &Client,
+    table_prefix: Option<&str>
+) -> Result<(), AppError> {
     // Use the new modular table creation orchestration
-    ensure_table_exists::ensure_all_tables_exist(client).await
+    ensure_table_exists::ensure_all_tables_exist(client, table_prefix).await
 }
@@ -0,0 +1,131 @@
+//! One-time data migrations.
+//!
+//! Each migration is safe to run more than once: rows already in the target
+//! shape are skipped, so a second run over clean data writes nothing.
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde_json::json;
+use tracing::info;
+
+use crate::{
+    error::AppError,
+    models::{
+        audit_event::AuditEvent,
+        maintenance_schedule::{ due_date_sort_key, MaintenanceSchedule, DUE_DATE_PARTITION },
+        user::User,
+    },
+    repository::WriteCondition,
+    Repository,
+};
+
+/// Actor recorded on audit events recovered from existing data
+const MIGRATION_ACTOR: &str = "system";
+
+/// Runs every data migration, in the order they were added
+///
+/// Called at startup once the tables exist. Stops at the first failing migration;
+/// since each one is idempotent the next start picks up where this one stopped.
+pub async fn run_migrations(repo: &Repository) -> Result<(), AppError> {
+    migrate_user_status_notes(repo).await?;
+    migrate_due_date_sort_keys(repo).await?;
+
+    Ok(())
+}
+
+/// Moves status reasons appended to `User::notes` into audit events
+///
+/// Older versions of `suspend_user`, `terminate_user`, `update_user_status` and
+/// `delete_user` appended `; SUSPENDED: <reason>` style entries to `notes`. For
+/// each user still carrying them, one audit event per entry is written and the
+/// entries are then stripped from `notes`. The original times were never stored,
+/// so the recovered events carry the migration time.
+///
+/// Events are written before the user is updated; a run interrupted between
+/// the two can leave duplicate events for that user on the next run, but never
+/// loses a reason.
+///
+/// # Returns
+///
+/// Number of users whose notes were rewritten
+pub async fn migrate_user_status_notes(repo: &Repository) -> Result<usize, AppError> {
+    let users = repo.list::<User>(None).await?;
+    let mut migrated = 0;
+
+    for mut user in users {
+        let history = user.take_status_history();
+        if history.is_empty() {
+            continue;
+        }
+
+        let events: Vec<AuditEvent> = history
+            .into_iter()
+            .map(|(action, reason)| {
+                AuditEvent::new(
+                    MIGRATION_ACTOR.to_string(),
+                    action,
+                    "user",
+                    user.id.clone(),
+                    json!({ "reason": reason, "source": "notes" })
+                )
+            })
+            .collect();
+
+        repo.batch_write(events, Vec::new()).await?;
+        repo.update(user).await?;
+        migrated += 1;
+    }
+
+    info!("Moved status history out of notes for {} users", migrated);
+
+    Ok(migrated)
+}
+
+/// Rewrites `DueDateIndex` sort keys still stored in the whole-second form
+///
+/// `next_due_date` used to be written as `2026-03-01T09:00:00Z`. The canonical
+/// `to_db_timestamp` form sorts before it within the same second, so a due-date
+/// cutoff could skip schedules still carrying the old key. Each indexed schedule's
+/// key is rewritten on the condition that it differs from the canonical one; a
+/// failed condition means it is already canonical.
+///
+/// # Returns
+///
+/// Number of schedules whose sort key was rewritten
+pub async fn migrate_due_date_sort_keys(repo: &Repository) -> Result<usize, AppError> {
+    let schedules = repo.query_index::<MaintenanceSchedule>(
+        "DueDateIndex",
+        "due_partition",
+        DUE_DATE_PARTITION,
+        None
+    ).await?;
+    let mut migrated = 0;
+
+    for schedule in schedules {
+        let sort_key = AttributeValue::S(due_date_sort_key(&schedule.next_due_at));
+        let condition = WriteCondition::new("#next_due_date <> :next_due_date")
+            .name("#next_due_date", "next_due_date")
+            .value(":next_due_date", sort_key.clone());
+
+        match
+            repo.update_fields_if::<MaintenanceSchedule>(
+                schedule.id,
+                HashMap::from([("next_due_date".to_string(), sort_key)]),
+                condition
+            ).await
+        {
+            Ok(_) => {
+                migrated += 1;
+            }
+            Err(AppError::Conflict(_)) => {}
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    info!("Rewrote due date sort keys for {} maintenance schedules", migrated);
+
+    Ok(migrated)
+}
@@ -1,25 +1,46 @@
 use aws_sdk_dynamodb::{
     Client,
     operation::list_tables::ListTablesOutput,
-    types::{ AttributeDefinition, BillingMode, KeySchemaElement, KeyType, ScalarAttributeType },
+    types::{
+        AttributeDefinition,
+        BillingMode,
+        GlobalSecondaryIndex,
+        KeySchemaElement,
+        KeyType,
+        Projection,
+        ProjectionType,
+        ScalarAttributeType,
+        TimeToLiveSpecification,
+    },
 };
 
 use crate::error::AppError;
-use super::common::build;
+use super::common::{ build, prefixed_table_name, wait_until_active };
 
 /// Creates the UserPreferences table.
+///
+/// Keyed on `id`, with `UserIndex` on `user_id` for the one-row-per-user lookup.
 pub async fn create_user_preferences_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "UserPreferences";
+    let table_name = prefixed_table_name(table_prefix, "UserPreferences");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
 
     // Define attribute definitions
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
     let ad_user_id = build(
         AttributeDefinition::builder()
             .attribute_name("user_id")
@@ -29,18 +50,35 @@ pub async fn create_user_preferences_table(
     )?;
 
     // Define key schema
-    let ks_user_id = build(
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    // Define GSI 1: User Index
+    let gsi1_pk = build(
         KeySchemaElement::builder().attribute_name("user_id").key_type(KeyType::Hash).build(),
-        "Failed to build user_id key schema"
+        "Failed to build User GSI PK"
+    )?;
+
+    let gsi1 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name("UserIndex")
+            .key_schema(gsi1_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build UserIndex GSI"
     )?;
 
     // Create the table
     let response = client
         .create_table()
-        .table_name("UserPreferences")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
         .attribute_definitions(ad_user_id)
-        .key_schema(ks_user_id)
+        .key_schema(ks_id)
+        .global_secondary_indexes(gsi1)
         .send().await
         .map_err(|e|
             AppError::DatabaseError(
@@ -48,6 +86,7 @@ pub async fn create_user_preferences_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("UserPreferences table created: {:?}", response);
     Ok(())
 }
@@ -55,11 +94,12 @@ pub async fn create_user_preferences_table(
 /// Creates the Tasks table for managing tasks associated with work orders, cleaning, and maintenance requests.
 pub async fn create_tasks_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Tasks";
+    let table_name = prefixed_table_name(table_prefix, "Tasks");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -191,7 +231,7 @@ pub async fn create_tasks_table(
     // Create the table with GSIs
     let response = client
         .create_table()
-        .table_name(table_name)
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         // .attribute_definitions(ad_task_number)
@@ -213,6 +253,121 @@ pub async fn create_tasks_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Tasks table created: {:?}", response);
     Ok(())
 }
+
+/// Creates the IdempotencyKeys table.
+///
+/// Rows are keyed by the scoped idempotency key and expire through DynamoDB's
+/// `TimeToLive` on the numeric `expires_at` attribute.
+pub async fn create_idempotency_keys_table(
+    tables: &ListTablesOutput,
+    client: &Client,
+    table_prefix: Option<&str>
+) -> Result<(), AppError> {
+    let table_name = prefixed_table_name(table_prefix, "IdempotencyKeys");
+
+    if tables.table_names().contains(&table_name) {
+        println!("Table '{}' already exists", table_name);
+        return Ok(());
+    }
+
+    // Define attribute definitions
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
+    // Define key schema
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    // Create the table
+    let response = client
+        .create_table()
+        .table_name(&table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
+        .key_schema(ks_id)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to create {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    wait_until_active(client, &table_name).await?;
+    println!("IdempotencyKeys table created: {:?}", response);
+
+    // TTL can only be enabled once the table exists
+    let ttl = build(
+        TimeToLiveSpecification::builder().attribute_name("expires_at").enabled(true).build(),
+        "Failed to build expires_at TTL specification"
+    )?;
+
+    client
+        .update_time_to_live()
+        .table_name(&table_name)
+        .time_to_live_specification(ttl)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to enable TTL on {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    Ok(())
+}
+
+/// Creates the Counters table.
+///
+/// One item per named counter, keyed on `id` and advanced atomically with `ADD`.
+pub async fn create_counters_table(
+    tables: &ListTablesOutput,
+    client: &Client,
+    table_prefix: Option<&str>
+) -> Result<(), AppError> {
+    let table_name = prefixed_table_name(table_prefix, "Counters");
+
+    if tables.table_names().contains(&table_name) {
+        println!("Table '{}' already exists", table_name);
+        return Ok(());
+    }
+
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    let response = client
+        .create_table()
+        .table_name(&table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
+        .key_schema(ks_id)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to create {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    wait_until_active(client, &table_name).await?;
+    println!("Counters table created: {:?}", response);
+    Ok(())
+}
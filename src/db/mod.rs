@@ -9,6 +9,7 @@
 //! - `vendor_tables.rs` - Vendor and supply chain management tables
 //! - `misc_tables.rs` - Miscellaneous system tables
 //! - `ensure_table_exists.rs` - Main orchestration for table creation
+//! - `migrations.rs` - One-time data migrations
 
 pub mod init;
 pub mod local;
@@ -21,6 +22,7 @@ pub mod security_tables;
 pub mod vendor_tables;
 pub mod misc_tables;
 pub mod ensure_table_exists;
+pub mod migrations;
 
 // Re-export commonly used items
 pub use common::build;
@@ -19,16 +19,17 @@ use aws_sdk_dynamodb::{
 };
 
 use crate::error::AppError;
-use super::common::build;
+use super::common::{ build, prefixed_table_name, wait_until_active };
 
 /// Creates the Notifications table.
 pub async fn create_notifications_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Notifications";
+    let table_name = prefixed_table_name(table_prefix, "Notifications");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -146,7 +147,7 @@ pub async fn create_notifications_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("Notifications")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_user_id)
@@ -165,6 +166,7 @@ pub async fn create_notifications_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Notifications table created: {:?}", response);
     Ok(())
 }
@@ -172,11 +174,12 @@ pub async fn create_notifications_table(
 /// Creates the NotificationTemplates table.
 pub async fn create_notification_templates_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "NotificationTemplates";
+    let table_name = prefixed_table_name(table_prefix, "NotificationTemplates");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -190,12 +193,12 @@ pub async fn create_notification_templates_table(
         "Failed to build id attribute definition"
     )?;
 
-    let ad_template_type = build(
+    let ad_key = build(
         AttributeDefinition::builder()
-            .attribute_name("template_type")
+            .attribute_name("key")
             .attribute_type(ScalarAttributeType::S)
             .build(),
-        "Failed to build template_type attribute definition"
+        "Failed to build key attribute definition"
     )?;
 
     // Define key schema
@@ -204,28 +207,28 @@ pub async fn create_notification_templates_table(
         "Failed to build id key schema"
     )?;
 
-    // Define GSI 1: Type Index
+    // Define GSI 1: Key Index
     let gsi1_pk = build(
-        KeySchemaElement::builder().attribute_name("template_type").key_type(KeyType::Hash).build(),
-        "Failed to build Type GSI PK"
+        KeySchemaElement::builder().attribute_name("key").key_type(KeyType::Hash).build(),
+        "Failed to build Key GSI PK"
     )?;
 
     let gsi1 = build(
         GlobalSecondaryIndex::builder()
-            .index_name("TypeIndex")
+            .index_name("KeyIndex")
             .key_schema(gsi1_pk)
             .projection(Projection::builder().projection_type(ProjectionType::All).build())
             .build(),
-        "Failed to build TypeIndex GSI"
+        "Failed to build KeyIndex GSI"
     )?;
 
     // Create the table
     let response = client
         .create_table()
-        .table_name("NotificationTemplates")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
-        .attribute_definitions(ad_template_type)
+        .attribute_definitions(ad_key)
         .key_schema(ks_id)
         .global_secondary_indexes(gsi1)
         .send().await
@@ -235,6 +238,7 @@ pub async fn create_notification_templates_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("NotificationTemplates table created: {:?}", response);
     Ok(())
 }
@@ -242,11 +246,12 @@ pub async fn create_notification_templates_table(
 /// Creates the NotificationDeliveryLogs table.
 pub async fn create_notification_delivery_logs_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "NotificationDeliveryLogs";
+    let table_name = prefixed_table_name(table_prefix, "NotificationDeliveryLogs");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -347,7 +352,7 @@ pub async fn create_notification_delivery_logs_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("NotificationDeliveryLogs")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_notification_id)
@@ -364,6 +369,7 @@ pub async fn create_notification_delivery_logs_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("NotificationDeliveryLogs table created: {:?}", response);
     Ok(())
 }
@@ -371,11 +377,12 @@ pub async fn create_notification_delivery_logs_table(
 /// Creates the UserNotificationPreferences table.
 pub async fn create_user_notification_preferences_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "UserNotificationPreferences";
+    let table_name = prefixed_table_name(table_prefix, "UserNotificationPreferences");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -432,7 +439,7 @@ pub async fn create_user_notification_preferences_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("UserNotificationPreferences")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_user_id)
         .attribute_definitions(ad_notification_type)
@@ -446,6 +453,7 @@ pub async fn create_user_notification_preferences_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("UserNotificationPreferences table created: {:?}", response);
     Ok(())
 }
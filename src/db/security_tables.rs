@@ -19,16 +19,17 @@ use aws_sdk_dynamodb::{
 };
 
 use crate::error::AppError;
-use super::common::build;
+use super::common::{ build, prefixed_table_name, wait_until_active };
 
 /// Creates the Roles table.
 pub async fn create_roles_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Roles";
+    let table_name = prefixed_table_name(table_prefix, "Roles");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -97,7 +98,7 @@ pub async fn create_roles_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("Roles")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_role_type)
@@ -112,6 +113,7 @@ pub async fn create_roles_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Roles table created: {:?}", response);
     Ok(())
 }
@@ -119,11 +121,12 @@ pub async fn create_roles_table(
 /// Creates the UserRoles table.
 pub async fn create_user_roles_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "UserRoles";
+    let table_name = prefixed_table_name(table_prefix, "UserRoles");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -215,7 +218,7 @@ pub async fn create_user_roles_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("UserRoles")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_user_id)
@@ -232,6 +235,7 @@ pub async fn create_user_roles_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("UserRoles table created: {:?}", response);
     Ok(())
 }
@@ -239,11 +243,12 @@ pub async fn create_user_roles_table(
 /// Creates the Permissions table.
 pub async fn create_permissions_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Permissions";
+    let table_name = prefixed_table_name(table_prefix, "Permissions");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -315,7 +320,7 @@ pub async fn create_permissions_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("Permissions")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_resource_type)
@@ -330,6 +335,7 @@ pub async fn create_permissions_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Permissions table created: {:?}", response);
     Ok(())
 }
@@ -337,11 +343,12 @@ pub async fn create_permissions_table(
 /// Creates the PermissionLogs table.
 pub async fn create_permission_logs_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "PermissionLogs";
+    let table_name = prefixed_table_name(table_prefix, "PermissionLogs");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -433,7 +440,7 @@ pub async fn create_permission_logs_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("PermissionLogs")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_user_id)
@@ -450,6 +457,7 @@ pub async fn create_permission_logs_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("PermissionLogs table created: {:?}", response);
     Ok(())
 }
@@ -457,11 +465,12 @@ pub async fn create_permission_logs_table(
 /// Creates the RoleHierarchy table.
 pub async fn create_role_hierarchy_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "RoleHierarchy";
+    let table_name = prefixed_table_name(table_prefix, "RoleHierarchy");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -518,7 +527,7 @@ pub async fn create_role_hierarchy_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("RoleHierarchy")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_parent_role_id)
         .attribute_definitions(ad_child_role_id)
@@ -532,6 +541,7 @@ pub async fn create_role_hierarchy_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("RoleHierarchy table created: {:?}", response);
     Ok(())
 }
@@ -539,11 +549,12 @@ pub async fn create_role_hierarchy_table(
 /// Creates the TempRoleElevation table.
 pub async fn create_temp_role_elevation_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "TempRoleElevation";
+    let table_name = prefixed_table_name(table_prefix, "TempRoleElevation");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -638,7 +649,7 @@ pub async fn create_temp_role_elevation_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("TempRoleElevation")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_user_id)
@@ -655,6 +666,79 @@ pub async fn create_temp_role_elevation_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("TempRoleElevation table created: {:?}", response);
     Ok(())
 }
+
+/// Creates the AuditEvents table.
+pub async fn create_audit_events_table(
+    tables: &ListTablesOutput,
+    client: &Client,
+    table_prefix: Option<&str>
+) -> Result<(), AppError> {
+    let table_name = prefixed_table_name(table_prefix, "AuditEvents");
+
+    if tables.table_names().contains(&table_name) {
+        println!("Table '{}' already exists", table_name);
+        return Ok(());
+    }
+
+    // Define attribute definitions
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
+    let ad_target_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("target_id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build target_id attribute definition"
+    )?;
+
+    // Define key schema
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    // Define GSI: Target Index
+    let gsi_pk = build(
+        KeySchemaElement::builder().attribute_name("target_id").key_type(KeyType::Hash).build(),
+        "Failed to build Target GSI PK"
+    )?;
+
+    let gsi = build(
+        GlobalSecondaryIndex::builder()
+            .index_name("TargetIndex")
+            .key_schema(gsi_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build TargetIndex GSI"
+    )?;
+
+    // Create the table
+    let response = client
+        .create_table()
+        .table_name(&table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
+        .attribute_definitions(ad_target_id)
+        .key_schema(ks_id)
+        .global_secondary_indexes(gsi)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to create {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    wait_until_active(client, &table_name).await?;
+    println!("AuditEvents table created: {:?}", response);
+    Ok(())
+}
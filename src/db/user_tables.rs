@@ -19,7 +19,7 @@ use aws_sdk_dynamodb::{
 };
 
 use crate::error::AppError;
-use super::common::build;
+use super::common::{ build, prefixed_table_name, wait_until_active };
 
 /// Creates the PantrySystem table using a single-table design pattern.
 ///
@@ -38,12 +38,13 @@ use super::common::build;
 /// * EmailLookupIndex: Look up users by email address
 pub async fn create_pantry_system_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "PantrySystem";
+    let table_name = prefixed_table_name(table_prefix, "PantrySystem");
 
     // Check if table already exists
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -192,7 +193,7 @@ pub async fn create_pantry_system_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("PantrySystem")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_pk)
         .attribute_definitions(ad_sk)
@@ -213,6 +214,7 @@ pub async fn create_pantry_system_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("PantrySystem table created: {:?}", response);
     Ok(())
 }
@@ -220,11 +222,12 @@ pub async fn create_pantry_system_table(
 /// Creates a dedicated Users table for a multi-table design approach.
 pub async fn create_users_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Users";
+    let table_name = prefixed_table_name(table_prefix, "Users");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -254,6 +257,14 @@ pub async fn create_users_table(
         "Failed to build role attribute definition"
     )?;
 
+    let ad_username = build(
+        AttributeDefinition::builder()
+            .attribute_name("username")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build username attribute definition"
+    )?;
+
     // Define key schema
     let ks_user_id = build(
         KeySchemaElement::builder().attribute_name("user_id").key_type(KeyType::Hash).build(),
@@ -290,17 +301,34 @@ pub async fn create_users_table(
         "Failed to build RoleIndex GSI"
     )?;
 
+    // Define GSI 3: Username Lookup Index
+    let gsi3_pk = build(
+        KeySchemaElement::builder().attribute_name("username").key_type(KeyType::Hash).build(),
+        "Failed to build Username GSI PK"
+    )?;
+
+    let gsi3 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name("UsernameIndex")
+            .key_schema(gsi3_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build UsernameIndex GSI"
+    )?;
+
     // Create the table
     let response = client
         .create_table()
-        .table_name("Users")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_user_id)
         .attribute_definitions(ad_email)
         .attribute_definitions(ad_role)
+        .attribute_definitions(ad_username)
         .key_schema(ks_user_id)
         .global_secondary_indexes(gsi1)
         .global_secondary_indexes(gsi2)
+        .global_secondary_indexes(gsi3)
         .send().await
         .map_err(|e|
             AppError::DatabaseError(
@@ -308,18 +336,70 @@ pub async fn create_users_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Users table created: {:?}", response);
     Ok(())
 }
 
+/// Creates the UserCredentials table.
+///
+/// Password hashes live in their own table, keyed by user id, so they are never
+/// read as part of a `Users` scan.
+pub async fn create_user_credentials_table(
+    tables: &ListTablesOutput,
+    client: &Client,
+    table_prefix: Option<&str>
+) -> Result<(), AppError> {
+    let table_name = prefixed_table_name(table_prefix, "UserCredentials");
+
+    if tables.table_names().contains(&table_name) {
+        println!("Table '{}' already exists", table_name);
+        return Ok(());
+    }
+
+    // Define attribute definitions
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
+    // Define key schema
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    // Create the table
+    let response = client
+        .create_table()
+        .table_name(&table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
+        .key_schema(ks_id)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to create {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    wait_until_active(client, &table_name).await?;
+    println!("UserCredentials table created: {:?}", response);
+    Ok(())
+}
+
 /// Creates the PantryAccess table for managing user-pantry access relationships.
 pub async fn create_pantry_access_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "PantryAccess";
+    let table_name = prefixed_table_name(table_prefix, "PantryAccess");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -437,7 +517,7 @@ pub async fn create_pantry_access_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("PantryAccess")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_pantry_id)
         .attribute_definitions(ad_user_id)
@@ -455,6 +535,7 @@ pub async fn create_pantry_access_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("PantryAccess table created: {:?}", response);
     Ok(())
 }
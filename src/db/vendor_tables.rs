@@ -19,16 +19,17 @@ use aws_sdk_dynamodb::{
 };
 
 use crate::error::AppError;
-use super::common::build;
+use super::common::{ build, prefixed_table_name, wait_until_active };
 
 /// Creates the Vendors table.
 pub async fn create_vendors_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "Vendors";
+    let table_name = prefixed_table_name(table_prefix, "Vendors");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -66,6 +67,14 @@ pub async fn create_vendors_table(
         "Failed to build rating attribute definition"
     )?;
 
+    let ad_vendor_code = build(
+        AttributeDefinition::builder()
+            .attribute_name("vendor_code")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build vendor_code attribute definition"
+    )?;
+
     // Define key schema
     let ks_id = build(
         KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
@@ -117,19 +126,36 @@ pub async fn create_vendors_table(
         "Failed to build RatingIndex GSI"
     )?;
 
+    // Define GSI 4: Vendor Code Index, used to keep vendor codes unique
+    let gsi4_pk = build(
+        KeySchemaElement::builder().attribute_name("vendor_code").key_type(KeyType::Hash).build(),
+        "Failed to build Vendor Code GSI PK"
+    )?;
+
+    let gsi4 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name("VendorCodeIndex")
+            .key_schema(gsi4_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build VendorCodeIndex GSI"
+    )?;
+
     // Create the table
     let response = client
         .create_table()
-        .table_name("Vendors")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .attribute_definitions(ad_category_id)
         .attribute_definitions(ad_status)
         .attribute_definitions(ad_rating)
+        .attribute_definitions(ad_vendor_code)
         .key_schema(ks_id)
         .global_secondary_indexes(gsi1)
         .global_secondary_indexes(gsi2)
         .global_secondary_indexes(gsi3)
+        .global_secondary_indexes(gsi4)
         .send().await
         .map_err(|e|
             AppError::DatabaseError(
@@ -137,6 +163,7 @@ pub async fn create_vendors_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("Vendors table created: {:?}", response);
     Ok(())
 }
@@ -144,11 +171,12 @@ pub async fn create_vendors_table(
 /// Creates the VendorCategories table.
 pub async fn create_vendor_categories_table(
     tables: &ListTablesOutput,
-    client: &Client
+    client: &Client,
+    table_prefix: Option<&str>
 ) -> Result<(), AppError> {
-    let table_name = "VendorCategories";
+    let table_name = prefixed_table_name(table_prefix, "VendorCategories");
 
-    if tables.table_names().contains(&table_name.to_string()) {
+    if tables.table_names().contains(&table_name) {
         println!("Table '{}' already exists", table_name);
         return Ok(());
     }
@@ -171,7 +199,7 @@ pub async fn create_vendor_categories_table(
     // Create the table
     let response = client
         .create_table()
-        .table_name("VendorCategories")
+        .table_name(&table_name)
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
         .key_schema(ks_id)
@@ -182,6 +210,7 @@ pub async fn create_vendor_categories_table(
             )
         )?;
 
+    wait_until_active(client, &table_name).await?;
     println!("VendorCategories table created: {:?}", response);
     Ok(())
 }
@@ -1,12 +1,52 @@
-use async_graphql::{ Error as GraphQLError, ErrorExtensions };
+use std::sync::Arc;
+
+use async_graphql::{
+    extensions::{ Extension, ExtensionContext, ExtensionFactory, NextValidation },
+    Error as GraphQLError,
+    ErrorExtensionValues,
+    ErrorExtensions,
+    ServerError,
+    ValidationResult,
+};
+use axum::http::StatusCode;
+use serde::Serialize;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+/// A single failed check on an input field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[derive(Error, Debug, Clone)]
 pub enum AppError {
     #[error("Database error: {0}")] DatabaseError(String),
 
     #[error("Validation error: {0}")] ValidationError(String),
 
+    #[error("Validation error on {field}: {message}")] InvalidField {
+        field: String,
+        message: String,
+    },
+
+    #[error("Validation failed: {}", join_field_errors(.0))] ValidationErrors(Vec<FieldError>),
+
     #[error("Not found: {0}")] NotFound(String),
 
     #[error("Unauthorized: {0}")] Unauthorized(String),
@@ -18,21 +58,68 @@ pub enum AppError {
     #[error("Configuration error: {0}")] ConfigError(String),
 
     #[error("Authentication error: {0}")] AuthError(String),
+
+    #[error("Conflict: {0}")] Conflict(String),
+}
+
+impl AppError {
+    /// Validation failure attributed to a single input field
+    pub fn invalid_field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        AppError::InvalidField {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Stable, machine-readable code exposed as `extensions.code`
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::DatabaseError(_) => "DATABASE_ERROR",
+            AppError::ValidationError(_) |
+            AppError::InvalidField { .. } |
+            AppError::ValidationErrors(_) => "VALIDATION_ERROR",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::InternalServerError(_) => "INTERNAL",
+            AppError::ConfigError(_) => "CONFIG_ERROR",
+            AppError::AuthError(_) => "AUTH_ERROR",
+            AppError::Conflict(_) => "CONFLICT",
+        }
+    }
+
+    /// HTTP status for errors surfaced by plain (non-GraphQL) routes
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::ValidationError(_) |
+            AppError::InvalidField { .. } |
+            AppError::ValidationErrors(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Unauthorized(_) | AppError::AuthError(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::DatabaseError(_) |
+            AppError::InternalServerError(_) |
+            AppError::ConfigError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
 }
 
 impl ErrorExtensions for AppError {
     fn extend(&self) -> GraphQLError {
         eprintln!("Error occurred: {}", self);
+        crate::telemetry::record_error(self);
         GraphQLError::new(format!("{}", self)).extend_with(|_err, e| {
+            e.set("code", self.code());
+
             match self {
-                AppError::DatabaseError(_) => e.set("code", "DATABASE_ERROR"),
-                AppError::ValidationError(_) => e.set("code", "VALIDATION_ERROR"),
-                AppError::NotFound(_) => e.set("code", "NOT_FOUND"),
-                AppError::Unauthorized(_) => e.set("code", "UNAUTHORIZED"),
-                AppError::Forbidden(_) => e.set("code", "FORBIDDEN"),
-                AppError::InternalServerError(_) => e.set("code", "INTERNAL_SERVER_ERROR"),
-                AppError::ConfigError(_) => e.set("code", "CONFIG_ERROR"),
-                AppError::AuthError(_) => e.set("code", "AUTH_ERROR"),
+                AppError::InvalidField { field, .. } => e.set("field", field.as_str()),
+                AppError::ValidationErrors(errors) => {
+                    if let Ok(errors) = async_graphql::to_value(errors) {
+                        e.set("errors", errors);
+                    }
+                }
+                _ => {}
             }
         })
     }
@@ -46,3 +133,134 @@ impl AppError {
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+/// Code set on the error returned when a query exceeds the schema's complexity limit
+pub const QUERY_TOO_COMPLEX: &str = "QUERY_TOO_COMPLEX";
+
+/// Code set on the error returned when a query exceeds the schema's depth limit
+pub const QUERY_TOO_DEEP: &str = "QUERY_TOO_DEEP";
+
+/// Schema extension tagging depth and complexity limit errors with `extensions.code`
+///
+/// async-graphql reports both limits as plain messages, so they are matched by text.
+pub struct QueryLimitErrors;
+
+impl ExtensionFactory for QueryLimitErrors {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(QueryLimitErrorsExtension)
+    }
+}
+
+struct QueryLimitErrorsExtension;
+
+#[async_trait::async_trait]
+impl Extension for QueryLimitErrorsExtension {
+    async fn validation(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidation<'_>
+    ) -> Result<ValidationResult, Vec<ServerError>> {
+        next.run(ctx).await.map_err(|errors| errors.into_iter().map(tag_query_limit).collect())
+    }
+}
+
+/// Sets the limit code on `error` if it reports an exceeded depth or complexity limit
+fn tag_query_limit(mut error: ServerError) -> ServerError {
+    let code = match error.message.as_str() {
+        "Query is too complex." => QUERY_TOO_COMPLEX,
+        "Query is nested too deep." => QUERY_TOO_DEEP,
+        _ => {
+            return error;
+        }
+    };
+
+    error.extensions.get_or_insert_with(ErrorExtensionValues::default).set("code", code);
+    error
+}
+
+fn join_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::Value;
+
+    fn extension(error: &GraphQLError, key: &str) -> Option<Value> {
+        error.extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get(key))
+            .cloned()
+    }
+
+    #[test]
+    fn test_each_variant_maps_to_stable_code() {
+        let cases = [
+            (AppError::DatabaseError("x".to_string()), "DATABASE_ERROR"),
+            (AppError::ValidationError("x".to_string()), "VALIDATION_ERROR"),
+            (AppError::invalid_field("email", "x"), "VALIDATION_ERROR"),
+            (AppError::ValidationErrors(vec![FieldError::new("email", "x")]), "VALIDATION_ERROR"),
+            (AppError::NotFound("x".to_string()), "NOT_FOUND"),
+            (AppError::Unauthorized("x".to_string()), "UNAUTHORIZED"),
+            (AppError::Forbidden("x".to_string()), "FORBIDDEN"),
+            (AppError::InternalServerError("x".to_string()), "INTERNAL"),
+            (AppError::ConfigError("x".to_string()), "CONFIG_ERROR"),
+            (AppError::AuthError("x".to_string()), "AUTH_ERROR"),
+            (AppError::Conflict("x".to_string()), "CONFLICT"),
+        ];
+
+        for (error, code) in cases {
+            let graphql_error = error.to_graphql_error();
+            assert_eq!(extension(&graphql_error, "code"), Some(Value::from(code)));
+        }
+    }
+
+    #[test]
+    fn test_invalid_field_sets_field_extension() {
+        let graphql_error = AppError::invalid_field("email", "Invalid email format").to_graphql_error();
+
+        assert_eq!(extension(&graphql_error, "field"), Some(Value::from("email")));
+        assert_eq!(
+            extension(&AppError::NotFound("x".to_string()).to_graphql_error(), "field"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validation_errors_expose_every_field() {
+        let graphql_error = AppError::ValidationErrors(
+            vec![
+                FieldError::new("name", "Vendor name cannot be empty"),
+                FieldError::new("email_address", "Invalid email format")
+            ]
+        ).to_graphql_error();
+
+        let errors = extension(&graphql_error, "errors").unwrap().into_json().unwrap();
+        assert_eq!(
+            errors,
+            serde_json::json!([
+                { "field": "name", "message": "Vendor name cannot be empty" },
+                { "field": "email_address", "message": "Invalid email format" },
+            ])
+        );
+        assert!(graphql_error.message.contains("name: Vendor name cannot be empty"));
+    }
+
+    #[test]
+    fn test_tag_query_limit_only_tags_limit_errors() {
+        let code = |message: &str| {
+            tag_query_limit(ServerError::new(message, None)).extensions.and_then(|extensions|
+                extensions.get("code").cloned()
+            )
+        };
+
+        assert_eq!(code("Query is too complex."), Some(Value::from(QUERY_TOO_COMPLEX)));
+        assert_eq!(code("Query is nested too deep."), Some(Value::from(QUERY_TOO_DEEP)));
+        assert_eq!(code("Unknown field \"foo\""), None);
+    }
+}
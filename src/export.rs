@@ -0,0 +1,208 @@
+use axum::{
+    body::{ Body, Bytes },
+    extract::{ Extension, Query },
+    http::header,
+    response::{ IntoResponse, Response },
+};
+use futures_util::stream;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::{
+    auth::{ guard::{ ensure_permission, EXPORT_ASSETS }, AuthUser },
+    config::Config,
+    models::asset::{ Asset, AssetCurrentStatusOptions },
+    AppError,
+    DbClient,
+    Repository,
+};
+
+/// Columns written to `assets.csv`, in order
+pub const ASSET_CSV_COLUMNS: [&str; 9] = [
+    "id",
+    "name",
+    "serial_number",
+    "model_number",
+    "current_status",
+    "location_id",
+    "manufacturer_id",
+    "next_maintenance_due",
+    "is_maintenance_overdue",
+];
+
+/// Items requested from DynamoDB per streamed chunk
+const EXPORT_PAGE_SIZE: i32 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct AssetExportParams {
+    pub status: Option<String>, // e.g. `operational`, served from StatusIndex
+}
+
+/// Pagination state carried between streamed chunks
+struct ExportState {
+    repo: Repository,
+    status: Option<AssetCurrentStatusOptions>,
+    cursor: Option<String>,
+    header_written: bool,
+    done: bool,
+}
+
+/// `GET /export/assets.csv`: stream the asset register as CSV
+///
+/// Requires a bearer token whose roles grant `asset:export`. Rows are written one
+/// DynamoDB page at a time, so the table is never held in memory. An optional
+/// `status` query parameter narrows the export via `StatusIndex`.
+pub async fn export_assets_csv(
+    Extension(db_client): Extension<DbClient>,
+    Extension(config): Extension<Config>,
+    auth_user: Option<Extension<AuthUser>>,
+    Query(params): Query<AssetExportParams>
+) -> Response {
+    let Some(Extension(auth_user)) = auth_user else {
+        return error_response(AppError::Unauthorized("Authentication required".to_string()));
+    };
+
+    let repo = Repository::from_config(db_client, &config.database);
+
+    if let Err(e) = ensure_permission(&repo, &auth_user, EXPORT_ASSETS).await {
+        return error_response(e);
+    }
+
+    let status = match params.status.as_deref().map(AssetCurrentStatusOptions::from_string) {
+        Some(Ok(status)) => Some(status),
+        Some(Err(_)) => {
+            return error_response(AppError::invalid_field("status", "Invalid asset status"));
+        }
+        None => None,
+    };
+
+    let state = ExportState {
+        repo,
+        status,
+        cursor: None,
+        header_written: false,
+        done: false,
+    };
+
+    let body = Body::from_stream(stream::unfold(state, next_chunk));
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"assets.csv\""),
+        ],
+        body,
+    ).into_response()
+}
+
+/// Produces the next CSV chunk: the header first, then one chunk per DynamoDB page
+async fn next_chunk(
+    mut state: ExportState
+) -> Option<(Result<Bytes, std::io::Error>, ExportState)> {
+    if !state.header_written {
+        state.header_written = true;
+        return Some((write_csv(&[], true).map(Bytes::from), state));
+    }
+
+    if state.done {
+        return None;
+    }
+
+    let page = match &state.status {
+        Some(status) =>
+            state.repo.query_index_paginated::<Asset>(
+                "StatusIndex",
+                "current_status",
+                status.to_str(),
+                Some(EXPORT_PAGE_SIZE),
+                state.cursor.take()
+            ).await,
+        None => state.repo.list_paginated::<Asset>(Some(EXPORT_PAGE_SIZE), state.cursor.take()).await,
+    };
+
+    match page {
+        Ok((assets, next_cursor)) => {
+            state.done = next_cursor.is_none();
+            state.cursor = next_cursor;
+            Some((write_csv(&assets, false).map(Bytes::from), state))
+        }
+        Err(e) => {
+            // Headers are already sent, so the only option is to abort the body
+            warn!("Asset export failed mid-stream: {}", e);
+            state.done = true;
+            Some((Err(std::io::Error::other(e.to_string())), state))
+        }
+    }
+}
+
+/// Serializes assets as CSV rows, optionally preceded by the header row
+fn write_csv(assets: &[Asset], include_header: bool) -> Result<Vec<u8>, std::io::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    if include_header {
+        writer.write_record(ASSET_CSV_COLUMNS)?;
+    }
+
+    for asset in assets {
+        writer.write_record([
+            asset.id.as_str(),
+            asset.name.as_str(),
+            asset.serial_number.as_str(),
+            asset.model_number.as_str(),
+            asset.current_status.to_str(),
+            asset.location_id.as_str(),
+            asset.manufacturer_id.as_str(),
+            asset.next_maintenance_due().to_rfc3339().as_str(),
+            if asset.is_maintenance_overdue() { "true" } else { "false" },
+        ])?;
+    }
+
+    writer.into_inner().map_err(|e| e.into_error())
+}
+
+fn error_response(error: AppError) -> Response {
+    (error.status_code(), error.to_string()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use chrono::Utc;
+
+    #[test]
+    fn test_csv_has_header_and_quotes_commas() {
+        let now = Utc::now();
+        let asset = Asset::new(
+            "asset-1".to_string(),
+            "Pump, north".to_string(),
+            "type-1".to_string(),
+            "SN-1".to_string(),
+            "M-1".to_string(),
+            now,
+            now,
+            "loc-1".to_string(),
+            "mfg-1".to_string(),
+            "monthly".to_string(),
+            None,
+            None
+        ).unwrap();
+
+        let header = String::from_utf8(write_csv(&[], true).unwrap()).unwrap();
+        assert_eq!(header.trim_end(), ASSET_CSV_COLUMNS.join(","));
+
+        let rows = String::from_utf8(write_csv(&[asset], false).unwrap()).unwrap();
+        assert!(rows.starts_with("asset-1,\"Pump, north\",SN-1,M-1,"), "{}", rows);
+        assert_eq!(rows.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_error_statuses() {
+        assert_eq!(
+            AppError::Unauthorized("x".to_string()).status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(AppError::Forbidden("x".to_string()).status_code(), StatusCode::FORBIDDEN);
+        assert_eq!(AppError::invalid_field("status", "x").status_code(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -7,14 +7,30 @@ pub mod repository; // Single repository instead of services
 pub mod config;
 pub mod context;
 pub mod s3;
+pub mod auth;
+pub mod request_id;
+pub mod export;
+pub mod webhooks;
+pub mod shutdown;
+pub mod rate_limit;
+pub mod telemetry;
+pub mod cors;
 
-use async_graphql::{ EmptySubscription, SchemaBuilder };
+use async_graphql::SchemaBuilder;
 // Re-exports
 pub use error::{ AppError, AppResult };
 pub use models::prelude::*;
 pub use repository::{ Repository, DynamoDbEntity };
 
-use crate::schema::resolvers::{ MutationRoot, QueryRoot };
+use crate::{
+    config::GraphQLConfig,
+    schema::resolvers::{
+        subscription::WorkOrderStatusBroker,
+        MutationRoot,
+        QueryRoot,
+        SubscriptionRoot,
+    },
+};
 
 // Type aliases
 pub type DbClient = aws_sdk_dynamodb::Client;
@@ -23,15 +39,33 @@ pub type S3Client = aws_sdk_s3::Client;
 pub type GraphQLSchema = async_graphql::Schema<
     schema::resolvers::query::QueryRoot,
     schema::resolvers::mutation::MutationRoot,
-    async_graphql::EmptySubscription
+    schema::resolvers::subscription::SubscriptionRoot
 >;
 
-pub fn create_schema() -> SchemaBuilder<QueryRoot, MutationRoot, EmptySubscription> {
-    use schema::resolvers::{ query::QueryRoot, mutation::MutationRoot };
+/// Builds the schema with the depth, complexity and introspection settings from `graphql_config`
+///
+/// The schema carries its own `WorkOrderStatusBroker`, so mutations and subscriptions
+/// executed against it share one event channel.
+pub fn create_schema(
+    graphql_config: &GraphQLConfig
+) -> SchemaBuilder<QueryRoot, MutationRoot, SubscriptionRoot> {
+    let mut builder = async_graphql::Schema
+        ::build(QueryRoot::default(), MutationRoot::default(), SubscriptionRoot::default())
+        .data(WorkOrderStatusBroker::default())
+        .extension(telemetry::GraphQLMetrics)
+        .extension(error::QueryLimitErrors);
 
-    async_graphql::Schema::build(
-        QueryRoot::default(),
-        MutationRoot::default(),
-        async_graphql::EmptySubscription
-    )
+    if let Some(depth_limit) = graphql_config.depth_limit {
+        builder = builder.limit_depth(depth_limit);
+    }
+
+    if let Some(complexity_limit) = graphql_config.complexity_limit {
+        builder = builder.limit_complexity(complexity_limit);
+    }
+
+    if !graphql_config.introspection {
+        builder = builder.disable_introspection();
+    }
+
+    builder
 }
@@ -1,25 +1,51 @@
-use std::env;
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::{ sync::Arc, time::Duration };
 
 use aws_config::Region;
-use axum::{ extract::Extension, http::{ HeaderValue, Method }, routing::get, Router };
+use axum::{
+    extract::{ ws::WebSocketUpgrade, Extension },
+    http::{ HeaderValue, Method, StatusCode },
+    middleware::{ from_fn, from_fn_with_state },
+    response::IntoResponse,
+    routing::get,
+    Json,
+    Router,
+};
 use dotenvy::dotenv;
 use ore_dock_cmms_lambda::{
+    auth::{ middleware::auth_middleware, AuthUser },
     config::Config,
     context::{ AppContext, ContextExtensions },
+    cors::OriginMatcher,
     create_schema,
-    db,
+    db::{ self, connect::apply_database_config },
+    export::export_assets_csv,
+    models::{ asset_type::AssetType, location::Location, manufacturer::Manufacturer },
+    rate_limit::{ rate_limit_middleware, RateLimiter, RATE_LIMIT_CLEANUP_INTERVAL },
+    repository::{ self, Repository },
+    request_id::{ request_id_middleware, RequestId, REQUEST_ID_HEADER },
     s3::connect::setup_aws_s3_client,
+    shutdown::{ serve_with_graceful_shutdown, shutdown_signal, SHUTDOWN_DRAIN_TIMEOUT },
+    schema::loaders,
+    telemetry,
     DbClient,
     GraphQLSchema,
     S3Client,
 };
 use tower::ServiceBuilder;
-use tower_http::{ compression::CompressionLayer, cors::{ Any, CorsLayer } };
-use async_graphql_axum::{ GraphQLBatchRequest, GraphQLRequest, GraphQLResponse };
+use tower_http::{ compression::CompressionLayer, cors::{ AllowOrigin, Any, CorsLayer } };
+use async_graphql::http::ALL_WEBSOCKET_PROTOCOLS;
+use async_graphql_axum::{
+    GraphQLBatchRequest,
+    GraphQLProtocol,
+    GraphQLResponse,
+    GraphQLWebSocket,
+};
 use serde::Serialize;
-use tracing::{ info, error };
-
-mod auth;
+use serde_json::json;
+use tracing::{ info, error, warn };
 
 // Success/Failure response structs (if still needed)
 #[derive(Debug, Serialize)]
@@ -43,9 +69,49 @@ impl std::error::Error for FailureResponse {}
 // Handler for GraphQL requests
 async fn graphql_handler(
     Extension(schema): Extension<GraphQLSchema>,
+    auth_user: Option<Extension<AuthUser>>,
+    request_id: Option<Extension<RequestId>>,
     req: GraphQLBatchRequest
 ) -> GraphQLResponse {
-    schema.execute_batch(req.into_inner()).await.into()
+    let mut req = req.into_inner();
+
+    // Tag the request span opened by `request_id_middleware` with the operation(s) being run
+    let operations: Vec<&str> = req
+        .iter()
+        .map(|r| r.operation_name.as_deref().unwrap_or("anonymous"))
+        .collect();
+    tracing::Span::current().record("operation", operations.join(",").as_str());
+
+    // Anonymous requests still reach resolvers; they check `ctx.auth_user()` themselves
+    if let Some(Extension(auth_user)) = auth_user {
+        req = req.data(auth_user);
+    }
+
+    if let Some(Extension(request_id)) = request_id {
+        req = req.data(request_id);
+    }
+
+    schema.execute_batch(req).await.into()
+}
+
+// Handler for GraphQL subscriptions over the graphql-ws / graphql-transport-ws protocols
+async fn graphql_ws_handler(
+    Extension(schema): Extension<GraphQLSchema>,
+    auth_user: Option<Extension<AuthUser>>,
+    protocol: GraphQLProtocol,
+    websocket: WebSocketUpgrade
+) -> axum::response::Response {
+    // Subscription resolvers check permissions against the user who opened the socket
+    let mut data = async_graphql::Data::default();
+    if let Some(Extension(auth_user)) = auth_user {
+        data.insert(auth_user);
+    }
+
+    websocket
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, schema, protocol).with_data(data).serve()
+        })
 }
 
 // Handler for GraphQL playground
@@ -73,8 +139,24 @@ async fn main() {
         Config::default()
     });
 
+    if let Err(e) = db_config.validate() {
+        error!("Invalid configuration, refusing to start: {}", e);
+        std::process::exit(1);
+    }
+
     info!("Configuration loaded: {:?}", db_config);
 
+    repository::set_max_scan_items(db_config.database.max_scan_items);
+
+    let metrics_handle = match telemetry::install_recorder() {
+        Ok(handle) => handle,
+        Err(e) => {
+            error!("Fatal error installing metrics recorder: {}", e);
+            std::process::exit(1);
+        }
+    };
+    telemetry::spawn_upkeep(metrics_handle.clone(), telemetry::METRICS_UPKEEP_INTERVAL);
+
     // Create database client
     let db_client = match setup_database_client(&db_config).await {
         Ok(client) => client,
@@ -93,26 +175,53 @@ async fn main() {
     };
 
     // Ensure all tables exist
-    if let Err(e) = db::init::ensure_tables_exist(&db_client).await {
+    if let Err(e) = db::init::ensure_tables_exist(
+        &db_client,
+        db_config.database.table_prefix.as_deref()
+    ).await {
         error!("Fatal error ensuring tables exist: {}", e);
         std::process::exit(1);
     }
 
     info!("Database tables verified/created successfully");
 
+    let migration_repository = Repository::from_config(db_client.clone(), &db_config.database);
+    if let Err(e) = db::migrations::run_migrations(&migration_repository).await {
+        error!("Fatal error running data migrations: {}", e);
+        std::process::exit(1);
+    }
+
     // Create application context
     let app_context = AppContext::new(db_client.clone(), db_config.clone(), s3_client.clone());
 
+    // DataLoaders batch nested lookups (e.g. Asset -> AssetType) within a request
+    let loader_repository = || {
+        Repository::from_config(app_context.db_client.as_ref().clone(), &db_config.database)
+    };
+    let asset_type_loader = loaders::entity_loader::<AssetType>(loader_repository());
+    let location_loader = loaders::entity_loader::<Location>(loader_repository());
+    let manufacturer_loader = loaders::entity_loader::<Manufacturer>(loader_repository());
+
     // Create GraphQL schema with all necessary data
-    let schema = create_schema()
+    let schema = create_schema(&db_config.graphql)
         .data(db_client.clone()) // For backward compatibility with existing resolvers
         .data(db_config.clone())
         .data(s3_client.clone())
+        .data(asset_type_loader)
+        .data(location_loader)
+        .data(manufacturer_loader)
         .data(app_context)
         .finish();
 
     info!("GraphQL schema created successfully");
 
+    // Parsed even when the playground allows every origin, so a bad pattern fails in
+    // development rather than on the first production deploy
+    let origin_matcher = OriginMatcher::parse(&db_config.allow_origins).unwrap_or_else(|e| {
+        error!("Could not configure cors layer: {:?}", e);
+        std::process::exit(1);
+    });
+
     // Configure CORS based on environment
     let cors = if db_config.graphql.playground {
         // Development mode - allow all origins
@@ -120,50 +229,79 @@ async fn main() {
             .allow_origin(Any)
             .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
             .allow_headers(Any)
+            .expose_headers([REQUEST_ID_HEADER])
     } else {
-        // Production mode - restrict origins (you'd configure this based on your needs)
-        let allowed_origins: Vec<String> = db_config
-            .clone()
-            .allow_origins.split(",")
-            .map(|s| s.to_string())
-            .collect();
-
-        let mut cors_layer = CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST])
-            .allow_headers(Any);
-
-        for origin in &allowed_origins {
-            let header_value = origin
-                .parse::<HeaderValue>()
-                .map_err(|e| {
-                    error!("Could not configure cors layer: {:?}", e);
-                    std::process::exit(1);
+        // Production mode - restrict origins to the configured list, wildcards included
+        CorsLayer::new()
+            .allow_origin(
+                AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+                    origin
+                        .to_str()
+                        .map(|origin| origin_matcher.matches(origin))
+                        .unwrap_or(false)
                 })
-                .unwrap();
-            cors_layer = cors_layer.allow_origin(header_value);
-        }
-
-        cors_layer
+            )
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers(Any)
+            .expose_headers([REQUEST_ID_HEADER])
     };
 
     // Build router
     let mut router = Router::new();
 
-    // Add GraphQL endpoint
-    router = router
-        .route("/graphql", get(graphql_playground))
-        .route("/graphql", axum::routing::post(graphql_handler));
+    let rate_limiter = Arc::new(RateLimiter::new(&db_config.rate_limit));
+    rate_limiter.spawn_cleanup(RATE_LIMIT_CLEANUP_INTERVAL);
+
+    // Add GraphQL endpoint; auth runs first so the rate limit is keyed by user when known
+    let graphql_route = axum::routing
+        ::post(graphql_handler)
+        .layer(from_fn_with_state(rate_limiter.clone(), rate_limit_middleware))
+        .layer(from_fn_with_state(db_config.auth.clone(), auth_middleware));
+
+    // Without the playground, GET /graphql is a plain 404 rather than 405 Method Not Allowed
+    let graphql_route = if db_config.graphql.playground {
+        graphql_route.get(graphql_playground)
+    } else {
+        graphql_route.get(|| async { StatusCode::NOT_FOUND })
+    };
+
+    router = router.route("/graphql", graphql_route);
+
+    // Subscriptions run over the graphql-ws / graphql-transport-ws WebSocket protocols
+    router = router.route(
+        "/graphql/ws",
+        get(graphql_ws_handler)
+            .layer(from_fn_with_state(rate_limiter, rate_limit_middleware))
+            .layer(from_fn_with_state(db_config.auth.clone(), auth_middleware))
+    );
+
+    // CSV exports authenticate with the same bearer token as GraphQL
+    router = router.route(
+        "/export/assets.csv",
+        get(export_assets_csv).layer(from_fn_with_state(db_config.auth.clone(), auth_middleware))
+    );
 
-    // Add health check endpoint
-    router = router.route("/health", get(health_check));
+    // Add health check endpoints
+    router = router
+        .route("/health", get(health_check))
+        .route("/health/ready", get(health_check))
+        .route("/health/live", get(liveness_check))
+        .route(
+            "/metrics",
+            get(telemetry::metrics_handler).layer(
+                from_fn_with_state(db_config.auth.clone(), auth_middleware)
+            )
+        );
 
     // Add middleware layers
     let app = router.layer(
         ServiceBuilder::new()
+            .layer(from_fn(request_id_middleware))
             .layer(CompressionLayer::new().gzip(true).deflate(true).br(true))
             .layer(Extension(db_client))
             .layer(Extension(schema))
             .layer(Extension(db_config.clone()))
+            .layer(Extension(metrics_handle))
             .layer(cors)
     );
 
@@ -186,9 +324,18 @@ async fn main() {
     };
 
     info!("Server running on http://localhost:{}", port);
-    info!("GraphQL Playground available at http://localhost:{}/graphql", port);
+    if db_config.graphql.playground {
+        info!("GraphQL Playground available at http://localhost:{}/graphql", port);
+    }
+
+    let server = serve_with_graceful_shutdown(
+        listener,
+        app,
+        shutdown_signal(),
+        SHUTDOWN_DRAIN_TIMEOUT
+    );
 
-    if let Err(e) = axum::serve(listener, app).await {
+    if let Err(e) = server.await {
         error!("Fatal error running server: {}", e);
         std::process::exit(1);
     }
@@ -232,10 +379,10 @@ async fn setup_local_client_with_config(
         .or_else(|| std::env::var("DB_URL").ok())
         .ok_or("No local DynamoDB endpoint configured")?;
 
-    let dynamo_config = aws_sdk_dynamodb::config::Builder
-        ::from(&aws_config)
-        .endpoint_url(endpoint)
-        .build();
+    let dynamo_config = apply_database_config(
+        aws_sdk_dynamodb::config::Builder::from(&aws_config).endpoint_url(endpoint),
+        &config.database
+    ).build();
 
     Ok(aws_sdk_dynamodb::Client::from_conf(dynamo_config))
 }
@@ -269,10 +416,42 @@ async fn setup_aws_client_with_config(
     }
 
     let aws_config = aws_config_builder.load().await;
-    Ok(aws_sdk_dynamodb::Client::new(&aws_config))
+    let dynamo_config = apply_database_config(
+        aws_sdk_dynamodb::config::Builder::from(&aws_config),
+        &config.database
+    ).build();
+
+    Ok(aws_sdk_dynamodb::Client::from_conf(dynamo_config))
+}
+
+// How long readiness waits on DynamoDB before reporting the backend as down
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Readiness check: verifies DynamoDB answers a lightweight `ListTables` call
+async fn health_check(Extension(db_client): Extension<DbClient>) -> impl IntoResponse {
+    let probe = db_client.list_tables().limit(1).send();
+
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, probe).await {
+        Ok(Ok(_)) => (StatusCode::OK, Json(json!({ "status": "ok" }))),
+        Ok(Err(e)) => {
+            warn!("Health check failed to reach DynamoDB: {}", e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "status": "unhealthy", "dynamodb": false })),
+            )
+        }
+        Err(_) => {
+            warn!("Health check timed out after {:?} waiting on DynamoDB", HEALTH_CHECK_TIMEOUT);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "status": "unhealthy", "dynamodb": false })),
+            )
+        }
+    }
 }
 
-// Health check endpoint
-async fn health_check() -> &'static str {
-    "OK"
+// Liveness check: only confirms the process is serving requests, so transient
+// DynamoDB hiccups don't get the container restarted
+async fn liveness_check() -> impl IntoResponse {
+    (StatusCode::OK, Json(json!({ "status": "ok" })))
 }
@@ -7,7 +7,12 @@ use rust_decimal::Decimal;
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ error::AppError, repository::DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    error::AppError,
+    models::validation::{ canonical_decimal, parse_decimal, parse_enum },
+    repository::DynamoDbEntity,
+};
 
 #[derive(Clone, Debug, InputObject)]
 pub struct DocumentUpload {
@@ -54,19 +59,17 @@ impl MaintenanceFrequencyOptions {
         }
     }
     pub(crate) fn from_string(s: &str) -> Result<MaintenanceFrequencyOptions, AppError> {
-        match s {
-            "one_time" => Ok(Self::OneTime),
-            "annually" => Ok(Self::Annually),
-            "quarterly" => Ok(Self::Quarterly),
-            "monthly" => Ok(Self::Monthly),
-            "as_needed" => Ok(Self::AsNeeded),
-            _ =>
-                Err(
-                    AppError::DatabaseError(
-                        "Cannot perform from_string on MaintenanceFrequencyOption input".to_string()
-                    )
-                ),
-        }
+        parse_enum(s, [
+            ("one_time", Self::OneTime),
+            ("annually", Self::Annually),
+            ("quarterly", Self::Quarterly),
+            ("monthly", Self::Monthly),
+            ("as_needed", Self::AsNeeded),
+        ]).ok_or_else(||
+            AppError::DatabaseError(
+                "Cannot perform from_string on MaintenanceFrequencyOption input".to_string()
+            )
+        )
     }
     pub(crate) fn to_days(f: &MaintenanceFrequencyOptions) -> Result<i32, AppError> {
         match f {
@@ -109,23 +112,31 @@ impl AssetCurrentStatusOptions {
         }
     }
     pub(crate) fn from_string(s: &str) -> Result<AssetCurrentStatusOptions, AppError> {
-        match s {
-            "operational" => Ok(Self::Operational),
-            "down" => Ok(Self::Down),
-            "maintenance" => Ok(Self::Maintenance),
-            "retired" => Ok(Self::Retired),
-            "needs_attention" => Ok(Self::NeedsAttention),
-            _ => {
-                return Err(
-                    AppError::DatabaseError(
-                        "Invalid maintenance frequency option string for asset".to_string()
-                    )
-                );
-            }
-        }
+        parse_enum(s, [
+            ("operational", Self::Operational),
+            ("down", Self::Down),
+            ("maintenance", Self::Maintenance),
+            ("retired", Self::Retired),
+            ("needs_attention", Self::NeedsAttention),
+        ]).ok_or_else(||
+            AppError::DatabaseError(
+                "Invalid maintenance frequency option string for asset".to_string()
+            )
+        )
+    }
+
+    /// Checks if an asset in this status is out of service and accruing downtime
+    pub(crate) fn accrues_downtime(&self) -> bool {
+        matches!(self, AssetCurrentStatusOptions::Down | AssetCurrentStatusOptions::Maintenance)
     }
 }
 
+/// Attribute the linked maintenance schedule ID is persisted under
+const MAINTENANCE_SCHEDULE_ID_ATTR: &str = "maintenance_schedule_id";
+
+/// Attribute older items stored the maintenance schedule ID under
+const LEGACY_MAINTENANCE_SCHEDULE_ID_ATTR: &str = "maintenance_interval_id";
+
 /// Represents an Asset in the system
 ///
 /// # Fields
@@ -248,7 +259,7 @@ impl Asset {
     /// # Returns
     ///
     /// DateTime<Utc> representing when the next maintenance is due
-    pub(crate) fn next_maintenance_due(&self) -> DateTime<Utc> {
+    pub fn next_maintenance_due(&self) -> DateTime<Utc> {
         // If we have a specific maintenance schedule, we should use that
         // For now, calculate based on maintenance frequency and last maintenance
 
@@ -264,12 +275,74 @@ impl Asset {
         base_date + chrono::Duration::days(self.interval_days as i64)
     }
 
+    /// Moves the asset to `new_status`, tracking downtime
+    ///
+    /// Entering `Down` or `Maintenance` from an in-service status stamps
+    /// `last_downtime_date`; leaving them for an in-service status (or `Retired`)
+    /// adds the hours since that stamp to `total_downtime_hours`. Moving between
+    /// `Down` and `Maintenance` keeps the same downtime window open.
+    ///
+    /// # Errors
+    ///
+    /// `AppError::ValidationError` if the asset is retired; use `unretire` first
+    pub fn change_status(&mut self, new_status: AssetCurrentStatusOptions) -> Result<(), AppError> {
+        self.change_status_at(new_status, Utc::now())
+    }
+
+    fn change_status_at(
+        &mut self,
+        new_status: AssetCurrentStatusOptions,
+        now: DateTime<Utc>
+    ) -> Result<(), AppError> {
+        if self.current_status == AssetCurrentStatusOptions::Retired {
+            if new_status == AssetCurrentStatusOptions::Retired {
+                return Ok(());
+            }
+            return Err(
+                AppError::ValidationError(
+                    "Retired assets must be un-retired before their status can change".to_string()
+                )
+            );
+        }
+
+        if self.current_status == new_status {
+            return Ok(());
+        }
+
+        match (self.current_status.accrues_downtime(), new_status.accrues_downtime()) {
+            (false, true) => {
+                self.last_downtime_date = now;
+            }
+            (true, false) => {
+                self.total_downtime_hours += downtime_hours(self.last_downtime_date, now);
+            }
+            _ => {}
+        }
+
+        self.current_status = new_status;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Returns a retired asset to service as `Operational`
+    pub fn unretire(&mut self) -> Result<(), AppError> {
+        if self.current_status != AssetCurrentStatusOptions::Retired {
+            return Err(
+                AppError::ValidationError("Only retired assets can be un-retired".to_string())
+            );
+        }
+
+        self.current_status = AssetCurrentStatusOptions::Operational;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Checks if maintenance is overdue
     ///
     /// # Returns
     ///
     /// true if maintenance is overdue, false otherwise
-    pub(crate) fn is_maintenance_overdue(&self) -> bool {
+    pub fn is_maintenance_overdue(&self) -> bool {
         self.next_maintenance_due() < Utc::now()
     }
 
@@ -278,11 +351,65 @@ impl Asset {
     /// # Returns
     ///
     /// Number of days until maintenance (negative if overdue)
-    pub(crate) fn days_until_maintenance(&self) -> i64 {
+    pub fn days_until_maintenance(&self) -> i64 {
         let next_due = self.next_maintenance_due();
         let now = Utc::now();
         (next_due - now).num_days()
     }
+
+    /// Whether the warranty covers `now`
+    ///
+    /// Assets without a warranty end date are never under warranty; a missing
+    /// start date is treated as covered from installation onwards.
+    pub fn is_under_warranty(&self, now: DateTime<Utc>) -> bool {
+        let Some(end) = self.warranty_end_date else {
+            return false;
+        };
+        let start = self.warranty_start_date.unwrap_or(self.installation_date);
+
+        start <= now && now < end
+    }
+
+    /// Whole days from `now` until the warranty ends, negative once it has expired
+    ///
+    /// `None` when the asset has no warranty end date.
+    pub fn warranty_days_remaining(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.warranty_end_date.map(|end| (end - now).num_days())
+    }
+}
+
+/// Assets under warranty at `now`, soonest expiry first
+///
+/// With `expiring_within_days` only warranties ending within that many days are kept.
+pub fn assets_under_warranty(
+    assets: Vec<Asset>,
+    expiring_within_days: Option<i64>,
+    now: DateTime<Utc>
+) -> Vec<Asset> {
+    let horizon = expiring_within_days.map(|days| now + chrono::Duration::days(days));
+    let mut covered: Vec<Asset> = assets
+        .into_iter()
+        .filter(|asset| asset.is_under_warranty(now))
+        .filter(|asset| {
+            match (horizon, asset.warranty_end_date) {
+                (Some(horizon), Some(end)) => end <= horizon,
+                _ => true,
+            }
+        })
+        .collect();
+
+    covered.sort_by(|a, b| {
+        a.warranty_end_date.cmp(&b.warranty_end_date).then_with(|| a.id.cmp(&b.id))
+    });
+
+    covered
+}
+
+/// Hours from `start` to `end` rounded to two decimal places, never negative
+fn downtime_hours(start: DateTime<Utc>, end: DateTime<Utc>) -> Decimal {
+    let seconds = (end - start).num_seconds().max(0);
+
+    (Decimal::from(seconds) / Decimal::from(3600)).round_dp(2)
 }
 
 impl DynamoDbEntity for Asset {
@@ -317,13 +444,13 @@ impl DynamoDbEntity for Asset {
         let purchase_date = item
             .get("purchase_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let installation_date = item
             .get("installation_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let current_status_str = item.get("current_status")?.as_s().ok()?;
@@ -339,13 +466,11 @@ impl DynamoDbEntity for Asset {
             .map_err(|e| e)
             .ok()?;
 
-        let maintenance_schedule_id = item.get("maintenance_schedule_id").and_then(|v| {
-            match v {
-                AttributeValue::S(s) => Some(s.clone()),
-                AttributeValue::Null(_) => None,
-                _ => None,
-            }
-        });
+        let maintenance_schedule_id = item
+            .get(MAINTENANCE_SCHEDULE_ID_ATTR)
+            .or_else(|| item.get(LEGACY_MAINTENANCE_SCHEDULE_ID_ATTR))
+            .and_then(|v| v.as_s().ok())
+            .map(|s| s.to_string());
 
         let interval_days = item
             .get("interval_days")
@@ -368,35 +493,35 @@ impl DynamoDbEntity for Asset {
         let warranty_start_date = item
             .get("warranty_start_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let warranty_end_date = item
             .get("warranty_end_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
-        let total_downtime_hours = item
-            .get("total_downtime_hours")
-            .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<Decimal>().ok())
-            .unwrap_or_else(|| Decimal::new(0, 0));
+        // A present-but-unparseable value rejects the item rather than reading as zero
+        let total_downtime_hours = match item.get("total_downtime_hours") {
+            Some(v) => parse_decimal(v.as_s().ok()?).ok()?,
+            None => Decimal::ZERO,
+        };
 
         let last_downtime_date = item
             .get("last_downtime_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let created_at: DateTime<Utc> = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at: DateTime<Utc> = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -444,10 +569,13 @@ impl DynamoDbEntity for Asset {
         item.insert("asset_type_id".to_string(), AttributeValue::S(self.asset_type_id.clone()));
         item.insert("serial_number".to_string(), AttributeValue::S(self.serial_number.clone()));
         item.insert("model_number".to_string(), AttributeValue::S(self.model_number.clone()));
-        item.insert("purchase_date".to_string(), AttributeValue::S(self.purchase_date.to_string()));
+        item.insert(
+            "purchase_date".to_string(),
+            AttributeValue::S(to_db_timestamp(&self.purchase_date))
+        );
         item.insert(
             "installation_date".to_string(),
-            AttributeValue::S(self.installation_date.to_string())
+            AttributeValue::S(to_db_timestamp(&self.installation_date))
         );
         item.insert(
             "current_status".to_string(),
@@ -463,7 +591,7 @@ impl DynamoDbEntity for Asset {
             Some(id) => AttributeValue::S(id.clone()),
             None => AttributeValue::Null(true),
         };
-        item.insert("maintenance_interval_id".to_string(), maintenance_schedule_id_attr_value);
+        item.insert(MAINTENANCE_SCHEDULE_ID_ATTR.to_string(), maintenance_schedule_id_attr_value);
         item.insert("interval_days".to_string(), AttributeValue::N(self.interval_days.to_string()));
 
         if !self.documentation_keys.is_empty() {
@@ -483,27 +611,27 @@ impl DynamoDbEntity for Asset {
         if let Some(warranty_start) = &self.warranty_start_date {
             item.insert(
                 "warranty_start_date".to_string(),
-                AttributeValue::S(warranty_start.to_string())
+                AttributeValue::S(to_db_timestamp(warranty_start))
             );
         }
 
         if let Some(warranty_end) = &self.warranty_end_date {
             item.insert(
                 "warranty_end_date".to_string(),
-                AttributeValue::S(warranty_end.to_string())
+                AttributeValue::S(to_db_timestamp(warranty_end))
             );
         }
 
         item.insert(
             "total_downtime_hours".to_string(),
-            AttributeValue::S(self.total_downtime_hours.to_string())
+            AttributeValue::S(canonical_decimal(&self.total_downtime_hours))
         );
         item.insert(
             "last_downtime_date".to_string(),
-            AttributeValue::S(self.last_downtime_date.to_string())
+            AttributeValue::S(to_db_timestamp(&self.last_downtime_date))
         );
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -907,4 +1035,126 @@ mod tests {
             assert_eq!(status.to_str(), parsed.to_str());
         }
     }
+
+    #[test]
+    fn test_maintenance_schedule_id_survives_item_round_trip() {
+        let mut asset = create_valid_asset().unwrap();
+        asset.maintenance_schedule_id = Some("schedule-1".to_string());
+
+        let item = asset.to_item();
+        assert!(!item.contains_key(LEGACY_MAINTENANCE_SCHEDULE_ID_ATTR));
+
+        let restored = Asset::from_item(&item).unwrap();
+        assert_eq!(restored.maintenance_schedule_id.as_deref(), Some("schedule-1"));
+    }
+
+    #[test]
+    fn test_maintenance_schedule_id_read_from_legacy_attribute() {
+        let mut item = create_valid_asset().unwrap().to_item();
+        item.remove(MAINTENANCE_SCHEDULE_ID_ATTR);
+        item.insert(
+            LEGACY_MAINTENANCE_SCHEDULE_ID_ATTR.to_string(),
+            AttributeValue::S("schedule-legacy".to_string())
+        );
+
+        let restored = Asset::from_item(&item).unwrap();
+        assert_eq!(restored.maintenance_schedule_id.as_deref(), Some("schedule-legacy"));
+    }
+
+    #[test]
+    fn test_down_up_cycles_accumulate_downtime_hours() {
+        let mut asset = create_valid_asset().unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap();
+
+        asset.change_status_at(AssetCurrentStatusOptions::Down, start).unwrap();
+        assert_eq!(asset.last_downtime_date, start);
+
+        // Switching to maintenance keeps the same outage open
+        let at = start + chrono::Duration::hours(1);
+        asset.change_status_at(AssetCurrentStatusOptions::Maintenance, at).unwrap();
+        let at = start + chrono::Duration::hours(2);
+        asset.change_status_at(AssetCurrentStatusOptions::Operational, at).unwrap();
+        assert_eq!(asset.total_downtime_hours, Decimal::new(2, 0));
+
+        let second_outage = start + chrono::Duration::days(1);
+        asset.change_status_at(AssetCurrentStatusOptions::Down, second_outage).unwrap();
+        asset
+            .change_status_at(
+                AssetCurrentStatusOptions::Operational,
+                second_outage + chrono::Duration::minutes(90)
+            )
+            .unwrap();
+
+        assert_eq!(asset.total_downtime_hours, Decimal::new(35, 1));
+        assert_eq!(asset.current_status, AssetCurrentStatusOptions::Operational);
+    }
+
+    #[test]
+    fn test_retired_asset_requires_unretire() {
+        let mut asset = create_valid_asset().unwrap();
+        asset.change_status(AssetCurrentStatusOptions::Retired).unwrap();
+
+        let result = asset.change_status(AssetCurrentStatusOptions::Operational);
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+
+        asset.unretire().unwrap();
+        assert_eq!(asset.current_status, AssetCurrentStatusOptions::Operational);
+        assert!(asset.unretire().is_err());
+    }
+
+    fn warranted_asset(id: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Asset {
+        let mut asset = create_valid_asset().unwrap();
+        asset.id = id.to_string();
+        asset.warranty_start_date = Some(start);
+        asset.warranty_end_date = Some(end);
+        asset
+    }
+
+    #[test]
+    fn test_warranty_status_for_active_and_expired_warranties() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+        let active = warranted_asset(
+            "asset-active",
+            now - chrono::Duration::days(100),
+            now + chrono::Duration::days(265)
+        );
+        let expired = warranted_asset(
+            "asset-expired",
+            now - chrono::Duration::days(400),
+            now - chrono::Duration::days(35)
+        );
+        let mut unwarranted = create_valid_asset().unwrap();
+        unwarranted.warranty_end_date = None;
+
+        assert!(active.is_under_warranty(now));
+        assert_eq!(active.warranty_days_remaining(now), Some(265));
+        assert!(!expired.is_under_warranty(now));
+        assert_eq!(expired.warranty_days_remaining(now), Some(-35));
+        assert!(!unwarranted.is_under_warranty(now));
+        assert_eq!(unwarranted.warranty_days_remaining(now), None);
+    }
+
+    #[test]
+    fn test_assets_under_warranty_filters_by_expiry_window() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+        let year_ago = now - chrono::Duration::days(365);
+        let assets = vec![
+            warranted_asset("asset-active", year_ago, now + chrono::Duration::days(200)),
+            warranted_asset("asset-expired", year_ago, now - chrono::Duration::days(1)),
+            warranted_asset("asset-expiring", year_ago, now + chrono::Duration::days(20))
+        ];
+
+        let ids = |assets: Vec<Asset>| {
+            assets
+                .into_iter()
+                .map(|a| a.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(assets_under_warranty(assets.clone(), None, now)), vec![
+            "asset-expiring",
+            "asset-active",
+        ]);
+        assert_eq!(ids(assets_under_warranty(assets, Some(30), now)), vec!["asset-expiring"]);
+    }
 }
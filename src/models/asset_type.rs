@@ -5,7 +5,12 @@ use aws_sdk_dynamodb::types::AttributeValue;
 use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 
-use crate::{ repository::DynamoDbEntity, AppError };
+use crate::{
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    models::validation::parse_enum,
+    repository::DynamoDbEntity,
+    AppError,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -45,27 +50,24 @@ impl AssetTypeCategory {
         }
     }
     pub(crate) fn from_string(s: &str) -> Result<AssetTypeCategory, AppError> {
-        match s {
-            "food-production" => Ok(Self::FoodProduction),
-            "beverage-production" => Ok(Self::BeverageProduction),
-            "bar" => Ok(Self::Bar),
-            "refrigeration" => Ok(Self::Refrigeration),
-            "storage" => Ok(Self::Storage),
-            "office" => Ok(Self::Office),
-            "garage" => Ok(Self::Garage),
-            "entertainment" => Ok(Self::Entertainment),
-            "infrastructure" => Ok(Self::Infrastructure),
-            "utilities" => Ok(Self::Utilities),
-            "lab" => Ok(Self::Lab),
-            "miscellaneous" => Ok(Self::Miscellaneous),
-            _ => {
-                return Err(
-                    AppError::DatabaseError(
-                        "Invalid category string string for asset type".to_string()
-                    )
-                );
-            }
-        }
+        parse_enum(s, [
+            ("food-production", Self::FoodProduction),
+            ("beverage-production", Self::BeverageProduction),
+            ("bar", Self::Bar),
+            ("refrigeration", Self::Refrigeration),
+            ("storage", Self::Storage),
+            ("office", Self::Office),
+            ("garage", Self::Garage),
+            ("entertainment", Self::Entertainment),
+            ("infrastructure", Self::Infrastructure),
+            ("utilities", Self::Utilities),
+            ("lab", Self::Lab),
+            ("miscellaneous", Self::Miscellaneous),
+        ]).ok_or_else(||
+            AppError::DatabaseError(
+                "Invalid category string string for asset type".to_string()
+            )
+        )
     }
 }
 
@@ -131,13 +133,13 @@ impl DynamoDbEntity for AssetType {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -159,8 +161,8 @@ impl DynamoDbEntity for AssetType {
         item.insert("name".to_string(), AttributeValue::S(self.name.clone()));
         item.insert("description".to_string(), AttributeValue::S(self.description.clone()));
         item.insert("category".to_string(), AttributeValue::S(self.category.to_string()));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Utc };
+use serde::{ Deserialize, Serialize };
+use serde_json::Value as Json;
+
+use crate::{
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    models::id::IdGenerator,
+    DynamoDbEntity,
+};
+
+/// A user account was suspended
+pub const USER_SUSPENDED: &str = "user.suspended";
+
+/// A user account was terminated
+pub const USER_TERMINATED: &str = "user.terminated";
+
+/// A user account was soft-deleted, which also terminates it
+pub const USER_DELETED: &str = "user.deleted";
+
+/// A user account's status was set directly
+pub const USER_STATUS_CHANGED: &str = "user.status_changed";
+
+/// A role assignment was revoked
+pub const USER_ROLE_REVOKED: &str = "user_role.revoked";
+
+/// A role assignment was suspended
+pub const USER_ROLE_SUSPENDED: &str = "user_role.suspended";
+
+/// A vendor was suspended
+pub const VENDOR_SUSPENDED: &str = "vendor.suspended";
+
+/// A vendor was blacklisted
+pub const VENDOR_BLACKLISTED: &str = "vendor.blacklisted";
+
+/// A work order's status was set through a bulk update
+pub const WORK_ORDER_STATUS_CHANGED: &str = "work_order.status_changed";
+
+/// Record of a sensitive mutation, written after the change it describes succeeds
+///
+/// Audit events are append-only; nothing updates or deletes them. They are
+/// looked up by the entity they concern through the table's `TargetIndex`.
+///
+/// # Fields
+///
+/// * `id` - Unique identifier, `audit_{ULID}` so ids sort by creation time
+/// * `actor_user_id` - User who performed the action
+/// * `action` - What happened, e.g. `user.terminated`
+/// * `target_type` - Kind of entity acted on, e.g. `user`
+/// * `target_id` - ID of the entity acted on
+/// * `metadata` - Action-specific details such as the stated reason
+/// * `created_at` - Date and time the action was recorded
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: String,
+    pub actor_user_id: String,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub metadata: Json,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Defines methods for AuditEvent
+impl AuditEvent {
+    /// Creates a new AuditEvent stamped with the current time
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_user_id` - User who performed the action
+    /// * `action` - One of the action constants in this module
+    /// * `target_type` - Kind of entity acted on
+    /// * `target_id` - ID of the entity acted on
+    /// * `metadata` - Action-specific details, `Json::Null` if there are none
+    pub fn new(
+        actor_user_id: String,
+        action: &str,
+        target_type: &str,
+        target_id: String,
+        metadata: Json
+    ) -> Self {
+        Self {
+            id: IdGenerator::new_id("audit"),
+            actor_user_id,
+            action: action.to_string(),
+            target_type: target_type.to_string(),
+            target_id,
+            metadata,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl DynamoDbEntity for AuditEvent {
+    fn table_name() -> &'static str {
+        "AuditEvents"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Creates AuditEvent instance from DynamoDB item
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The dynamo db item
+    ///
+    /// # Returns
+    ///
+    /// 'Some' AuditEvent if item fields match, 'None' otherwise
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let id = item.get("id")?.as_s().ok()?.to_string();
+        let actor_user_id = item.get("actor_user_id")?.as_s().ok()?.to_string();
+        let action = item.get("action")?.as_s().ok()?.to_string();
+        let target_type = item.get("target_type")?.as_s().ok()?.to_string();
+        let target_id = item.get("target_id")?.as_s().ok()?.to_string();
+
+        let metadata = item
+            .get("metadata")
+            .and_then(|v| v.as_s().ok())
+            .and_then(|s| serde_json::from_str::<Json>(s).ok())
+            .unwrap_or(Json::Null);
+
+        let created_at = item
+            .get("created_at")
+            .and_then(|v| v.as_s().ok())
+            .and_then(|s| from_db_timestamp(s))?;
+
+        Some(Self {
+            id,
+            actor_user_id,
+            action,
+            target_type,
+            target_id,
+            metadata,
+            created_at,
+        })
+    }
+
+    /// Creates DynamoDB item from AuditEvent instance
+    ///
+    /// `metadata` is stored as a JSON string.
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        item.insert("actor_user_id".to_string(), AttributeValue::S(self.actor_user_id.clone()));
+        item.insert("action".to_string(), AttributeValue::S(self.action.clone()));
+        item.insert("target_type".to_string(), AttributeValue::S(self.target_type.clone()));
+        item.insert("target_id".to_string(), AttributeValue::S(self.target_id.clone()));
+        item.insert("metadata".to_string(), AttributeValue::S(self.metadata.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_round_trip_keeps_metadata() {
+        let event = AuditEvent::new(
+            "user_admin".to_string(),
+            USER_TERMINATED,
+            "user",
+            "user_target".to_string(),
+            serde_json::json!({ "reason": "left the company" })
+        );
+
+        let item = event.to_item();
+        assert!(event.id.starts_with("audit_"));
+        assert_eq!(item.get("target_id").unwrap().as_s().unwrap(), "user_target");
+
+        let restored = AuditEvent::from_item(&item).unwrap();
+        assert_eq!(restored.actor_user_id, "user_admin");
+        assert_eq!(restored.action, USER_TERMINATED);
+        assert_eq!(restored.metadata["reason"], "left the company");
+    }
+}
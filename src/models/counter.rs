@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+
+use crate::DynamoDbEntity;
+
+/// Counter handing out work order numbers
+pub const WORK_ORDER_NUMBER_COUNTER: &str = "work_order_number";
+
+/// A named sequence, advanced with `Repository::increment` on its `value` attribute
+///
+/// # Fields
+///
+/// * `name` - Counter name, the primary key
+/// * `value` - Last value handed out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counter {
+    pub name: String,
+    pub value: u64,
+}
+
+impl DynamoDbEntity for Counter {
+    fn table_name() -> &'static str {
+        "Counters"
+    }
+
+    fn primary_key(&self) -> String {
+        self.name.clone()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let name = item.get("id")?.as_s().ok()?.to_string();
+        let value = item.get("value")?.as_n().ok()?.parse::<u64>().ok()?;
+
+        Some(Self { name, value })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S(self.name.clone())),
+            ("value".to_string(), AttributeValue::N(self.value.to_string())),
+        ])
+    }
+}
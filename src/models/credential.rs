@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use argon2::{
+    password_hash::{ rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString },
+    Argon2,
+};
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Utc };
+use serde::{ Deserialize, Serialize };
+
+use crate::{
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    error::AppError,
+    models::validation::parse_enum,
+    DynamoDbEntity,
+};
+
+/// Shortest password accepted by `UserCredential::new`
+pub const MIN_PASSWORD_LENGTH: usize = 12;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordAlgorithm {
+    Argon2id, // Argon2id with the crate's default parameters
+}
+
+impl PasswordAlgorithm {
+    pub(crate) fn to_str(self) -> &'static str {
+        match self {
+            PasswordAlgorithm::Argon2id => "argon2id",
+        }
+    }
+
+    pub(crate) fn from_string(s: &str) -> Result<PasswordAlgorithm, AppError> {
+        parse_enum(s, [
+            ("argon2id", Self::Argon2id),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid password algorithm".to_string()))
+    }
+}
+
+impl std::fmt::Display for PasswordAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+/// Represents a user's stored password credential
+///
+/// Kept out of `User` so the hash is never loaded or exposed alongside
+/// profile data. There is deliberately no GraphQL object for this type.
+///
+/// # Fields
+///
+/// * `user_id` - ID of the user the credential belongs to
+/// * `password_hash` - PHC-format password hash
+/// * `algorithm` - Algorithm used to produce `password_hash`
+/// * `updated_at` - Date and time the password was last set
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserCredential {
+    pub user_id: String,
+    pub password_hash: String,
+    pub algorithm: PasswordAlgorithm,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Defines methods for UserCredential
+impl UserCredential {
+    /// Creates a new UserCredential by hashing a plaintext password
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - ID of the user
+    /// * `password` - Plaintext password, at least `MIN_PASSWORD_LENGTH` characters
+    ///
+    /// # Returns
+    ///
+    /// New UserCredential instance, or `AppError::InvalidField` for a weak password
+    pub fn new(user_id: String, password: &str) -> Result<Self, AppError> {
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(
+                AppError::invalid_field(
+                    "new_password",
+                    format!("Password must be at least {} characters", MIN_PASSWORD_LENGTH)
+                )
+            );
+        }
+
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to hash password: {}", e)))?
+            .to_string();
+
+        Ok(Self {
+            user_id,
+            password_hash,
+            algorithm: PasswordAlgorithm::Argon2id,
+            updated_at: Utc::now(),
+        })
+    }
+
+    /// Checks a plaintext password against the stored hash
+    ///
+    /// A malformed stored hash is treated as a mismatch.
+    pub fn verify(&self, password: &str) -> bool {
+        PasswordHash::new(&self.password_hash)
+            .map(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+            .unwrap_or(false)
+    }
+}
+
+impl DynamoDbEntity for UserCredential {
+    fn table_name() -> &'static str {
+        "UserCredentials"
+    }
+
+    fn primary_key(&self) -> String {
+        self.user_id.clone()
+    }
+
+    /// Creates UserCredential instance from DynamoDB item
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The dynamo db item
+    ///
+    /// # Returns
+    ///
+    /// 'Some' UserCredential if item fields match, 'None' otherwise
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let user_id = item.get("id")?.as_s().ok()?.to_string();
+        let password_hash = item.get("password_hash")?.as_s().ok()?.to_string();
+
+        let algorithm_str = item.get("algorithm")?.as_s().ok()?;
+        let algorithm = PasswordAlgorithm::from_string(algorithm_str).ok()?;
+
+        let updated_at = item
+            .get("updated_at")
+            .and_then(|v| v.as_s().ok())
+            .and_then(|s| from_db_timestamp(s))?;
+
+        Some(Self {
+            user_id,
+            password_hash,
+            algorithm,
+            updated_at,
+        })
+    }
+
+    /// Creates DynamoDB item from UserCredential instance
+    ///
+    /// The item is keyed by the user's id so `Repository::get` takes a user id.
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+
+        item.insert("id".to_string(), AttributeValue::S(self.user_id.clone()));
+        item.insert("password_hash".to_string(), AttributeValue::S(self.password_hash.clone()));
+        item.insert("algorithm".to_string(), AttributeValue::S(self.algorithm.to_string()));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
+
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "correct horse battery";
+
+    #[test]
+    fn test_hashed_password_verifies() {
+        let credential = UserCredential::new("user-1".to_string(), PASSWORD).unwrap();
+
+        assert!(credential.password_hash.starts_with("$argon2id$"));
+        assert!(credential.verify(PASSWORD));
+        assert!(!credential.verify("wrong horse battery"));
+    }
+
+    #[test]
+    fn test_short_password_is_rejected() {
+        let result = UserCredential::new("user-1".to_string(), "short");
+
+        assert!(matches!(result, Err(AppError::InvalidField { field, .. }) if field == "new_password"));
+    }
+
+    #[test]
+    fn test_item_round_trip_keeps_hash() {
+        let credential = UserCredential::new("user-1".to_string(), PASSWORD).unwrap();
+
+        let restored = UserCredential::from_item(&credential.to_item()).unwrap();
+
+        assert_eq!(restored.user_id, "user-1");
+        assert_eq!(restored.algorithm, PasswordAlgorithm::Argon2id);
+        assert!(restored.verify(PASSWORD));
+    }
+}
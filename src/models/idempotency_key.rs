@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Duration, TimeZone, Utc };
+use serde::{ Deserialize, Serialize };
+
+use crate::{ db::common::{ from_db_timestamp, to_db_timestamp }, DynamoDbEntity };
+
+/// How long a client-supplied idempotency key is honoured
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+/// Maps a client-supplied idempotency key to the entity its first request created
+///
+/// Keys are scoped by operation (`create_user:<key>`) so the same client key can be
+/// reused across different mutations. `expires_at` is the table's DynamoDB
+/// `TimeToLive` attribute; expired rows may linger until DynamoDB sweeps them, so
+/// readers must check `is_expired` too.
+///
+/// # Fields
+///
+/// * `key` - Scoped idempotency key, the primary key
+/// * `entity_id` - ID of the entity created by the first request
+/// * `created_at` - Date and time the key was claimed
+/// * `expires_at` - Date and time after which the key may be reused
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IdempotencyKey {
+    pub key: String,
+    pub entity_id: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Defines methods for IdempotencyKey
+impl IdempotencyKey {
+    /// Creates a new IdempotencyKey expiring after `IDEMPOTENCY_KEY_TTL_HOURS`
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Operation the key belongs to, e.g. `create_user`
+    /// * `key` - Client-supplied idempotency key
+    /// * `entity_id` - ID the operation will create
+    pub fn new(scope: &str, key: &str, entity_id: String) -> Self {
+        let now = Utc::now();
+
+        Self {
+            key: Self::scoped(scope, key),
+            entity_id,
+            created_at: now,
+            expires_at: now + Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS),
+        }
+    }
+
+    /// Primary key for a client key within an operation
+    pub fn scoped(scope: &str, key: &str) -> String {
+        format!("{}:{}", scope, key)
+    }
+
+    /// Checks if the key has outlived its TTL
+    pub fn is_expired(&self) -> bool {
+        Utc::now() >= self.expires_at
+    }
+}
+
+impl DynamoDbEntity for IdempotencyKey {
+    fn table_name() -> &'static str {
+        "IdempotencyKeys"
+    }
+
+    fn primary_key(&self) -> String {
+        self.key.clone()
+    }
+
+    /// Creates IdempotencyKey instance from DynamoDB item
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The dynamo db item
+    ///
+    /// # Returns
+    ///
+    /// 'Some' IdempotencyKey if item fields match, 'None' otherwise
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let key = item.get("id")?.as_s().ok()?.to_string();
+        let entity_id = item.get("entity_id")?.as_s().ok()?.to_string();
+
+        let created_at = item
+            .get("created_at")
+            .and_then(|v| v.as_s().ok())
+            .and_then(|s| from_db_timestamp(s))?;
+
+        let expires_at = item
+            .get("expires_at")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|n| n.parse::<i64>().ok())
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())?;
+
+        Some(Self {
+            key,
+            entity_id,
+            created_at,
+            expires_at,
+        })
+    }
+
+    /// Creates DynamoDB item from IdempotencyKey instance
+    ///
+    /// `expires_at` is stored as epoch seconds, the format `TimeToLive` requires.
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+
+        item.insert("id".to_string(), AttributeValue::S(self.key.clone()));
+        item.insert("entity_id".to_string(), AttributeValue::S(self.entity_id.clone()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert(
+            "expires_at".to_string(),
+            AttributeValue::N(self.expires_at.timestamp().to_string())
+        );
+
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_round_trip_stores_ttl_as_epoch_seconds() {
+        let record = IdempotencyKey::new("create_user", "abc", "user-1".to_string());
+
+        let item = record.to_item();
+        assert_eq!(item.get("id").unwrap().as_s().unwrap(), "create_user:abc");
+        assert_eq!(
+            item.get("expires_at").unwrap().as_n().unwrap(),
+            &record.expires_at.timestamp().to_string()
+        );
+
+        let restored = IdempotencyKey::from_item(&item).unwrap();
+        assert_eq!(restored.entity_id, "user-1");
+        assert_eq!(restored.expires_at.timestamp(), record.expires_at.timestamp());
+        assert!(!restored.is_expired());
+    }
+}
@@ -1,10 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
 
 use aws_sdk_dynamodb::types::AttributeValue;
 use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 
-use crate::{ Address, DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    Address,
+    AppError,
+    DynamoDbEntity,
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Location {
@@ -66,6 +71,76 @@ impl Location {
     }
 }
 
+/// Ancestor chain from the root down to and including `location_id`
+///
+/// A parent id missing from `locations` ends the chain, so a dangling link is
+/// treated as the root.
+///
+/// # Errors
+///
+/// `AppError::NotFound` if `location_id` is unknown, `AppError::ValidationError` if
+/// the stored parent links already loop
+pub(crate) fn location_path(
+    location_id: &str,
+    locations: &HashMap<String, Location>
+) -> Result<Vec<Location>, AppError> {
+    let mut current = locations
+        .get(location_id)
+        .ok_or_else(|| AppError::NotFound(format!("Location {} not found", location_id)))?;
+
+    let mut visited = HashSet::new();
+    let mut path = Vec::new();
+
+    loop {
+        if !visited.insert(current.id.as_str()) {
+            return Err(
+                AppError::ValidationError(
+                    format!("Location hierarchy contains a cycle at {}", current.id)
+                )
+            );
+        }
+        path.push(current.clone());
+
+        match current.parent_location_id.as_deref().and_then(|id| locations.get(id)) {
+            Some(parent) => {
+                current = parent;
+            }
+            None => {
+                break;
+            }
+        }
+    }
+
+    path.reverse();
+    Ok(path)
+}
+
+/// Rejects making `parent_id` the parent of `location_id` if `location_id` would
+/// become its own ancestor
+pub(crate) fn ensure_acyclic_parent(
+    location_id: &str,
+    parent_id: &str,
+    locations: &HashMap<String, Location>
+) -> Result<(), AppError> {
+    let creates_cycle =
+        parent_id == location_id ||
+        location_path(parent_id, locations)?.iter().any(|ancestor| ancestor.id == location_id);
+
+    if creates_cycle {
+        return Err(
+            AppError::ValidationError(
+                format!(
+                    "Location {} cannot be placed under its own descendant {}",
+                    location_id,
+                    parent_id
+                )
+            )
+        );
+    }
+
+    Ok(())
+}
+
 impl DynamoDbEntity for Location {
     fn table_name() -> &'static str {
         "Locations"
@@ -93,21 +168,18 @@ impl DynamoDbEntity for Location {
             .and_then(|v| v.as_s().ok())
             .map(|s| s.to_string());
 
-        let is_active = item
-            .get("is_active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let is_active = get_bool(item, "is_active", true);
 
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         Some(Self {
@@ -118,7 +190,7 @@ impl DynamoDbEntity for Location {
             parent_location_id,
             address,
             coordinates,
-            is_active: *is_active,
+            is_active,
             created_at,
             updated_at,
         })
@@ -135,8 +207,8 @@ impl DynamoDbEntity for Location {
             AttributeValue::S(self.location_type_id.clone())
         );
         item.insert("is_active".to_string(), AttributeValue::Bool(self.is_active));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         if let Some(ref parent_id) = self.parent_location_id {
             item.insert("parent_location_id".to_string(), AttributeValue::S(parent_id.clone()));
@@ -151,3 +223,62 @@ impl DynamoDbEntity for Location {
         item
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(id: &str, parent_location_id: Option<&str>) -> Location {
+        Location::new(
+            id.to_string(),
+            id.to_string(),
+            format!("{} description", id),
+            "type-1".to_string(),
+            parent_location_id.map(|p| p.to_string()),
+            Address::new(
+                "1 Main St".to_string(),
+                None,
+                "Denver".to_string(),
+                "CO".to_string(),
+                "US".to_string(),
+                "80202".to_string()
+            ),
+            None
+        )
+    }
+
+    fn building_floor_room() -> HashMap<String, Location> {
+        [
+            location("building", None),
+            location("floor", Some("building")),
+            location("room", Some("floor")),
+        ]
+            .into_iter()
+            .map(|l| (l.id.clone(), l))
+            .collect()
+    }
+
+    #[test]
+    fn test_location_path_runs_from_root_to_node() {
+        let locations = building_floor_room();
+
+        let ids: Vec<String> = location_path("room", &locations)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.id)
+            .collect();
+
+        assert_eq!(ids, vec!["building", "floor", "room"]);
+    }
+
+    #[test]
+    fn test_parent_that_is_a_descendant_is_rejected() {
+        let locations = building_floor_room();
+
+        let result = ensure_acyclic_parent("building", "room", &locations);
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+        assert!(ensure_acyclic_parent("floor", "floor", &locations).is_err());
+
+        assert!(ensure_acyclic_parent("room", "building", &locations).is_ok());
+    }
+}
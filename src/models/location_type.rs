@@ -4,7 +4,7 @@ use aws_sdk_dynamodb::types::AttributeValue;
 use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 
-use crate::DynamoDbEntity;
+use crate::{ db::common::{ from_db_timestamp, to_db_timestamp }, DynamoDbEntity };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LocationType {
@@ -55,13 +55,13 @@ impl DynamoDbEntity for LocationType {
         let created_at: DateTime<Utc> = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at: DateTime<Utc> = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -81,8 +81,8 @@ impl DynamoDbEntity for LocationType {
         item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
         item.insert("name".to_string(), AttributeValue::S(self.name.clone()));
         item.insert("description".to_string(), AttributeValue::S(self.description.clone()));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -44,7 +44,12 @@ use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ models::work_order::WorkOrderSeverity, AppError, DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    models::{ validation::parse_enum, work_order::WorkOrderSeverity },
+    AppError,
+    DynamoDbEntity,
+};
 
 // declare MaintenanceRequestStatus Enum
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -76,15 +81,18 @@ impl MaintenanceRequestStatus {
     }
 
     pub fn from_string(s: &str) -> Result<MaintenanceRequestStatus, AppError> {
-        match s {
-            "submitted" => Ok(Self::Submitted),
-            "read" => Ok(Self::Read),
-            "accepted" => Ok(Self::Accepted),
-            "resolved" => Ok(Self::Resolved),
-            "denied" => Ok(Self::Denied),
-            "archived" => Ok(Self::Archived),
-            _ => Err(AppError::ValidationError("Invalid maintenance request status".to_string())),
-        }
+        parse_enum(s, [
+            ("submitted", Self::Submitted),
+            ("read", Self::Read),
+            ("accepted", Self::Accepted),
+            ("resolved", Self::Resolved),
+            ("denied", Self::Denied),
+            ("archived", Self::Archived),
+        ]).ok_or_else(||
+            AppError::ValidationError(
+                "Invalid maintenance request status".to_string()
+            )
+        )
     }
 }
 // declare MaintenanceRequest struct
@@ -280,13 +288,13 @@ impl DynamoDbEntity for MaintenanceRequest {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         Some(Self {
@@ -338,8 +346,8 @@ impl DynamoDbEntity for MaintenanceRequest {
 
         item.insert("severity".to_string(), AttributeValue::S(self.severity.to_string()));
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -5,7 +5,16 @@ use aws_sdk_dynamodb::types::AttributeValue;
 use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 
-use crate::{ error::AppError, repository::DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::{
+        asset::{ Asset, MaintenanceFrequencyOptions },
+        validation::parse_enum,
+        work_order::WorkOrder,
+    },
+    repository::DynamoDbEntity,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -141,19 +150,34 @@ impl CadenceUnit {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<Self, AppError> {
-        match s {
-            "hours" => Ok(Self::Hours),
-            "days" => Ok(Self::Days),
-            "weeks" => Ok(Self::Weeks),
-            "months" => Ok(Self::Months),
-            "years" => Ok(Self::Years),
-            "run_hours" => Ok(Self::RunHours),
-            "cycles" => Ok(Self::Cycles),
-            _ => Err(AppError::ValidationError("Invalid cadence unit".to_string())),
-        }
+        parse_enum(s, [
+            ("hours", Self::Hours),
+            ("days", Self::Days),
+            ("weeks", Self::Weeks),
+            ("months", Self::Months),
+            ("years", Self::Years),
+            ("run_hours", Self::RunHours),
+            ("cycles", Self::Cycles),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid cadence unit".to_string()))
     }
 }
 
+/// Days until the next occurrence after completing a runtime-based schedule
+///
+/// Run hours and cycles have no calendar interval, so completions push the due date
+/// out by this much instead of leaving it in the past.
+const FALLBACK_INTERVAL_DAYS: i64 = 30;
+
+/// Constant partition value shared by every schedule projected into `DueDateIndex`
+pub(crate) const DUE_DATE_PARTITION: &str = "due";
+
+/// Formats a due date as the `DueDateIndex` sort key
+///
+/// Uses the canonical stored form, so lexical ordering matches chronological ordering.
+pub(crate) fn due_date_sort_key(due_at: &DateTime<Utc>) -> String {
+    to_db_timestamp(due_at)
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MaintenanceSchedule {
     pub id: String,
@@ -161,6 +185,9 @@ pub struct MaintenanceSchedule {
     pub cadences: Vec<MaintenanceCadence>,
     pub last_completed_at: Option<DateTime<Utc>>,
     pub last_completed_by_user_id: Option<String>,
+    pub last_completion_notes: Option<String>,
+    pub missed_cycles: i32, // cycles skipped before the last completion
+    pub last_generated_due_at: Option<DateTime<Utc>>,
     pub next_due_at: DateTime<Utc>,
     pub duration_estimate: Option<i32>,
     pub recurring: bool,
@@ -210,6 +237,9 @@ impl MaintenanceSchedule {
             cadences,
             last_completed_at,
             last_completed_by_user_id,
+            last_completion_notes: None,
+            missed_cycles: 0,
+            last_generated_due_at: None,
             next_due_at,
             duration_estimate,
             recurring,
@@ -218,6 +248,206 @@ impl MaintenanceSchedule {
             updated_at: now,
         })
     }
+
+    /// Builds the recurring schedule implied by an asset's maintenance frequency
+    ///
+    /// The first occurrence falls `interval_days` after installation.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for the new schedule
+    /// * `asset` - Asset the schedule is generated for
+    ///
+    /// # Returns
+    ///
+    /// New MaintenanceSchedule, or `None` for one-time and as-needed assets which have no fixed interval
+    pub fn for_asset(id: String, asset: &Asset) -> Option<Self> {
+        if
+            matches!(
+                asset.maintenance_frequency,
+                MaintenanceFrequencyOptions::OneTime | MaintenanceFrequencyOptions::AsNeeded
+            ) ||
+            asset.interval_days <= 0
+        {
+            return None;
+        }
+
+        let cadence = MaintenanceCadence {
+            interval: asset.interval_days,
+            unit: CadenceUnit::Days,
+        };
+        let next_due_at =
+            asset.installation_date + chrono::Duration::days(asset.interval_days as i64);
+
+        Self::new(
+            id,
+            asset.id.clone(),
+            vec![cadence],
+            None,
+            None,
+            next_due_at,
+            None,
+            true,
+            true
+        ).ok()
+    }
+
+    /// Days between occurrences, taken from the primary cadence
+    ///
+    /// # Returns
+    ///
+    /// Interval in days, or 0 for runtime-based or sub-day cadences with no fixed calendar interval
+    pub(crate) fn interval_days(&self) -> i32 {
+        self.cadences
+            .first()
+            .and_then(|cadence| cadence.to_days().ok())
+            .unwrap_or(0)
+            .max(0)
+    }
+
+    /// Records a completed maintenance and rolls `next_due_at` forward
+    ///
+    /// Normally the next occurrence is `completed_at + interval_days`. With
+    /// `anchor_to_cycle` (quarterly and annual work) due dates stay on the original
+    /// cycle boundaries, so a late completion lands on the first boundary after
+    /// `completed_at` rather than drifting. Either way, cycles whose due date passed
+    /// entirely before this completion are counted in `missed_cycles`. Runtime-based
+    /// cadences are next due `FALLBACK_INTERVAL_DAYS` after `completed_at`.
+    /// Non-recurring schedules are deactivated instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `completed_at` - When the maintenance was done
+    /// * `completed_by_user_id` - Optional user who did it
+    /// * `notes` - Optional completion notes
+    /// * `anchor_to_cycle` - Keep due dates on cycle boundaries
+    ///
+    /// # Returns
+    ///
+    /// The number of missed cycles, also stored in `missed_cycles`
+    pub fn record_completion(
+        &mut self,
+        completed_at: DateTime<Utc>,
+        completed_by_user_id: Option<String>,
+        notes: Option<String>,
+        anchor_to_cycle: bool
+    ) -> i32 {
+        let previous_due = self.next_due_at;
+        let interval_days = self.interval_days() as i64;
+
+        self.last_completed_at = Some(completed_at);
+        self.last_completed_by_user_id = completed_by_user_id;
+        self.last_completion_notes = notes;
+        self.missed_cycles = 0;
+        self.updated_at = Utc::now();
+
+        if !self.recurring {
+            self.active = false;
+            return 0;
+        }
+
+        if interval_days == 0 {
+            if self.cadences.first().is_some_and(|cadence| cadence.to_days().is_err()) {
+                self.next_due_at = completed_at + chrono::Duration::days(FALLBACK_INTERVAL_DAYS);
+            }
+            return 0;
+        }
+
+        let interval = chrono::Duration::days(interval_days);
+        let days_late = (completed_at - previous_due).num_days();
+        let missed_cycles = if days_late > 0 { days_late / interval_days } else { 0 };
+
+        self.next_due_at = if anchor_to_cycle {
+            previous_due + interval * ((missed_cycles + 1) as i32)
+        } else {
+            completed_at + interval
+        };
+        self.missed_cycles = missed_cycles as i32;
+
+        self.missed_cycles
+    }
+
+    /// Builds the preventive work order for the occurrence due at `next_due_at`
+    ///
+    /// Returns `None` when the schedule is not due by `now` or a work order was already
+    /// generated for this occurrence. Otherwise the occurrence is recorded in
+    /// `last_generated_due_at` and `next_due_at` moves to the first boundary after `now`;
+    /// non-recurring schedules keep their due date until completed. The work order id
+    /// is derived from the schedule and due date, so a repeated run cannot create it twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `asset` - Asset this schedule maintains
+    /// * `work_order_number` - Number for the new work order
+    /// * `now` - Time the generation runs at
+    pub fn generate_work_order(
+        &mut self,
+        asset: &Asset,
+        work_order_number: String,
+        now: DateTime<Utc>
+    ) -> Result<Option<WorkOrder>, AppError> {
+        let due_at = self.next_due_at;
+        let already_generated = self.last_generated_due_at.is_some_and(|last| last >= due_at);
+
+        if !self.tracks_due_date() || due_at > now || already_generated {
+            return Ok(None);
+        }
+
+        let work_order = WorkOrder::from_maintenance(asset, self, work_order_number, now)?;
+
+        self.last_generated_due_at = Some(due_at);
+        if self.recurring {
+            let interval = chrono::Duration::days(self.interval_days() as i64);
+            while self.next_due_at <= now {
+                self.next_due_at += interval;
+            }
+        }
+        self.updated_at = Utc::now();
+
+        Ok(Some(work_order))
+    }
+
+    /// Whether the schedule belongs in `DueDateIndex`
+    ///
+    /// Inactive schedules and those without a fixed interval are left out so they
+    /// never show up as perpetually overdue.
+    pub(crate) fn tracks_due_date(&self) -> bool {
+        self.active && self.interval_days() > 0
+    }
+
+    /// Attributes to write with `Repository::update_fields` after changing `active`
+    ///
+    /// Includes the `DueDateIndex` attributes, set or removed to match, so activating
+    /// indexes the schedule again and deactivating drops it from the index.
+    pub(crate) fn activation_updates(&self) -> HashMap<String, AttributeValue> {
+        let (due_partition, next_due_date) = if self.tracks_due_date() {
+            (
+                AttributeValue::S(DUE_DATE_PARTITION.to_string()),
+                AttributeValue::S(due_date_sort_key(&self.next_due_at)),
+            )
+        } else {
+            (AttributeValue::Null(true), AttributeValue::Null(true))
+        };
+
+        HashMap::from([
+            ("active".to_string(), AttributeValue::Bool(self.active)),
+            ("due_partition".to_string(), due_partition),
+            ("next_due_date".to_string(), next_due_date),
+        ])
+    }
+
+    /// Derives `next_due_at` from the last completion (or creation) plus the interval
+    ///
+    /// Leaves `next_due_at` untouched when the schedule has no fixed interval.
+    pub fn recalculate_next_due(&mut self) {
+        let interval_days = self.interval_days();
+        if interval_days == 0 {
+            return;
+        }
+
+        let base_date = self.last_completed_at.unwrap_or(self.created_at);
+        self.next_due_at = base_date + chrono::Duration::days(interval_days as i64);
+    }
 }
 
 impl DynamoDbEntity for MaintenanceSchedule {
@@ -262,17 +492,33 @@ impl DynamoDbEntity for MaintenanceSchedule {
         let last_completed_at = item
             .get("last_completed_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let last_completed_by_user_id = item
             .get("last_completed_by_user_id")
             .and_then(|v| v.as_s().ok())
             .map(|s| s.to_string());
 
+        let last_completion_notes = item
+            .get("last_completion_notes")
+            .and_then(|v| v.as_s().ok())
+            .map(|s| s.to_string());
+
+        let missed_cycles = item
+            .get("missed_cycles")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|n| n.parse::<i32>().ok())
+            .unwrap_or(0);
+
+        let last_generated_due_at = item
+            .get("last_generated_due_at")
+            .and_then(|v| v.as_s().ok())
+            .and_then(|s| from_db_timestamp(s));
+
         let next_due_at = item
             .get("next_due_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let duration_estimate = item
@@ -280,26 +526,20 @@ impl DynamoDbEntity for MaintenanceSchedule {
             .and_then(|v| v.as_n().ok())
             .and_then(|s| s.parse::<i32>().ok());
 
-        let recurring = item
-            .get("recurring")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let recurring = get_bool(item, "recurring", false);
 
-        let active = item
-            .get("active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let active = get_bool(item, "active", true);
 
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -308,10 +548,13 @@ impl DynamoDbEntity for MaintenanceSchedule {
             cadences,
             last_completed_at,
             last_completed_by_user_id,
+            last_completion_notes,
+            missed_cycles,
+            last_generated_due_at,
             next_due_at,
             duration_estimate,
-            recurring: *recurring,
-            active: *active,
+            recurring,
+            active,
             created_at,
             updated_at,
         });
@@ -344,7 +587,7 @@ impl DynamoDbEntity for MaintenanceSchedule {
         if let Some(last_completed) = &self.last_completed_at {
             item.insert(
                 "last_completed_at".to_string(),
-                AttributeValue::S(last_completed.to_string())
+                AttributeValue::S(to_db_timestamp(last_completed))
             );
         }
 
@@ -355,7 +598,32 @@ impl DynamoDbEntity for MaintenanceSchedule {
             );
         }
 
-        item.insert("next_due_at".to_string(), AttributeValue::S(self.next_due_at.to_string()));
+        if let Some(notes) = &self.last_completion_notes {
+            item.insert("last_completion_notes".to_string(), AttributeValue::S(notes.clone()));
+        }
+
+        item.insert("missed_cycles".to_string(), AttributeValue::N(self.missed_cycles.to_string()));
+
+        if let Some(generated) = &self.last_generated_due_at {
+            item.insert(
+                "last_generated_due_at".to_string(),
+                AttributeValue::S(to_db_timestamp(generated))
+            );
+        }
+
+        item.insert(
+            "next_due_at".to_string(),
+            AttributeValue::S(to_db_timestamp(&self.next_due_at))
+        );
+
+        // Sparse GSI attributes: only schedules with a real interval are indexed by due date
+        if self.tracks_due_date() {
+            item.insert("due_partition".to_string(), AttributeValue::S(DUE_DATE_PARTITION.to_string()));
+            item.insert(
+                "next_due_date".to_string(),
+                AttributeValue::S(due_date_sort_key(&self.next_due_at))
+            );
+        }
 
         if let Some(duration) = &self.duration_estimate {
             item.insert("duration_estimate".to_string(), AttributeValue::N(duration.to_string()));
@@ -363,9 +631,232 @@ impl DynamoDbEntity for MaintenanceSchedule {
 
         item.insert("recurring".to_string(), AttributeValue::Bool(self.recurring));
         item.insert("active".to_string(), AttributeValue::Bool(self.active));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::work_order::{ WorkOrderStatus, WorkOrderType };
+    use chrono::TimeZone;
+
+    fn schedule(interval: i32, unit: CadenceUnit) -> MaintenanceSchedule {
+        MaintenanceSchedule::new(
+            "schedule-1".to_string(),
+            "asset-1".to_string(),
+            vec![MaintenanceCadence { interval, unit }],
+            None,
+            None,
+            Utc::now(),
+            None,
+            true,
+            true
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_recalculate_next_due_from_last_completion() {
+        let mut schedule = schedule(2, CadenceUnit::Weeks);
+        let completed = Utc.with_ymd_and_hms(2024, 3, 1, 8, 0, 0).unwrap();
+        schedule.last_completed_at = Some(completed);
+
+        schedule.recalculate_next_due();
+
+        assert_eq!(schedule.next_due_at, completed + chrono::Duration::days(14));
+        assert_eq!(
+            schedule.to_item().get("next_due_date").and_then(|v| v.as_s().ok()).cloned(),
+            Some("2024-03-15T08:00:00.000000000Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_on_time_completion_advances_one_interval() {
+        let mut schedule = schedule(90, CadenceUnit::Days);
+        let due = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        schedule.next_due_at = due;
+        let completed = due - chrono::Duration::days(2);
+
+        let missed = schedule.record_completion(completed, None, Some("Filters replaced".to_string()), true);
+
+        assert_eq!(missed, 0);
+        assert_eq!(schedule.next_due_at, due + chrono::Duration::days(90));
+        assert_eq!(schedule.last_completed_at, Some(completed));
+        assert_eq!(schedule.last_completion_notes.as_deref(), Some("Filters replaced"));
+    }
+
+    #[test]
+    fn test_late_quarterly_completion_lands_on_next_boundary() {
+        let mut schedule = schedule(90, CadenceUnit::Days);
+        let due = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        schedule.next_due_at = due;
+        let completed = due + chrono::Duration::days(100);
+
+        let missed = schedule.record_completion(completed, None, None, true);
+
+        // The cycle due at +90 days passed with no completion; the next boundary is +180
+        assert_eq!(missed, 1);
+        assert_eq!(schedule.missed_cycles, 1);
+        assert_eq!(schedule.next_due_at, due + chrono::Duration::days(180));
+        assert!(schedule.next_due_at > completed);
+    }
+
+    #[test]
+    fn test_late_unanchored_completion_restarts_from_completion() {
+        let mut schedule = schedule(30, CadenceUnit::Days);
+        let due = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        schedule.next_due_at = due;
+        let completed = due + chrono::Duration::days(100);
+
+        let missed = schedule.record_completion(completed, None, None, false);
+
+        assert_eq!(missed, 3);
+        assert_eq!(schedule.next_due_at, completed + chrono::Duration::days(30));
+    }
+
+    #[test]
+    fn test_runtime_completion_falls_back_to_thirty_days() {
+        let mut schedule = schedule(500, CadenceUnit::RunHours);
+        let completed = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+
+        let missed = schedule.record_completion(completed, None, None, false);
+
+        assert_eq!(missed, 0);
+        assert_eq!(schedule.next_due_at, completed + chrono::Duration::days(30));
+    }
+
+    #[test]
+    fn test_zero_interval_schedule_is_not_indexed() {
+        let mut schedule = schedule(10, CadenceUnit::RunHours);
+        let before = schedule.next_due_at;
+
+        schedule.recalculate_next_due();
+
+        assert_eq!(schedule.next_due_at, before);
+        let item = schedule.to_item();
+        assert!(!item.contains_key("next_due_date"));
+        assert!(!item.contains_key("due_partition"));
+    }
+
+    #[test]
+    fn test_activation_updates_follow_due_date_index() {
+        let mut schedule = schedule(30, CadenceUnit::Days);
+
+        schedule.active = false;
+        let updates = schedule.activation_updates();
+        assert_eq!(updates.get("active"), Some(&AttributeValue::Bool(false)));
+        assert_eq!(updates.get("due_partition"), Some(&AttributeValue::Null(true)));
+        assert_eq!(updates.get("next_due_date"), Some(&AttributeValue::Null(true)));
+
+        schedule.active = true;
+        let updates = schedule.activation_updates();
+        let item = schedule.to_item();
+        assert_eq!(updates.get("due_partition"), item.get("due_partition"));
+        assert_eq!(updates.get("next_due_date"), item.get("next_due_date"));
+    }
+
+    #[test]
+    fn test_due_schedule_generates_one_preventive_work_order_and_advances() {
+        let mut schedule = schedule(30, CadenceUnit::Days);
+        let asset = asset("monthly");
+        let due = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        let now = due + chrono::Duration::days(2);
+        schedule.next_due_at = due;
+
+        let work_order = schedule
+            .generate_work_order(&asset, "000007".to_string(), now)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(work_order.asset_id, "asset-1");
+        assert_eq!(work_order.work_order_number, "000007");
+        assert_eq!(work_order.scheduled_date, Some(due));
+        assert_eq!(work_order.status, WorkOrderStatus::Scheduled);
+        assert_eq!(work_order.work_order_type, WorkOrderType::Preventive);
+        assert_eq!(schedule.last_generated_due_at, Some(due));
+        assert_eq!(schedule.next_due_at, due + chrono::Duration::days(30));
+
+        // Running again before the next occurrence creates nothing
+        assert!(schedule.generate_work_order(&asset, "000008".to_string(), now).unwrap().is_none());
+
+        let restored = MaintenanceSchedule::from_item(&schedule.to_item()).unwrap();
+        assert_eq!(restored.last_generated_due_at, Some(due));
+    }
+
+    #[test]
+    fn test_generation_without_advancing_is_not_repeated() {
+        let mut schedule = schedule(30, CadenceUnit::Days);
+        schedule.recurring = false;
+        let asset = asset("monthly");
+        let due = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        schedule.next_due_at = due;
+
+        let first = schedule.generate_work_order(&asset, "000001".to_string(), due).unwrap();
+        let second = schedule.generate_work_order(&asset, "000002".to_string(), due).unwrap();
+
+        assert!(first.is_some());
+        assert!(second.is_none());
+        assert_eq!(schedule.next_due_at, due);
+    }
+
+    #[test]
+    fn test_schedule_not_yet_due_generates_nothing() {
+        let mut schedule = schedule(30, CadenceUnit::Days);
+        let asset = asset("monthly");
+        let now = Utc::now();
+        schedule.next_due_at = now + chrono::Duration::days(1);
+
+        assert!(schedule.generate_work_order(&asset, "000001".to_string(), now).unwrap().is_none());
+        assert!(schedule.last_generated_due_at.is_none());
+    }
+
+    fn asset(maintenance_frequency: &str) -> Asset {
+        let installed = Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap();
+        Asset::new(
+            "asset-1".to_string(),
+            "Pump".to_string(),
+            "type-1".to_string(),
+            "SN-1".to_string(),
+            "M-1".to_string(),
+            installed,
+            installed,
+            "loc-1".to_string(),
+            "mfg-1".to_string(),
+            maintenance_frequency.to_string(),
+            None,
+            None
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_schedule_for_asset_is_due_one_interval_after_installation() {
+        let asset = asset("quarterly");
+
+        let schedule = MaintenanceSchedule::for_asset("ms-1".to_string(), &asset).unwrap();
+
+        assert_eq!(schedule.asset_id, "asset-1");
+        assert_eq!(schedule.interval_days(), asset.interval_days);
+        assert_eq!(
+            schedule.next_due_at,
+            asset.installation_date + chrono::Duration::days(asset.interval_days as i64)
+        );
+        assert!(schedule.recurring && schedule.tracks_due_date());
+    }
+
+    #[test]
+    fn test_no_schedule_for_one_time_or_as_needed_assets() {
+        assert!(MaintenanceSchedule::for_asset("ms-1".to_string(), &asset("one_time")).is_none());
+        assert!(MaintenanceSchedule::for_asset("ms-1".to_string(), &asset("as_needed")).is_none());
+    }
+
+    #[test]
+    fn test_due_date_sort_key_orders_chronologically() {
+        let earlier = Utc.with_ymd_and_hms(2024, 9, 30, 23, 59, 59).unwrap();
+        let later = Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap();
+
+        assert!(due_date_sort_key(&earlier) < due_date_sort_key(&later));
+    }
+}
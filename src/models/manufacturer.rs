@@ -5,7 +5,12 @@ use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ error::AppError, models::address::Address, repository::DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::{ address::Address, validation::{ validate_email, validate_url } },
+    repository::DynamoDbEntity,
+};
 
 /// Represents a Manufacturer in the system
 ///
@@ -87,21 +92,18 @@ impl DynamoDbEntity for Manufacturer {
             .and_then(|v| v.as_s().ok())
             .map(|s| s.to_string());
 
-        let active = item
-            .get("active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let active = get_bool(item, "active", true);
 
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -114,7 +116,7 @@ impl DynamoDbEntity for Manufacturer {
             address,
             support_contact,
             warranty_contact,
-            active: *active,
+            active,
             created_at,
             updated_at,
         });
@@ -160,8 +162,8 @@ impl DynamoDbEntity for Manufacturer {
         }
 
         item.insert("active".to_string(), AttributeValue::Bool(self.active));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -207,6 +209,11 @@ impl Manufacturer {
         if email.trim().is_empty() {
             return Err(AppError::ValidationError("Email cannot be empty".to_string()));
         }
+        validate_email(&email)?;
+
+        if let Some(website) = &website {
+            validate_url(website)?;
+        }
 
         Ok(Self {
             id,
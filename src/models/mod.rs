@@ -1,5 +1,10 @@
 pub mod asset_type;
 pub mod asset;
+pub mod audit_event;
+pub mod counter;
+pub mod credential;
+pub mod id;
+pub mod idempotency_key;
 pub mod location_type;
 pub mod location;
 pub mod address;
@@ -18,6 +23,7 @@ pub mod user;
 pub mod user_notification_preferences;
 pub mod user_preferences;
 pub mod user_role;
+pub mod validation;
 pub mod vendor;
 pub mod vendor_category;
 pub mod work_order;
@@ -6,7 +6,12 @@ use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ error::AppError, models::notification::NotificationChannels, DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    error::AppError,
+    models::{ notification::NotificationChannels, validation::parse_enum },
+    DynamoDbEntity,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -34,14 +39,13 @@ impl DeliveryStatus {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<DeliveryStatus, AppError> {
-        match s {
-            "pending" => Ok(Self::Pending),
-            "delivered" => Ok(Self::Delivered),
-            "failed" => Ok(Self::Failed),
-            "retrying" => Ok(Self::Retrying),
-            "cancelled" => Ok(Self::Cancelled),
-            _ => Err(AppError::ValidationError("Invalid delivery status".to_string())),
-        }
+        parse_enum(s, [
+            ("pending", Self::Pending),
+            ("delivered", Self::Delivered),
+            ("failed", Self::Failed),
+            ("retrying", Self::Retrying),
+            ("cancelled", Self::Cancelled),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid delivery status".to_string()))
     }
 }
 
@@ -167,13 +171,13 @@ impl DynamoDbEntity for NotificationDeliveryLog {
         let attempted_at = item
             .get("attempted_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let delivered_at = item
             .get("delivered_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let error_message = item
             .get("error_message")
@@ -194,13 +198,13 @@ impl DynamoDbEntity for NotificationDeliveryLog {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -240,10 +244,13 @@ impl DynamoDbEntity for NotificationDeliveryLog {
             "delivery_status".to_string(),
             AttributeValue::S(self.delivery_status.to_string())
         );
-        item.insert("attempted_at".to_string(), AttributeValue::S(self.attempted_at.to_string()));
+        item.insert(
+            "attempted_at".to_string(),
+            AttributeValue::S(to_db_timestamp(&self.attempted_at))
+        );
 
         if let Some(delivered) = &self.delivered_at {
-            item.insert("delivered_at".to_string(), AttributeValue::S(delivered.to_string()));
+            item.insert("delivered_at".to_string(), AttributeValue::S(to_db_timestamp(delivered)));
         }
 
         if let Some(error) = &self.error_message {
@@ -256,8 +263,8 @@ impl DynamoDbEntity for NotificationDeliveryLog {
             item.insert("recipient_address".to_string(), AttributeValue::S(address.clone()));
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -1,101 +1,43 @@
 use std::collections::HashMap;
 
-use async_graphql::Enum;
 use aws_sdk_dynamodb::types::AttributeValue;
-use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
-use tracing::info;
-
-use crate::{
-    error::AppError,
-    models::notification::{ NotificationChannels, SeverityLevel },
-    DynamoDbEntity,
-};
-
-#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum NotificationType {
-    Alert,
-    Reminder,
-    Status,
-    Maintenance,
-    Emergency,
-    Update,
-}
+use serde_json::{ Map, Value as Json };
 
-impl NotificationType {
-    pub(crate) fn to_str(&self) -> &str {
-        match self {
-            NotificationType::Alert => "alert",
-            NotificationType::Reminder => "reminder",
-            NotificationType::Status => "status",
-            NotificationType::Maintenance => "maintenance",
-            NotificationType::Emergency => "emergency",
-            NotificationType::Update => "update",
-        }
-    }
+use crate::{ error::AppError, DynamoDbEntity };
 
-    pub(crate) fn to_string(&self) -> String {
-        self.to_str().to_string()
-    }
+/// Opens a `{{var}}` placeholder
+const PLACEHOLDER_OPEN: &str = "{{";
 
-    pub(crate) fn from_string(s: &str) -> Result<NotificationType, AppError> {
-        match s {
-            "alert" => Ok(Self::Alert),
-            "reminder" => Ok(Self::Reminder),
-            "status" => Ok(Self::Status),
-            "maintenance" => Ok(Self::Maintenance),
-            "emergency" => Ok(Self::Emergency),
-            "update" => Ok(Self::Update),
-            _ => Err(AppError::ValidationError("Invalid notification type".to_string())),
-        }
-    }
-}
+/// Closes a `{{var}}` placeholder
+const PLACEHOLDER_CLOSE: &str = "}}";
 
-/// Represents template variables that can be used in message templates
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct TemplateVariable {
-    pub name: String,
-    pub description: String,
-    pub variable_type: String, // "string", "datetime", "number", "boolean"
-    pub required: bool,
-    pub default_value: Option<String>,
+/// Subject and body produced by rendering a NotificationTemplate
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RenderedNotification {
+    pub subject: String,
+    pub body: String,
 }
 
 /// Represents a Notification Template in the system
 ///
+/// Templates reference variables as `{{name}}` placeholders, which `render`
+/// replaces with the matching entry of the supplied variables object.
+///
 /// # Fields
 ///
 /// * `id` - Unique identifier for the template
-/// * `name` - Name of the template
-/// * `notification_type` - Type of notification (alert, reminder, etc.)
-/// * `subject_template` - Template for notification subject/title
-/// * `message_template` - Template for notification message body
-/// * `default_severity` - Default severity level for notifications using this template
-/// * `supported_channels` - List of channels that support this template
-/// * `variables` - List of template variables that can be substituted
-/// * `template_engine` - Template engine to use (handlebars, liquid, etc.)
-/// * `active` - Whether this template is currently active
-/// * `version` - Version number for template versioning
-/// * `created_by` - User who created the template
-/// * `created_at` - Date and time of creation
-/// * `updated_at` - Date and time of last update
+/// * `key` - Stable lookup key used by the code that sends the notification, e.g. `work_order_assigned`
+/// * `subject_template` - Template for the notification title
+/// * `body_template` - Template for the notification body
+/// * `variables` - Names of the variables the templates expect
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct NotificationTemplate {
     pub id: String,
-    pub name: String,
-    pub notification_type: NotificationType,
+    pub key: String,
     pub subject_template: String,
-    pub message_template: String,
-    pub default_severity: SeverityLevel,
-    pub supported_channels: Vec<NotificationChannels>,
-    pub variables: Vec<TemplateVariable>,
-    pub template_engine: String, // "handlebars", "liquid", "mustache", "simple"
-    pub active: bool,
-    pub version: i32,
-    pub created_by: Option<String>,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+    pub body_template: String,
+    pub variables: Vec<String>,
 }
 
 /// Defines methods for NotificationTemplate
@@ -105,71 +47,87 @@ impl NotificationTemplate {
     /// # Arguments
     ///
     /// * `id` - Unique identifier
-    /// * `name` - Name of the template
-    /// * `notification_type` - Type as string
+    /// * `key` - Lookup key
     /// * `subject_template` - Subject template string
-    /// * `message_template` - Message template string
-    /// * `default_severity` - Default severity as string
-    /// * `supported_channels` - List of supported channels
-    /// * `variables` - List of template variables
-    /// * `template_engine` - Template engine to use
-    /// * `active` - Whether template is active
-    /// * `version` - Version number
-    /// * `created_by` - Optional creator user ID
+    /// * `body_template` - Body template string
+    /// * `variables` - Names of the variables the templates expect
     ///
     /// # Returns
     ///
     /// New NotificationTemplate instance
     pub fn new(
         id: String,
-        name: String,
-        notification_type: String,
+        key: String,
         subject_template: String,
-        message_template: String,
-        default_severity: String,
-        supported_channels: Vec<String>,
-        variables: Vec<TemplateVariable>,
-        template_engine: String,
-        active: bool,
-        version: i32,
-        created_by: Option<String>
+        body_template: String,
+        variables: Vec<String>
     ) -> Result<Self, AppError> {
-        let now = Utc::now();
-
-        if name.trim().is_empty() {
-            return Err(AppError::ValidationError("Template name cannot be empty".to_string()));
+        if key.trim().is_empty() {
+            return Err(AppError::ValidationError("Template key cannot be empty".to_string()));
         }
 
-        if message_template.trim().is_empty() {
-            return Err(AppError::ValidationError("Message template cannot be empty".to_string()));
+        if body_template.trim().is_empty() {
+            return Err(AppError::ValidationError("Body template cannot be empty".to_string()));
         }
 
-        let notif_type = NotificationType::from_string(&notification_type)?;
-        let severity = SeverityLevel::from_string(&default_severity)?;
-
-        let channels: Result<Vec<NotificationChannels>, AppError> = supported_channels
-            .iter()
-            .map(|c| NotificationChannels::from_string(c))
-            .collect();
-        let channels = channels?;
-
         Ok(Self {
             id,
-            name,
-            notification_type: notif_type,
+            key,
             subject_template,
-            message_template,
-            default_severity: severity,
-            supported_channels: channels,
+            body_template,
             variables,
-            template_engine,
-            active,
-            version,
-            created_by,
-            created_at: now,
-            updated_at: now,
         })
     }
+
+    /// Renders the subject and body with `vars` substituted for their placeholders
+    ///
+    /// # Arguments
+    ///
+    /// * `vars` - Variable values by name; strings are inserted as-is, other values as JSON
+    ///
+    /// # Returns
+    ///
+    /// The rendered notification, or `AppError::ValidationError` if a placeholder
+    /// names a variable missing from `vars` or is never closed
+    pub fn render(&self, vars: &Map<String, Json>) -> Result<RenderedNotification, AppError> {
+        Ok(RenderedNotification {
+            subject: render_template(&self.subject_template, vars)?,
+            body: render_template(&self.body_template, vars)?,
+        })
+    }
+}
+
+/// Replaces every `{{name}}` in `template` with the value of `name` in `vars`
+fn render_template(template: &str, vars: &Map<String, Json>) -> Result<String, AppError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        rendered.push_str(&rest[..start]);
+
+        let after_open = &rest[start + PLACEHOLDER_OPEN.len()..];
+        let end = after_open
+            .find(PLACEHOLDER_CLOSE)
+            .ok_or_else(|| {
+                AppError::ValidationError("Unclosed template placeholder".to_string())
+            })?;
+
+        let name = after_open[..end].trim();
+        match vars.get(name) {
+            Some(Json::String(value)) => rendered.push_str(value),
+            Some(Json::Null) | None => {
+                return Err(
+                    AppError::ValidationError(format!("Missing template variable '{}'", name))
+                );
+            }
+            Some(value) => rendered.push_str(&value.to_string()),
+        }
+
+        rest = &after_open[end + PLACEHOLDER_CLOSE.len()..];
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
 }
 
 impl DynamoDbEntity for NotificationTemplate {
@@ -191,105 +149,24 @@ impl DynamoDbEntity for NotificationTemplate {
     ///
     /// 'Some' NotificationTemplate if item fields match, 'None' otherwise
     fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
-        // info!("calling from_item with: {:?}", &item);
-
         let id = item.get("id")?.as_s().ok()?.to_string();
-        let name = item.get("name")?.as_s().ok()?.to_string();
-
-        let notification_type_str = item.get("notification_type")?.as_s().ok()?;
-        let notification_type = NotificationType::from_string(&notification_type_str)
-            .map_err(|e| e)
-            .ok()?;
-
+        let key = item.get("key")?.as_s().ok()?.to_string();
         let subject_template = item.get("subject_template")?.as_s().ok()?.to_string();
-        let message_template = item.get("message_template")?.as_s().ok()?.to_string();
-
-        let default_severity_str = item.get("default_severity")?.as_s().ok()?;
-        let default_severity = SeverityLevel::from_string(&default_severity_str)
-            .map_err(|e| e)
-            .ok()?;
-
-        let supported_channels = item
-            .get("supported_channels")
-            .and_then(|v| v.as_ss().ok())
-            .map(|channel_strs| {
-                channel_strs
-                    .iter()
-                    .filter_map(|c| NotificationChannels::from_string(c).ok())
-                    .collect::<Vec<NotificationChannels>>()
-            })
-            .unwrap_or_default();
+        let body_template = item.get("body_template")?.as_s().ok()?.to_string();
 
-        // Handle variables as a list of maps
         let variables = item
             .get("variables")
-            .and_then(|v| v.as_l().ok())
-            .map(|var_list| {
-                var_list
-                    .iter()
-                    .filter_map(|var_attr| {
-                        var_attr
-                            .as_m()
-                            .ok()
-                            .and_then(|var_map| TemplateVariable::from_item(var_map))
-                    })
-                    .collect::<Vec<TemplateVariable>>()
-            })
+            .and_then(|v| v.as_ss().ok())
+            .cloned()
             .unwrap_or_default();
 
-        let template_engine = item
-            .get("template_engine")
-            .and_then(|v| v.as_s().ok())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| "simple".to_string());
-
-        let active = item
-            .get("active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
-
-        let version = item
-            .get("version")
-            .and_then(|v| v.as_n().ok())
-            .and_then(|s| s.parse::<i32>().ok())
-            .unwrap_or(1);
-
-        let created_by = item
-            .get("created_by")
-            .and_then(|v| v.as_s().ok())
-            .map(|s| s.to_string());
-
-        let created_at = item
-            .get("created_at")
-            .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
-            .unwrap_or_else(|| Utc::now());
-
-        let updated_at = item
-            .get("updated_at")
-            .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
-            .unwrap_or_else(|| Utc::now());
-
-        let res = Some(Self {
+        Some(Self {
             id,
-            name,
-            notification_type,
+            key,
             subject_template,
-            message_template,
-            default_severity,
-            supported_channels,
+            body_template,
             variables,
-            template_engine,
-            active: *active,
-            version,
-            created_by,
-            created_at,
-            updated_at,
-        });
-
-        // info!("result of from_item on notification_template: {:?}", res);
-        res
+        })
     }
 
     /// Creates DynamoDB item from NotificationTemplate instance
@@ -305,91 +182,70 @@ impl DynamoDbEntity for NotificationTemplate {
         let mut item = HashMap::new();
 
         item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
-        item.insert("name".to_string(), AttributeValue::S(self.name.clone()));
-        item.insert(
-            "notification_type".to_string(),
-            AttributeValue::S(self.notification_type.to_string())
-        );
+        item.insert("key".to_string(), AttributeValue::S(self.key.clone()));
         item.insert(
             "subject_template".to_string(),
             AttributeValue::S(self.subject_template.clone())
         );
-        item.insert(
-            "message_template".to_string(),
-            AttributeValue::S(self.message_template.clone())
-        );
-        item.insert(
-            "default_severity".to_string(),
-            AttributeValue::S(self.default_severity.to_string())
-        );
-
-        // Convert channels to string set
-        let channel_strings: Vec<String> = self.supported_channels
-            .iter()
-            .map(|c| c.to_string())
-            .collect();
-        if !channel_strings.is_empty() {
-            item.insert("supported_channels".to_string(), AttributeValue::Ss(channel_strings));
-        }
+        item.insert("body_template".to_string(), AttributeValue::S(self.body_template.clone()));
 
-        // Convert variables to list of maps
+        // String sets cannot be empty
         if !self.variables.is_empty() {
-            let var_list: Vec<AttributeValue> = self.variables
-                .iter()
-                .map(|var| AttributeValue::M(var.to_item()))
-                .collect();
-            item.insert("variables".to_string(), AttributeValue::L(var_list));
+            item.insert("variables".to_string(), AttributeValue::Ss(self.variables.clone()));
         }
 
-        item.insert("template_engine".to_string(), AttributeValue::S(self.template_engine.clone()));
-        item.insert("active".to_string(), AttributeValue::Bool(self.active));
-        item.insert("version".to_string(), AttributeValue::N(self.version.to_string()));
+        item
+    }
+}
 
-        if let Some(creator) = &self.created_by {
-            item.insert("created_by".to_string(), AttributeValue::S(creator.clone()));
-        }
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+    use super::*;
 
-        item
+    fn template() -> NotificationTemplate {
+        NotificationTemplate::new(
+            "template-1".to_string(),
+            "work_order_assigned".to_string(),
+            "Work order {{work_order_number}} assigned".to_string(),
+            "You have been assigned {{ title }} ({{estimated_minutes}} min)".to_string(),
+            vec!["work_order_number".to_string(), "title".to_string()]
+        ).unwrap()
     }
-}
 
-impl TemplateVariable {
-    /// Creates TemplateVariable from DynamoDB item
-    pub(crate) fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
-        let name = item.get("name")?.as_s().ok()?.to_string();
-        let description = item.get("description")?.as_s().ok()?.to_string();
-        let variable_type = item.get("variable_type")?.as_s().ok()?.to_string();
-        let required = item.get("required")?.as_bool().ok().unwrap_or(&false);
-        let default_value = item
-            .get("default_value")
-            .and_then(|v| v.as_s().ok())
-            .map(|s| s.to_string());
+    #[test]
+    fn test_render_substitutes_variables() {
+        let vars = json!({
+            "work_order_number": "000042",
+            "title": "Replace pump seal",
+            "estimated_minutes": 90
+        });
 
-        Some(Self {
-            name,
-            description,
-            variable_type,
-            required: *required,
-            default_value,
-        })
+        let rendered = template().render(vars.as_object().unwrap()).unwrap();
+
+        assert_eq!(rendered.subject, "Work order 000042 assigned");
+        assert_eq!(rendered.body, "You have been assigned Replace pump seal (90 min)");
     }
 
-    /// Creates DynamoDB item from TemplateVariable
-    pub(crate) fn to_item(&self) -> HashMap<String, AttributeValue> {
-        let mut item = HashMap::new();
+    #[test]
+    fn test_render_rejects_missing_variable() {
+        let vars = json!({ "work_order_number": "000042", "estimated_minutes": 90 });
 
-        item.insert("name".to_string(), AttributeValue::S(self.name.clone()));
-        item.insert("description".to_string(), AttributeValue::S(self.description.clone()));
-        item.insert("variable_type".to_string(), AttributeValue::S(self.variable_type.clone()));
-        item.insert("required".to_string(), AttributeValue::Bool(self.required));
+        let result = template().render(vars.as_object().unwrap());
 
-        if let Some(default) = &self.default_value {
-            item.insert("default_value".to_string(), AttributeValue::S(default.clone()));
-        }
+        assert!(
+            matches!(result, Err(AppError::ValidationError(msg)) if msg.contains("'title'"))
+        );
+    }
 
-        item
+    #[test]
+    fn test_render_rejects_unclosed_placeholder() {
+        let mut template = template();
+        template.subject_template = "Work order {{work_order_number".to_string();
+
+        let vars = json!({ "work_order_number": "000042" });
+
+        assert!(template.render(vars.as_object().unwrap()).is_err());
     }
 }
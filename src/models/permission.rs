@@ -8,8 +8,9 @@ use serde_json::Value as Json;
 use tracing::info;
 
 use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
     error::AppError,
-    models::permission_log::{ PermissionAction, ResourceType },
+    models::{ permission_log::{ PermissionAction, ResourceType }, validation::parse_enum },
     DynamoDbEntity,
 };
 
@@ -39,14 +40,13 @@ impl PermissionScope {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<PermissionScope, AppError> {
-        match s {
-            "global" => Ok(Self::Global),
-            "organization" => Ok(Self::Organization),
-            "location" => Ok(Self::Location),
-            "asset" => Ok(Self::Asset),
-            "own" => Ok(Self::Own),
-            _ => Err(AppError::ValidationError("Invalid permission scope".to_string())),
-        }
+        parse_enum(s, [
+            ("global", Self::Global),
+            ("organization", Self::Organization),
+            ("location", Self::Location),
+            ("asset", Self::Asset),
+            ("own", Self::Own),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid permission scope".to_string()))
     }
 }
 
@@ -235,28 +235,25 @@ impl DynamoDbEntity for Permission {
             .and_then(|v| v.as_s().ok())
             .and_then(|s| serde_json::from_str::<Json>(s).ok());
 
-        let active = item
-            .get("active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let active = get_bool(item, "active", true);
 
         let expires_at = item
             .get("expires_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let created_by = item.get("created_by")?.as_s().ok()?.to_string();
 
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -267,7 +264,7 @@ impl DynamoDbEntity for Permission {
             scope,
             conditions,
             resource_filters,
-            active: *active,
+            active,
             expires_at,
             created_by,
             created_at,
@@ -320,12 +317,12 @@ impl DynamoDbEntity for Permission {
         item.insert("active".to_string(), AttributeValue::Bool(self.active));
 
         if let Some(expires) = &self.expires_at {
-            item.insert("expires_at".to_string(), AttributeValue::S(expires.to_string()));
+            item.insert("expires_at".to_string(), AttributeValue::S(to_db_timestamp(expires)));
         }
 
         item.insert("created_by".to_string(), AttributeValue::S(self.created_by.clone()));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -6,7 +6,11 @@ use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::error::AppError;
+use crate::{
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    error::AppError,
+    models::validation::parse_enum,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -44,19 +48,18 @@ impl PermissionAction {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<PermissionAction, AppError> {
-        match s {
-            "create" => Ok(Self::Create),
-            "read" => Ok(Self::Read),
-            "update" => Ok(Self::Update),
-            "delete" => Ok(Self::Delete),
-            "execute" => Ok(Self::Execute),
-            "approve" => Ok(Self::Approve),
-            "assign" => Ok(Self::Assign),
-            "view" => Ok(Self::View),
-            "export" => Ok(Self::Export),
-            "import" => Ok(Self::Import),
-            _ => Err(AppError::ValidationError("Invalid permission action".to_string())),
-        }
+        parse_enum(s, [
+            ("create", Self::Create),
+            ("read", Self::Read),
+            ("update", Self::Update),
+            ("delete", Self::Delete),
+            ("execute", Self::Execute),
+            ("approve", Self::Approve),
+            ("assign", Self::Assign),
+            ("view", Self::View),
+            ("export", Self::Export),
+            ("import", Self::Import),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid permission action".to_string()))
     }
 }
 
@@ -86,14 +89,13 @@ impl PermissionStatus {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<PermissionStatus, AppError> {
-        match s {
-            "granted" => Ok(Self::Granted),
-            "denied" => Ok(Self::Denied),
-            "pending" => Ok(Self::Pending),
-            "expired" => Ok(Self::Expired),
-            "revoked" => Ok(Self::Revoked),
-            _ => Err(AppError::ValidationError("Invalid permission status".to_string())),
-        }
+        parse_enum(s, [
+            ("granted", Self::Granted),
+            ("denied", Self::Denied),
+            ("pending", Self::Pending),
+            ("expired", Self::Expired),
+            ("revoked", Self::Revoked),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid permission status".to_string()))
     }
 }
 
@@ -131,18 +133,17 @@ impl ResourceType {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<ResourceType, AppError> {
-        match s {
-            "asset" => Ok(Self::Asset),
-            "location" => Ok(Self::Location),
-            "maintenance_schedule" => Ok(Self::MaintenanceSchedule),
-            "work_order" => Ok(Self::WorkOrder),
-            "user" => Ok(Self::User),
-            "role" => Ok(Self::Role),
-            "notification" => Ok(Self::Notification),
-            "report" => Ok(Self::Report),
-            "system" => Ok(Self::System),
-            _ => Err(AppError::ValidationError("Invalid resource type".to_string())),
-        }
+        parse_enum(s, [
+            ("asset", Self::Asset),
+            ("location", Self::Location),
+            ("maintenance_schedule", Self::MaintenanceSchedule),
+            ("work_order", Self::WorkOrder),
+            ("user", Self::User),
+            ("role", Self::Role),
+            ("notification", Self::Notification),
+            ("report", Self::Report),
+            ("system", Self::System),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid resource type".to_string()))
     }
 }
 
@@ -290,13 +291,13 @@ impl PermissionLog {
         let attempted_at = item
             .get("attempted_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let granted_at = item
             .get("granted_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let denied_reason = item
             .get("denied_reason")
@@ -319,13 +320,13 @@ impl PermissionLog {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -368,10 +369,13 @@ impl PermissionLog {
         item.insert("resource_id".to_string(), AttributeValue::S(self.resource_id.clone()));
         item.insert("action".to_string(), AttributeValue::S(self.action.to_string()));
         item.insert("status".to_string(), AttributeValue::S(self.status.to_string()));
-        item.insert("attempted_at".to_string(), AttributeValue::S(self.attempted_at.to_string()));
+        item.insert(
+            "attempted_at".to_string(),
+            AttributeValue::S(to_db_timestamp(&self.attempted_at))
+        );
 
         if let Some(granted) = &self.granted_at {
-            item.insert("granted_at".to_string(), AttributeValue::S(granted.to_string()));
+            item.insert("granted_at".to_string(), AttributeValue::S(to_db_timestamp(granted)));
         }
 
         if let Some(reason) = &self.denied_reason {
@@ -389,8 +393,8 @@ impl PermissionLog {
             item.insert("role_at_time".to_string(), AttributeValue::S(role.clone()));
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
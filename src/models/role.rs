@@ -1,11 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
 
 use aws_sdk_dynamodb::types::AttributeValue;
 use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ error::AppError, DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::validation::parse_enum,
+    DynamoDbEntity,
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -31,13 +36,12 @@ impl RoleType {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<RoleType, AppError> {
-        match s {
-            "system" => Ok(Self::System),
-            "custom" => Ok(Self::Custom),
-            "group" => Ok(Self::Group),
-            "temporary" => Ok(Self::Temporary),
-            _ => Err(AppError::ValidationError("Invalid role type".to_string())),
-        }
+        parse_enum(s, [
+            ("system", Self::System),
+            ("custom", Self::Custom),
+            ("group", Self::Group),
+            ("temporary", Self::Temporary),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid role type".to_string()))
     }
 }
 
@@ -174,6 +178,48 @@ impl Role {
     pub(crate) fn has_permission(&self, permission_id: &str) -> bool {
         self.permission_ids.contains(&permission_id.to_string())
     }
+
+    /// Checks that giving `role_id` the parent `parent_role_id` keeps the hierarchy acyclic
+    ///
+    /// Walks the parent chain from `parent_role_id` through `roles` and fails if it
+    /// reaches `role_id` or visits any role twice, so chains that are already
+    /// cyclic are rejected too instead of being walked forever.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - Role being created or re-parented
+    /// * `parent_role_id` - Proposed parent
+    /// * `roles` - All stored roles
+    pub(crate) fn ensure_acyclic_parent(
+        role_id: &str,
+        parent_role_id: &str,
+        roles: &[Role]
+    ) -> Result<(), AppError> {
+        let parents: HashMap<&str, Option<&str>> = roles
+            .iter()
+            .map(|r| (r.id.as_str(), r.parent_role_id.as_deref()))
+            .collect();
+
+        let mut visited = HashSet::from([role_id]);
+        let mut current = Some(parent_role_id);
+
+        while let Some(id) = current {
+            if !visited.insert(id) {
+                return Err(AppError::ValidationError("Role hierarchy cycle detected".to_string()));
+            }
+            current = parents.get(id).copied().flatten();
+        }
+
+        Ok(())
+    }
+}
+
+/// Sorts roles by priority, highest first, breaking ties by id
+///
+/// Scans return rows in no fixed order, so without the tie-break equal-priority
+/// roles could swap places between calls and shift page boundaries.
+pub(crate) fn sort_roles_by_priority(roles: &mut [Role]) {
+    roles.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.id.cmp(&b.id)));
 }
 
 impl DynamoDbEntity for Role {
@@ -210,10 +256,7 @@ impl DynamoDbEntity for Role {
             .map_err(|e| e)
             .ok()?;
 
-        let is_system_role = item
-            .get("is_system_role")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let is_system_role = get_bool(item, "is_system_role", false);
 
         let permission_ids = item
             .get("permission_ids")
@@ -237,15 +280,12 @@ impl DynamoDbEntity for Role {
             .and_then(|s| s.parse::<i32>().ok())
             .unwrap_or(0);
 
-        let active = item
-            .get("active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let active = get_bool(item, "active", true);
 
         let expires_at = item
             .get("expires_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let max_users = item
             .get("max_users")
@@ -260,13 +300,13 @@ impl DynamoDbEntity for Role {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -274,11 +314,11 @@ impl DynamoDbEntity for Role {
             name,
             description,
             role_type,
-            is_system_role: *is_system_role,
+            is_system_role,
             permission_ids,
             parent_role_id,
             priority,
-            active: *active,
+            active,
             expires_at,
             max_users,
             created_by,
@@ -328,7 +368,7 @@ impl DynamoDbEntity for Role {
         item.insert("active".to_string(), AttributeValue::Bool(self.active));
 
         if let Some(expires) = &self.expires_at {
-            item.insert("expires_at".to_string(), AttributeValue::S(expires.to_string()));
+            item.insert("expires_at".to_string(), AttributeValue::S(to_db_timestamp(expires)));
         }
 
         if let Some(max) = &self.max_users {
@@ -339,9 +379,75 @@ impl DynamoDbEntity for Role {
             item.insert("created_by".to_string(), AttributeValue::S(creator.clone()));
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(id: &str, parent_role_id: Option<&str>) -> Role {
+        Role::new(
+            id.to_string(),
+            id.to_uppercase(),
+            None,
+            "custom".to_string(),
+            false,
+            vec![],
+            parent_role_id.map(str::to_string),
+            0,
+            true,
+            None,
+            None,
+            None
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_reparenting_into_own_descendant_is_rejected() {
+        // a <- b <- c; making c the parent of a would close the loop
+        let roles = vec![role("a", None), role("b", Some("a")), role("c", Some("b"))];
+
+        let result = Role::ensure_acyclic_parent("a", "c", &roles);
+
+        assert!(
+            matches!(result, Err(AppError::ValidationError(msg)) if msg == "Role hierarchy cycle detected")
+        );
+        assert!(Role::ensure_acyclic_parent("a", "a", &roles).is_err());
+        assert!(Role::ensure_acyclic_parent("c", "a", &roles).is_ok());
+    }
+
+    #[test]
+    fn test_existing_cycle_is_rejected_without_looping() {
+        let roles = vec![role("a", Some("b")), role("b", Some("a"))];
+
+        assert!(Role::ensure_acyclic_parent("new", "a", &roles).is_err());
+    }
+
+    #[test]
+    fn test_equal_priority_roles_sort_deterministically() {
+        let mut high = role("high", None);
+        high.priority = 10;
+        let mut first = role("role_a", None);
+        first.priority = 5;
+        let mut second = role("role_b", None);
+        second.priority = 5;
+
+        let mut roles = vec![second.clone(), high.clone(), first.clone()];
+        let mut reversed = vec![first, second, high];
+
+        sort_roles_by_priority(&mut roles);
+        sort_roles_by_priority(&mut reversed);
+
+        let ids: Vec<&str> = roles.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["high", "role_a", "role_b"]);
+        assert_eq!(
+            reversed.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            ids
+        );
+    }
+}
@@ -6,7 +6,11 @@ use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::error::AppError;
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::validation::parse_enum,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -32,13 +36,12 @@ impl HierarchyType {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<HierarchyType, AppError> {
-        match s {
-            "direct" => Ok(Self::Direct),
-            "inherited" => Ok(Self::Inherited),
-            "delegated" => Ok(Self::Delegated),
-            "conditional" => Ok(Self::Conditional),
-            _ => Err(AppError::ValidationError("Invalid hierarchy type".to_string())),
-        }
+        parse_enum(s, [
+            ("direct", Self::Direct),
+            ("inherited", Self::Inherited),
+            ("delegated", Self::Delegated),
+            ("conditional", Self::Conditional),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid hierarchy type".to_string()))
     }
 }
 
@@ -175,10 +178,7 @@ impl RoleHierarchy {
             .map_err(|e| e)
             .ok()?;
 
-        let inherited_permissions = item
-            .get("inherited_permissions")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let inherited_permissions = get_bool(item, "inherited_permissions", true);
 
         let permission_overrides = item
             .get("permission_overrides")
@@ -197,10 +197,7 @@ impl RoleHierarchy {
             .and_then(|s| s.parse::<i32>().ok())
             .unwrap_or(0);
 
-        let active = item
-            .get("active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let active = get_bool(item, "active", true);
 
         let priority = item
             .get("priority")
@@ -216,20 +213,20 @@ impl RoleHierarchy {
         let delegation_expires_at = item
             .get("delegation_expires_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let created_by = item.get("created_by")?.as_s().ok()?.to_string();
 
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -237,10 +234,10 @@ impl RoleHierarchy {
             parent_role_id,
             child_role_id,
             hierarchy_type,
-            inherited_permissions: *inherited_permissions,
+            inherited_permissions,
             permission_overrides,
             depth_level,
-            active: *active,
+            active,
             priority,
             conditions,
             delegation_expires_at,
@@ -296,13 +293,13 @@ impl RoleHierarchy {
         if let Some(expires) = &self.delegation_expires_at {
             item.insert(
                 "delegation_expires_at".to_string(),
-                AttributeValue::S(expires.to_string())
+                AttributeValue::S(to_db_timestamp(expires))
             );
         }
 
         item.insert("created_by".to_string(), AttributeValue::S(self.created_by.clone()));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 use aws_sdk_dynamodb::types::AttributeValue;
-use crate::{ AppError, DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    models::validation::parse_enum,
+    AppError,
+    DynamoDbEntity,
+};
 // DynamoDbEntity implementation for Task
 use async_graphql::Enum;
 use chrono::{ DateTime, Utc };
@@ -28,12 +33,11 @@ impl TaskType {
     }
 
     pub fn from_string(s: &str) -> Result<TaskType, AppError> {
-        match s {
-            "workOrder" => Ok(Self::WorkOrder),
-            "cleaning" => Ok(Self::Cleaning),
-            "maintenanceRequest" => Ok(Self::MaintenanceRequest),
-            _ => Err(AppError::ValidationError("Invalid task type".to_string())),
-        }
+        parse_enum(s, [
+            ("workOrder", Self::WorkOrder),
+            ("cleaning", Self::Cleaning),
+            ("maintenanceRequest", Self::MaintenanceRequest),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid task type".to_string()))
     }
 }
 
@@ -182,9 +186,9 @@ impl DynamoDbEntity for Task {
 
         let task_type = TaskType::from_string(&task_type_str).ok()?;
 
-        let private = item.get("private")?.as_bool().ok().unwrap_or(&false);
+        let private = get_bool(item, "private", false);
 
-        let completed = item.get("completed")?.as_bool().ok().unwrap_or(&false);
+        let completed = get_bool(item, "completed", false);
 
         let assigned_to = item
             .get("assigned_to")
@@ -197,12 +201,12 @@ impl DynamoDbEntity for Task {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         Some(Self {
@@ -212,8 +216,8 @@ impl DynamoDbEntity for Task {
             description,
             work_order_id,
             task_type,
-            private: *private,
-            completed: *completed,
+            private,
+            completed,
             assigned_to,
             completed_by,
             created_at,
@@ -250,9 +254,9 @@ impl DynamoDbEntity for Task {
             item.insert("completed_by".to_string(), AttributeValue::S(completed_by.clone()));
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
 
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -6,7 +6,11 @@ use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::error::AppError;
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::validation::parse_enum,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -38,16 +42,15 @@ impl ElevationStatus {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<ElevationStatus, AppError> {
-        match s {
-            "pending" => Ok(Self::Pending),
-            "approved" => Ok(Self::Approved),
-            "active" => Ok(Self::Active),
-            "expired" => Ok(Self::Expired),
-            "revoked" => Ok(Self::Revoked),
-            "denied" => Ok(Self::Denied),
-            "cancelled" => Ok(Self::Cancelled),
-            _ => Err(AppError::ValidationError("Invalid elevation status".to_string())),
-        }
+        parse_enum(s, [
+            ("pending", Self::Pending),
+            ("approved", Self::Approved),
+            ("active", Self::Active),
+            ("expired", Self::Expired),
+            ("revoked", Self::Revoked),
+            ("denied", Self::Denied),
+            ("cancelled", Self::Cancelled),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid elevation status".to_string()))
     }
 }
 
@@ -75,13 +78,12 @@ impl ElevationPriority {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<ElevationPriority, AppError> {
-        match s {
-            "low" => Ok(Self::Low),
-            "normal" => Ok(Self::Normal),
-            "high" => Ok(Self::High),
-            "emergency" => Ok(Self::Emergency),
-            _ => Err(AppError::ValidationError("Invalid elevation priority".to_string())),
-        }
+        parse_enum(s, [
+            ("low", Self::Low),
+            ("normal", Self::Normal),
+            ("high", Self::High),
+            ("emergency", Self::Emergency),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid elevation priority".to_string()))
     }
 }
 
@@ -235,6 +237,9 @@ impl TempRoleElevation {
 
     /// Creates TempRoleElevation instance from DynamoDB item
     ///
+    /// Missing `start_time`, `end_time`, `created_at` and `updated_at` read as now;
+    /// missing flags take the defaults noted where they are read.
+    ///
     /// # Arguments
     ///
     /// * `item` - The dynamo db item
@@ -270,24 +275,24 @@ impl TempRoleElevation {
         let start_time = item
             .get("start_time")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let end_time = item
             .get("end_time")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let actual_start_time = item
             .get("actual_start_time")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let actual_end_time = item
             .get("actual_end_time")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let status_str = item.get("status")?.as_s().ok()?;
         let status = ElevationStatus::from_string(&status_str)
@@ -299,25 +304,19 @@ impl TempRoleElevation {
             .map_err(|e| e)
             .ok()?;
 
-        let auto_revoke = item
-            .get("auto_revoke")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        // Elevations stored without the flag expire on their own
+        let auto_revoke = get_bool(item, "auto_revoke", true);
 
-        let notification_sent = item
-            .get("notification_sent")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        // Elevations stored without the flag have not been notified about yet
+        let notification_sent = get_bool(item, "notification_sent", false);
 
-        let approval_required = item
-            .get("approval_required")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        // Elevations stored without the flag fail safe by requiring sign-off
+        let approval_required = get_bool(item, "approval_required", true);
 
         let approval_deadline = item
             .get("approval_deadline")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let revoked_by_user_id = item
             .get("revoked_by_user_id")
@@ -332,13 +331,13 @@ impl TempRoleElevation {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -356,9 +355,9 @@ impl TempRoleElevation {
             actual_end_time,
             status,
             priority,
-            auto_revoke: *auto_revoke,
-            notification_sent: *notification_sent,
-            approval_required: *approval_required,
+            auto_revoke,
+            notification_sent,
+            approval_required,
             approval_deadline,
             revoked_by_user_id,
             revocation_reason,
@@ -410,18 +409,21 @@ impl TempRoleElevation {
             item.insert("approved_by_user_id".to_string(), AttributeValue::S(approved_by.clone()));
         }
 
-        item.insert("start_time".to_string(), AttributeValue::S(self.start_time.to_string()));
-        item.insert("end_time".to_string(), AttributeValue::S(self.end_time.to_string()));
+        item.insert("start_time".to_string(), AttributeValue::S(to_db_timestamp(&self.start_time)));
+        item.insert("end_time".to_string(), AttributeValue::S(to_db_timestamp(&self.end_time)));
 
         if let Some(actual_start) = &self.actual_start_time {
             item.insert(
                 "actual_start_time".to_string(),
-                AttributeValue::S(actual_start.to_string())
+                AttributeValue::S(to_db_timestamp(actual_start))
             );
         }
 
         if let Some(actual_end) = &self.actual_end_time {
-            item.insert("actual_end_time".to_string(), AttributeValue::S(actual_end.to_string()));
+            item.insert(
+                "actual_end_time".to_string(),
+                AttributeValue::S(to_db_timestamp(actual_end))
+            );
         }
 
         item.insert("status".to_string(), AttributeValue::S(self.status.to_string()));
@@ -431,7 +433,10 @@ impl TempRoleElevation {
         item.insert("approval_required".to_string(), AttributeValue::Bool(self.approval_required));
 
         if let Some(deadline) = &self.approval_deadline {
-            item.insert("approval_deadline".to_string(), AttributeValue::S(deadline.to_string()));
+            item.insert(
+                "approval_deadline".to_string(),
+                AttributeValue::S(to_db_timestamp(deadline))
+            );
         }
 
         if let Some(revoked_by) = &self.revoked_by_user_id {
@@ -445,8 +450,8 @@ impl TempRoleElevation {
             );
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -7,7 +7,23 @@ use serde::{ Deserialize, Serialize };
 use serde_json::Value as Json;
 use tracing::info;
 
-use crate::{ error::AppError, DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    error::AppError,
+    models::{
+        audit_event::{ USER_DELETED, USER_STATUS_CHANGED, USER_SUSPENDED, USER_TERMINATED },
+        validation::{ parse_enum, validate_email },
+    },
+    DynamoDbEntity,
+};
+
+/// Separators older status mutations appended to `notes`, with the audit action each implies
+const STATUS_NOTE_MARKERS: [(&str, &str); 4] = [
+    ("; SUSPENDED: ", USER_SUSPENDED),
+    ("; TERMINATED: ", USER_TERMINATED),
+    ("; STATUS CHANGE: ", USER_STATUS_CHANGED),
+    ("; DELETED: ", USER_DELETED),
+];
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 
 #[serde(rename_all = "snake_case")]
@@ -37,15 +53,14 @@ impl UserStatus {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<UserStatus, AppError> {
-        match s {
-            "active" => Ok(Self::Active),
-            "inactive" => Ok(Self::Inactive),
-            "suspended" => Ok(Self::Suspended),
-            "pending" => Ok(Self::Pending),
-            "locked" => Ok(Self::Locked),
-            "terminated" => Ok(Self::Terminated),
-            _ => Err(AppError::ValidationError("Invalid user status".to_string())),
-        }
+        parse_enum(s, [
+            ("active", Self::Active),
+            ("inactive", Self::Inactive),
+            ("suspended", Self::Suspended),
+            ("pending", Self::Pending),
+            ("locked", Self::Locked),
+            ("terminated", Self::Terminated),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid user status".to_string()))
     }
 }
 
@@ -74,13 +89,12 @@ impl UserType {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<UserType, AppError> {
-        match s {
-            "employee" => Ok(Self::Employee),
-            "admin" => Ok(Self::Admin),
-            "system" => Ok(Self::System),
-            "service" => Ok(Self::Service),
-            _ => Err(AppError::ValidationError("Invalid user type".to_string())),
-        }
+        parse_enum(s, [
+            ("employee", Self::Employee),
+            ("admin", Self::Admin),
+            ("system", Self::System),
+            ("service", Self::Service),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid user type".to_string()))
     }
 
     pub(crate) fn description(&self) -> &str {
@@ -130,6 +144,7 @@ impl UserType {
 /// * `notes` - Administrative notes about the user
 /// * `metadata` - Additional metadata as JSON
 /// * `created_by` - User who created this account
+/// * `version` - Optimistic concurrency version, incremented on every update
 /// * `created_at` - Date and time of creation
 /// * `updated_at` - Date and time of last update
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -166,6 +181,7 @@ pub struct User {
     pub notes: Option<String>,
     pub metadata: Option<Json>,
     pub created_by: Option<String>,
+    pub version: u64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -255,18 +271,15 @@ impl User {
             return Err(AppError::ValidationError("Last name cannot be empty".to_string()));
         }
 
-        // Validate email format (basic validation)
-        if !email.contains('@') || !email.contains('.') {
-            return Err(AppError::ValidationError("Invalid email format".to_string()));
-        }
+        // Validate email format
+        validate_email(&email)?;
 
         // Validate secondary email if provided
         if let Some(ref sec_email) = secondary_email {
-            if
-                !sec_email.trim().is_empty() &&
-                (!sec_email.contains('@') || !sec_email.contains('.'))
-            {
-                return Err(AppError::ValidationError("Invalid secondary email format".to_string()));
+            if !sec_email.trim().is_empty() {
+                validate_email(sec_email).map_err(|_| {
+                    AppError::ValidationError("Invalid secondary email format".to_string())
+                })?;
             }
         }
 
@@ -306,6 +319,7 @@ impl User {
             notes,
             metadata,
             created_by,
+            version: 0,
             created_at: now,
             updated_at: now,
         })
@@ -349,7 +363,7 @@ impl User {
     }
 
     /// Records a failed login attempt
-    fn record_failed_login(&mut self, max_attempts: i32, lockout_duration_minutes: i64) {
+    pub fn record_failed_login(&mut self, max_attempts: i32, lockout_duration_minutes: i64) {
         self.failed_login_attempts += 1;
 
         if self.failed_login_attempts >= max_attempts {
@@ -361,24 +375,21 @@ impl User {
     }
 
     /// Unlocks the user account
-    fn unlock_account(&mut self) {
+    pub fn unlock_account(&mut self) {
         self.failed_login_attempts = 0;
         self.account_locked_until = None;
         self.updated_at = Utc::now();
     }
 
     /// Suspends the user account
-    fn suspend(&mut self, reason: Option<String>) -> Result<(), AppError> {
+    ///
+    /// `notes` is left untouched; callers record the reason as an audit event.
+    pub fn suspend(&mut self) -> Result<(), AppError> {
         if matches!(self.status, UserStatus::Terminated) {
             return Err(AppError::ValidationError("Cannot suspend terminated user".to_string()));
         }
 
         self.status = UserStatus::Suspended;
-        if let Some(reason_text) = reason {
-            // Add suspension reason to notes
-            let current_notes = self.notes.clone().unwrap_or_default();
-            self.notes = Some(format!("{}; SUSPENDED: {}", current_notes, reason_text));
-        }
         self.updated_at = Utc::now();
         Ok(())
     }
@@ -401,7 +412,7 @@ impl User {
     }
 
     /// Terminates the user account
-    fn terminate(&mut self, termination_date: Option<DateTime<Utc>>) -> Result<(), AppError> {
+    pub fn terminate(&mut self, termination_date: Option<DateTime<Utc>>) -> Result<(), AppError> {
         if matches!(self.status, UserStatus::Terminated) {
             return Err(AppError::ValidationError("User is already terminated".to_string()));
         }
@@ -413,10 +424,53 @@ impl User {
     }
 
     /// Updates the user's password change timestamp
-    fn record_password_change(&mut self) {
+    pub fn record_password_change(&mut self) {
         self.password_changed_at = Some(Utc::now());
         self.updated_at = Utc::now();
     }
+
+    /// Removes status reasons that older mutations appended to `notes`
+    ///
+    /// Everything before the first `; SUSPENDED: `, `; TERMINATED: `,
+    /// `; STATUS CHANGE: ` or `; DELETED: ` separator is kept as the user-authored
+    /// notes; notes that were nothing but history are cleared. Clean notes are
+    /// left as they are.
+    ///
+    /// # Returns
+    ///
+    /// The removed entries as `(audit action, reason)` pairs, oldest first
+    pub fn take_status_history(&mut self) -> Vec<(&'static str, String)> {
+        let Some(notes) = self.notes.as_deref() else {
+            return Vec::new();
+        };
+
+        let Some((start, _, _)) = next_status_marker(notes) else {
+            return Vec::new();
+        };
+
+        let mut history = Vec::new();
+        let mut rest = &notes[start..];
+
+        while let Some((_, marker, action)) = next_status_marker(rest) {
+            let reason = &rest[marker.len()..];
+            let end = next_status_marker(reason).map_or(reason.len(), |(pos, _, _)| pos);
+
+            history.push((action, reason[..end].to_string()));
+            rest = &reason[end..];
+        }
+
+        let kept = notes[..start].to_string();
+        self.notes = if kept.is_empty() { None } else { Some(kept) };
+
+        history
+    }
+}
+
+/// Earliest status separator in `notes` as `(position, separator, audit action)`
+fn next_status_marker(notes: &str) -> Option<(usize, &'static str, &'static str)> {
+    STATUS_NOTE_MARKERS.iter()
+        .filter_map(|&(marker, action)| notes.find(marker).map(|pos| (pos, marker, action)))
+        .min_by_key(|&(pos, _, _)| pos)
 }
 
 impl DynamoDbEntity for User {
@@ -427,6 +481,16 @@ impl DynamoDbEntity for User {
     fn primary_key(&self) -> String {
         self.id.clone()
     }
+
+    const VERSIONED: bool = true;
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
     /// Creates User instance from DynamoDB item
     ///
     /// # Arguments
@@ -493,22 +557,22 @@ impl DynamoDbEntity for User {
         let hire_date = item
             .get("hire_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let termination_date = item
             .get("termination_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let last_login_at = item
             .get("last_login_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let password_changed_at = item
             .get("password_changed_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let failed_login_attempts = item
             .get("failed_login_attempts")
@@ -519,7 +583,7 @@ impl DynamoDbEntity for User {
         let account_locked_until = item
             .get("account_locked_until")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let certification_levels = item
             .get("certification_levels")
@@ -582,16 +646,22 @@ impl DynamoDbEntity for User {
             .and_then(|v| v.as_s().ok())
             .map(|s| s.to_string());
 
+        let version = item
+            .get("version")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -627,6 +697,7 @@ impl DynamoDbEntity for User {
             notes,
             metadata,
             created_by,
+            version,
             created_at,
             updated_at,
         });
@@ -685,21 +756,21 @@ impl DynamoDbEntity for User {
         }
 
         if let Some(hire) = &self.hire_date {
-            item.insert("hire_date".to_string(), AttributeValue::S(hire.to_string()));
+            item.insert("hire_date".to_string(), AttributeValue::S(to_db_timestamp(hire)));
         }
 
         if let Some(term) = &self.termination_date {
-            item.insert("termination_date".to_string(), AttributeValue::S(term.to_string()));
+            item.insert("termination_date".to_string(), AttributeValue::S(to_db_timestamp(term)));
         }
 
         if let Some(login) = &self.last_login_at {
-            item.insert("last_login_at".to_string(), AttributeValue::S(login.to_string()));
+            item.insert("last_login_at".to_string(), AttributeValue::S(to_db_timestamp(login)));
         }
 
         if let Some(pwd_changed) = &self.password_changed_at {
             item.insert(
                 "password_changed_at".to_string(),
-                AttributeValue::S(pwd_changed.to_string())
+                AttributeValue::S(to_db_timestamp(pwd_changed))
             );
         }
 
@@ -711,7 +782,7 @@ impl DynamoDbEntity for User {
         if let Some(locked_until) = &self.account_locked_until {
             item.insert(
                 "account_locked_until".to_string(),
-                AttributeValue::S(locked_until.to_string())
+                AttributeValue::S(to_db_timestamp(locked_until))
             );
         }
 
@@ -769,9 +840,86 @@ impl DynamoDbEntity for User {
             item.insert("created_by".to_string(), AttributeValue::S(creator.clone()));
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("version".to_string(), AttributeValue::N(self.version.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(notes: Option<&str>) -> User {
+        User::new(
+            "user-1".to_string(),
+            "jdoe".to_string(),
+            "jdoe@example.com".to_string(),
+            "Jane".to_string(),
+            "Doe".to_string(),
+            None,
+            "employee".to_string(),
+            "active".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Json::Null,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            notes.map(str::to_string),
+            None,
+            None
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_suspend_leaves_notes_untouched() {
+        let mut user = user(Some("Prefers email contact"));
+
+        user.suspend().unwrap();
+
+        assert_eq!(user.status, UserStatus::Suspended);
+        assert_eq!(user.notes.as_deref(), Some("Prefers email contact"));
+    }
+
+    #[test]
+    fn test_take_status_history_splits_appended_reasons() {
+        let mut user = user(
+            Some("Night shift; SUSPENDED: no-show; STATUS CHANGE: back; TERMINATED: quit")
+        );
+
+        let history = user.take_status_history();
+
+        assert_eq!(user.notes.as_deref(), Some("Night shift"));
+        assert_eq!(history, vec![
+            (USER_SUSPENDED, "no-show".to_string()),
+            (USER_STATUS_CHANGED, "back".to_string()),
+            (USER_TERMINATED, "quit".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_take_status_history_is_noop_on_clean_notes() {
+        let mut clean = user(Some("Night shift"));
+        let mut history_only = user(Some("; SUSPENDED: no-show"));
+
+        assert!(clean.take_status_history().is_empty());
+        assert_eq!(clean.notes.as_deref(), Some("Night shift"));
+
+        assert_eq!(history_only.take_status_history().len(), 1);
+        assert_eq!(history_only.notes, None);
+        assert!(history_only.take_status_history().is_empty());
+    }
+}
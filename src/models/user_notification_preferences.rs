@@ -6,7 +6,11 @@ use chrono::{ DateTime, Utc, NaiveTime };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ error::AppError, models::notification::{ NotificationChannels, SeverityLevel } };
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::{ notification::{ NotificationChannels, SeverityLevel }, validation::parse_enum },
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 
@@ -33,13 +37,12 @@ impl PreferenceScope {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<PreferenceScope, AppError> {
-        match s {
-            "global" => Ok(Self::Global),
-            "template" => Ok(Self::Template),
-            "type" => Ok(Self::Type),
-            "severity" => Ok(Self::Severity),
-            _ => Err(AppError::ValidationError("Invalid preference scope".to_string())),
-        }
+        parse_enum(s, [
+            ("global", Self::Global),
+            ("template", Self::Template),
+            ("type", Self::Type),
+            ("severity", Self::Severity),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid preference scope".to_string()))
     }
 }
 
@@ -218,10 +221,7 @@ impl UserNotificationPreferences {
             .and_then(|v| v.as_s().ok())
             .map(|s| s.to_string());
 
-        let enabled = item
-            .get("enabled")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let enabled = get_bool(item, "enabled", true);
 
         let preferred_channels = item
             .get("preferred_channels")
@@ -245,10 +245,7 @@ impl UserNotificationPreferences {
             })
             .unwrap_or_default();
 
-        let do_not_disturb = item
-            .get("do_not_disturb")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let do_not_disturb = get_bool(item, "do_not_disturb", false);
 
         let quiet_hours_start = item
             .get("quiet_hours_start")
@@ -276,10 +273,7 @@ impl UserNotificationPreferences {
             .and_then(|s| s.parse::<i32>().ok())
             .unwrap_or(0);
 
-        let digest_enabled = item
-            .get("digest_enabled")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let digest_enabled = get_bool(item, "digest_enabled", false);
 
         let digest_frequency_hours = item
             .get("digest_frequency_hours")
@@ -287,10 +281,7 @@ impl UserNotificationPreferences {
             .and_then(|s| s.parse::<i32>().ok())
             .unwrap_or(24);
 
-        let escalation_enabled = item
-            .get("escalation_enabled")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let escalation_enabled = get_bool(item, "escalation_enabled", false);
 
         let escalation_delay_minutes = item
             .get("escalation_delay_minutes")
@@ -298,21 +289,18 @@ impl UserNotificationPreferences {
             .and_then(|s| s.parse::<i32>().ok())
             .unwrap_or(15);
 
-        let active = item
-            .get("active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let active = get_bool(item, "active", true);
 
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -320,20 +308,20 @@ impl UserNotificationPreferences {
             user_id,
             scope,
             scope_value,
-            enabled: *enabled,
+            enabled,
             preferred_channels,
             blocked_channels,
-            do_not_disturb: *do_not_disturb,
+            do_not_disturb,
             quiet_hours_start,
             quiet_hours_end,
             quiet_hours_timezone,
             min_severity_level,
             frequency_limit,
-            digest_enabled: *digest_enabled,
+            digest_enabled,
             digest_frequency_hours,
-            escalation_enabled: *escalation_enabled,
+            escalation_enabled,
             escalation_delay_minutes,
-            active: *active,
+            active,
             created_at,
             updated_at,
         });
@@ -427,8 +415,8 @@ impl UserNotificationPreferences {
             AttributeValue::N(self.escalation_delay_minutes.to_string())
         );
         item.insert("active".to_string(), AttributeValue::Bool(self.active));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
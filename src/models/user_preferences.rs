@@ -3,11 +3,17 @@ use std::collections::HashMap;
 use async_graphql::{ Enum, Object };
 use aws_sdk_dynamodb::types::AttributeValue;
 use chrono::{ DateTime, Timelike, Utc };
+use chrono_tz::Tz;
 use serde::{ Deserialize, Serialize };
 use serde_json::Value as Json;
 use tracing::info;
 
-use crate::error::AppError;
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::{ id::IdGenerator, notification::NotificationChannels, validation::parse_enum },
+    DynamoDbEntity,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -33,13 +39,12 @@ impl ThemeOptions {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<ThemeOptions, AppError> {
-        match s {
-            "light" => Ok(Self::Light),
-            "dark" => Ok(Self::Dark),
-            "auto" => Ok(Self::Auto),
-            "high_contrast" => Ok(Self::HighContrast),
-            _ => Err(AppError::ValidationError("Invalid theme option".to_string())),
-        }
+        parse_enum(s, [
+            ("light", Self::Light),
+            ("dark", Self::Dark),
+            ("auto", Self::Auto),
+            ("high_contrast", Self::HighContrast),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid theme option".to_string()))
     }
 }
 
@@ -73,16 +78,15 @@ impl LanguageOptions {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<LanguageOptions, AppError> {
-        match s {
-            "en" => Ok(Self::English),
-            "es" => Ok(Self::Spanish),
-            "fr" => Ok(Self::French),
-            "de" => Ok(Self::German),
-            "pt" => Ok(Self::Portuguese),
-            "zh" => Ok(Self::Chinese),
-            "ja" => Ok(Self::Japanese),
-            _ => Err(AppError::ValidationError("Invalid language option".to_string())),
-        }
+        parse_enum(s, [
+            ("en", Self::English),
+            ("es", Self::Spanish),
+            ("fr", Self::French),
+            ("de", Self::German),
+            ("pt", Self::Portuguese),
+            ("zh", Self::Chinese),
+            ("ja", Self::Japanese),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid language option".to_string()))
     }
 }
 
@@ -107,14 +111,30 @@ impl TimezoneFormat {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<TimezoneFormat, AppError> {
-        match s {
-            "12h" => Ok(Self::TwelveHour),
-            "24h" => Ok(Self::TwentyFourHour),
-            _ => Err(AppError::ValidationError("Invalid timezone format".to_string())),
-        }
+        parse_enum(s, [
+            ("12h", Self::TwelveHour),
+            ("24h", Self::TwentyFourHour),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid timezone format".to_string()))
     }
 }
 
+/// Local hour the auto theme switches to dark unless the user overrides it
+pub const DEFAULT_DARK_MODE_START_HOUR: u32 = 18;
+
+/// Local hour the auto theme switches back to light unless the user overrides it
+pub const DEFAULT_DARK_MODE_END_HOUR: u32 = 6;
+
+/// Date display patterns the clients know how to render
+pub const SUPPORTED_DATE_FORMATS: [&str; 3] = ["MM/DD/YYYY", "DD/MM/YYYY", "YYYY-MM-DD"];
+
+/// Rejects anything that is not an IANA timezone name, e.g. `America/Chicago` or `UTC`
+fn validate_timezone(timezone: &str) -> Result<(), AppError> {
+    timezone
+        .parse::<Tz>()
+        .map(|_| ())
+        .map_err(|_| AppError::ValidationError(format!("Unknown timezone '{}'", timezone)))
+}
+
 /// Represents User Preferences in the system
 ///
 /// # Fields
@@ -139,6 +159,8 @@ impl TimezoneFormat {
 /// * `show_tooltips` - Whether to show helpful tooltips
 /// * `default_page_size` - Default number of items per page in lists
 /// * `custom_shortcuts` - JSON object containing custom keyboard shortcuts
+/// * `dark_mode_start_hour` - Local hour (0-23) the auto theme switches to dark
+/// * `dark_mode_end_hour` - Local hour (0-23) the auto theme switches back to light
 /// * `created_at` - Date and time of creation
 /// * `updated_at` - Date and time of last update
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -163,6 +185,8 @@ pub struct UserPreferences {
     pub show_tooltips: bool,
     pub default_page_size: i32,
     pub custom_shortcuts: Option<Json>,
+    pub dark_mode_start_hour: u32,
+    pub dark_mode_end_hour: u32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -228,6 +252,19 @@ impl UserPreferences {
         let language_enum = LanguageOptions::from_string(&language)?;
         let theme_enum = ThemeOptions::from_string(&theme)?;
         let time_format_enum = TimezoneFormat::from_string(&time_format)?;
+        validate_timezone(&timezone)?;
+
+        if !SUPPORTED_DATE_FORMATS.contains(&date_format.as_str()) {
+            return Err(
+                AppError::ValidationError(
+                    format!(
+                        "Unsupported date format '{}', expected one of {}",
+                        date_format,
+                        SUPPORTED_DATE_FORMATS.join(", ")
+                    )
+                )
+            );
+        }
 
         // Validate font size multiplier
         if font_size_multiplier < 0.5 || font_size_multiplier > 3.0 {
@@ -273,11 +310,176 @@ impl UserPreferences {
             show_tooltips,
             default_page_size,
             custom_shortcuts,
+            dark_mode_start_hour: DEFAULT_DARK_MODE_START_HOUR,
+            dark_mode_end_hour: DEFAULT_DARK_MODE_END_HOUR,
             created_at: now,
             updated_at: now,
         })
     }
 
+    /// Picks the row to use when a user has more than one
+    ///
+    /// Racing first accesses can each create defaults. Ids are ULIDs, so the
+    /// smallest id is the row written first and every reader settles on it.
+    pub(crate) fn first_created(rows: Vec<Self>) -> Option<Self> {
+        rows.into_iter().min_by(|a, b| a.id.cmp(&b.id))
+    }
+
+    /// Creates default preferences for a new user
+    pub(crate) fn create_default(user_id: String) -> Result<Self, AppError> {
+        let default_dashboard =
+            serde_json::json!({
+            "widgets": [
+                {"type": "asset_summary", "position": {"x": 0, "y": 0, "w": 6, "h": 4}},
+                {"type": "maintenance_schedule", "position": {"x": 6, "y": 0, "w": 6, "h": 4}},
+                {"type": "recent_notifications", "position": {"x": 0, "y": 4, "w": 12, "h": 3}}
+            ],
+            "layout": "grid"
+        });
+
+        Self::new(
+            IdGenerator::new_id("pref"),
+            user_id,
+            "en".to_string(),
+            "auto".to_string(),
+            "UTC".to_string(),
+            "12h".to_string(),
+            "MM/DD/YYYY".to_string(),
+            default_dashboard,
+            true,
+            true,
+            true,
+            None,
+            30,
+            false,
+            false,
+            1.0,
+            false,
+            true,
+            25,
+            None
+        )
+    }
+
+    /// Updates specific preference fields
+    pub(crate) fn update_preferences(
+        &mut self,
+        updates: HashMap<String, serde_json::Value>
+    ) -> Result<(), AppError> {
+        for (key, value) in updates {
+            match key.as_str() {
+                "language" => {
+                    if let Some(lang_str) = value.as_str() {
+                        self.language = LanguageOptions::from_string(lang_str)?;
+                    }
+                }
+                "theme" => {
+                    if let Some(theme_str) = value.as_str() {
+                        self.theme = ThemeOptions::from_string(theme_str)?;
+                    }
+                }
+                "timezone" => {
+                    if let Some(tz_str) = value.as_str() {
+                        validate_timezone(tz_str)?;
+                        self.timezone = tz_str.to_string();
+                    }
+                }
+                "dashboard_layout" => {
+                    self.dashboard_layout = value;
+                }
+                "auto_refresh_interval" => {
+                    if let Some(interval) = value.as_i64()
+                        && interval >= 0
+                    {
+                        self.auto_refresh_interval = interval as i32;
+                    }
+                }
+                "font_size_multiplier" => {
+                    if let Some(multiplier) = value.as_f64()
+                        && (0.5..=3.0).contains(&multiplier)
+                    {
+                        self.font_size_multiplier = multiplier;
+                    }
+                }
+                "default_page_size" => {
+                    if let Some(size) = value.as_i64()
+                        && (5..=500).contains(&size)
+                    {
+                        self.default_page_size = size as i32;
+                    }
+                }
+                "dark_mode_start_hour" => {
+                    if let Some(hour) = value.as_u64().filter(|hour| *hour < 24) {
+                        self.dark_mode_start_hour = hour as u32;
+                    }
+                }
+                "dark_mode_end_hour" => {
+                    if let Some(hour) = value.as_u64().filter(|hour| *hour < 24) {
+                        self.dark_mode_end_hour = hour as u32;
+                    }
+                }
+                _ => {} // Ignore unknown fields
+            }
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Resolves the theme to display at `now`
+    ///
+    /// `Auto` becomes dark from `dark_mode_start_hour` until `dark_mode_end_hour` in
+    /// the user's `timezone` (an IANA name such as `Asia/Tokyo`), falling back to
+    /// UTC when the zone can't be parsed. Other themes are returned unchanged.
+    pub fn effective_theme_at(&self, now: DateTime<Utc>) -> &str {
+        if !matches!(self.theme, ThemeOptions::Auto) {
+            return self.theme.to_str();
+        }
+
+        let local_hour = match self.timezone.parse::<Tz>() {
+            Ok(tz) => now.with_timezone(&tz).hour(),
+            Err(_) => now.hour(),
+        };
+
+        let (start, end) = (self.dark_mode_start_hour, self.dark_mode_end_hour);
+        let is_dark = if start <= end {
+            local_hour >= start && local_hour < end
+        } else {
+            // Window wraps past midnight, e.g. 18:00 to 06:00
+            local_hour >= start || local_hour < end
+        };
+
+        if is_dark { "dark" } else { "light" }
+    }
+
+    /// Checks if the user accepts notifications over `channel`
+    ///
+    /// Email follows `email_notifications_enabled`, push and SMS follow
+    /// `mobile_notifications_enabled` and desktop follows
+    /// `desktop_notifications_enabled`. Channels without a setting are always allowed.
+    pub fn allows_channel(&self, channel: NotificationChannels) -> bool {
+        match channel {
+            NotificationChannels::Email => self.email_notifications_enabled,
+            NotificationChannels::Push | NotificationChannels::Sms => {
+                self.mobile_notifications_enabled
+            }
+            NotificationChannels::Desktop => self.desktop_notifications_enabled,
+            NotificationChannels::InApp |
+            NotificationChannels::Slack |
+            NotificationChannels::Teams |
+            NotificationChannels::Webhook => true,
+        }
+    }
+}
+
+impl DynamoDbEntity for UserPreferences {
+    fn table_name() -> &'static str {
+        "UserPreferences"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+
     /// Creates UserPreferences instance from DynamoDB item
     ///
     /// # Arguments
@@ -287,7 +489,7 @@ impl UserPreferences {
     /// # Returns
     ///
     /// 'Some' UserPreferences if item fields match, 'None' otherwise
-    pub(crate) fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
         // info!("calling from_item with: {:?}", &item);
 
         let id = item.get("id")?.as_s().ok()?.to_string();
@@ -318,20 +520,12 @@ impl UserPreferences {
             .and_then(|s| serde_json::from_str::<Json>(s).ok())
             .unwrap_or(Json::Object(serde_json::Map::new()));
 
-        let email_notifications_enabled = item
-            .get("email_notifications_enabled")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        // Notification channels and tooltips are opt-out; accessibility and layout changes are opt-in
+        let email_notifications_enabled = get_bool(item, "email_notifications_enabled", true);
 
-        let mobile_notifications_enabled = item
-            .get("mobile_notifications_enabled")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let mobile_notifications_enabled = get_bool(item, "mobile_notifications_enabled", true);
 
-        let desktop_notifications_enabled = item
-            .get("desktop_notifications_enabled")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let desktop_notifications_enabled = get_bool(item, "desktop_notifications_enabled", true);
 
         let default_location_id = item
             .get("default_location_id")
@@ -344,15 +538,9 @@ impl UserPreferences {
             .and_then(|s| s.parse::<i32>().ok())
             .unwrap_or(30);
 
-        let accessibility_enabled = item
-            .get("accessibility_enabled")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let accessibility_enabled = get_bool(item, "accessibility_enabled", false);
 
-        let high_contrast_mode = item
-            .get("high_contrast_mode")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let high_contrast_mode = get_bool(item, "high_contrast_mode", false);
 
         let font_size_multiplier = item
             .get("font_size_multiplier")
@@ -360,15 +548,9 @@ impl UserPreferences {
             .and_then(|s| s.parse::<f64>().ok())
             .unwrap_or(1.0);
 
-        let sidebar_collapsed = item
-            .get("sidebar_collapsed")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let sidebar_collapsed = get_bool(item, "sidebar_collapsed", false);
 
-        let show_tooltips = item
-            .get("show_tooltips")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let show_tooltips = get_bool(item, "show_tooltips", true);
 
         let default_page_size = item
             .get("default_page_size")
@@ -381,16 +563,30 @@ impl UserPreferences {
             .and_then(|v| v.as_s().ok())
             .and_then(|s| serde_json::from_str::<Json>(s).ok());
 
+        let dark_mode_start_hour = item
+            .get("dark_mode_start_hour")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|s| s.parse::<u32>().ok())
+            .filter(|hour| *hour < 24)
+            .unwrap_or(DEFAULT_DARK_MODE_START_HOUR);
+
+        let dark_mode_end_hour = item
+            .get("dark_mode_end_hour")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|s| s.parse::<u32>().ok())
+            .filter(|hour| *hour < 24)
+            .unwrap_or(DEFAULT_DARK_MODE_END_HOUR);
+
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -402,18 +598,20 @@ impl UserPreferences {
             time_format,
             date_format,
             dashboard_layout,
-            email_notifications_enabled: *email_notifications_enabled,
-            mobile_notifications_enabled: *mobile_notifications_enabled,
-            desktop_notifications_enabled: *desktop_notifications_enabled,
+            email_notifications_enabled,
+            mobile_notifications_enabled,
+            desktop_notifications_enabled,
             default_location_id,
             auto_refresh_interval,
-            accessibility_enabled: *accessibility_enabled,
-            high_contrast_mode: *high_contrast_mode,
+            accessibility_enabled,
+            high_contrast_mode,
             font_size_multiplier,
-            sidebar_collapsed: *sidebar_collapsed,
-            show_tooltips: *show_tooltips,
+            sidebar_collapsed,
+            show_tooltips,
             default_page_size,
             custom_shortcuts,
+            dark_mode_start_hour,
+            dark_mode_end_hour,
             created_at,
             updated_at,
         });
@@ -431,7 +629,7 @@ impl UserPreferences {
     /// # Returns
     ///
     /// HashMap representing DB item for UserPreferences instance
-    pub(crate) fn to_item(&self) -> HashMap<String, AttributeValue> {
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
         let mut item = HashMap::new();
 
         item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
@@ -492,33 +690,92 @@ impl UserPreferences {
             }
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert(
+            "dark_mode_start_hour".to_string(),
+            AttributeValue::N(self.dark_mode_start_hour.to_string())
+        );
+        item.insert(
+            "dark_mode_end_hour".to_string(),
+            AttributeValue::N(self.dark_mode_end_hour.to_string())
+        );
+
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
+}
 
-    /// Creates default preferences for a new user
-    pub(crate) fn create_default(user_id: String) -> Result<Self, AppError> {
-        let default_dashboard =
-            serde_json::json!({
-            "widgets": [
-                {"type": "asset_summary", "position": {"x": 0, "y": 0, "w": 6, "h": 4}},
-                {"type": "maintenance_schedule", "position": {"x": 6, "y": 0, "w": 6, "h": 4}},
-                {"type": "recent_notifications", "position": {"x": 0, "y": 4, "w": 12, "h": 3}}
-            ],
-            "layout": "grid"
-        });
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
 
-        Self::new(
-            format!("pref_{}", user_id),
-            user_id,
+    fn auto_preferences(timezone: &str) -> UserPreferences {
+        let mut preferences = UserPreferences::create_default("user-1".to_string()).unwrap();
+        preferences.theme = ThemeOptions::Auto;
+        preferences.timezone = timezone.to_string();
+        preferences
+    }
+
+    #[test]
+    fn test_effective_theme_uses_local_hour() {
+        // 12:00 in Tokyo (UTC+9), 19:00 the previous day in Los Angeles (UTC-8)
+        let now = Utc.with_ymd_and_hms(2026, 1, 15, 3, 0, 0).unwrap();
+
+        assert_eq!(auto_preferences("Asia/Tokyo").effective_theme_at(now), "light");
+        assert_eq!(auto_preferences("America/Los_Angeles").effective_theme_at(now), "dark");
+    }
+
+    #[test]
+    fn test_effective_theme_falls_back_to_utc_and_honours_custom_hours() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 15, 3, 0, 0).unwrap();
+        let mut preferences = auto_preferences("Mars/Olympus_Mons");
+
+        assert_eq!(preferences.effective_theme_at(now), "dark");
+
+        preferences.dark_mode_end_hour = 2;
+        assert_eq!(preferences.effective_theme_at(now), "light");
+
+        preferences.theme = ThemeOptions::Light;
+        preferences.dark_mode_end_hour = DEFAULT_DARK_MODE_END_HOUR;
+        assert_eq!(preferences.effective_theme_at(now), "light");
+    }
+
+    #[test]
+    fn test_defaults_round_trip_through_item() {
+        let mut preferences = UserPreferences::create_default("user-1".to_string()).unwrap();
+        preferences.dark_mode_start_hour = 20;
+
+        let restored = UserPreferences::from_item(&preferences.to_item()).unwrap();
+
+        assert_eq!(restored.primary_key(), preferences.id);
+        assert_eq!(restored.user_id, "user-1");
+        assert_eq!(restored.dark_mode_start_hour, 20);
+        assert_eq!(restored.dark_mode_end_hour, DEFAULT_DARK_MODE_END_HOUR);
+    }
+
+    #[test]
+    fn test_first_created_picks_earliest_row() {
+        let first = UserPreferences::create_default("user-1".to_string()).unwrap();
+        let second = UserPreferences::create_default("user-1".to_string()).unwrap();
+
+        let chosen = UserPreferences::first_created(vec![second, first.clone()]).unwrap();
+
+        assert_eq!(chosen.id, first.id);
+        assert!(UserPreferences::first_created(vec![]).is_none());
+    }
+
+    fn preferences_with(timezone: &str, date_format: &str) -> Result<UserPreferences, AppError> {
+        UserPreferences::new(
+            "pref-1".to_string(),
+            "user-1".to_string(),
             "en".to_string(),
             "auto".to_string(),
-            "UTC".to_string(),
-            "12h".to_string(),
-            "MM/DD/YYYY".to_string(),
-            default_dashboard,
+            timezone.to_string(),
+            "24h".to_string(),
+            date_format.to_string(),
+            Json::Null,
             true,
             true,
             true,
@@ -534,56 +791,24 @@ impl UserPreferences {
         )
     }
 
-    /// Updates specific preference fields
-    pub(crate) fn update_preferences(
-        &mut self,
-        updates: HashMap<String, serde_json::Value>
-    ) -> Result<(), AppError> {
-        for (key, value) in updates {
-            match key.as_str() {
-                "language" => {
-                    if let Some(lang_str) = value.as_str() {
-                        self.language = LanguageOptions::from_string(lang_str)?;
-                    }
-                }
-                "theme" => {
-                    if let Some(theme_str) = value.as_str() {
-                        self.theme = ThemeOptions::from_string(theme_str)?;
-                    }
-                }
-                "timezone" => {
-                    if let Some(tz_str) = value.as_str() {
-                        self.timezone = tz_str.to_string();
-                    }
-                }
-                "dashboard_layout" => {
-                    self.dashboard_layout = value;
-                }
-                "auto_refresh_interval" => {
-                    if let Some(interval) = value.as_i64() {
-                        if interval >= 0 {
-                            self.auto_refresh_interval = interval as i32;
-                        }
-                    }
-                }
-                "font_size_multiplier" => {
-                    if let Some(multiplier) = value.as_f64() {
-                        if multiplier >= 0.5 && multiplier <= 3.0 {
-                            self.font_size_multiplier = multiplier;
-                        }
-                    }
-                }
-                "default_page_size" => {
-                    if let Some(size) = value.as_i64() {
-                        if size >= 5 && size <= 500 {
-                            self.default_page_size = size as i32;
-                        }
-                    }
-                }
-                _ => {} // Ignore unknown fields
-            }
-        }
-        self.updated_at = Utc::now();
-        Ok(())
+    #[test]
+    fn test_new_accepts_iana_timezone() {
+        let preferences = preferences_with("Europe/Berlin", "YYYY-MM-DD").unwrap();
+
+        assert_eq!(preferences.timezone, "Europe/Berlin");
+    }
+
+    #[test]
+    fn test_new_rejects_unknown_timezone() {
+        let result = preferences_with("Mars/Phobos", "YYYY-MM-DD");
+
+        assert!(matches!(result, Err(AppError::ValidationError(msg)) if msg.contains("Mars/Phobos")));
+    }
+
+    #[test]
+    fn test_new_rejects_unsupported_date_format() {
+        let result = preferences_with("UTC", "YYYY.MM.DD");
+
+        assert!(matches!(result, Err(AppError::ValidationError(msg)) if msg.contains("date format")));
     }
 }
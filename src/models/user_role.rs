@@ -6,7 +6,12 @@ use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ error::AppError, DynamoDbEntity };
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::validation::parse_enum,
+    DynamoDbEntity,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 
@@ -35,14 +40,13 @@ impl RoleAssignmentStatus {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<RoleAssignmentStatus, AppError> {
-        match s {
-            "active" => Ok(Self::Active),
-            "suspended" => Ok(Self::Suspended),
-            "expired" => Ok(Self::Expired),
-            "revoked" => Ok(Self::Revoked),
-            "pending" => Ok(Self::Pending),
-            _ => Err(AppError::ValidationError("Invalid role assignment status".to_string())),
-        }
+        parse_enum(s, [
+            ("active", Self::Active),
+            ("suspended", Self::Suspended),
+            ("expired", Self::Expired),
+            ("revoked", Self::Revoked),
+            ("pending", Self::Pending),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid role assignment status".to_string()))
     }
 }
 
@@ -73,14 +77,13 @@ impl AssignmentSource {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<AssignmentSource, AppError> {
-        match s {
-            "manual" => Ok(Self::Manual),
-            "automatic" => Ok(Self::Automatic),
-            "elevation" => Ok(Self::Elevation),
-            "inheritance" => Ok(Self::Inheritance),
-            "import" => Ok(Self::Import),
-            _ => Err(AppError::ValidationError("Invalid assignment source".to_string())),
-        }
+        parse_enum(s, [
+            ("manual", Self::Manual),
+            ("automatic", Self::Automatic),
+            ("elevation", Self::Elevation),
+            ("inheritance", Self::Inheritance),
+            ("import", Self::Import),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid assignment source".to_string()))
     }
 }
 
@@ -351,15 +354,12 @@ impl DynamoDbEntity for UserRole {
             .map_err(|e| e)
             .ok()?;
 
-        let is_primary_role = item
-            .get("is_primary_role")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&false);
+        let is_primary_role = get_bool(item, "is_primary_role", false);
 
         let assigned_at = item
             .get("assigned_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let assigned_by_user_id = item
@@ -370,18 +370,18 @@ impl DynamoDbEntity for UserRole {
         let effective_from = item
             .get("effective_from")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let expires_at = item
             .get("expires_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let last_used_at = item
             .get("last_used_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let conditions = item
             .get("conditions")
@@ -396,7 +396,7 @@ impl DynamoDbEntity for UserRole {
         let revoked_at = item
             .get("revoked_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let revoked_by_user_id = item
             .get("revoked_by_user_id")
@@ -416,13 +416,13 @@ impl DynamoDbEntity for UserRole {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -431,7 +431,7 @@ impl DynamoDbEntity for UserRole {
             role_id,
             assignment_source,
             status,
-            is_primary_role: *is_primary_role,
+            is_primary_role,
             assigned_at,
             assigned_by_user_id,
             effective_from,
@@ -472,7 +472,10 @@ impl DynamoDbEntity for UserRole {
         );
         item.insert("status".to_string(), AttributeValue::S(self.status.to_string()));
         item.insert("is_primary_role".to_string(), AttributeValue::Bool(self.is_primary_role));
-        item.insert("assigned_at".to_string(), AttributeValue::S(self.assigned_at.to_string()));
+        item.insert(
+            "assigned_at".to_string(),
+            AttributeValue::S(to_db_timestamp(&self.assigned_at))
+        );
 
         if let Some(assigned_by) = &self.assigned_by_user_id {
             item.insert("assigned_by_user_id".to_string(), AttributeValue::S(assigned_by.clone()));
@@ -480,15 +483,15 @@ impl DynamoDbEntity for UserRole {
 
         item.insert(
             "effective_from".to_string(),
-            AttributeValue::S(self.effective_from.to_string())
+            AttributeValue::S(to_db_timestamp(&self.effective_from))
         );
 
         if let Some(expires) = &self.expires_at {
-            item.insert("expires_at".to_string(), AttributeValue::S(expires.to_string()));
+            item.insert("expires_at".to_string(), AttributeValue::S(to_db_timestamp(expires)));
         }
 
         if let Some(last_used) = &self.last_used_at {
-            item.insert("last_used_at".to_string(), AttributeValue::S(last_used.to_string()));
+            item.insert("last_used_at".to_string(), AttributeValue::S(to_db_timestamp(last_used)));
         }
 
         if let Some(cond) = &self.conditions {
@@ -503,7 +506,7 @@ impl DynamoDbEntity for UserRole {
         }
 
         if let Some(revoked) = &self.revoked_at {
-            item.insert("revoked_at".to_string(), AttributeValue::S(revoked.to_string()));
+            item.insert("revoked_at".to_string(), AttributeValue::S(to_db_timestamp(revoked)));
         }
 
         if let Some(revoked_by) = &self.revoked_by_user_id {
@@ -518,8 +521,8 @@ impl DynamoDbEntity for UserRole {
             item.insert("metadata".to_string(), AttributeValue::S(meta.clone()));
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
@@ -0,0 +1,331 @@
+//! Shared input validation helpers used by model constructors and mutations
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use rust_decimal::Decimal;
+
+use crate::error::AppError;
+
+/// Dot-separated local part, a single `@`, dot-separated domain labels and an alphabetic TLD of 2+ chars
+static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^[A-Za-z0-9!#$%&'*+/=?^_`{|}~-]+(\.[A-Za-z0-9!#$%&'*+/=?^_`{|}~-]+)*@([A-Za-z0-9]([A-Za-z0-9-]*[A-Za-z0-9])?\.)+[A-Za-z]{2,}$"
+    ).expect("email regex is valid")
+});
+
+/// Validates the format of an email address
+///
+/// # Arguments
+///
+/// * `s` - Email address to check
+///
+/// # Returns
+///
+/// `Ok(())` if the address is well formed, `AppError::ValidationError` otherwise
+pub fn validate_email(s: &str) -> Result<(), AppError> {
+    if EMAIL_REGEX.is_match(s.trim()) {
+        Ok(())
+    } else {
+        Err(AppError::ValidationError("Invalid email format".to_string()))
+    }
+}
+
+/// `http` or `https`, a dotted host of DNS labels, an optional port, then an optional path
+static URL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?i)https?://([a-z0-9]([a-z0-9-]*[a-z0-9])?\.)+[a-z]{2,}(:[0-9]{1,5})?([/?#][^\s]*)?$"
+    ).expect("url regex is valid")
+});
+
+/// Validates that a string is an absolute `http`/`https` URL
+///
+/// # Arguments
+///
+/// * `s` - URL to check
+///
+/// # Returns
+///
+/// `Ok(())` if the URL is well formed, `AppError::ValidationError` otherwise
+pub fn validate_url(s: &str) -> Result<(), AppError> {
+    if URL_REGEX.is_match(s.trim()) {
+        Ok(())
+    } else {
+        Err(AppError::ValidationError("Invalid URL format".to_string()))
+    }
+}
+
+/// Country calling codes for the regions `normalize_phone` accepts as a default
+const CALLING_CODES: [(&str, &str); 16] = [
+    ("US", "1"),
+    ("CA", "1"),
+    ("GB", "44"),
+    ("IE", "353"),
+    ("AU", "61"),
+    ("NZ", "64"),
+    ("DE", "49"),
+    ("FR", "33"),
+    ("ES", "34"),
+    ("IT", "39"),
+    ("NL", "31"),
+    ("IN", "91"),
+    ("MX", "52"),
+    ("BR", "55"),
+    ("ZA", "27"),
+    ("JP", "81"),
+];
+
+/// Minimum digits in a meaningful phone number
+const MIN_PHONE_DIGITS: usize = 7;
+
+/// Maximum digits allowed by E.164, including the country code
+const MAX_E164_DIGITS: usize = 15;
+
+/// Looks up the country calling code for an ISO 3166 alpha-2 region
+pub fn calling_code(region: &str) -> Option<&'static str> {
+    CALLING_CODES.iter()
+        .find(|(r, _)| r.eq_ignore_ascii_case(region.trim()))
+        .map(|(_, code)| *code)
+}
+
+/// Normalizes a phone number to E.164 (`+<country code><number>`)
+///
+/// Spaces, dashes, dots and parentheses are stripped. Numbers without a leading `+`
+/// are treated as national numbers in `default_region`, dropping a trunk `0`
+/// (or a leading `1` for North American numbers).
+///
+/// # Arguments
+///
+/// * `raw` - Phone number as entered
+/// * `default_region` - ISO 3166 alpha-2 region used for national numbers
+///
+/// # Returns
+///
+/// The E.164 string, or `AppError::ValidationError` for invalid characters, too few
+/// digits, too many digits or an unsupported region
+pub fn normalize_phone(raw: &str, default_region: &str) -> Result<String, AppError> {
+    let trimmed = raw.trim();
+    let (international, rest) = match trimmed.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed),
+    };
+
+    let mut digits = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' | '-' | '.' | '(' | ')' => {}
+            _ => {
+                return Err(
+                    AppError::ValidationError(
+                        format!("Phone number contains invalid character '{}'", c)
+                    )
+                );
+            }
+        }
+    }
+
+    if digits.len() < MIN_PHONE_DIGITS {
+        return Err(
+            AppError::ValidationError(
+                format!("Phone number must have at least {} digits", MIN_PHONE_DIGITS)
+            )
+        );
+    }
+
+    let e164_digits = if international {
+        digits
+    } else {
+        let code = calling_code(default_region).ok_or_else(|| {
+            AppError::ValidationError(format!("Unsupported phone region {}", default_region))
+        })?;
+
+        let national = if code == "1" {
+            digits.strip_prefix('1').filter(|_| digits.len() == 11).unwrap_or(&digits)
+        } else {
+            digits.strip_prefix('0').unwrap_or(&digits)
+        };
+
+        format!("{}{}", code, national)
+    };
+
+    if e164_digits.len() > MAX_E164_DIGITS {
+        return Err(
+            AppError::ValidationError(
+                format!("Phone number cannot exceed {} digits", MAX_E164_DIGITS)
+            )
+        );
+    }
+
+    Ok(format!("+{}", e164_digits))
+}
+
+/// Formats a decimal in the canonical form it is persisted as
+///
+/// Plain `-1234.5678` notation with trailing zeros removed: no grouping separators,
+/// no exponent and independent of locale.
+pub fn canonical_decimal(value: &Decimal) -> String {
+    value.normalize().to_string()
+}
+
+/// Parses a decimal written by `canonical_decimal`
+///
+/// Anything other than an optional `-`, digits and at most one `.` is rejected, so
+/// locale-formatted values such as `1,234.56` fail loudly instead of being misread.
+///
+/// # Returns
+///
+/// The exact decimal, or `AppError::ValidationError` for malformed or out-of-precision input
+pub fn parse_decimal(s: &str) -> Result<Decimal, AppError> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, "0"));
+
+    let well_formed =
+        !whole.is_empty() &&
+        !fraction.is_empty() &&
+        whole.chars().all(|c| c.is_ascii_digit()) &&
+        fraction.chars().all(|c| c.is_ascii_digit());
+
+    if !well_formed {
+        return Err(AppError::ValidationError(format!("Invalid decimal value '{}'", s)));
+    }
+
+    Decimal::from_str_exact(s).map_err(|e| {
+        AppError::ValidationError(format!("Invalid decimal value '{}': {}", s, e))
+    })
+}
+
+/// Reduces an enum spelling to lowercase letters and digits
+fn enum_key(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Finds the variant whose name matches `s`, ignoring case and word separators
+///
+/// `snake_case`, `camelCase`, `SCREAMING_SNAKE_CASE` (the GraphQL enum spelling) and
+/// `kebab-case` input all match, so `hireOut`, `hire_out` and `HIRE_OUT` are the same
+/// name. Every enum's `from_string` goes through here so the rules cannot drift.
+///
+/// # Arguments
+///
+/// * `s` - Input to parse
+/// * `variants` - Each variant paired with its stored name
+///
+/// # Returns
+///
+/// The matching variant, `None` if no name matches
+pub fn parse_enum<T, const N: usize>(s: &str, variants: [(&str, T); N]) -> Option<T> {
+    let key = enum_key(s);
+
+    variants
+        .into_iter()
+        .find(|(name, _)| enum_key(name) == key)
+        .map(|(_, variant)| variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_emails() {
+        for email in ["a@b.co", "first.last+tag@mail.example.com", "o'brien@example.ie"] {
+            assert!(validate_email(email).is_ok(), "{}", email);
+        }
+    }
+
+    #[test]
+    fn test_invalid_emails() {
+        for email in [
+            "user@@x.com",
+            "no-at-sign",
+            "user@example.com.",
+            "user.@example.com",
+            "user@example.",
+            "@.",
+            "a@b",
+            "a@b.c",
+            "",
+        ] {
+            assert!(
+                matches!(validate_email(email), Err(AppError::ValidationError(_))),
+                "{}",
+                email
+            );
+        }
+    }
+
+    #[test]
+    fn test_urls() {
+        for url in ["https://example.com", "http://support.acme.co.uk:8080/parts?id=1"] {
+            assert!(validate_url(url).is_ok(), "{}", url);
+        }
+
+        let invalid = ["example.com", "ftp://example.com", "https://localhost", "https://a b.com", ""];
+        for url in invalid {
+            assert!(matches!(validate_url(url), Err(AppError::ValidationError(_))), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_normalize_us_national_number() {
+        assert_eq!(normalize_phone("(555) 123-4567", "US").unwrap(), "+15551234567");
+        assert_eq!(normalize_phone("1-555-123-4567", "US").unwrap(), "+15551234567");
+    }
+
+    #[test]
+    fn test_normalize_international_number() {
+        assert_eq!(normalize_phone("+44 20 7946 0958", "US").unwrap(), "+442079460958");
+        assert_eq!(normalize_phone("020 7946 0958", "GB").unwrap(), "+442079460958");
+    }
+
+    #[test]
+    fn test_normalize_rejects_bad_numbers() {
+        for (raw, region) in [("123-45", "US"), ("555-CALL-NOW", "US"), ("555 123 4567", "XX")] {
+            assert!(
+                matches!(normalize_phone(raw, region), Err(AppError::ValidationError(_))),
+                "{}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_decimal_round_trip_preserves_precision() {
+        let value = Decimal::new(12345678, 4);
+
+        let stored = canonical_decimal(&value);
+
+        assert_eq!(stored, "1234.5678");
+        assert_eq!(parse_decimal(&stored).unwrap(), value);
+        assert_eq!(canonical_decimal(&Decimal::new(1500, 3)), "1.5");
+    }
+
+    #[test]
+    fn test_parse_decimal_rejects_locale_formats() {
+        for raw in ["1,234.56", "1.234,56", "1_234", "1e3", " 12", "12.", ".5", "", "-"] {
+            assert!(
+                matches!(parse_decimal(raw), Err(AppError::ValidationError(_))),
+                "{}",
+                raw
+            );
+        }
+        assert_eq!(parse_decimal("-0.25").unwrap(), Decimal::new(-25, 2));
+    }
+
+    #[test]
+    fn test_parse_enum_ignores_case_and_separators() {
+        let variants = || [("in_progress", 1), ("hireOut", 2)];
+
+        for input in ["in_progress", "IN_PROGRESS", "InProgress", "inProgress", "in-progress"] {
+            assert_eq!(parse_enum(input, variants()), Some(1), "{}", input);
+        }
+        for input in ["hireOut", "hire_out", "HIRE_OUT", "hireout"] {
+            assert_eq!(parse_enum(input, variants()), Some(2), "{}", input);
+        }
+        assert_eq!(parse_enum("in_progres", variants()), None);
+    }
+}
@@ -7,7 +7,12 @@ use serde::{ Deserialize, Serialize };
 use serde_json::Value as Json;
 use tracing::info;
 
-use crate::error::AppError;
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::{ AppError, FieldError },
+    models::validation::{ normalize_phone, parse_enum, validate_email },
+    DynamoDbEntity,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 
@@ -38,15 +43,14 @@ impl VendorStatus {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<VendorStatus, AppError> {
-        match s {
-            "active" => Ok(Self::Active),
-            "inactive" => Ok(Self::Inactive),
-            "pending" => Ok(Self::Pending),
-            "suspended" => Ok(Self::Suspended),
-            "blacklisted" => Ok(Self::Blacklisted),
-            "terminated" => Ok(Self::Terminated),
-            _ => Err(AppError::ValidationError("Invalid vendor status".to_string())),
-        }
+        parse_enum(s, [
+            ("active", Self::Active),
+            ("inactive", Self::Inactive),
+            ("pending", Self::Pending),
+            ("suspended", Self::Suspended),
+            ("blacklisted", Self::Blacklisted),
+            ("terminated", Self::Terminated),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid vendor status".to_string()))
     }
 }
 
@@ -74,13 +78,12 @@ impl VendorTier {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<VendorTier, AppError> {
-        match s {
-            "preferred" => Ok(Self::Preferred),
-            "standard" => Ok(Self::Standard),
-            "occasional" => Ok(Self::Occasional),
-            "trial" => Ok(Self::Trial),
-            _ => Err(AppError::ValidationError("Invalid vendor tier".to_string())),
-        }
+        parse_enum(s, [
+            ("preferred", Self::Preferred),
+            ("standard", Self::Standard),
+            ("occasional", Self::Occasional),
+            ("trial", Self::Trial),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid vendor tier".to_string()))
     }
 }
 
@@ -229,6 +232,7 @@ impl Vendor {
     /// * `auto_approval_limit` - Optional auto approval limit
     /// * `notes` - Optional notes
     /// * `created_by` - Optional creator user ID
+    /// * `default_phone_region` - Region used to normalize national phone numbers to E.164
     ///
     /// # Returns
     ///
@@ -272,84 +276,79 @@ impl Vendor {
         approval_required: &bool,
         auto_approval_limit: Option<f64>,
         notes: Option<String>,
-        created_by: Option<String>
+        created_by: Option<String>,
+        default_phone_region: &str
     ) -> Result<Self, AppError> {
         let now = Utc::now();
 
-        // Validate required fields
-        if name.trim().is_empty() {
-            return Err(AppError::ValidationError("Vendor name cannot be empty".to_string()));
-        }
+        // Collect every failure so callers can report all bad fields at once
+        let mut errors: Vec<FieldError> = Vec::new();
 
-        if vendor_category_id.trim().is_empty() {
-            return Err(AppError::ValidationError("Vendor category ID cannot be empty".to_string()));
-        }
-
-        if phone_number.trim().is_empty() {
-            return Err(AppError::ValidationError("Phone number cannot be empty".to_string()));
-        }
-
-        if email_address.trim().is_empty() {
-            return Err(AppError::ValidationError("Email address cannot be empty".to_string()));
-        }
-
-        // Validate email format (basic validation)
-        if !email_address.contains('@') || !email_address.contains('.') {
-            return Err(AppError::ValidationError("Invalid email format".to_string()));
-        }
-
-        if tax_id.trim().is_empty() {
-            return Err(AppError::ValidationError("Tax ID cannot be empty".to_string()));
-        }
-
-        if payment_terms.trim().is_empty() {
-            return Err(AppError::ValidationError("Payment terms cannot be empty".to_string()));
-        }
-
-        if currency.trim().is_empty() {
-            return Err(AppError::ValidationError("Currency cannot be empty".to_string()));
-        }
-
-        if primary_contact_name.trim().is_empty() {
-            return Err(
-                AppError::ValidationError("Primary contact name cannot be empty".to_string())
-            );
-        }
-
-        if primary_contact_title.trim().is_empty() {
-            return Err(
-                AppError::ValidationError("Primary contact title cannot be empty".to_string())
-            );
-        }
-
-        if compliance_status.trim().is_empty() {
-            return Err(AppError::ValidationError("Compliance status cannot be empty".to_string()));
+        // Validate required fields
+        let required = [
+            ("name", &name, "Vendor name cannot be empty"),
+            ("vendor_category_id", &vendor_category_id, "Vendor category ID cannot be empty"),
+            ("phone_number", &phone_number, "Phone number cannot be empty"),
+            ("email_address", &email_address, "Email address cannot be empty"),
+            ("tax_id", &tax_id, "Tax ID cannot be empty"),
+            ("payment_terms", &payment_terms, "Payment terms cannot be empty"),
+            ("currency", &currency, "Currency cannot be empty"),
+            ("primary_contact_name", &primary_contact_name, "Primary contact name cannot be empty"),
+            (
+                "primary_contact_title",
+                &primary_contact_title,
+                "Primary contact title cannot be empty",
+            ),
+            ("compliance_status", &compliance_status, "Compliance status cannot be empty"),
+            (
+                "preferred_communication",
+                &preferred_communication,
+                "Preferred communication cannot be empty",
+            ),
+        ];
+
+        for (field, value, message) in required {
+            if value.trim().is_empty() {
+                errors.push(FieldError::new(field, message));
+            }
         }
 
-        if preferred_communication.trim().is_empty() {
-            return Err(
-                AppError::ValidationError("Preferred communication cannot be empty".to_string())
-            );
+        // Normalize phone numbers to E.164
+        let phone_number = if phone_number.trim().is_empty() {
+            phone_number
+        } else {
+            normalize_phone_field("phone_number", phone_number, default_phone_region, &mut errors)
+        };
+
+        let secondary_phone = secondary_phone
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| {
+                normalize_phone_field("secondary_phone", p, default_phone_region, &mut errors)
+            });
+
+        let primary_contact_phone = primary_contact_phone
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| {
+                normalize_phone_field("primary_contact_phone", p, default_phone_region, &mut errors)
+            });
+
+        // Validate email format
+        if !email_address.trim().is_empty() && validate_email(&email_address).is_err() {
+            errors.push(FieldError::new("email_address", "Invalid email format"));
         }
 
         // Validate secondary email if provided
         if let Some(ref sec_email) = secondary_email {
-            if
-                !sec_email.trim().is_empty() &&
-                (!sec_email.contains('@') || !sec_email.contains('.'))
-            {
-                return Err(AppError::ValidationError("Invalid secondary email format".to_string()));
+            if !sec_email.trim().is_empty() && validate_email(sec_email).is_err() {
+                errors.push(FieldError::new("secondary_email", "Invalid secondary email format"));
             }
         }
 
         // Validate primary contact email if provided
         if let Some(ref contact_email) = primary_contact_email {
-            if
-                !contact_email.trim().is_empty() &&
-                (!contact_email.contains('@') || !contact_email.contains('.'))
-            {
-                return Err(
-                    AppError::ValidationError("Invalid primary contact email format".to_string())
+            if !contact_email.trim().is_empty() && validate_email(contact_email).is_err() {
+                errors.push(
+                    FieldError::new("primary_contact_email", "Invalid primary contact email format")
                 );
             }
         }
@@ -357,34 +356,45 @@ impl Vendor {
         // Validate credit limit
         if let Some(limit) = credit_limit {
             if limit < 0.0 {
-                return Err(
-                    AppError::ValidationError("Credit limit cannot be negative".to_string())
-                );
+                errors.push(FieldError::new("credit_limit", "Credit limit cannot be negative"));
             }
         }
 
         // Validate auto approval limit
         if let Some(limit) = auto_approval_limit {
             if limit < 0.0 {
-                return Err(
-                    AppError::ValidationError("Auto approval limit cannot be negative".to_string())
+                errors.push(
+                    FieldError::new("auto_approval_limit", "Auto approval limit cannot be negative")
                 );
             }
         }
 
         // Validate contract dates
-        if let (Some(start), Some(end)) = (&contract_start_date, &contract_end_date) {
-            if end <= start {
-                return Err(
-                    AppError::ValidationError(
-                        "Contract end date must be after start date".to_string()
-                    )
-                );
-            }
+        if
+            let Err(AppError::InvalidField { field, message }) = Self::validate_contract_dates(
+                contract_start_date,
+                contract_end_date
+            )
+        {
+            errors.push(FieldError::new(field, message));
+        }
+
+        let status_enum = VendorStatus::from_string(&status).ok();
+        if status_enum.is_none() {
+            errors.push(FieldError::new("status", "Invalid vendor status"));
         }
 
-        let status_enum = VendorStatus::from_string(&status)?;
-        let tier_enum = VendorTier::from_string(&tier)?;
+        let tier_enum = VendorTier::from_string(&tier).ok();
+        if tier_enum.is_none() {
+            errors.push(FieldError::new("tier", "Invalid vendor tier"));
+        }
+
+        let (status_enum, tier_enum) = match (status_enum, tier_enum) {
+            (Some(status_enum), Some(tier_enum)) if errors.is_empty() => (status_enum, tier_enum),
+            _ => {
+                return Err(AppError::ValidationErrors(errors));
+            }
+        };
 
         Ok(Self {
             id,
@@ -435,6 +445,16 @@ impl Vendor {
             updated_at: now,
         })
     }
+}
+
+impl DynamoDbEntity for Vendor {
+    fn table_name() -> &'static str {
+        "Vendors"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
 
     /// Creates Vendor instance from DynamoDB item
     ///
@@ -445,7 +465,7 @@ impl Vendor {
     /// # Returns
     ///
     /// 'Some' Vendor if item fields match, 'None' otherwise
-    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
         // info!("calling from_item with: {:?}", &item);
 
         let id = item.get("id")?.as_s().ok()?.to_string();
@@ -551,17 +571,17 @@ impl Vendor {
         let contract_start_date = item
             .get("contract_start_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let contract_end_date = item
             .get("contract_end_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let last_order_date = item
             .get("last_order_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let total_orders = item
             .get("total_orders")
@@ -629,10 +649,8 @@ impl Vendor {
             })
             .unwrap_or_default();
 
-        let approval_required = item
-            .get("approval_required")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        // Vendors stored without the flag fail safe by requiring approval
+        let approval_required = get_bool(item, "approval_required", true);
 
         let auto_approval_limit = item
             .get("auto_approval_limit")
@@ -652,13 +670,13 @@ impl Vendor {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -702,7 +720,7 @@ impl Vendor {
             tags,
             custom_fields,
             attachments,
-            approval_required: *approval_required,
+            approval_required,
             auto_approval_limit,
             notes,
             created_by,
@@ -723,7 +741,7 @@ impl Vendor {
     /// # Returns
     ///
     /// HashMap representing DB item for Vendor instance
-    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
         let mut item = HashMap::new();
 
         item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
@@ -829,16 +847,22 @@ impl Vendor {
         if let Some(start_date) = &self.contract_start_date {
             item.insert(
                 "contract_start_date".to_string(),
-                AttributeValue::S(start_date.to_string())
+                AttributeValue::S(to_db_timestamp(start_date))
             );
         }
 
         if let Some(end_date) = &self.contract_end_date {
-            item.insert("contract_end_date".to_string(), AttributeValue::S(end_date.to_string()));
+            item.insert(
+                "contract_end_date".to_string(),
+                AttributeValue::S(to_db_timestamp(end_date))
+            );
         }
 
         if let Some(last_order) = &self.last_order_date {
-            item.insert("last_order_date".to_string(), AttributeValue::S(last_order.to_string()));
+            item.insert(
+                "last_order_date".to_string(),
+                AttributeValue::S(to_db_timestamp(last_order))
+            );
         }
 
         item.insert("total_orders".to_string(), AttributeValue::N(self.total_orders.to_string()));
@@ -906,12 +930,15 @@ impl Vendor {
             item.insert("created_by".to_string(), AttributeValue::S(creator.clone()));
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
+}
 
+/// Domain methods for Vendor
+impl Vendor {
     /// Checks if the vendor is currently active and usable
     pub fn is_active(&self) -> bool {
         matches!(self.status, VendorStatus::Active)
@@ -1007,6 +1034,21 @@ impl Vendor {
         Ok(())
     }
 
+    /// Blacklists the vendor with a reason
+    pub fn blacklist(&mut self, reason: Option<String>) -> Result<(), AppError> {
+        if matches!(self.status, VendorStatus::Blacklisted) {
+            return Err(AppError::ValidationError("Vendor is already blacklisted".to_string()));
+        }
+
+        self.status = VendorStatus::Blacklisted;
+        if let Some(reason_text) = reason {
+            let current_notes = self.notes.clone().unwrap_or_default();
+            self.notes = Some(format!("{}; BLACKLISTED: {}", current_notes, reason_text));
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Reactivates a suspended vendor
     pub fn reactivate(&mut self) -> Result<(), AppError> {
         if !matches!(self.status, VendorStatus::Suspended | VendorStatus::Inactive) {
@@ -1026,4 +1068,262 @@ impl Vendor {
     pub fn average_order_value(&self) -> f64 {
         if self.total_orders > 0 { self.total_spent / (self.total_orders as f64) } else { 0.0 }
     }
+
+    /// Checks if the contract ends after `now` but no more than `within_days` later
+    ///
+    /// Vendors without a contract end date never match.
+    pub fn contract_expires_within(&self, within_days: i64, now: DateTime<Utc>) -> bool {
+        self.contract_end_date.is_some_and(|end_date| {
+            end_date > now && end_date <= now + chrono::Duration::days(within_days)
+        })
+    }
+
+    /// Ensures a contract ends after it starts; either date may be absent
+    pub fn validate_contract_dates(
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>
+    ) -> Result<(), AppError> {
+        match (start, end) {
+            (Some(start), Some(end)) if end <= start =>
+                Err(
+                    AppError::invalid_field(
+                        "contract_end_date",
+                        "Contract end date must be after start date"
+                    )
+                ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Changes either contract date, validating the result against the stored other date
+    ///
+    /// `None` leaves that date as stored. Nothing changes if the combination is invalid.
+    pub fn update_contract_dates(
+        &mut self,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>
+    ) -> Result<(), AppError> {
+        let start = start.or(self.contract_start_date);
+        let end = end.or(self.contract_end_date);
+
+        Self::validate_contract_dates(start, end)?;
+
+        self.contract_start_date = start;
+        self.contract_end_date = end;
+        Ok(())
+    }
+
+    /// Non-blocking advisories about data worth completing
+    ///
+    /// Unlike the checks in `Vendor::new` these never prevent a save; they are
+    /// returned to the client so it can suggest improvements.
+    pub fn validate_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if self.website.as_deref().is_none_or(|website| website.trim().is_empty()) {
+            warnings.push("Consider adding a website".to_string());
+        }
+
+        if self.secondary_email.as_deref().is_none_or(|email| email.trim().is_empty()) {
+            warnings.push("Consider adding a secondary email".to_string());
+        }
+
+        if self.credit_limit == Some(0.0) {
+            warnings.push("Credit limit is zero, so no orders can be placed on credit".to_string());
+        }
+
+        warnings
+    }
+}
+
+/// Vendors whose contract expires within `within_days` of `now`, soonest first
+pub fn expiring_contracts(vendors: Vec<Vendor>, within_days: i64, now: DateTime<Utc>) -> Vec<Vendor> {
+    let mut expiring: Vec<Vendor> = vendors
+        .into_iter()
+        .filter(|vendor| vendor.contract_expires_within(within_days, now))
+        .collect();
+
+    expiring.sort_by(|a, b| {
+        a.contract_end_date.cmp(&b.contract_end_date).then_with(|| a.id.cmp(&b.id))
+    });
+
+    expiring
+}
+
+/// Normalizes a phone field, recording a `FieldError` and keeping the raw value on failure
+fn normalize_phone_field(
+    field: &str,
+    raw: String,
+    default_region: &str,
+    errors: &mut Vec<FieldError>
+) -> String {
+    match normalize_phone(&raw, default_region) {
+        Ok(normalized) => normalized,
+        Err(e) => {
+            let message = match e {
+                AppError::ValidationError(message) => message,
+                other => other.to_string(),
+            };
+            errors.push(FieldError::new(field, message));
+            raw
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vendor(id: &str, contract_end_date: Option<DateTime<Utc>>) -> Vendor {
+        Vendor::new(
+            id.to_string(),
+            format!("Vendor {}", id),
+            None,
+            None,
+            "vendor-category-1".to_string(),
+            "active".to_string(),
+            "standard".to_string(),
+            "+15555550100".to_string(),
+            None,
+            "orders@vendor.example".to_string(),
+            None,
+            None,
+            "12-3456789".to_string(),
+            None,
+            "Net 30".to_string(),
+            "USD".to_string(),
+            None,
+            "Jane Doe".to_string(),
+            "Account Manager".to_string(),
+            None,
+            None,
+            None,
+            None,
+            vec![],
+            "verified".to_string(),
+            None,
+            None,
+            contract_end_date,
+            None,
+            "email".to_string(),
+            None,
+            None,
+            vec![],
+            None,
+            vec![],
+            &false,
+            None,
+            None,
+            Some("user-1".to_string()),
+            "US"
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_minimal_vendor_warns_about_optional_details() {
+        let mut vendor = vendor("vendor-1", None);
+        vendor.credit_limit = Some(0.0);
+
+        assert_eq!(vendor.validate_warnings(), vec![
+            "Consider adding a website".to_string(),
+            "Consider adding a secondary email".to_string(),
+            "Credit limit is zero, so no orders can be placed on credit".to_string()
+        ]);
+    }
+
+    #[test]
+    fn test_complete_vendor_has_no_warnings() {
+        let mut vendor = vendor("vendor-1", None);
+        vendor.website = Some("https://vendor.example".to_string());
+        vendor.secondary_email = Some("billing@vendor.example".to_string());
+        vendor.credit_limit = Some(5000.0);
+
+        assert!(vendor.validate_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_missing_approval_required_defaults_to_required() {
+        let mut item = vendor("vendor-1", None).to_item();
+        item.remove("approval_required");
+
+        let restored = Vendor::from_item(&item).unwrap();
+
+        assert!(restored.approval_required);
+    }
+
+    #[test]
+    fn test_stored_approval_required_is_kept() {
+        let item = vendor("vendor-1", None).to_item();
+
+        let restored = Vendor::from_item(&item).unwrap();
+
+        assert!(!restored.approval_required);
+    }
+
+    #[test]
+    fn test_expiring_contracts_only_returns_vendors_inside_window() {
+        let now = Utc::now();
+        let vendors = vec![
+            vendor("vendor-400", Some(now + chrono::Duration::days(400))),
+            vendor("vendor-10", Some(now + chrono::Duration::days(10))),
+            vendor("vendor-40", Some(now + chrono::Duration::days(40))),
+            vendor("vendor-expired", Some(now - chrono::Duration::days(1))),
+            vendor("vendor-no-contract", None)
+        ];
+
+        let expiring = expiring_contracts(vendors, 30, now);
+
+        let ids: Vec<&str> = expiring.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, vec!["vendor-10"]);
+    }
+
+    #[test]
+    fn test_expiring_contracts_sorts_soonest_first() {
+        let now = Utc::now();
+        let vendors = vec![
+            vendor("vendor-40", Some(now + chrono::Duration::days(40))),
+            vendor("vendor-10", Some(now + chrono::Duration::days(10)))
+        ];
+
+        let expiring = expiring_contracts(vendors, 60, now);
+
+        let ids: Vec<&str> = expiring.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, vec!["vendor-10", "vendor-40"]);
+    }
+
+    fn contracted_vendor() -> (Vendor, DateTime<Utc>, DateTime<Utc>) {
+        let start = Utc::now() - chrono::Duration::days(100);
+        let end = start + chrono::Duration::days(365);
+        let mut vendor = vendor("vendor-1", Some(end));
+        vendor.contract_start_date = Some(start);
+        (vendor, start, end)
+    }
+
+    #[test]
+    fn test_updating_end_before_stored_start_is_rejected() {
+        let (mut vendor, start, end) = contracted_vendor();
+
+        let result = vendor.update_contract_dates(None, Some(start - chrono::Duration::days(1)));
+
+        match result {
+            Err(AppError::InvalidField { field, .. }) => assert_eq!(field, "contract_end_date"),
+            other => panic!("expected an invalid contract_end_date, got {:?}", other),
+        }
+        assert_eq!(vendor.contract_end_date, Some(end));
+
+        let result = vendor.update_contract_dates(Some(end + chrono::Duration::days(1)), None);
+        assert!(result.is_err());
+        assert_eq!(vendor.contract_start_date, Some(start));
+    }
+
+    #[test]
+    fn test_extending_contract_keeps_stored_start() {
+        let (mut vendor, start, end) = contracted_vendor();
+        let extended = end + chrono::Duration::days(365);
+
+        vendor.update_contract_dates(None, Some(extended)).unwrap();
+
+        assert_eq!(vendor.contract_start_date, Some(start));
+        assert_eq!(vendor.contract_end_date, Some(extended));
+    }
 }
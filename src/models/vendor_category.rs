@@ -6,7 +6,12 @@ use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::error::AppError;
+use crate::{
+    db::common::{ from_db_timestamp, get_bool, to_db_timestamp },
+    error::AppError,
+    models::validation::parse_enum,
+    DynamoDbEntity,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -42,18 +47,17 @@ impl CategoryType {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<CategoryType, AppError> {
-        match s {
-            "service" => Ok(Self::Service),
-            "supplier" => Ok(Self::Supplier),
-            "contractor" => Ok(Self::Contractor),
-            "consultant" => Ok(Self::Consultant),
-            "technology" => Ok(Self::Technology),
-            "equipment" => Ok(Self::Equipment),
-            "emergency" => Ok(Self::Emergency),
-            "utility" => Ok(Self::Utility),
-            "other" => Ok(Self::Other),
-            _ => Err(AppError::ValidationError("Invalid category type".to_string())),
-        }
+        parse_enum(s, [
+            ("service", Self::Service),
+            ("supplier", Self::Supplier),
+            ("contractor", Self::Contractor),
+            ("consultant", Self::Consultant),
+            ("technology", Self::Technology),
+            ("equipment", Self::Equipment),
+            ("emergency", Self::Emergency),
+            ("utility", Self::Utility),
+            ("other", Self::Other),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid category type".to_string()))
     }
 }
 
@@ -81,13 +85,12 @@ impl CategoryStatus {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<CategoryStatus, AppError> {
-        match s {
-            "active" => Ok(Self::Active),
-            "inactive" => Ok(Self::Inactive),
-            "deprecated" => Ok(Self::Deprecated),
-            "archived" => Ok(Self::Archived),
-            _ => Err(AppError::ValidationError("Invalid category status".to_string())),
-        }
+        parse_enum(s, [
+            ("active", Self::Active),
+            ("inactive", Self::Inactive),
+            ("deprecated", Self::Deprecated),
+            ("archived", Self::Archived),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid category status".to_string()))
     }
 }
 
@@ -257,6 +260,17 @@ impl VendorCategory {
         })
     }
 
+}
+
+impl DynamoDbEntity for VendorCategory {
+    fn table_name() -> &'static str {
+        "VendorCategories"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+
     /// Creates VendorCategory instance from DynamoDB item
     ///
     /// # Arguments
@@ -266,7 +280,7 @@ impl VendorCategory {
     /// # Returns
     ///
     /// 'Some' VendorCategory if item fields match, 'None' otherwise
-    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
         // info!("calling from_item with: {:?}", &item);
 
         let id = item.get("id")?.as_s().ok()?.to_string();
@@ -367,10 +381,7 @@ impl VendorCategory {
             .and_then(|s| s.parse::<i32>().ok())
             .unwrap_or(0);
 
-        let active = item
-            .get("active")
-            .and_then(|v| v.as_bool().ok())
-            .unwrap_or(&true);
+        let active = get_bool(item, "active", true);
 
         let created_by = item
             .get("created_by")
@@ -380,13 +391,13 @@ impl VendorCategory {
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let res = Some(Self {
@@ -408,7 +419,7 @@ impl VendorCategory {
             risk_level,
             auto_approval_limit,
             vendor_count,
-            active: *active,
+            active,
             created_by,
             created_at,
             updated_at,
@@ -427,7 +438,7 @@ impl VendorCategory {
     /// # Returns
     ///
     /// HashMap representing DB item for VendorCategory instance
-    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
         let mut item = HashMap::new();
 
         item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
@@ -499,12 +510,15 @@ impl VendorCategory {
             item.insert("created_by".to_string(), AttributeValue::S(creator.clone()));
         }
 
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
+}
 
+/// Domain methods for VendorCategory
+impl VendorCategory {
     /// Checks if this category is at the root level
     pub fn is_root_category(&self) -> bool {
         self.parent_category_id.is_none() && self.level == 0
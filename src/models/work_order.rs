@@ -7,7 +7,17 @@ use rust_decimal::Decimal;
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ error::AppError, DynamoDbEntity };
+use crate::{
+    config::SlaConfig,
+    db::common::{ from_db_timestamp, to_db_timestamp },
+    error::AppError,
+    models::{
+        asset::{ Asset, AssetCurrentStatusOptions },
+        maintenance_schedule::MaintenanceSchedule,
+        validation::{ canonical_decimal, parse_decimal, parse_enum },
+    },
+    DynamoDbEntity,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -33,13 +43,12 @@ impl WorkOrderSeverity {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<WorkOrderSeverity, AppError> {
-        match s {
-            "critical" => Ok(Self::Critical),
-            "important" => Ok(Self::Important),
-            "valuable" => Ok(Self::Valuable),
-            "nice" => Ok(Self::Nice),
-            _ => Err(AppError::ValidationError("Invalid work order severity".to_string())),
-        }
+        parse_enum(s, [
+            ("critical", Self::Critical),
+            ("important", Self::Important),
+            ("valuable", Self::Valuable),
+            ("nice", Self::Nice),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid work order severity".to_string()))
     }
 
     pub fn description(&self) -> &str {
@@ -85,13 +94,12 @@ impl WorkOrderDifficulty {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<WorkOrderDifficulty, AppError> {
-        match s {
-            "normal" => Ok(Self::Normal),
-            "extended" => Ok(Self::Extended),
-            "advanced" => Ok(Self::Advanced),
-            "hireOut" => Ok(Self::HireOut),
-            _ => Err(AppError::ValidationError("Invalid work order difficulty".to_string())),
-        }
+        parse_enum(s, [
+            ("normal", Self::Normal),
+            ("extended", Self::Extended),
+            ("advanced", Self::Advanced),
+            ("hireOut", Self::HireOut),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid work order difficulty".to_string()))
     }
 
     pub fn description(&self) -> &str {
@@ -137,13 +145,12 @@ impl WorkOrderCost {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<WorkOrderCost, AppError> {
-        match s {
-            "one" => Ok(Self::One),
-            "two" => Ok(Self::Two),
-            "three" => Ok(Self::Three),
-            "four" => Ok(Self::Four),
-            _ => Err(AppError::ValidationError("Invalid work order cost".to_string())),
-        }
+        parse_enum(s, [
+            ("one", Self::One),
+            ("two", Self::Two),
+            ("three", Self::Three),
+            ("four", Self::Four),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid work order cost".to_string()))
     }
 
     pub fn description(&self) -> &str {
@@ -163,6 +170,18 @@ impl WorkOrderCost {
             WorkOrderCost::Four => 4,
         }
     }
+
+    /// Midpoint of the band in dollars, the baseline actual costs are compared to
+    ///
+    /// The top band is open-ended, so its floor of $2,000 is used instead.
+    pub fn midpoint(&self) -> Decimal {
+        match self {
+            WorkOrderCost::One => Decimal::new(125, 0),
+            WorkOrderCost::Two => Decimal::new(3745, 1),
+            WorkOrderCost::Three => Decimal::new(1250, 0),
+            WorkOrderCost::Four => Decimal::new(2000, 0),
+        }
+    }
 }
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize, Hash)]
@@ -201,18 +220,66 @@ impl WorkOrderStatus {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<WorkOrderStatus, AppError> {
-        match s {
-            "draft" => Ok(Self::Draft),
-            "scheduled" => Ok(Self::Scheduled),
-            "in_progress" => Ok(Self::InProgress),
-            "on_hold" => Ok(Self::OnHold),
-            "completed" => Ok(Self::Completed),
-            "cancelled" => Ok(Self::Cancelled),
-            "failed" => Ok(Self::Failed),
-            "deferred" => Ok(Self::Deferred),
-            "waiting_parts" => Ok(Self::WaitingParts),
-            "waiting_approval" => Ok(Self::WaitingApproval),
-            _ => Err(AppError::ValidationError("Invalid work order status".to_string())),
+        parse_enum(s, [
+            ("draft", Self::Draft),
+            ("scheduled", Self::Scheduled),
+            ("in_progress", Self::InProgress),
+            ("on_hold", Self::OnHold),
+            ("completed", Self::Completed),
+            ("cancelled", Self::Cancelled),
+            ("failed", Self::Failed),
+            ("deferred", Self::Deferred),
+            ("waiting_parts", Self::WaitingParts),
+            ("waiting_approval", Self::WaitingApproval),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid work order status".to_string()))
+    }
+
+    /// Every status `WorkOrder::is_open` accepts, for reading open orders by `StatusIndex`
+    pub(crate) const OPEN: [WorkOrderStatus; 8] = [
+        WorkOrderStatus::Draft,
+        WorkOrderStatus::Scheduled,
+        WorkOrderStatus::InProgress,
+        WorkOrderStatus::OnHold,
+        WorkOrderStatus::Failed,
+        WorkOrderStatus::Deferred,
+        WorkOrderStatus::WaitingParts,
+        WorkOrderStatus::WaitingApproval,
+    ];
+
+    /// Whether the work order can no longer change status
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            WorkOrderStatus::Completed | WorkOrderStatus::Cancelled | WorkOrderStatus::Failed
+        )
+    }
+
+    /// Checks whether moving from this status to `next` is a legal transition
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - Status the work order would move to
+    ///
+    /// # Returns
+    ///
+    /// `true` if the transition is part of the work order lifecycle, `false` otherwise
+    pub fn can_transition_to(&self, next: WorkOrderStatus) -> bool {
+        use WorkOrderStatus::*;
+
+        // Any non-terminal work order may be cancelled
+        if next == Cancelled {
+            return !self.is_terminal();
+        }
+
+        match self {
+            Draft => matches!(next, Scheduled | WaitingApproval),
+            WaitingApproval => matches!(next, Scheduled | Draft),
+            Scheduled => matches!(next, InProgress | OnHold | Deferred),
+            InProgress => matches!(next, OnHold | Completed | Failed | WaitingParts),
+            OnHold => matches!(next, Scheduled | InProgress),
+            WaitingParts => matches!(next, InProgress | OnHold),
+            Deferred => matches!(next, Scheduled),
+            Completed | Cancelled | Failed => false,
         }
     }
 }
@@ -243,13 +310,23 @@ impl WorkOrderPriority {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<WorkOrderPriority, AppError> {
-        match s {
-            "low" => Ok(Self::Low),
-            "normal" => Ok(Self::Normal),
-            "high" => Ok(Self::High),
-            "urgent" => Ok(Self::Urgent),
-            "emergency" => Ok(Self::Emergency),
-            _ => Err(AppError::ValidationError("Invalid work order priority".to_string())),
+        parse_enum(s, [
+            ("low", Self::Low),
+            ("normal", Self::Normal),
+            ("high", Self::High),
+            ("urgent", Self::Urgent),
+            ("emergency", Self::Emergency),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid work order priority".to_string()))
+    }
+
+    /// Urgency from 1 (emergency) to 5 (low), matching the ordering of `severity.numeric_level`
+    pub fn numeric_level(&self) -> u8 {
+        match self {
+            WorkOrderPriority::Emergency => 1,
+            WorkOrderPriority::Urgent => 2,
+            WorkOrderPriority::High => 3,
+            WorkOrderPriority::Normal => 4,
+            WorkOrderPriority::Low => 5,
         }
     }
 }
@@ -290,19 +367,54 @@ impl WorkOrderType {
     }
 
     pub(crate) fn from_string(s: &str) -> Result<WorkOrderType, AppError> {
-        match s {
-            "preventive" => Ok(Self::Preventive),
-            "corrective" => Ok(Self::Corrective),
-            "emergency" => Ok(Self::Emergency),
-            "inspection" => Ok(Self::Inspection),
-            "calibration" => Ok(Self::Calibration),
-            "installation" => Ok(Self::Installation),
-            "upgrade" => Ok(Self::Upgrade),
-            "replacement" => Ok(Self::Replacement),
-            "cleaning" => Ok(Self::Cleaning),
-            "safety" => Ok(Self::Safety),
-            _ => Err(AppError::ValidationError("Invalid work order type".to_string())),
+        parse_enum(s, [
+            ("preventive", Self::Preventive),
+            ("corrective", Self::Corrective),
+            ("emergency", Self::Emergency),
+            ("inspection", Self::Inspection),
+            ("calibration", Self::Calibration),
+            ("installation", Self::Installation),
+            ("upgrade", Self::Upgrade),
+            ("replacement", Self::Replacement),
+            ("cleaning", Self::Cleaning),
+            ("safety", Self::Safety),
+        ]).ok_or_else(|| AppError::ValidationError("Invalid work order type".to_string()))
+    }
+}
+
+/// A part or material consumed by a work order, priced per unit
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkOrderPart {
+    pub part_number: String,
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit_cost: Decimal,
+}
+
+impl WorkOrderPart {
+    pub fn new(
+        part_number: String,
+        description: String,
+        quantity: Decimal,
+        unit_cost: Decimal
+    ) -> Result<Self, AppError> {
+        if part_number.trim().is_empty() {
+            return Err(AppError::invalid_field("part_number", "Cannot be empty"));
         }
+
+        if quantity.is_sign_negative() && !quantity.is_zero() {
+            return Err(AppError::invalid_field("quantity", "Must not be negative"));
+        }
+
+        if unit_cost.is_sign_negative() && !unit_cost.is_zero() {
+            return Err(AppError::invalid_field("unit_cost", "Must not be negative"));
+        }
+
+        Ok(Self { part_number, description, quantity, unit_cost })
+    }
+
+    pub fn line_total(&self) -> Decimal {
+        self.quantity * self.unit_cost
     }
 }
 
@@ -320,14 +432,19 @@ pub struct WorkOrder {
     pub severity: WorkOrderSeverity,
     pub difficulty: WorkOrderDifficulty,
     pub assigned_technician_id: Option<String>,
+    pub vendor_id: Option<String>,
     pub estimated_duration_minutes: i32,
     pub actual_duration_minutes: Option<i32>,
+    pub scheduled_date: Option<DateTime<Utc>>,
     pub completed_date: Option<DateTime<Utc>>,
     pub estimated_cost: WorkOrderCost,
     pub actual_cost: Option<Decimal>,
+    pub parts: Vec<WorkOrderPart>,
     pub labor_hours: Option<f64>,
     pub completion_notes: Option<String>,
+    pub vendor_rating: Option<f64>,
     pub created_by: String,
+    pub version: u64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -395,19 +512,76 @@ impl WorkOrder {
             severity,
             difficulty,
             assigned_technician_id,
+            vendor_id: None,
             estimated_duration_minutes,
             actual_duration_minutes: None,
+            scheduled_date: None,
             estimated_cost,
             actual_cost: None,
+            parts: Vec::new(),
             completed_date: None,
             labor_hours: None,
             completion_notes: None,
+            vendor_rating: None,
             created_by,
+            version: 0,
             created_at: now,
             updated_at: now,
         })
     }
 
+    /// Builds the preventive work order for the occurrence `schedule` is due for
+    ///
+    /// Priority rises with how overdue the schedule is at `now`; severity follows the
+    /// asset's status and difficulty and cost follow the schedule's duration estimate.
+    /// The id is derived from the schedule and due date, so one occurrence always maps
+    /// to the same work order. The work order starts out `Scheduled` for the due date.
+    ///
+    /// # Arguments
+    ///
+    /// * `asset` - Asset the schedule maintains
+    /// * `schedule` - Schedule whose `next_due_at` occurrence the work order covers
+    /// * `work_order_number` - Number for the new work order
+    /// * `now` - Time the work order is generated at
+    pub fn from_maintenance(
+        asset: &Asset,
+        schedule: &MaintenanceSchedule,
+        work_order_number: String,
+        now: DateTime<Utc>
+    ) -> Result<Self, AppError> {
+        let due_at = schedule.next_due_at;
+        let estimated_minutes = schedule.duration_estimate
+            .filter(|minutes| *minutes > 0)
+            .unwrap_or(DEFAULT_MAINTENANCE_MINUTES);
+
+        let description = format!(
+            "Generated from maintenance schedule {} due {}",
+            schedule.id,
+            due_at.to_rfc3339()
+        );
+
+        let mut work_order = Self::new(
+            format!("work_order-{}-{}", schedule.id, due_at.format("%Y%m%dT%H%M%S")),
+            work_order_number,
+            format!("Preventive maintenance: {}", asset.name),
+            description,
+            None,
+            asset.id.clone(),
+            WorkOrderType::Preventive.to_string(),
+            maintenance_priority((now - due_at).num_days()).to_string(),
+            maintenance_severity(asset.current_status),
+            maintenance_difficulty(estimated_minutes),
+            None,
+            estimated_minutes,
+            maintenance_cost(estimated_minutes),
+            "system".to_string()
+        )?;
+        work_order.scheduled_date = Some(due_at);
+        work_order.transition_to(WorkOrderStatus::Scheduled)?;
+
+        Ok(work_order)
+    }
+
     pub fn is_in_progress(&self) -> bool {
         matches!(self.status, WorkOrderStatus::InProgress)
     }
@@ -416,6 +590,16 @@ impl WorkOrder {
         matches!(self.status, WorkOrderStatus::Completed)
     }
 
+    /// Whether the work order responds to an asset breaking down
+    pub fn is_breakdown(&self) -> bool {
+        matches!(self.work_order_type, WorkOrderType::Corrective | WorkOrderType::Emergency)
+    }
+
+    /// Whether the work order still counts toward a technician's workload
+    pub fn is_open(&self) -> bool {
+        !matches!(self.status, WorkOrderStatus::Completed | WorkOrderStatus::Cancelled)
+    }
+
     pub fn start_work(&mut self, technician_id: String) -> Result<(), AppError> {
         if !matches!(self.status, WorkOrderStatus::Scheduled) {
             return Err(
@@ -429,7 +613,17 @@ impl WorkOrder {
         Ok(())
     }
 
-    pub fn complete_work(&mut self, completion_notes: Option<String>) -> Result<(), AppError> {
+    /// Completes the work order, recording whichever actuals were supplied
+    ///
+    /// Actuals that are not supplied keep their current value. All actuals must be
+    /// non-negative; nothing is changed if any of them is invalid.
+    pub fn complete_work(
+        &mut self,
+        completion_notes: Option<String>,
+        actual_duration_minutes: Option<i32>,
+        actual_cost: Option<Decimal>,
+        labor_hours: Option<f64>
+    ) -> Result<(), AppError> {
         if !matches!(self.status, WorkOrderStatus::InProgress) {
             return Err(
                 AppError::ValidationError(
@@ -438,9 +632,32 @@ impl WorkOrder {
             );
         }
 
+        if actual_duration_minutes.is_some_and(|minutes| minutes < 0) {
+            return Err(
+                AppError::invalid_field("actual_duration_minutes", "Must not be negative")
+            );
+        }
+        if actual_cost.is_some_and(|cost| cost.is_sign_negative() && !cost.is_zero()) {
+            return Err(AppError::invalid_field("actual_cost", "Must not be negative"));
+        }
+        if labor_hours.is_some_and(|hours| !hours.is_finite() || hours < 0.0) {
+            return Err(
+                AppError::invalid_field("labor_hours", "Must be a non-negative number")
+            );
+        }
+
         let now = Utc::now();
         self.status = WorkOrderStatus::Completed;
         self.completion_notes = completion_notes;
+        if actual_duration_minutes.is_some() {
+            self.actual_duration_minutes = actual_duration_minutes;
+        }
+        if actual_cost.is_some() {
+            self.actual_cost = actual_cost;
+        }
+        if labor_hours.is_some() {
+            self.labor_hours = labor_hours;
+        }
         self.updated_at = now;
         self.completed_date = Some(now);
         Ok(())
@@ -454,12 +671,9 @@ impl WorkOrder {
                 )
             );
         }
-        let now = Utc::now();
-
         self.status = WorkOrderStatus::Cancelled;
         self.completion_notes = Some(reason);
-        self.updated_at = now;
-        self.completed_date = Some(now);
+        self.updated_at = Utc::now();
         Ok(())
     }
 
@@ -490,14 +704,139 @@ impl WorkOrder {
         Ok(())
     }
 
+    /// Moves the work order to `next` if the lifecycle allows it
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - Target status
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, `AppError::ValidationError` for an illegal transition
+    pub fn transition_to(&mut self, next: WorkOrderStatus) -> Result<(), AppError> {
+        if !self.status.can_transition_to(next) {
+            return Err(
+                AppError::ValidationError(
+                    format!(
+                        "Illegal work order status transition: {} -> {}",
+                        self.status.to_str(),
+                        next.to_str()
+                    )
+                )
+            );
+        }
+
+        let now = Utc::now();
+        if next == WorkOrderStatus::Completed {
+            self.completed_date = Some(now);
+        }
+
+        self.status = next;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Whether the work has run past its estimated completion
+    ///
+    /// The estimate starts from `scheduled_date`, or from `created_at` for
+    /// unscheduled work orders.
     pub fn is_overdue(&self) -> bool {
+        self.is_overdue_at(Utc::now())
+    }
+
+    fn is_overdue_at(&self, now: DateTime<Utc>) -> bool {
         if matches!(self.status, WorkOrderStatus::Completed | WorkOrderStatus::Cancelled) {
             return false;
         }
 
+        let start = self.scheduled_date.unwrap_or(self.created_at);
         let estimated_completion =
-            self.created_at + chrono::Duration::minutes(self.estimated_duration_minutes as i64);
-        Utc::now() > estimated_completion
+            start + chrono::Duration::minutes(self.estimated_duration_minutes as i64);
+        now > estimated_completion
+    }
+
+    /// When the work order must be resolved by under `sla`, counted from `created_at`
+    pub fn sla_deadline(&self, sla: &SlaConfig) -> DateTime<Utc> {
+        self.created_at + chrono::Duration::hours(sla.hours_for(self.priority))
+    }
+
+    /// Whether the work order is still open past its SLA deadline
+    pub fn sla_breached(&self, sla: &SlaConfig, now: DateTime<Utc>) -> bool {
+        self.is_open() && now > self.sla_deadline(sla)
+    }
+
+    /// Actual cost minus the midpoint of the estimated cost band; positive means over budget
+    pub fn cost_variance(&self) -> Option<Decimal> {
+        self.actual_cost.map(|cost| cost - self.estimated_cost.midpoint())
+    }
+
+    /// Actual minus estimated duration in minutes; positive means the work ran long
+    pub fn duration_variance(&self) -> Option<i32> {
+        self.actual_duration_minutes.map(|minutes| minutes - self.estimated_duration_minutes)
+    }
+
+    /// Rates the vendor the work was hired out to
+    ///
+    /// Only completed work orders with a vendor can be rated; the rating must be
+    /// between 0.0 and 5.0.
+    pub fn rate_vendor(&mut self, rating: f64) -> Result<(), AppError> {
+        if self.vendor_id.is_none() {
+            return Err(
+                AppError::ValidationError("Work order has no vendor to rate".to_string())
+            );
+        }
+
+        if !self.is_completed() {
+            return Err(
+                AppError::ValidationError(
+                    "Only completed work orders can be rated".to_string()
+                )
+            );
+        }
+
+        if !(0.0..=5.0).contains(&rating) {
+            return Err(
+                AppError::invalid_field("vendor_rating", "Rating must be between 0.0 and 5.0")
+            );
+        }
+
+        self.vendor_rating = Some(rating);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Adds a line item to the parts list; part numbers must be unique within the order
+    pub fn add_part(&mut self, part: WorkOrderPart) -> Result<(), AppError> {
+        if self.parts.iter().any(|p| p.part_number == part.part_number) {
+            return Err(
+                AppError::invalid_field(
+                    "part_number",
+                    format!("Part {} is already on this work order", part.part_number)
+                )
+            );
+        }
+
+        self.parts.push(part);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Removes and returns the line item for `part_number`
+    pub fn remove_part(&mut self, part_number: &str) -> Result<WorkOrderPart, AppError> {
+        let index = self.parts
+            .iter()
+            .position(|p| p.part_number == part_number)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Part {} not on work order {}", part_number, self.id))
+            })?;
+
+        self.updated_at = Utc::now();
+        Ok(self.parts.remove(index))
+    }
+
+    /// Sum of `quantity * unit_cost` over the parts list
+    pub fn parts_total_cost(&self) -> Decimal {
+        self.parts.iter().map(WorkOrderPart::line_total).sum()
     }
 
     pub fn set_classification(
@@ -511,6 +850,109 @@ impl WorkOrder {
     }
 }
 
+/// Longest SLA window, and look-ahead for `work_orders_breaching_sla`, accepted: one year
+pub const MAX_SLA_HOURS: i64 = 24 * 365;
+
+/// Default resolution window in hours for each priority, used unless `SlaConfig` overrides it
+pub fn sla_hours_for(priority: WorkOrderPriority) -> i64 {
+    match priority {
+        WorkOrderPriority::Emergency => 4,
+        WorkOrderPriority::Urgent => 24,
+        WorkOrderPriority::High => 72,
+        WorkOrderPriority::Normal => 168,
+        WorkOrderPriority::Low => 336,
+    }
+}
+
+/// Open work orders whose SLA deadline falls before `now` plus `within_hours`, soonest first
+///
+/// With `within_hours` of zero only orders already in breach are returned; a horizon
+/// beyond the representable range matches every open order.
+pub fn work_orders_breaching_sla(
+    work_orders: Vec<WorkOrder>,
+    sla: &SlaConfig,
+    within_hours: i64,
+    now: DateTime<Utc>
+) -> Vec<WorkOrder> {
+    let horizon = chrono::Duration
+        ::try_hours(within_hours)
+        .and_then(|window| now.checked_add_signed(window))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+    let mut breaching: Vec<WorkOrder> = work_orders
+        .into_iter()
+        .filter(|wo| wo.is_open() && wo.sla_deadline(sla) < horizon)
+        .collect();
+
+    breaching.sort_by(|a, b| {
+        a.sla_deadline(sla).cmp(&b.sla_deadline(sla)).then_with(|| a.id.cmp(&b.id))
+    });
+
+    breaching
+}
+
+/// Duration assumed for preventive work when the schedule has no estimate
+const DEFAULT_MAINTENANCE_MINUTES: i32 = 60;
+
+/// Priority of preventive work whose schedule is `days_overdue` days past due
+///
+/// On-time and slightly late work is `Normal`; a month overdue is `High` and a
+/// quarter overdue is `Urgent`.
+pub fn maintenance_priority(days_overdue: i64) -> WorkOrderPriority {
+    match days_overdue {
+        ..30 => WorkOrderPriority::Normal,
+        30..90 => WorkOrderPriority::High,
+        _ => WorkOrderPriority::Urgent,
+    }
+}
+
+/// Severity of preventive work on an asset in `status`
+///
+/// Maintaining an asset that is already down or flagged for attention matters more
+/// than routine upkeep.
+pub fn maintenance_severity(status: AssetCurrentStatusOptions) -> WorkOrderSeverity {
+    match status {
+        AssetCurrentStatusOptions::Down | AssetCurrentStatusOptions::NeedsAttention => {
+            WorkOrderSeverity::Important
+        }
+        _ => WorkOrderSeverity::Valuable,
+    }
+}
+
+/// Difficulty of preventive work expected to take `estimated_minutes`
+pub fn maintenance_difficulty(estimated_minutes: i32) -> WorkOrderDifficulty {
+    match estimated_minutes {
+        ..=120 => WorkOrderDifficulty::Normal,
+        121..=480 => WorkOrderDifficulty::Extended,
+        _ => WorkOrderDifficulty::Advanced,
+    }
+}
+
+/// Cost band of preventive work expected to take `estimated_minutes`
+pub fn maintenance_cost(estimated_minutes: i32) -> WorkOrderCost {
+    match estimated_minutes {
+        ..=120 => WorkOrderCost::One,
+        121..=480 => WorkOrderCost::Two,
+        _ => WorkOrderCost::Three,
+    }
+}
+
+/// Mean vendor rating over the completed, rated work orders hired out to `vendor_id`
+///
+/// Returns `None` when no such work order has been rated.
+pub fn average_vendor_rating(work_orders: &[WorkOrder], vendor_id: &str) -> Option<f64> {
+    let ratings: Vec<f64> = work_orders
+        .iter()
+        .filter(|wo| wo.is_completed() && wo.vendor_id.as_deref() == Some(vendor_id))
+        .filter_map(|wo| wo.vendor_rating)
+        .collect();
+
+    if ratings.is_empty() {
+        return None;
+    }
+
+    Some(ratings.iter().sum::<f64>() / (ratings.len() as f64))
+}
+
 impl DynamoDbEntity for WorkOrder {
     fn table_name() -> &'static str {
         "WorkOrders"
@@ -520,6 +962,16 @@ impl DynamoDbEntity for WorkOrder {
         self.id.clone()
     }
 
+    const VERSIONED: bool = true;
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+
     fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
         // info!("calling from_item with: {:?}", &item);
 
@@ -529,7 +981,7 @@ impl DynamoDbEntity for WorkOrder {
         let description = item.get("description")?.as_s().ok()?.to_string();
 
         let notes = item
-            .get("completion_notes")
+            .get("notes")
             .and_then(|v| v.as_s().ok())
             .map(|s| s.to_string());
 
@@ -555,16 +1007,26 @@ impl DynamoDbEntity for WorkOrder {
             .and_then(|v| v.as_s().ok())
             .map(|s| s.to_string());
 
+        let vendor_id = item
+            .get("vendor_id")
+            .and_then(|v| v.as_s().ok())
+            .map(|s| s.to_string());
+
         let estimated_duration_minutes = item
             .get("estimated_duration_minutes")
             .and_then(|v| v.as_n().ok())
             .and_then(|s| s.parse::<i32>().ok())
             .unwrap_or(60);
 
+        let scheduled_date = item
+            .get("scheduled_date")
+            .and_then(|v| v.as_s().ok())
+            .and_then(|s| from_db_timestamp(s));
+
         let completed_date = item
             .get("completed_date")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+            .and_then(|s| from_db_timestamp(s));
 
         let actual_duration_minutes = item
             .get("actual_duration_minutes")
@@ -574,10 +1036,17 @@ impl DynamoDbEntity for WorkOrder {
         let estimated_cost_str = item.get("estimated_cost")?.as_s().ok()?;
         let estimated_cost = WorkOrderCost::from_string(&estimated_cost_str).ok()?;
 
-        let actual_cost = item
-            .get("actual_cost")
-            .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<Decimal>().ok());
+        // A present-but-unparseable cost rejects the item rather than reading as missing
+        let actual_cost = match item.get("actual_cost") {
+            Some(v) => Some(parse_decimal(v.as_s().ok()?).ok()?),
+            None => None,
+        };
+
+        // Same for the parts list: a corrupt list must not silently read as empty
+        let parts = match item.get("parts") {
+            Some(v) => serde_json::from_str::<Vec<WorkOrderPart>>(v.as_s().ok()?).ok()?,
+            None => Vec::new(),
+        };
 
         let labor_hours = item
             .get("labor_hours")
@@ -589,18 +1058,29 @@ impl DynamoDbEntity for WorkOrder {
             .and_then(|v| v.as_s().ok())
             .map(|s| s.to_string());
 
+        let vendor_rating = item
+            .get("vendor_rating")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|s| s.parse::<f64>().ok());
+
         let created_by = item.get("created_by")?.as_s().ok()?.to_string();
 
+        let version = item
+            .get("version")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+
         let created_at = item
             .get("created_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         let updated_at = item
             .get("updated_at")
             .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .and_then(|s| from_db_timestamp(s))
             .unwrap_or_else(|| Utc::now());
 
         Some(Self {
@@ -616,14 +1096,19 @@ impl DynamoDbEntity for WorkOrder {
             severity,
             difficulty,
             assigned_technician_id,
+            vendor_id,
             estimated_duration_minutes,
             actual_duration_minutes,
+            scheduled_date,
             completed_date,
             estimated_cost,
             actual_cost,
+            parts,
             labor_hours,
             completion_notes,
+            vendor_rating,
             created_by,
+            version,
             created_at,
             updated_at,
         })
@@ -656,6 +1141,10 @@ impl DynamoDbEntity for WorkOrder {
             item.insert("assigned_technician_id".to_string(), AttributeValue::S(tech_id.clone()));
         }
 
+        if let Some(vendor_id) = &self.vendor_id {
+            item.insert("vendor_id".to_string(), AttributeValue::S(vendor_id.clone()));
+        }
+
         item.insert(
             "estimated_duration_minutes".to_string(),
             AttributeValue::N(self.estimated_duration_minutes.to_string())
@@ -668,10 +1157,17 @@ impl DynamoDbEntity for WorkOrder {
             );
         }
 
+        if let Some(scheduled_date) = &self.scheduled_date {
+            item.insert(
+                "scheduled_date".to_string(),
+                AttributeValue::S(to_db_timestamp(scheduled_date))
+            );
+        }
+
         if let Some(completed_date) = &self.completed_date {
             item.insert(
                 "completed_date".to_string(),
-                AttributeValue::S(completed_date.to_string())
+                AttributeValue::S(to_db_timestamp(completed_date))
             );
         }
 
@@ -681,7 +1177,13 @@ impl DynamoDbEntity for WorkOrder {
         );
 
         if let Some(actual_cost) = &self.actual_cost {
-            item.insert("actual_cost".to_string(), AttributeValue::S(actual_cost.to_string()));
+            item.insert("actual_cost".to_string(), AttributeValue::S(canonical_decimal(actual_cost)));
+        }
+
+        if !self.parts.is_empty()
+            && let Ok(parts_json) = serde_json::to_string(&self.parts)
+        {
+            item.insert("parts".to_string(), AttributeValue::S(parts_json));
         }
 
         if let Some(labor) = &self.labor_hours {
@@ -692,10 +1194,506 @@ impl DynamoDbEntity for WorkOrder {
             item.insert("completion_notes".to_string(), AttributeValue::S(notes.clone()));
         }
 
+        if let Some(rating) = &self.vendor_rating {
+            item.insert("vendor_rating".to_string(), AttributeValue::N(rating.to_string()));
+        }
+
         item.insert("created_by".to_string(), AttributeValue::S(self.created_by.clone()));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+        item.insert("version".to_string(), AttributeValue::N(self.version.to_string()));
+        item.insert("created_at".to_string(), AttributeValue::S(to_db_timestamp(&self.created_at)));
+        item.insert("updated_at".to_string(), AttributeValue::S(to_db_timestamp(&self.updated_at)));
 
         item
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use WorkOrderStatus::*;
+
+    const ALL_STATUSES: [WorkOrderStatus; 10] = [
+        Draft,
+        Scheduled,
+        InProgress,
+        OnHold,
+        Completed,
+        Cancelled,
+        Failed,
+        Deferred,
+        WaitingParts,
+        WaitingApproval,
+    ];
+
+    /// Legal targets for each status; every pair not listed must be rejected
+    fn legal_targets(from: WorkOrderStatus) -> &'static [WorkOrderStatus] {
+        match from {
+            Draft => &[Scheduled, WaitingApproval, Cancelled],
+            WaitingApproval => &[Scheduled, Draft, Cancelled],
+            Scheduled => &[InProgress, OnHold, Deferred, Cancelled],
+            InProgress => &[OnHold, Completed, Failed, WaitingParts, Cancelled],
+            OnHold => &[Scheduled, InProgress, Cancelled],
+            WaitingParts => &[InProgress, OnHold, Cancelled],
+            Deferred => &[Scheduled, Cancelled],
+            Completed | Cancelled | Failed => &[],
+        }
+    }
+
+    #[test]
+    fn test_can_transition_to_covers_all_pairs() {
+        for from in ALL_STATUSES {
+            for to in ALL_STATUSES {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    legal_targets(from).contains(&to),
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_status_parsing_ignores_case_and_spelling() {
+        for input in ["in_progress", "IN_PROGRESS", "In_Progress", "inProgress", "InProgress"] {
+            assert_eq!(WorkOrderStatus::from_string(input).unwrap(), InProgress, "{}", input);
+        }
+        assert_eq!(WorkOrderStatus::from_string("WAITING_PARTS").unwrap(), WaitingParts);
+        assert!(WorkOrderStatus::from_string("in progress!").is_err());
+    }
+
+    #[test]
+    fn test_priority_parsing_ignores_case() {
+        for input in ["urgent", "URGENT", "Urgent", "uRgEnT"] {
+            assert_eq!(WorkOrderPriority::from_string(input).unwrap(), WorkOrderPriority::Urgent);
+        }
+    }
+
+    #[test]
+    fn test_difficulty_parsing_normalizes_hire_out() {
+        for input in ["hireOut", "hire_out", "HIRE_OUT", "HireOut", "hire-out"] {
+            assert_eq!(
+                WorkOrderDifficulty::from_string(input).unwrap(),
+                WorkOrderDifficulty::HireOut,
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            WorkOrderDifficulty::from_string("ADVANCED").unwrap(),
+            WorkOrderDifficulty::Advanced
+        );
+
+        // The stored spelling still round trips
+        let stored = WorkOrderDifficulty::HireOut.to_str();
+        assert_eq!(WorkOrderDifficulty::from_string(stored).unwrap(), WorkOrderDifficulty::HireOut);
+    }
+
+    fn work_order() -> WorkOrder {
+        WorkOrder::new(
+            "wo-1".to_string(),
+            "WO-0001".to_string(),
+            "Replace belt".to_string(),
+            "Belt is worn".to_string(),
+            None,
+            "asset-1".to_string(),
+            "preventive".to_string(),
+            "normal".to_string(),
+            WorkOrderSeverity::Nice,
+            WorkOrderDifficulty::Normal,
+            None,
+            60,
+            WorkOrderCost::One,
+            "user-1".to_string()
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_actual_cost_round_trips_exactly() {
+        let mut work_order = work_order();
+        work_order.actual_cost = Some(Decimal::new(12345678, 4));
+
+        let item = work_order.to_item();
+        assert_eq!(item.get("actual_cost").unwrap().as_s().unwrap(), "1234.5678");
+
+        let restored = WorkOrder::from_item(&item).unwrap();
+        assert_eq!(restored.actual_cost, Some(Decimal::new(12345678, 4)));
+    }
+
+    #[test]
+    fn test_unparseable_actual_cost_rejects_item() {
+        let mut item = work_order().to_item();
+        item.insert("actual_cost".to_string(), AttributeValue::S("1,234.56".to_string()));
+
+        assert!(WorkOrder::from_item(&item).is_none());
+    }
+
+    fn part(part_number: &str, quantity: Decimal, unit_cost: Decimal) -> WorkOrderPart {
+        WorkOrderPart::new(part_number.to_string(), "Drive belt".to_string(), quantity, unit_cost)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parts_total_cost_sums_line_items_exactly() {
+        let mut work_order = work_order();
+        assert_eq!(work_order.parts_total_cost(), Decimal::ZERO);
+
+        work_order.add_part(part("BLT-100", Decimal::new(3, 0), Decimal::new(1999, 2))).unwrap();
+        work_order.add_part(part("GRS-7", Decimal::new(25, 1), Decimal::new(410, 2))).unwrap();
+
+        // 3 x 19.99 + 2.5 x 4.10
+        assert_eq!(work_order.parts_total_cost(), Decimal::new(7022, 2));
+
+        let removed = work_order.remove_part("GRS-7").unwrap();
+        assert_eq!(removed.line_total(), Decimal::new(1025, 2));
+        assert_eq!(work_order.parts_total_cost(), Decimal::new(5997, 2));
+        assert!(matches!(work_order.remove_part("GRS-7"), Err(AppError::NotFound(_))));
+
+        let restored = WorkOrder::from_item(&work_order.to_item()).unwrap();
+        assert_eq!(restored.parts, work_order.parts);
+    }
+
+    #[test]
+    fn test_parts_reject_negative_quantity_and_cost() {
+        let rejected_field = |quantity: Decimal, unit_cost: Decimal| {
+            match WorkOrderPart::new("BLT-100".to_string(), String::new(), quantity, unit_cost) {
+                Err(AppError::InvalidField { field, .. }) => Some(field),
+                _ => None,
+            }
+        };
+
+        assert_eq!(rejected_field(Decimal::new(-1, 0), Decimal::ONE).as_deref(), Some("quantity"));
+        assert_eq!(rejected_field(Decimal::ONE, Decimal::new(-1, 2)).as_deref(), Some("unit_cost"));
+        assert_eq!(rejected_field(Decimal::ZERO, Decimal::ZERO), None);
+    }
+
+    #[test]
+    fn test_add_part_rejects_duplicate_part_number() {
+        let mut work_order = work_order();
+        work_order.add_part(part("BLT-100", Decimal::ONE, Decimal::ONE)).unwrap();
+
+        assert!(work_order.add_part(part("BLT-100", Decimal::TWO, Decimal::ONE)).is_err());
+        assert_eq!(work_order.parts.len(), 1);
+    }
+
+    #[test]
+    fn test_transition_to_rejects_illegal_jump() {
+        let mut work_order = WorkOrder::new(
+            "wo-1".to_string(),
+            "WO-0001".to_string(),
+            "Replace belt".to_string(),
+            "Belt is worn".to_string(),
+            None,
+            "asset-1".to_string(),
+            "preventive".to_string(),
+            "normal".to_string(),
+            WorkOrderSeverity::Nice,
+            WorkOrderDifficulty::Normal,
+            None,
+            60,
+            WorkOrderCost::One,
+            "user-1".to_string()
+        ).unwrap();
+        work_order.status = Completed;
+
+        let result = work_order.transition_to(InProgress);
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+        assert_eq!(work_order.status, Completed);
+    }
+
+    #[test]
+    fn test_only_completion_sets_completed_date() {
+        let mut cancelled = work_order();
+        cancelled.status = Scheduled;
+        cancelled.cancel_work("Asset retired".to_string()).unwrap();
+
+        let mut transitioned = work_order();
+        transitioned.status = InProgress;
+        transitioned.transition_to(Cancelled).unwrap();
+
+        let mut completed = work_order();
+        completed.status = InProgress;
+        completed.transition_to(Completed).unwrap();
+
+        assert_eq!(cancelled.completed_date, None);
+        assert_eq!(transitioned.completed_date, None);
+        assert!(completed.completed_date.is_some());
+    }
+
+    #[test]
+    fn test_complete_work_records_actuals_and_variances() {
+        let mut work_order = work_order();
+        work_order.status = InProgress;
+
+        work_order
+            .complete_work(
+                Some("Belt replaced".to_string()),
+                Some(75),
+                Some(Decimal::new(14050, 2)),
+                Some(1.25)
+            )
+            .unwrap();
+
+        assert_eq!(work_order.status, Completed);
+        assert_eq!(work_order.actual_duration_minutes, Some(75));
+        assert_eq!(work_order.actual_cost, Some(Decimal::new(14050, 2)));
+        assert_eq!(work_order.labor_hours, Some(1.25));
+        assert_eq!(work_order.duration_variance(), Some(15));
+        assert_eq!(work_order.cost_variance(), Some(Decimal::new(1550, 2)));
+    }
+
+    #[test]
+    fn test_complete_work_without_actuals() {
+        let mut work_order = work_order();
+        work_order.status = InProgress;
+
+        work_order.complete_work(None, None, None, None).unwrap();
+
+        assert_eq!(work_order.status, Completed);
+        assert!(work_order.completed_date.is_some());
+        assert_eq!(work_order.actual_duration_minutes, None);
+        assert_eq!(work_order.duration_variance(), None);
+        assert_eq!(work_order.cost_variance(), None);
+    }
+
+    #[test]
+    fn test_complete_work_rejects_negative_actuals() {
+        let mut work_order = work_order();
+        work_order.status = InProgress;
+
+        let results = [
+            work_order.complete_work(None, Some(-1), None, None),
+            work_order.complete_work(None, None, Some(Decimal::new(-1, 2)), None),
+            work_order.complete_work(None, None, None, Some(-0.5)),
+            work_order.complete_work(None, None, None, Some(f64::NAN)),
+        ];
+
+        for result in results {
+            assert!(matches!(result, Err(AppError::InvalidField { .. })));
+        }
+        assert_eq!(work_order.status, InProgress);
+    }
+
+    #[test]
+    fn test_scheduled_work_is_not_overdue_before_its_window() {
+        let mut work_order = work_order();
+        work_order.status = Scheduled;
+        work_order.scheduled_date = Some(work_order.created_at + chrono::Duration::days(30));
+
+        let tomorrow = work_order.created_at + chrono::Duration::days(1);
+        assert!(!work_order.is_overdue_at(tomorrow));
+
+        let after_window =
+            work_order.created_at + chrono::Duration::days(30) + chrono::Duration::minutes(61);
+        assert!(work_order.is_overdue_at(after_window));
+    }
+
+    #[test]
+    fn test_unscheduled_work_is_overdue_from_created_at() {
+        let work_order = work_order();
+
+        assert!(!work_order.is_overdue_at(work_order.created_at + chrono::Duration::minutes(59)));
+        assert!(work_order.is_overdue_at(work_order.created_at + chrono::Duration::minutes(61)));
+    }
+
+    #[test]
+    fn test_scheduled_date_round_trips() {
+        let mut work_order = work_order();
+        let scheduled = Utc.with_ymd_and_hms(2026, 11, 2, 8, 30, 0).unwrap();
+        work_order.scheduled_date = Some(scheduled);
+
+        let restored = WorkOrder::from_item(&work_order.to_item()).unwrap();
+
+        assert_eq!(restored.scheduled_date, Some(scheduled));
+    }
+
+    fn rated_work_order(id: &str, vendor_id: &str, rating: Option<f64>) -> WorkOrder {
+        let mut work_order = work_order();
+        work_order.id = id.to_string();
+        work_order.vendor_id = Some(vendor_id.to_string());
+        work_order.status = Completed;
+        work_order.vendor_rating = rating;
+        work_order
+    }
+
+    #[test]
+    fn test_average_vendor_rating_over_completed_rated_orders() {
+        let mut in_progress = rated_work_order("wo-5", "vendor-1", Some(1.0));
+        in_progress.status = InProgress;
+
+        let work_orders = vec![
+            rated_work_order("wo-1", "vendor-1", Some(5.0)),
+            rated_work_order("wo-2", "vendor-1", Some(4.0)),
+            rated_work_order("wo-3", "vendor-1", Some(3.0)),
+            rated_work_order("wo-4", "vendor-1", None),
+            in_progress,
+            rated_work_order("wo-6", "vendor-2", Some(0.5))
+        ];
+
+        assert_eq!(average_vendor_rating(&work_orders, "vendor-1"), Some(4.0));
+        assert_eq!(average_vendor_rating(&work_orders, "vendor-3"), None);
+    }
+
+    #[test]
+    fn test_rate_vendor_requires_completed_work_with_vendor() {
+        let mut work_order = work_order();
+        work_order.status = Completed;
+        assert!(matches!(work_order.rate_vendor(4.0), Err(AppError::ValidationError(_))));
+
+        work_order.vendor_id = Some("vendor-1".to_string());
+        assert!(matches!(work_order.rate_vendor(5.5), Err(AppError::InvalidField { .. })));
+
+        work_order.rate_vendor(4.5).unwrap();
+        let restored = WorkOrder::from_item(&work_order.to_item()).unwrap();
+        assert_eq!(restored.vendor_id.as_deref(), Some("vendor-1"));
+        assert_eq!(restored.vendor_rating, Some(4.5));
+    }
+
+    fn maintained_asset() -> Asset {
+        let installed = Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap();
+        Asset::new(
+            "asset-1".to_string(),
+            "Boiler".to_string(),
+            "type-1".to_string(),
+            "SN-1".to_string(),
+            "B-1".to_string(),
+            installed,
+            installed,
+            "loc-1".to_string(),
+            "mfg-1".to_string(),
+            "monthly".to_string(),
+            None,
+            None
+        ).unwrap()
+    }
+
+    fn due_schedule(due: DateTime<Utc>) -> MaintenanceSchedule {
+        let mut schedule = MaintenanceSchedule::for_asset(
+            "ms-1".to_string(),
+            &maintained_asset()
+        ).unwrap();
+        schedule.next_due_at = due;
+        schedule
+    }
+
+    #[test]
+    fn test_overdue_maintenance_gets_higher_priority_than_on_time() {
+        let asset = maintained_asset();
+        let due = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let schedule = due_schedule(due);
+
+        let on_time = WorkOrder::from_maintenance(&asset, &schedule, "000001".to_string(), due)
+            .unwrap();
+        let overdue = WorkOrder::from_maintenance(
+            &asset,
+            &schedule,
+            "000002".to_string(),
+            due + chrono::Duration::days(45)
+        ).unwrap();
+
+        assert_eq!(on_time.priority, WorkOrderPriority::Normal);
+        assert_eq!(overdue.priority, WorkOrderPriority::High);
+        for work_order in [&on_time, &overdue] {
+            assert_eq!(work_order.work_order_type, WorkOrderType::Preventive);
+            assert_eq!(work_order.asset_id, "asset-1");
+            assert_eq!(work_order.status, Scheduled);
+            assert_eq!(work_order.scheduled_date, Some(due));
+        }
+    }
+
+    fn work_order_created(id: &str, priority: WorkOrderPriority, hours_ago: i64) -> WorkOrder {
+        let mut work_order = work_order();
+        work_order.id = id.to_string();
+        work_order.priority = priority;
+        work_order.created_at = Utc::now() - chrono::Duration::hours(hours_ago);
+        work_order
+    }
+
+    #[test]
+    fn test_emergency_order_open_past_four_hours_breaches_sla() {
+        let sla = SlaConfig::default();
+        let work_order = work_order_created("wo-1", WorkOrderPriority::Emergency, 5);
+
+        let deadline = work_order.created_at + chrono::Duration::hours(4);
+        assert_eq!(work_order.sla_deadline(&sla), deadline);
+        assert!(work_order.sla_breached(&sla, Utc::now()));
+    }
+
+    #[test]
+    fn test_normal_order_is_on_track_within_a_week() {
+        let sla = SlaConfig::default();
+        let mut work_order = work_order_created("wo-1", WorkOrderPriority::Normal, 5);
+
+        assert!(!work_order.sla_breached(&sla, Utc::now()));
+
+        work_order.created_at = Utc::now() - chrono::Duration::days(8);
+        work_order.status = Completed;
+        assert!(!work_order.sla_breached(&sla, Utc::now()), "closed orders never breach");
+    }
+
+    #[test]
+    fn test_work_orders_breaching_sla_includes_near_deadlines_soonest_first() {
+        let sla = SlaConfig::default();
+        let now = Utc::now();
+        let work_orders = vec![
+            // Due in 20 hours
+            work_order_created("near", WorkOrderPriority::Urgent, 4),
+            // Overdue by an hour
+            work_order_created("breached", WorkOrderPriority::Emergency, 5),
+            // Due in six days
+            work_order_created("on-track", WorkOrderPriority::Normal, 24)
+        ];
+
+        let ids = |orders: Vec<WorkOrder>| orders.into_iter().map(|wo| wo.id).collect::<Vec<_>>();
+        assert_eq!(ids(work_orders_breaching_sla(work_orders.clone(), &sla, 0, now)), ["breached"]);
+        assert_eq!(
+            ids(work_orders_breaching_sla(work_orders.clone(), &sla, 24, now)),
+            ["breached", "near"]
+        );
+        assert_eq!(
+            ids(work_orders_breaching_sla(work_orders, &sla, i64::MAX, now)),
+            ["breached", "near", "on-track"]
+        );
+    }
+
+    #[test]
+    fn test_open_statuses_match_is_open() {
+        for status in ALL_STATUSES {
+            let mut work_order = work_order();
+            work_order.status = status;
+            assert_eq!(work_order.is_open(), WorkOrderStatus::OPEN.contains(&status), "{:?}", status);
+        }
+    }
+
+    #[test]
+    fn test_maintenance_priority_rises_with_days_overdue() {
+        assert_eq!(maintenance_priority(-3), WorkOrderPriority::Normal);
+        assert_eq!(maintenance_priority(29), WorkOrderPriority::Normal);
+        assert_eq!(maintenance_priority(30), WorkOrderPriority::High);
+        assert_eq!(maintenance_priority(89), WorkOrderPriority::High);
+        assert_eq!(maintenance_priority(90), WorkOrderPriority::Urgent);
+    }
+
+    #[test]
+    fn test_maintenance_defaults_follow_asset_status_and_duration() {
+        assert_eq!(
+            maintenance_severity(AssetCurrentStatusOptions::Operational),
+            WorkOrderSeverity::Valuable
+        );
+        assert_eq!(
+            maintenance_severity(AssetCurrentStatusOptions::Down),
+            WorkOrderSeverity::Important
+        );
+
+        assert_eq!(maintenance_difficulty(60), WorkOrderDifficulty::Normal);
+        assert_eq!(maintenance_difficulty(240), WorkOrderDifficulty::Extended);
+        assert_eq!(maintenance_difficulty(600), WorkOrderDifficulty::Advanced);
+
+        assert_eq!(maintenance_cost(120), WorkOrderCost::One);
+        assert_eq!(maintenance_cost(121), WorkOrderCost::Two);
+        assert_eq!(maintenance_cost(481), WorkOrderCost::Three);
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    net::{ IpAddr, SocketAddr },
+    sync::Arc,
+    time::{ Duration, Instant },
+};
+
+use axum::{
+    body::Body,
+    extract::{ ConnectInfo, State },
+    http::{ header::RETRY_AFTER, HeaderValue, Request, StatusCode },
+    middleware::Next,
+    response::{ IntoResponse, Response },
+};
+use dashmap::DashMap;
+use tracing::warn;
+
+use crate::{ auth::AuthUser, config::RateLimitConfig, AppError };
+
+/// How often idle buckets are swept from memory
+pub const RATE_LIMIT_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token buckets keyed by client, shared by every request to a route
+///
+/// Each client starts with `burst` tokens and regains them at
+/// `requests_per_minute`; a request spends one token.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    tokens_per_second: f64,
+    trusted_proxies: Vec<IpAddr>,
+    buckets: DashMap<String, Bucket>,
+}
+
+/// Parses the comma-separated `rate_limit.trusted_proxies` setting, ignoring blank entries
+///
+/// # Errors
+///
+/// `AppError::ConfigError` naming the first entry that is not an IP address
+pub fn parse_trusted_proxies(trusted_proxies: &str) -> Result<Vec<IpAddr>, AppError> {
+    trusted_proxies
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<IpAddr>()
+                .map_err(|_| {
+                    AppError::ConfigError(
+                        format!("Invalid rate_limit.trusted_proxies entry {}", entry)
+                    )
+                })
+        })
+        .collect()
+}
+
+impl RateLimiter {
+    /// Builds a limiter from an already validated config; malformed proxy entries are skipped
+    pub fn new(config: &RateLimitConfig) -> Self {
+        let trusted_proxies = config.trusted_proxies
+            .split(',')
+            .filter_map(|entry| entry.trim().parse::<IpAddr>().ok())
+            .collect();
+
+        Self {
+            capacity: f64::from(config.burst),
+            tokens_per_second: f64::from(config.requests_per_minute) / 60.0,
+            trusted_proxies,
+            buckets: DashMap::new(),
+        }
+    }
+
+    /// Spends a token for `key`
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the request may proceed, otherwise `Err` with how long until a
+    /// token is available
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    fn check_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        // The entry stays locked until it is dropped, so concurrent checks for one key
+        // can't both spend the same token
+        let mut bucket = self.buckets
+            .entry(key.to_string())
+            .or_insert(Bucket { tokens: self.capacity, refilled_at: now });
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.tokens_per_second).min(self.capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.tokens_per_second))
+        }
+    }
+
+    /// Drops buckets that have refilled completely, which behave exactly like new ones
+    pub fn cleanup(&self) {
+        self.cleanup_at(Instant::now());
+    }
+
+    fn cleanup_at(&self, now: Instant) {
+        self.buckets.retain(|_, bucket| {
+            let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens + elapsed * self.tokens_per_second < self.capacity
+        });
+    }
+
+    /// Runs `cleanup` every `interval` for as long as the limiter is alive
+    pub fn spawn_cleanup(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let limiter = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match limiter.upgrade() {
+                    Some(limiter) => limiter.cleanup(),
+                    None => {
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Bucket key for a request: the authenticated user, else the client IP
+    ///
+    /// `X-Forwarded-For` is only honoured when the connection comes from a trusted
+    /// proxy, and then the right-most hop that isn't itself a trusted proxy is used;
+    /// hops further left were written by the client and can be anything.
+    fn client_key(&self, request: &Request<Body>) -> String {
+        if let Some(auth_user) = request.extensions().get::<AuthUser>() {
+            return format!("user:{}", auth_user.user_id);
+        }
+
+        let Some(ConnectInfo(address)) = request.extensions().get::<ConnectInfo<SocketAddr>>() else {
+            return "anonymous".to_string();
+        };
+        let peer = address.ip();
+
+        if !self.trusted_proxies.contains(&peer) {
+            return format!("ip:{}", peer);
+        }
+
+        let client = request
+            .headers()
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .rev()
+            .map(|hop| hop.trim().parse::<IpAddr>().ok())
+            // An unparseable hop can't be attributed, so stop there and fall back to the proxy
+            .map_while(|hop| hop)
+            .find(|hop| !self.trusted_proxies.contains(hop));
+
+        format!("ip:{}", client.unwrap_or(peer))
+    }
+}
+
+/// Reject requests over the client's rate limit with `429 Too Many Requests`
+///
+/// Must run after `auth_middleware` so authenticated callers are keyed by user.
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request<Body>,
+    next: Next
+) -> Response {
+    let key = limiter.client_key(&request);
+
+    match limiter.check(&key) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            warn!("Rate limit exceeded for {}", key);
+
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                format!("Rate limit exceeded, retry in {} seconds", seconds),
+            ).into_response();
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(seconds));
+
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_minute: u32, burst: u32) -> RateLimiter {
+        RateLimiter::new(
+            &(RateLimitConfig {
+                requests_per_minute,
+                burst,
+                trusted_proxies: "10.0.0.1, 10.0.0.2".to_string(),
+            })
+        )
+    }
+
+    fn request_from(peer: &str, forwarded_for: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder();
+        if let Some(forwarded_for) = forwarded_for {
+            builder = builder.header("x-forwarded-for", forwarded_for);
+        }
+        let mut request = builder.body(Body::empty()).unwrap();
+        let address: SocketAddr = format!("{}:443", peer).parse().unwrap();
+        request.extensions_mut().insert(ConnectInfo(address));
+        request
+    }
+
+    #[test]
+    fn test_request_after_burst_is_rejected() {
+        let limiter = limiter(60, 3);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at("user:1", now).is_ok());
+        }
+
+        let retry_after = limiter.check_at("user:1", now).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(1));
+
+        // Other clients have their own bucket
+        assert!(limiter.check_at("user:2", now).is_ok());
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = limiter(60, 2);
+        let now = Instant::now();
+
+        assert!(limiter.check_at("ip:10.0.0.1", now).is_ok());
+        assert!(limiter.check_at("ip:10.0.0.1", now).is_ok());
+        assert!(limiter.check_at("ip:10.0.0.1", now).is_err());
+
+        let later = now + Duration::from_secs(1);
+        assert!(limiter.check_at("ip:10.0.0.1", later).is_ok());
+        assert!(limiter.check_at("ip:10.0.0.1", later).is_err());
+    }
+
+    #[test]
+    fn test_cleanup_drops_only_refilled_buckets() {
+        let limiter = limiter(60, 2);
+        let now = Instant::now();
+
+        limiter.check_at("idle", now).unwrap();
+        limiter.check_at("busy", now + Duration::from_secs(5)).unwrap();
+        limiter.check_at("busy", now + Duration::from_secs(5)).unwrap();
+
+        limiter.cleanup_at(now + Duration::from_secs(6));
+
+        assert!(!limiter.buckets.contains_key("idle"));
+        assert!(limiter.buckets.contains_key("busy"));
+    }
+
+    #[test]
+    fn test_forwarded_for_ignored_from_untrusted_peer() {
+        let limiter = limiter(60, 1);
+
+        let key = limiter.client_key(&request_from("203.0.113.7", Some("198.51.100.1")));
+
+        assert_eq!(key, "ip:203.0.113.7");
+    }
+
+    #[test]
+    fn test_rightmost_untrusted_hop_used_behind_proxy() {
+        let limiter = limiter(60, 1);
+
+        // The client prepended a spoofed hop; the proxies appended the real one
+        let request = request_from("10.0.0.1", Some("1.2.3.4, 198.51.100.1, 10.0.0.2"));
+        assert_eq!(limiter.client_key(&request), "ip:198.51.100.1");
+
+        let request = request_from("10.0.0.1", Some("5.6.7.8, 198.51.100.1"));
+        assert_eq!(limiter.client_key(&request), "ip:198.51.100.1");
+
+        let request = request_from("10.0.0.1", None);
+        assert_eq!(limiter.client_key(&request), "ip:10.0.0.1");
+    }
+}
@@ -1,9 +1,48 @@
-use std::collections::HashMap;
-use aws_sdk_dynamodb::{ Client, types::AttributeValue };
+use std::{
+    collections::{ HashMap, HashSet },
+    future::Future,
+    sync::{ atomic::{ AtomicUsize, Ordering }, Arc },
+    time::Duration,
+};
+use aws_sdk_dynamodb::{
+    Client,
+    error::SdkError,
+    primitives::Blob,
+    types::{
+        AttributeValue,
+        ConditionCheck,
+        Delete,
+        DeleteRequest,
+        KeysAndAttributes,
+        Put,
+        PutRequest,
+        ReturnValue,
+        Select,
+        TransactWriteItem,
+        WriteRequest,
+    },
+};
 use async_trait::async_trait;
+use base64::Engine;
+use chrono::Utc;
+use serde_json::{ Map, Value as Json };
 use tracing::{ info, warn };
 
-use crate::AppError;
+use crate::{
+    config::DatabaseConfig,
+    db::common::{ prefixed_table_name, to_db_timestamp },
+    telemetry,
+    AppError,
+};
+
+/// Describes a failed DynamoDB call, spelling out timeouts so they read as such in errors
+fn sdk_error_message<E, R>(e: &SdkError<E, R>) -> String {
+    match e {
+        SdkError::TimeoutError(_) =>
+            "DynamoDB did not respond within the configured operation timeout".to_string(),
+        _ => e.to_string(),
+    }
+}
 
 #[async_trait]
 pub trait DynamoDbEntity: Clone + Send + Sync {
@@ -11,114 +50,1154 @@ pub trait DynamoDbEntity: Clone + Send + Sync {
     fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self>;
     fn to_item(&self) -> HashMap<String, AttributeValue>;
     fn primary_key(&self) -> String;
+
+    /// Whether items carry a `version` attribute, which in-place updates then bump too
+    ///
+    /// Entities that return `Some` from `version` set this to `true`.
+    const VERSIONED: bool = false;
+
+    /// Optimistic concurrency version, `None` for entities that don't opt in
+    fn version(&self) -> Option<u64> {
+        None
+    }
+
+    /// Stores the version written by `Repository::update`
+    fn set_version(&mut self, _version: u64) {}
+}
+
+/// Condition on a GSI sort key, compared as strings
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortKeyCondition {
+    LessThanOrEqual(String),
+    GreaterThanOrEqual(String),
+    Between(String, String),
+}
+
+impl SortKeyCondition {
+    /// Key condition fragment on `#sk` plus its placeholder values
+    fn expression(&self) -> (&'static str, Vec<(&'static str, String)>) {
+        match self {
+            SortKeyCondition::LessThanOrEqual(v) => ("#sk <= :sk", vec![(":sk", v.clone())]),
+            SortKeyCondition::GreaterThanOrEqual(v) => ("#sk >= :sk", vec![(":sk", v.clone())]),
+            SortKeyCondition::Between(lo, hi) =>
+                ("#sk BETWEEN :sk_lo AND :sk_hi", vec![(":sk_lo", lo.clone()), (":sk_hi", hi.clone())]),
+        }
+    }
+}
+
+/// Condition attached to a transactional write, with its placeholder names and values
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WriteCondition {
+    pub expression: String,
+    pub names: HashMap<String, String>,
+    pub values: HashMap<String, AttributeValue>,
+}
+
+impl WriteCondition {
+    pub fn new(expression: impl Into<String>) -> Self {
+        Self {
+            expression: expression.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn name(mut self, placeholder: &str, attribute: &str) -> Self {
+        self.names.insert(placeholder.to_string(), attribute.to_string());
+        self
+    }
+
+    pub fn value(mut self, placeholder: &str, value: AttributeValue) -> Self {
+        self.values.insert(placeholder.to_string(), value);
+        self
+    }
+}
+
+/// Number of items `Repository::batch_write` wrote, by kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchWriteSummary {
+    pub put: usize,
+    pub deleted: usize,
+}
+
+impl BatchWriteSummary {
+    fn count(requests: &[WriteRequest]) -> Self {
+        Self {
+            put: requests
+                .iter()
+                .filter(|r| r.put_request.is_some())
+                .count(),
+            deleted: requests
+                .iter()
+                .filter(|r| r.delete_request.is_some())
+                .count(),
+        }
+    }
+}
+
+/// A single write inside `Repository::transact_write`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactWriteOp {
+    Put {
+        table_name: String,
+        item: HashMap<String, AttributeValue>,
+        condition: Option<WriteCondition>,
+    },
+    Delete {
+        table_name: String,
+        key: HashMap<String, AttributeValue>,
+        condition: Option<WriteCondition>,
+    },
+    ConditionCheck {
+        table_name: String,
+        key: HashMap<String, AttributeValue>,
+        condition: WriteCondition,
+    },
+}
+
+impl TransactWriteOp {
+    /// Inserts a new entity, failing the transaction if its id is already taken
+    pub fn create<T: DynamoDbEntity>(entity: &T) -> Self {
+        TransactWriteOp::Put {
+            table_name: T::table_name().to_string(),
+            item: entity.to_item(),
+            condition: Some(WriteCondition::new("attribute_not_exists(id)")),
+        }
+    }
+
+    /// Overwrites an existing entity with the same version check as `Repository::update`
+    ///
+    /// The entity's version is incremented in place so the caller holds what was written.
+    pub fn update<T: DynamoDbEntity>(entity: &mut T) -> Self {
+        let condition = match entity.version() {
+            Some(expected) => {
+                entity.set_version(expected + 1);
+                WriteCondition::new(version_condition(expected))
+                    .name("#version", "version")
+                    .value(":expected", AttributeValue::N(expected.to_string()))
+            }
+            None => WriteCondition::new("attribute_exists(id)"),
+        };
+
+        TransactWriteOp::Put {
+            table_name: T::table_name().to_string(),
+            item: entity.to_item(),
+            condition: Some(condition),
+        }
+    }
+
+    /// Deletes an existing entity by id
+    pub fn delete<T: DynamoDbEntity>(id: String) -> Self {
+        TransactWriteOp::Delete {
+            table_name: T::table_name().to_string(),
+            key: HashMap::from([("id".to_string(), AttributeValue::S(id))]),
+            condition: Some(WriteCondition::new("attribute_exists(id)")),
+        }
+    }
+
+    /// Points the operation at the prefixed name of its table
+    fn with_table_prefix(mut self, prefix: Option<&str>) -> Self {
+        let (TransactWriteOp::Put { table_name, .. } |
+        TransactWriteOp::Delete { table_name, .. } |
+        TransactWriteOp::ConditionCheck { table_name, .. }) = &mut self;
+        *table_name = prefixed_table_name(prefix, table_name);
+        self
+    }
+
+    fn into_item(self) -> Result<TransactWriteItem, AppError> {
+        let build_error = |e: aws_sdk_dynamodb::error::BuildError| {
+            AppError::DatabaseError(format!("Failed to build transaction item: {}", e))
+        };
+
+        let item = match self {
+            TransactWriteOp::Put { table_name, item, condition } => {
+                let (expression, names, values) = split_condition(condition);
+                let put = Put::builder()
+                    .table_name(table_name)
+                    .set_item(Some(item))
+                    .set_condition_expression(expression)
+                    .set_expression_attribute_names(names)
+                    .set_expression_attribute_values(values)
+                    .build()
+                    .map_err(build_error)?;
+                TransactWriteItem::builder().put(put).build()
+            }
+            TransactWriteOp::Delete { table_name, key, condition } => {
+                let (expression, names, values) = split_condition(condition);
+                let delete = Delete::builder()
+                    .table_name(table_name)
+                    .set_key(Some(key))
+                    .set_condition_expression(expression)
+                    .set_expression_attribute_names(names)
+                    .set_expression_attribute_values(values)
+                    .build()
+                    .map_err(build_error)?;
+                TransactWriteItem::builder().delete(delete).build()
+            }
+            TransactWriteOp::ConditionCheck { table_name, key, condition } => {
+                let (expression, names, values) = split_condition(Some(condition));
+                let check = ConditionCheck::builder()
+                    .table_name(table_name)
+                    .set_key(Some(key))
+                    .set_condition_expression(expression)
+                    .set_expression_attribute_names(names)
+                    .set_expression_attribute_values(values)
+                    .build()
+                    .map_err(build_error)?;
+                TransactWriteItem::builder().condition_check(check).build()
+            }
+        };
+
+        Ok(item)
+    }
+}
+
+type ConditionParts = (
+    Option<String>,
+    Option<HashMap<String, String>>,
+    Option<HashMap<String, AttributeValue>>,
+);
+
+/// Splits a condition into SDK builder arguments, omitting empty placeholder maps
+fn split_condition(condition: Option<WriteCondition>) -> ConditionParts {
+    match condition {
+        Some(c) =>
+            (
+                Some(c.expression),
+                Some(c.names).filter(|n| !n.is_empty()),
+                Some(c.values).filter(|v| !v.is_empty()),
+            ),
+        None => (None, None, None),
+    }
+}
+
+/// Condition guarding an overwrite of a versioned entity stored at `expected`
+fn version_condition(expected: u64) -> &'static str {
+    // Items written before versioning was introduced have no version attribute yet
+    if expected == 0 {
+        "attribute_exists(id) AND (attribute_not_exists(#version) OR #version = :expected)"
+    } else {
+        "attribute_exists(id) AND #version = :expected"
+    }
+}
+
+/// Builds the `SET`/`REMOVE` expression for `Repository::update_fields`
+///
+/// Attribute names and values go through placeholders, ordered by attribute name so the
+/// expression is deterministic. `updated_at` is always set to `updated_at`, and `version`
+/// is bumped when the entity is `versioned`.
+fn field_update_expression(
+    updates: HashMap<String, AttributeValue>,
+    updated_at: String,
+    versioned: bool
+) -> (String, WriteCondition) {
+    let mut updates: Vec<(String, AttributeValue)> = updates
+        .into_iter()
+        .filter(|(name, _)| name != "updated_at")
+        .collect();
+    updates.sort_by(|a, b| a.0.cmp(&b.0));
+    updates.push(("updated_at".to_string(), AttributeValue::S(updated_at)));
+
+    let mut placeholders = WriteCondition::default();
+    let mut set = Vec::new();
+    let mut remove = Vec::new();
+
+    for (index, (attribute, value)) in updates.into_iter().enumerate() {
+        let name = format!("#f{}", index);
+        placeholders = placeholders.name(&name, &attribute);
+
+        if matches!(value, AttributeValue::Null(_)) {
+            remove.push(name);
+        } else {
+            let placeholder = format!(":f{}", index);
+            set.push(format!("{} = {}", name, placeholder));
+            placeholders = placeholders.value(&placeholder, value);
+        }
+    }
+
+    let mut expression = format!("SET {}", set.join(", "));
+    if !remove.is_empty() {
+        expression.push_str(&format!(" REMOVE {}", remove.join(", ")));
+    }
+
+    // Versioned entities read a stale copy as out of date, so the next `update` of one
+    // fetched before this write is rejected instead of silently reverting it
+    if versioned {
+        expression.push_str(" ADD #version :version_increment");
+        placeholders = placeholders
+            .name("#version", "version")
+            .value(":version_increment", AttributeValue::N("1".to_string()));
+    }
+
+    (expression, placeholders)
+}
+
+/// Maximum number of items DynamoDB accepts in a single `TransactWriteItems` request
+const MAX_TRANSACT_WRITE_ITEMS: usize = 100;
+
+/// Maximum number of keys DynamoDB accepts in a single `BatchGetItem` request
+const BATCH_GET_CHUNK_SIZE: usize = 100;
+
+/// Maximum number of items DynamoDB accepts in a single `BatchWriteItem` request
+const BATCH_WRITE_CHUNK_SIZE: usize = 25;
+
+/// Number of times unprocessed batch keys are retried before giving up
+const MAX_BATCH_RETRIES: u32 = 5;
+
+/// Items `Repository::list` may scan when `DatabaseConfig.max_scan_items` is not applied
+const DEFAULT_MAX_SCAN_ITEMS: usize = 10_000;
+
+static MAX_SCAN_ITEMS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_SCAN_ITEMS);
+
+/// Sets the scan safety limit for repositories created from now on
+///
+/// Called once at startup with `DatabaseConfig.max_scan_items`.
+pub fn set_max_scan_items(limit: usize) {
+    MAX_SCAN_ITEMS.store(limit, Ordering::Relaxed);
+}
+
+tokio::task_local! {
+    static CALL_COUNTER: Arc<AtomicUsize>;
+}
+
+/// Records one DynamoDB operation in the metrics and in any enclosing `with_call_counter`
+fn record_call(operation: &'static str, table: &str) {
+    telemetry::record_dynamodb_call(operation, table);
+    let _ = CALL_COUNTER.try_with(|calls| calls.fetch_add(1, Ordering::Relaxed));
 }
 
 pub struct Repository {
     client: Client,
+    max_scan_items: usize,
+    table_prefix: Option<String>,
 }
 
 impl Repository {
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            max_scan_items: MAX_SCAN_ITEMS.load(Ordering::Relaxed),
+            table_prefix: None,
+        }
+    }
+
+    /// A repository using `config`'s scan limit and table name prefix
+    pub fn from_config(client: Client, config: &DatabaseConfig) -> Self {
+        Self::new(client)
+            .with_max_scan_items(config.max_scan_items)
+            .with_table_prefix(config.table_prefix.clone())
     }
 
+    /// Overrides the scan safety limit for this repository only
+    pub fn with_max_scan_items(mut self, limit: usize) -> Self {
+        self.max_scan_items = limit;
+        self
+    }
+
+    /// Overrides the table name prefix for this repository only
+    pub fn with_table_prefix(mut self, prefix: Option<String>) -> Self {
+        self.table_prefix = prefix;
+        self
+    }
+
+    /// Physical name of `T`'s table, with this repository's prefix applied
+    pub fn table_name<T: DynamoDbEntity>(&self) -> String {
+        prefixed_table_name(self.table_prefix.as_deref(), T::table_name())
+    }
+
+    /// Runs `future` and counts the DynamoDB operations any repository makes while it runs
+    ///
+    /// The count is scoped to the current task, so concurrent work elsewhere is not included.
+    /// Meant for tests that guard resolvers against N+1 query patterns.
+    pub async fn with_call_counter<F: Future>(future: F) -> (F::Output, usize) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let output = CALL_COUNTER.scope(calls.clone(), future).await;
+        (output, calls.load(Ordering::Relaxed))
+    }
+
+    /// Loads an entity by id with an eventually consistent read
+    ///
+    /// This is the cheaper read and may briefly miss a write made moments ago;
+    /// use `get_consistent` when that matters.
     pub async fn get<T: DynamoDbEntity>(&self, id: String) -> Result<Option<T>, AppError> {
+        self.get_item(id, false).await
+    }
+
+    /// Loads an entity by id with a strongly consistent read
+    ///
+    /// Prefer this for read-after-write within a mutation, e.g. re-fetching an entity
+    /// that this or a concurrent request has just created or updated. It costs twice
+    /// the read capacity of `get`.
+    pub async fn get_consistent<T: DynamoDbEntity>(
+        &self,
+        id: String
+    ) -> Result<Option<T>, AppError> {
+        self.get_item(id, true).await
+    }
+
+    /// Checks whether an entity with `id` exists without loading it
+    ///
+    /// Only the key attribute is projected, so the check reads the id back rather
+    /// than the whole item and never deserializes it.
+    pub async fn exists<T: DynamoDbEntity>(&self, id: String) -> Result<bool, AppError> {
         let mut key = HashMap::new();
         key.insert("id".to_string(), AttributeValue::S(id));
 
+        record_call("GetItem", &self.table_name::<T>());
         let response = self.client
             .get_item()
-            .table_name(T::table_name())
+            .table_name(self.table_name::<T>())
             .set_key(Some(key))
+            .projection_expression("#id")
+            .expression_attribute_names("#id", "id")
             .send().await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to get item: {}", e)))?;
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to get item: {}", sdk_error_message(&e)))
+            })?;
+
+        Ok(response.item.is_some())
+    }
 
-        Ok(response.item.and_then(|item| T::from_item(&item)))
+    async fn get_item<T: DynamoDbEntity>(
+        &self,
+        id: String,
+        consistent_read: bool
+    ) -> Result<Option<T>, AppError> {
+        let mut key = HashMap::new();
+        key.insert("id".to_string(), AttributeValue::S(id.clone()));
+
+        record_call("GetItem", &self.table_name::<T>());
+        let response = self.client
+            .get_item()
+            .table_name(self.table_name::<T>())
+            .set_key(Some(key))
+            .consistent_read(consistent_read)
+            .send().await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to get item: {}", sdk_error_message(&e)))
+            })?;
+
+        // An item that is present but won't parse is a bug, not a missing record
+        match response.item {
+            None => Ok(None),
+            Some(item) =>
+                T::from_item(&item)
+                    .map(Some)
+                    .ok_or_else(|| {
+                        AppError::DatabaseError(
+                            format!("Failed to deserialize {} item {}", self.table_name::<T>(), id)
+                        )
+                    }),
+        }
     }
 
+    /// Inserts a new entity
+    ///
+    /// The write is conditioned on `attribute_not_exists(id)`, so an existing item with the
+    /// same id is never overwritten; that collision yields `AppError::Conflict`. Use
+    /// `update` to replace a stored entity.
     pub async fn create<T: DynamoDbEntity>(&self, entity: T) -> Result<T, AppError> {
         let item = entity.to_item();
-        // info!("new location_type item in repository: {:?}", &item);
-
-        // self.client
-        //     .put_item()
-        //     .table_name(T::table_name())
-        //     .set_item(Some(item))
-        //     .condition_expression("attribute_not_exists(id)")
-        //     .send()
-        //     .await
-        //     .map_err(|e| {
-        //         if e.to_string().contains("ConditionalCheckFailed") {
-        //             AppError::ValidationError("Entity with this ID already exists".to_string())
-        //         } else {
-        //             AppError::DatabaseError(format!("Failed to create entity: {}", e))
-        //         }
-        //     })?;
-
-        let temp = self.client
+
+        record_call("PutItem", &self.table_name::<T>());
+        self.client
             .put_item()
-            .table_name(T::table_name())
+            .table_name(self.table_name::<T>())
             .set_item(Some(item))
             .condition_expression("attribute_not_exists(id)")
-            .send().await;
-        match &temp {
-            Ok(v) => {
-                // info!("new item temp in repository: {:#?}", &v);
-            }
-            Err(e) => {
-                warn!("new item temp has sdk error: {:#?}", e);
-            }
-        }
-
-        let temp2 = &temp.map_err(|e| {
-            if e.to_string().contains("ConditionalCheckFailed") {
-                AppError::ValidationError("Entity with this ID already exists".to_string())
-            } else {
-                AppError::DatabaseError(format!("Failed to create entity: {}", e))
-            }
-        })?;
+            .send().await
+            .map_err(|e| {
+                let conditional_failed = e
+                    .as_service_error()
+                    .map(|se| se.is_conditional_check_failed_exception())
+                    .unwrap_or(false);
 
-        // info!("new iten putitemoutput in repository: {:#?}", temp2);
+                if conditional_failed {
+                    AppError::Conflict(
+                        format!("{} {} already exists", self.table_name::<T>(), entity.primary_key())
+                    )
+                } else {
+                    warn!("Failed to create {} item: {:?}", self.table_name::<T>(), e);
+                    AppError::DatabaseError(
+                        format!("Failed to create entity: {}", sdk_error_message(&e))
+                    )
+                }
+            })?;
 
         Ok(entity)
     }
 
-    pub async fn update<T: DynamoDbEntity>(&self, entity: T) -> Result<T, AppError> {
+    /// Overwrites an existing entity
+    ///
+    /// Entities that expose a `version` are only written if the stored version still
+    /// matches, and the version is incremented on success. A stale version yields
+    /// `AppError::Conflict` so the caller can re-fetch and retry.
+    pub async fn update<T: DynamoDbEntity>(&self, mut entity: T) -> Result<T, AppError> {
+        let Some(expected) = entity.version() else {
+            let item = entity.to_item();
+
+            record_call("PutItem", &self.table_name::<T>());
+            self.client
+                .put_item()
+                .table_name(self.table_name::<T>())
+                .set_item(Some(item))
+                .condition_expression("attribute_exists(id)")
+                .send().await
+                .map_err(|e| {
+                    AppError::DatabaseError(
+                        format!("Failed to update entity: {}", sdk_error_message(&e))
+                    )
+                })?;
+
+            return Ok(entity);
+        };
+
+        entity.set_version(expected + 1);
         let item = entity.to_item();
 
+        let condition = version_condition(expected);
+
+        record_call("PutItem", &self.table_name::<T>());
         self.client
             .put_item()
-            .table_name(T::table_name())
+            .table_name(self.table_name::<T>())
             .set_item(Some(item))
-            .condition_expression("attribute_exists(id)")
+            .condition_expression(condition)
+            .expression_attribute_names("#version", "version")
+            .expression_attribute_values(":expected", AttributeValue::N(expected.to_string()))
             .send().await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to update entity: {}", e)))?;
+            .map_err(|e| {
+                let conditional_failed = e
+                    .as_service_error()
+                    .map(|se| se.is_conditional_check_failed_exception())
+                    .unwrap_or(false);
+
+                if conditional_failed {
+                    AppError::Conflict(
+                        format!(
+                            "{} {} was modified by another request; re-fetch and retry",
+                            self.table_name::<T>(),
+                            entity.primary_key()
+                        )
+                    )
+                } else {
+                    AppError::DatabaseError(
+                        format!("Failed to update entity: {}", sdk_error_message(&e))
+                    )
+                }
+            })?;
 
         Ok(entity)
     }
 
-    pub async fn delete<T: DynamoDbEntity>(&self, id: String) -> Result<bool, AppError> {
+    /// Applies an `UpdateItem` expression in place and returns the stored entity
+    ///
+    /// Use this for counters and other fields that must not be lost to concurrent
+    /// read-modify-write cycles. `condition` is combined with `attribute_exists(id)` and
+    /// its names and values are shared with `update_expression`. A failed condition
+    /// yields `AppError::Conflict`.
+    pub async fn update_attributes<T: DynamoDbEntity>(
+        &self,
+        id: String,
+        update_expression: &str,
+        condition: WriteCondition
+    ) -> Result<T, AppError> {
+        let condition_expression = if condition.expression.is_empty() {
+            "attribute_exists(id)".to_string()
+        } else {
+            format!("attribute_exists(id) AND ({})", condition.expression)
+        };
+
+        record_call("UpdateItem", &self.table_name::<T>());
+        let response = self.client
+            .update_item()
+            .table_name(self.table_name::<T>())
+            .key("id", AttributeValue::S(id.clone()))
+            .update_expression(update_expression)
+            .condition_expression(condition_expression)
+            .set_expression_attribute_names(Some(condition.names).filter(|n| !n.is_empty()))
+            .set_expression_attribute_values(Some(condition.values).filter(|v| !v.is_empty()))
+            .return_values(ReturnValue::AllNew)
+            .send().await
+            .map_err(|e| {
+                let conditional_failed = e
+                    .as_service_error()
+                    .map(|se| se.is_conditional_check_failed_exception())
+                    .unwrap_or(false);
+
+                if conditional_failed {
+                    AppError::Conflict(
+                        format!("{} {} did not meet the update condition", self.table_name::<T>(), id)
+                    )
+                } else {
+                    AppError::DatabaseError(
+                        format!("Failed to update attributes: {}", sdk_error_message(&e))
+                    )
+                }
+            })?;
+
+        response.attributes
+            .as_ref()
+            .and_then(|item| T::from_item(item))
+            .ok_or_else(|| {
+                AppError::DatabaseError(
+                    format!("Failed to read back {} {} after update", self.table_name::<T>(), id)
+                )
+            })
+    }
+
+    /// Sets or removes individual attributes with `UpdateItem`, leaving the rest untouched
+    ///
+    /// `AttributeValue::Null` removes an attribute, `updated_at` is always bumped and so is
+    /// `version` on versioned entities. Unlike `update` this never rewrites the whole item,
+    /// so concurrent changes to other attributes survive. A missing item yields
+    /// `AppError::NotFound`.
+    pub async fn update_fields<T: DynamoDbEntity>(
+        &self,
+        id: String,
+        updates: HashMap<String, AttributeValue>
+    ) -> Result<T, AppError> {
+        self.update_fields_if(id, updates, WriteCondition::default()).await
+    }
+
+    /// Like `update_fields`, but only if `condition` also holds against the stored item
+    ///
+    /// The condition's placeholders must not start with `#f`/`:f` or be `#version`. A
+    /// missing item yields `AppError::NotFound` when there is no condition and
+    /// `AppError::Conflict` otherwise, as DynamoDB doesn't say which check failed.
+    pub async fn update_fields_if<T: DynamoDbEntity>(
+        &self,
+        id: String,
+        updates: HashMap<String, AttributeValue>,
+        condition: WriteCondition
+    ) -> Result<T, AppError> {
+        if updates.contains_key("id") {
+            return Err(AppError::ValidationError("The id attribute cannot be updated".to_string()));
+        }
+        if updates.contains_key("version") {
+            return Err(
+                AppError::ValidationError("The version attribute cannot be updated".to_string())
+            );
+        }
+
+        let (update_expression, mut placeholders) = field_update_expression(
+            updates,
+            to_db_timestamp(&Utc::now()),
+            T::VERSIONED
+        );
+
+        let conditional = !condition.expression.is_empty();
+        let condition_expression = if conditional {
+            format!("attribute_exists(id) AND ({})", condition.expression)
+        } else {
+            "attribute_exists(id)".to_string()
+        };
+        placeholders.names.extend(condition.names);
+        placeholders.values.extend(condition.values);
+
+        record_call("UpdateItem", &self.table_name::<T>());
+        let response = self.client
+            .update_item()
+            .table_name(self.table_name::<T>())
+            .key("id", AttributeValue::S(id.clone()))
+            .update_expression(update_expression)
+            .condition_expression(condition_expression)
+            .set_expression_attribute_names(Some(placeholders.names))
+            .set_expression_attribute_values(Some(placeholders.values))
+            .return_values(ReturnValue::AllNew)
+            .send().await
+            .map_err(|e| {
+                let conditional_failed = e
+                    .as_service_error()
+                    .map(|se| se.is_conditional_check_failed_exception())
+                    .unwrap_or(false);
+
+                if conditional_failed && conditional {
+                    AppError::Conflict(
+                        format!("{} {} did not meet the update condition", self.table_name::<T>(), id)
+                    )
+                } else if conditional_failed {
+                    AppError::NotFound(format!("{} not found in {}", id, self.table_name::<T>()))
+                } else {
+                    AppError::DatabaseError(
+                        format!("Failed to update fields: {}", sdk_error_message(&e))
+                    )
+                }
+            })?;
+
+        response.attributes
+            .as_ref()
+            .and_then(|item| T::from_item(item))
+            .ok_or_else(|| {
+                AppError::DatabaseError(
+                    format!("Failed to read back {} {} after update", self.table_name::<T>(), id)
+                )
+            })
+    }
+
+    /// Atomically adds one to the numeric `attribute` of item `id` and returns the new value
+    ///
+    /// Uses `ADD`, which starts a missing item or attribute at zero, so concurrent callers
+    /// each get a distinct value.
+    pub async fn increment<T: DynamoDbEntity>(
+        &self,
+        id: String,
+        attribute: &str
+    ) -> Result<u64, AppError> {
+        record_call("UpdateItem", &self.table_name::<T>());
+        let response = self.client
+            .update_item()
+            .table_name(self.table_name::<T>())
+            .key("id", AttributeValue::S(id.clone()))
+            .update_expression("ADD #attribute :one")
+            .expression_attribute_names("#attribute", attribute)
+            .expression_attribute_values(":one", AttributeValue::N("1".to_string()))
+            .return_values(ReturnValue::UpdatedNew)
+            .send().await
+            .map_err(|e| {
+                AppError::DatabaseError(
+                    format!(
+                        "Failed to increment {} in {}: {}",
+                        attribute,
+                        self.table_name::<T>(),
+                        sdk_error_message(&e)
+                    )
+                )
+            })?;
+
+        response.attributes
+            .as_ref()
+            .and_then(|item| item.get(attribute))
+            .and_then(|value| value.as_n().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .ok_or_else(|| {
+                AppError::DatabaseError(
+                    format!("{} {} returned no {} after increment", self.table_name::<T>(), id, attribute)
+                )
+            })
+    }
+
+    /// Atomically adds `amount` to the numeric `attribute` of existing item `id`
+    ///
+    /// Bumps `version` on versioned entities like `update_fields`. Never creates the item:
+    /// a missing item yields `AppError::NotFound`, except for a negative `amount`, where a missing item or one
+    /// the subtraction would take below zero both yield `AppError::Conflict`.
+    pub async fn add_to_count<T: DynamoDbEntity>(
+        &self,
+        id: String,
+        attribute: &str,
+        amount: i64
+    ) -> Result<(), AppError> {
+        let mut request = self.client
+            .update_item()
+            .table_name(self.table_name::<T>())
+            .key("id", AttributeValue::S(id.clone()))
+            .expression_attribute_names("#attribute", attribute)
+            .expression_attribute_values(":amount", AttributeValue::N(amount.to_string()));
+        request = if T::VERSIONED {
+            request
+                .update_expression("ADD #attribute :amount, #version :version_increment")
+                .expression_attribute_names("#version", "version")
+                .expression_attribute_values(
+                    ":version_increment",
+                    AttributeValue::N("1".to_string())
+                )
+        } else {
+            request.update_expression("ADD #attribute :amount")
+        };
+        request = if amount < 0 {
+            request
+                .condition_expression("attribute_exists(id) AND #attribute >= :floor")
+                .expression_attribute_values(":floor", AttributeValue::N((-amount).to_string()))
+        } else {
+            request.condition_expression("attribute_exists(id)")
+        };
+
+        record_call("UpdateItem", &self.table_name::<T>());
+        request.send().await.map_err(|e| {
+            let conditional_failed = e
+                .as_service_error()
+                .map(|se| se.is_conditional_check_failed_exception())
+                .unwrap_or(false);
+
+            if conditional_failed && amount < 0 {
+                AppError::Conflict(
+                    format!(
+                        "{} {} is missing or has no {} left to remove",
+                        self.table_name::<T>(),
+                        id,
+                        attribute
+                    )
+                )
+            } else if conditional_failed {
+                AppError::NotFound(format!("{} not found in {}", id, self.table_name::<T>()))
+            } else {
+                AppError::DatabaseError(
+                    format!(
+                        "Failed to add to {} in {}: {}",
+                        attribute,
+                        self.table_name::<T>(),
+                        sdk_error_message(&e)
+                    )
+                )
+            }
+        })?;
+
+        Ok(())
+    }
+
+    /// Writes an entity only if `condition` holds against the stored item
+    ///
+    /// A failed condition yields `AppError::Conflict`.
+    pub async fn put_if<T: DynamoDbEntity>(
+        &self,
+        entity: &T,
+        condition: WriteCondition
+    ) -> Result<(), AppError> {
+        let (expression, names, values) = split_condition(Some(condition));
+
+        record_call("PutItem", &self.table_name::<T>());
         self.client
+            .put_item()
+            .table_name(self.table_name::<T>())
+            .set_item(Some(entity.to_item()))
+            .set_condition_expression(expression)
+            .set_expression_attribute_names(names)
+            .set_expression_attribute_values(values)
+            .send().await
+            .map_err(|e| {
+                let conditional_failed = e
+                    .as_service_error()
+                    .map(|se| se.is_conditional_check_failed_exception())
+                    .unwrap_or(false);
+
+                if conditional_failed {
+                    AppError::Conflict(
+                        format!("{} {} already exists", self.table_name::<T>(), entity.primary_key())
+                    )
+                } else {
+                    AppError::DatabaseError(
+                        format!("Failed to write entity: {}", sdk_error_message(&e))
+                    )
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// Applies several writes atomically with `TransactWriteItems`
+    ///
+    /// Either every operation succeeds or none are applied. A failed condition or a
+    /// concurrent write cancels the transaction and yields `AppError::Conflict`.
+    pub async fn transact_write(&self, ops: Vec<TransactWriteOp>) -> Result<(), AppError> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+
+        if ops.len() > MAX_TRANSACT_WRITE_ITEMS {
+            return Err(
+                AppError::ValidationError(
+                    format!("Transactions are limited to {} items", MAX_TRANSACT_WRITE_ITEMS)
+                )
+            );
+        }
+
+        let items = ops
+            .into_iter()
+            .map(|op| op.with_table_prefix(self.table_prefix.as_deref()).into_item())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        record_call("TransactWriteItems", "transaction");
+        self.client
+            .transact_write_items()
+            .set_transact_items(Some(items))
+            .send().await
+            .map_err(|e| {
+                match e.as_service_error() {
+                    Some(se) if se.is_transaction_canceled_exception() => {
+                        AppError::Conflict(format!("Transaction was cancelled: {}", se))
+                    }
+                    _ => AppError::DatabaseError(
+                        format!("Failed to write transaction: {}", sdk_error_message(&e))
+                    ),
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// Deletes an entity by id
+    ///
+    /// # Returns
+    ///
+    /// `true` if an item was removed, `false` if no item had that id
+    pub async fn delete<T: DynamoDbEntity>(&self, id: String) -> Result<bool, AppError> {
+        record_call("DeleteItem", &self.table_name::<T>());
+        let response = self.client
             .delete_item()
-            .table_name(T::table_name())
+            .table_name(self.table_name::<T>())
             .key("id", AttributeValue::S(id))
-            .condition_expression("attribute_exists(id)")
+            .return_values(ReturnValue::AllOld)
             .send().await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to delete entity: {}", e)))?;
+            .map_err(|e| {
+                AppError::DatabaseError(
+                    format!("Failed to delete entity: {}", sdk_error_message(&e))
+                )
+            })?;
 
-        Ok(true)
+        Ok(response.attributes.is_some_and(|old| !old.is_empty()))
     }
 
+    /// Scans a table, following `LastEvaluatedKey` until it is exhausted or `limit` is reached
+    ///
+    /// A scan that reads more than the repository's `max_scan_items` stops paging and yields
+    /// `AppError::DatabaseError`, so an unbounded table can't exhaust the Lambda's memory.
     pub async fn list<T: DynamoDbEntity>(&self, limit: Option<i32>) -> Result<Vec<T>, AppError> {
-        let mut scan = self.client.scan().table_name(T::table_name());
+        let mut entities = Vec::new();
+        let mut scanned = 0;
+        let mut exclusive_start_key = None;
+
+        loop {
+            let mut scan = self.client
+                .scan()
+                .table_name(self.table_name::<T>())
+                .set_exclusive_start_key(exclusive_start_key);
+
+            if let Some(limit) = limit {
+                scan = scan.limit(limit - (entities.len() as i32));
+            }
+
+            record_call("Scan", &self.table_name::<T>());
+            let response = scan
+                .send().await
+                .map_err(|e| {
+                    AppError::DatabaseError(
+                        format!("Failed to scan table: {}", sdk_error_message(&e))
+                    )
+                })?;
+
+            let items = response.items.unwrap_or_default();
+            scanned += items.len();
+            if scanned > self.max_scan_items {
+                warn!(
+                    "Scan of {} stopped after {} items (limit {})",
+                    self.table_name::<T>(),
+                    scanned,
+                    self.max_scan_items
+                );
+                return Err(
+                    AppError::DatabaseError(
+                        "Scan exceeded safety limit; use a filtered/indexed query".to_string()
+                    )
+                );
+            }
+
+            entities.extend(items.iter().filter_map(|item| T::from_item(item)));
+
+            if let Some(limit) = limit
+                && entities.len() >= (limit as usize)
+            {
+                entities.truncate(limit as usize);
+                break;
+            }
+
+            match response.last_evaluated_key {
+                Some(key) if !key.is_empty() => {
+                    exclusive_start_key = Some(key);
+                }
+                _ => {
+                    break;
+                }
+            }
+        }
+
+        Ok(entities)
+    }
+
+    /// Scans a single page of a table, resuming from an opaque cursor
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Optional maximum number of items to evaluate for this page
+    /// * `cursor` - Cursor returned by a previous call, or `None` for the first page
+    ///
+    /// # Returns
+    ///
+    /// The page of entities and the cursor for the next page, `None` once the table is exhausted
+    pub async fn list_paginated<T: DynamoDbEntity>(
+        &self,
+        limit: Option<i32>,
+        cursor: Option<String>
+    ) -> Result<(Vec<T>, Option<String>), AppError> {
+        let mut scan = self.client.scan().table_name(self.table_name::<T>());
 
         if let Some(limit) = limit {
             scan = scan.limit(limit);
         }
 
+        if let Some(cursor) = cursor {
+            scan = scan.set_exclusive_start_key(Some(decode_cursor(&cursor)?));
+        }
+
+        record_call("Scan", &self.table_name::<T>());
         let response = scan
             .send().await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to scan table: {}", e)))?;
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to scan table: {}", sdk_error_message(&e)))
+            })?;
+
+        let entities = response.items
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|item| T::from_item(item))
+            .collect();
+
+        let next_cursor = match response.last_evaluated_key {
+            Some(key) if !key.is_empty() => Some(encode_cursor(&key)?),
+            _ => None,
+        };
+
+        Ok((entities, next_cursor))
+    }
+
+    /// Queries a global secondary index by its partition key
+    ///
+    /// Follows `LastEvaluatedKey` internally, so the caller receives every match
+    /// (or the first `limit` matches) rather than a single DynamoDB page.
+    ///
+    /// # Arguments
+    ///
+    /// * `index_name` - Name of the GSI on `T::table_name()`
+    /// * `key_attr` - Partition key attribute of the index
+    /// * `key_value` - Value to match against the partition key
+    /// * `limit` - Optional maximum number of entities to return
+    ///
+    /// # Returns
+    ///
+    /// Matching entities, or a DatabaseError if the index does not exist on the table
+    pub async fn query_index<T: DynamoDbEntity>(
+        &self,
+        index_name: &str,
+        key_attr: &str,
+        key_value: &str,
+        limit: Option<i32>
+    ) -> Result<Vec<T>, AppError> {
+        self.run_index_query(index_name, key_attr, key_value, None, limit).await
+    }
+
+    /// Counts the items of a global secondary index partition without transferring them
+    ///
+    /// Uses `Select::Count` and sums `Count` across every page, following
+    /// `LastEvaluatedKey` until the partition is exhausted.
+    ///
+    /// # Arguments
+    ///
+    /// * `index_name` - Name of the GSI on `T::table_name()`
+    /// * `key_attr` - Partition key attribute of the index
+    /// * `key_value` - Value to match against the partition key
+    ///
+    /// # Returns
+    ///
+    /// Number of matching items, or a DatabaseError if the index does not exist on the table
+    pub async fn count_index<T: DynamoDbEntity>(
+        &self,
+        index_name: &str,
+        key_attr: &str,
+        key_value: &str
+    ) -> Result<i32, AppError> {
+        let mut count = 0;
+        let mut exclusive_start_key = None;
+
+        loop {
+            record_call("Query", &self.table_name::<T>());
+            let response = self.client
+                .query()
+                .table_name(self.table_name::<T>())
+                .index_name(index_name)
+                .key_condition_expression("#pk = :pk")
+                .expression_attribute_names("#pk", key_attr)
+                .expression_attribute_values(":pk", AttributeValue::S(key_value.to_string()))
+                .select(Select::Count)
+                .set_exclusive_start_key(exclusive_start_key)
+                .send().await
+                .map_err(|e| {
+                    AppError::DatabaseError(
+                        format!(
+                            "Failed to count index {} on {}: {}",
+                            index_name,
+                            self.table_name::<T>(),
+                            sdk_error_message(&e)
+                        )
+                    )
+                })?;
+
+            count += response.count;
+
+            match response.last_evaluated_key {
+                Some(key) if !key.is_empty() => {
+                    exclusive_start_key = Some(key);
+                }
+                _ => {
+                    break;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Queries a single page of a global secondary index, resuming from an opaque cursor
+    ///
+    /// Unlike `query_index` this does not follow `LastEvaluatedKey`, so callers can
+    /// stream large result sets page by page.
+    ///
+    /// # Arguments
+    ///
+    /// * `index_name` - Name of the GSI on `T::table_name()`
+    /// * `key_attr` - Partition key attribute of the index
+    /// * `key_value` - Value to match against the partition key
+    /// * `limit` - Optional maximum number of items to evaluate for this page
+    /// * `cursor` - Cursor returned by a previous call, or `None` for the first page
+    ///
+    /// # Returns
+    ///
+    /// The page of entities and the cursor for the next page, `None` once the index is exhausted
+    pub async fn query_index_paginated<T: DynamoDbEntity>(
+        &self,
+        index_name: &str,
+        key_attr: &str,
+        key_value: &str,
+        limit: Option<i32>,
+        cursor: Option<String>
+    ) -> Result<(Vec<T>, Option<String>), AppError> {
+        let mut query = self.client
+            .query()
+            .table_name(self.table_name::<T>())
+            .index_name(index_name)
+            .key_condition_expression("#pk = :pk")
+            .expression_attribute_names("#pk", key_attr)
+            .expression_attribute_values(":pk", AttributeValue::S(key_value.to_string()));
+
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+
+        if let Some(cursor) = cursor {
+            query = query.set_exclusive_start_key(Some(decode_cursor(&cursor)?));
+        }
+
+        record_call("Query", &self.table_name::<T>());
+        let response = query
+            .send().await
+            .map_err(|e| {
+                AppError::DatabaseError(
+                    format!(
+                        "Failed to query index {} on {}: {}",
+                        index_name,
+                        self.table_name::<T>(),
+                        sdk_error_message(&e)
+                    )
+                )
+            })?;
 
         let entities = response.items
             .unwrap_or_default()
@@ -126,6 +1205,559 @@ impl Repository {
             .filter_map(|item| T::from_item(item))
             .collect();
 
+        let next_cursor = match response.last_evaluated_key {
+            Some(key) if !key.is_empty() => Some(encode_cursor(&key)?),
+            _ => None,
+        };
+
+        Ok((entities, next_cursor))
+    }
+
+    /// Queries a global secondary index by partition key and a sort key condition
+    ///
+    /// Results come back in ascending sort key order.
+    ///
+    /// # Arguments
+    ///
+    /// * `index_name` - Name of the GSI on `T::table_name()`
+    /// * `key_attr` - Partition key attribute of the index
+    /// * `key_value` - Value to match against the partition key
+    /// * `sort_attr` - Sort key attribute of the index
+    /// * `sort_condition` - Condition applied to the sort key
+    /// * `limit` - Optional maximum number of entities to return
+    ///
+    /// # Returns
+    ///
+    /// Matching entities, or a DatabaseError if the index does not exist on the table
+    pub async fn query_index_range<T: DynamoDbEntity>(
+        &self,
+        index_name: &str,
+        key_attr: &str,
+        key_value: &str,
+        sort_attr: &str,
+        sort_condition: SortKeyCondition,
+        limit: Option<i32>
+    ) -> Result<Vec<T>, AppError> {
+        self.run_index_query(
+            index_name,
+            key_attr,
+            key_value,
+            Some((sort_attr, sort_condition)),
+            limit
+        ).await
+    }
+
+    async fn run_index_query<T: DynamoDbEntity>(
+        &self,
+        index_name: &str,
+        key_attr: &str,
+        key_value: &str,
+        sort: Option<(&str, SortKeyCondition)>,
+        limit: Option<i32>
+    ) -> Result<Vec<T>, AppError> {
+        let mut entities = Vec::new();
+        let mut exclusive_start_key = None;
+
+        let (key_condition, sort_values) = match &sort {
+            Some((_, condition)) => {
+                let (expression, values) = condition.expression();
+                (format!("#pk = :pk AND {}", expression), values)
+            }
+            None => ("#pk = :pk".to_string(), Vec::new()),
+        };
+
+        loop {
+            let mut query = self.client
+                .query()
+                .table_name(self.table_name::<T>())
+                .index_name(index_name)
+                .key_condition_expression(&key_condition)
+                .expression_attribute_names("#pk", key_attr)
+                .expression_attribute_values(":pk", AttributeValue::S(key_value.to_string()))
+                .set_exclusive_start_key(exclusive_start_key);
+
+            if let Some((sort_attr, _)) = &sort {
+                query = query.expression_attribute_names("#sk", *sort_attr);
+            }
+
+            for (name, value) in &sort_values {
+                query = query.expression_attribute_values(*name, AttributeValue::S(value.clone()));
+            }
+
+            if let Some(limit) = limit {
+                query = query.limit(limit - (entities.len() as i32));
+            }
+
+            record_call("Query", &self.table_name::<T>());
+            let response = query
+                .send().await
+                .map_err(|e| {
+                    AppError::DatabaseError(
+                        format!(
+                            "Failed to query index {} on {}: {}",
+                            index_name,
+                            self.table_name::<T>(),
+                            sdk_error_message(&e)
+                        )
+                    )
+                })?;
+
+            entities.extend(
+                response.items
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|item| T::from_item(item))
+            );
+
+            if let Some(limit) = limit
+                && entities.len() >= (limit as usize)
+            {
+                entities.truncate(limit as usize);
+                break;
+            }
+
+            match response.last_evaluated_key {
+                Some(key) if !key.is_empty() => {
+                    exclusive_start_key = Some(key);
+                }
+                _ => {
+                    break;
+                }
+            }
+        }
+
         Ok(entities)
     }
+
+    /// Loads many entities by id with as few round trips as possible
+    ///
+    /// Ids are chunked into groups of 100 (the `BatchGetItem` limit) and unprocessed keys
+    /// are retried with exponential backoff. The result follows the order of `ids`;
+    /// ids that don't exist are simply absent.
+    pub async fn batch_get<T: DynamoDbEntity>(&self, ids: Vec<String>) -> Result<Vec<T>, AppError> {
+        let mut seen = HashSet::new();
+        let unique_ids: Vec<&String> = ids
+            .iter()
+            .filter(|id| seen.insert(id.as_str()))
+            .collect();
+
+        let mut found: HashMap<String, T> = HashMap::new();
+
+        for chunk in unique_ids.chunks(BATCH_GET_CHUNK_SIZE) {
+            let keys = chunk
+                .iter()
+                .map(|id| HashMap::from([("id".to_string(), AttributeValue::S(id.to_string()))]))
+                .collect::<Vec<_>>();
+
+            let mut pending = Some(
+                KeysAndAttributes::builder()
+                    .set_keys(Some(keys))
+                    .build()
+                    .map_err(|e| {
+                        AppError::DatabaseError(format!("Failed to build batch get keys: {}", e))
+                    })?
+            );
+            let mut attempt = 0;
+
+            while let Some(request_keys) = pending.take() {
+                record_call("BatchGetItem", &self.table_name::<T>());
+                let response = self.client
+                    .batch_get_item()
+                    .request_items(self.table_name::<T>(), request_keys)
+                    .send().await
+                    .map_err(|e| {
+                        AppError::DatabaseError(
+                            format!("Failed to batch get items: {}", sdk_error_message(&e))
+                        )
+                    })?;
+
+                if let Some(items) = response.responses.and_then(|mut r| r.remove(&self.table_name::<T>())) {
+                    for item in items {
+                        if let Some(entity) = T::from_item(&item) {
+                            found.insert(entity.primary_key(), entity);
+                        }
+                    }
+                }
+
+                let unprocessed = response.unprocessed_keys
+                    .and_then(|mut u| u.remove(&self.table_name::<T>()))
+                    .filter(|k| !k.keys().is_empty());
+
+                if let Some(unprocessed) = unprocessed {
+                    attempt += 1;
+                    if attempt > MAX_BATCH_RETRIES {
+                        return Err(
+                            AppError::DatabaseError(
+                                format!(
+                                    "Batch get on {} still had unprocessed keys after {} retries",
+                                    self.table_name::<T>(),
+                                    MAX_BATCH_RETRIES
+                                )
+                            )
+                        );
+                    }
+                    warn!(
+                        "Retrying {} unprocessed keys on {} (attempt {})",
+                        unprocessed.keys().len(),
+                        self.table_name::<T>(),
+                        attempt
+                    );
+                    tokio::time::sleep(Duration::from_millis(50 * (1 << attempt))).await;
+                    pending = Some(unprocessed);
+                }
+            }
+        }
+
+        Ok(
+            ids
+                .iter()
+                .filter_map(|id| found.get(id).cloned())
+                .collect()
+        )
+    }
+
+    /// Puts and deletes many entities of one table with `BatchWriteItem`
+    ///
+    /// Requests are chunked into groups of 25 (the `BatchWriteItem` limit) and unprocessed
+    /// items are retried with exponential backoff. Every request targets `T`'s table, so a
+    /// batch never spans tables. Batch puts carry no condition, unlike `create`, and chunks
+    /// are not atomic with each other.
+    ///
+    /// # Errors
+    ///
+    /// `AppError::ValidationError` if the same id appears more than once, which
+    /// DynamoDB rejects within a batch
+    pub async fn batch_write<T: DynamoDbEntity>(
+        &self,
+        puts: Vec<T>,
+        deletes: Vec<String>
+    ) -> Result<BatchWriteSummary, AppError> {
+        let mut ids = HashSet::new();
+        let duplicate = puts
+            .iter()
+            .map(|entity| entity.primary_key())
+            .chain(deletes.iter().cloned())
+            .find(|id| !ids.insert(id.clone()));
+        if let Some(id) = duplicate {
+            return Err(
+                AppError::ValidationError(
+                    format!("{} {} appears more than once in the batch", self.table_name::<T>(), id)
+                )
+            );
+        }
+
+        let mut requests = Vec::with_capacity(puts.len() + deletes.len());
+        for entity in &puts {
+            let put = PutRequest::builder()
+                .set_item(Some(entity.to_item()))
+                .build()
+                .map_err(|e| AppError::DatabaseError(format!("Failed to build batch put: {}", e)))?;
+            requests.push(WriteRequest::builder().put_request(put).build());
+        }
+        for id in deletes {
+            let delete = DeleteRequest::builder()
+                .key("id", AttributeValue::S(id))
+                .build()
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to build batch delete: {}", e))
+                })?;
+            requests.push(WriteRequest::builder().delete_request(delete).build());
+        }
+
+        let mut summary = BatchWriteSummary::default();
+
+        for chunk in requests.chunks(BATCH_WRITE_CHUNK_SIZE) {
+            let mut pending = Some(chunk.to_vec());
+            let mut attempt = 0;
+
+            while let Some(request_items) = pending.take() {
+                let sent = BatchWriteSummary::count(&request_items);
+
+                record_call("BatchWriteItem", &self.table_name::<T>());
+                let response = self.client
+                    .batch_write_item()
+                    .request_items(self.table_name::<T>(), request_items)
+                    .send().await
+                    .map_err(|e| {
+                        AppError::DatabaseError(
+                            format!("Failed to batch write items: {}", sdk_error_message(&e))
+                        )
+                    })?;
+
+                let unprocessed = response.unprocessed_items
+                    .and_then(|mut u| u.remove(&self.table_name::<T>()))
+                    .filter(|items| !items.is_empty());
+                let retrying = unprocessed
+                    .as_deref()
+                    .map(BatchWriteSummary::count)
+                    .unwrap_or_default();
+
+                summary.put += sent.put - retrying.put;
+                summary.deleted += sent.deleted - retrying.deleted;
+
+                if let Some(unprocessed) = unprocessed {
+                    attempt += 1;
+                    if attempt > MAX_BATCH_RETRIES {
+                        return Err(
+                            AppError::DatabaseError(
+                                format!(
+                                    "Batch write on {} left unprocessed items after {} retries",
+                                    self.table_name::<T>(),
+                                    MAX_BATCH_RETRIES
+                                )
+                            )
+                        );
+                    }
+                    warn!(
+                        "Retrying {} unprocessed items on {} (attempt {})",
+                        unprocessed.len(),
+                        self.table_name::<T>(),
+                        attempt
+                    );
+                    tokio::time::sleep(Duration::from_millis(50 * (1 << attempt))).await;
+                    pending = Some(unprocessed);
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Encodes a DynamoDB `LastEvaluatedKey` as an opaque, URL-safe cursor string
+///
+/// Every attribute of the key is kept so tables with composite keys resume correctly.
+pub(crate) fn encode_cursor(key: &HashMap<String, AttributeValue>) -> Result<String, AppError> {
+    let mut map = Map::new();
+
+    for (name, value) in key {
+        let encoded = match value {
+            AttributeValue::S(s) => serde_json::json!({ "S": s }),
+            AttributeValue::N(n) => serde_json::json!({ "N": n }),
+            AttributeValue::B(b) =>
+                serde_json::json!({
+                    "B": base64::engine::general_purpose::STANDARD.encode(b.as_ref()),
+                }),
+            _ => {
+                return Err(
+                    AppError::InternalServerError(
+                        format!("Unsupported key attribute type for cursor: {}", name)
+                    )
+                );
+            }
+        };
+        map.insert(name.clone(), encoded);
+    }
+
+    let json = serde_json
+        ::to_vec(&Json::Object(map))
+        .map_err(|e| AppError::InternalServerError(format!("Failed to encode cursor: {}", e)))?;
+
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json))
+}
+
+/// Decodes a cursor produced by `encode_cursor` back into an `ExclusiveStartKey`
+pub(crate) fn decode_cursor(cursor: &str) -> Result<HashMap<String, AttributeValue>, AppError> {
+    let invalid = || AppError::ValidationError("Invalid pagination cursor".to_string());
+
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|_| invalid())?;
+
+    let json = serde_json::from_slice::<Json>(&bytes).map_err(|_| invalid())?;
+    let map = json.as_object().ok_or_else(invalid)?;
+
+    if map.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut key = HashMap::new();
+
+    for (name, value) in map {
+        let typed = value.as_object().ok_or_else(invalid)?;
+        let (type_tag, raw) = typed.iter().next().ok_or_else(invalid)?;
+        let raw = raw.as_str().ok_or_else(invalid)?;
+
+        let attr = match type_tag.as_str() {
+            "S" => AttributeValue::S(raw.to_string()),
+            "N" => AttributeValue::N(raw.to_string()),
+            "B" => {
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(raw)
+                    .map_err(|_| invalid())?;
+                AttributeValue::B(Blob::new(bytes))
+            }
+            _ => {
+                return Err(invalid());
+            }
+        };
+        key.insert(name.clone(), attr);
+    }
+
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trips_composite_key() {
+        let mut key = HashMap::new();
+        key.insert("id".to_string(), AttributeValue::S("asset-123".to_string()));
+        key.insert("created_at".to_string(), AttributeValue::N("1700000000".to_string()));
+
+        let cursor = encode_cursor(&key).unwrap();
+        let decoded = decode_cursor(&cursor).unwrap();
+
+        assert_eq!(decoded, key);
+    }
+
+    #[test]
+    fn test_garbage_cursor_is_validation_error() {
+        for cursor in ["not a cursor!", "", "e30", "eyJpZCI6MX0"] {
+            let result = decode_cursor(cursor);
+            assert!(matches!(result, Err(AppError::ValidationError(_))), "cursor: {}", cursor);
+        }
+    }
+
+    #[test]
+    fn test_field_update_touches_only_named_attributes() {
+        // Another process wrote `notes` after we read the vendor
+        let updates = HashMap::from([
+            ("status".to_string(), AttributeValue::S("active".to_string())),
+        ]);
+
+        let (expression, placeholders) = field_update_expression(
+            updates,
+            "2024-03-15T08:00:00.000000000Z".to_string(),
+            true
+        );
+
+        assert_eq!(expression, "SET #f0 = :f0, #f1 = :f1 ADD #version :version_increment");
+        assert_eq!(
+            placeholders.names,
+            HashMap::from([
+                ("#f0".to_string(), "status".to_string()),
+                ("#f1".to_string(), "updated_at".to_string()),
+                ("#version".to_string(), "version".to_string()),
+            ])
+        );
+        assert!(!placeholders.names.values().any(|attribute| attribute == "notes"));
+        assert_eq!(placeholders.values.get(":f0"), Some(&AttributeValue::S("active".to_string())));
+    }
+
+    #[test]
+    fn test_field_update_removes_null_attributes() {
+        let updates = HashMap::from([
+            ("read_by_id".to_string(), AttributeValue::Null(true)),
+            ("status".to_string(), AttributeValue::S("read".to_string())),
+        ]);
+
+        let (expression, placeholders) = field_update_expression(
+            updates,
+            "2024-03-15T08:00:00.000000000Z".to_string(),
+            true
+        );
+
+        assert_eq!(
+            expression,
+            "SET #f1 = :f1, #f2 = :f2 REMOVE #f0 ADD #version :version_increment"
+        );
+        assert_eq!(placeholders.names.get("#f0"), Some(&"read_by_id".to_string()));
+        assert!(!placeholders.values.contains_key(":f0"));
+    }
+
+    #[test]
+    fn test_field_update_leaves_unversioned_entities_without_version() {
+        let updates = HashMap::from([
+            ("status".to_string(), AttributeValue::S("inactive".to_string())),
+        ]);
+
+        let (expression, placeholders) = field_update_expression(
+            updates,
+            "2024-03-15T08:00:00.000000000Z".to_string(),
+            false
+        );
+
+        assert_eq!(expression, "SET #f0 = :f0, #f1 = :f1");
+        assert!(!placeholders.names.contains_key("#version"));
+        assert!(!placeholders.values.contains_key(":version_increment"));
+    }
+
+    #[derive(Clone)]
+    struct Versioned {
+        id: String,
+        version: u64,
+    }
+
+    impl DynamoDbEntity for Versioned {
+        fn table_name() -> &'static str {
+            "Versioned"
+        }
+
+        fn from_item(_item: &HashMap<String, AttributeValue>) -> Option<Self> {
+            None
+        }
+
+        fn to_item(&self) -> HashMap<String, AttributeValue> {
+            HashMap::from([
+                ("id".to_string(), AttributeValue::S(self.id.clone())),
+                ("version".to_string(), AttributeValue::N(self.version.to_string())),
+            ])
+        }
+
+        fn primary_key(&self) -> String {
+            self.id.clone()
+        }
+
+        const VERSIONED: bool = true;
+
+        fn version(&self) -> Option<u64> {
+            Some(self.version)
+        }
+
+        fn set_version(&mut self, version: u64) {
+            self.version = version;
+        }
+    }
+
+    #[test]
+    fn test_transact_update_bumps_version_and_checks_previous() {
+        let mut entity = Versioned { id: "v-1".to_string(), version: 3 };
+
+        let TransactWriteOp::Put { item, condition: Some(condition), .. } =
+            TransactWriteOp::update(&mut entity) else {
+            panic!("expected a conditional put");
+        };
+
+        assert_eq!(entity.version, 4);
+        assert_eq!(item.get("version"), Some(&AttributeValue::N("4".to_string())));
+        assert_eq!(condition.expression, "attribute_exists(id) AND #version = :expected");
+        assert_eq!(condition.values.get(":expected"), Some(&AttributeValue::N("3".to_string())));
+    }
+
+    #[test]
+    fn test_transact_ops_target_prefixed_table() {
+        let op = TransactWriteOp::delete::<Versioned>("v-1".to_string());
+
+        let TransactWriteOp::Delete { table_name, .. } = op.with_table_prefix(Some("staging")) else {
+            panic!("expected a delete");
+        };
+
+        assert_eq!(table_name, "staging_Versioned");
+    }
+
+    #[tokio::test]
+    async fn test_batch_write_rejects_repeated_ids_before_calling_dynamodb() {
+        let config = aws_sdk_dynamodb::Config::builder()
+            .behavior_version(aws_sdk_dynamodb::config::BehaviorVersion::latest())
+            .build();
+        let repo = Repository::new(Client::from_conf(config));
+
+        let put = Versioned { id: "v-1".to_string(), version: 0 };
+        let result = repo.batch_write(vec![put], vec!["v-1".to_string()]).await;
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
 }
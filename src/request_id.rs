@@ -0,0 +1,102 @@
+use axum::{
+    body::Body,
+    http::{ HeaderMap, HeaderName, HeaderValue, Request },
+    middleware::Next,
+    response::Response,
+};
+use tracing::{ field::Empty, info_span, Instrument };
+use uuid::Uuid;
+
+/// Header a request id is read from and echoed back in
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest caller-supplied request id that is reused rather than replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Correlation id for a single HTTP request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Reuse the caller's `X-Request-Id` when it is a sane token, otherwise generate one
+///
+/// # Arguments
+///
+/// * `headers` - Incoming request headers
+///
+/// # Returns
+///
+/// The request id to use for logs and the response header
+pub fn request_id_from_headers(headers: &HeaderMap) -> RequestId {
+    headers
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|id| {
+            !id.is_empty() &&
+                id.len() <= MAX_REQUEST_ID_LEN &&
+                id.chars().all(|c| c.is_ascii_graphic())
+        })
+        .map(|id| RequestId(id.to_string()))
+        .unwrap_or_else(|| RequestId(Uuid::new_v4().to_string()))
+}
+
+/// Run each request inside a `request` span tagged with its correlation id.
+///
+/// The id is stored in the request extensions for handlers and echoed back in
+/// the `X-Request-Id` response header. The span's `operation` field is left
+/// empty for the GraphQL handler to record once the request body is parsed.
+pub async fn request_id_middleware(mut request: Request<Body>, next: Next) -> Response {
+    let request_id = request_id_from_headers(request.headers());
+
+    let span = info_span!(
+        "request",
+        request_id = %request_id.as_str(),
+        method = %request.method(),
+        path = %request.uri().path(),
+        operation = Empty
+    );
+
+    request.extensions_mut().insert(request_id.clone());
+
+    let mut response = next.run(request).instrument(span).await;
+
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_caller_request_id_is_reused() {
+        let request_id = request_id_from_headers(&headers_with("abc-123"));
+
+        assert_eq!(request_id, RequestId("abc-123".to_string()));
+    }
+
+    #[test]
+    fn test_missing_or_unsafe_request_id_is_generated() {
+        let too_long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+
+        for headers in [HeaderMap::new(), headers_with(""), headers_with("has space"), headers_with(&too_long)] {
+            let request_id = request_id_from_headers(&headers);
+            assert!(Uuid::parse_str(request_id.as_str()).is_ok(), "{:?}", request_id);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! DataLoaders used by nested GraphQL resolvers.
+//!
+//! Lookups made while resolving a single request are collected per tick and
+//! fetched together through `Repository::batch_get`, so listing N assets costs
+//! one batch per related table instead of N individual reads.
+
+use std::{ collections::HashMap, marker::PhantomData };
+
+use async_graphql::dataloader::{ DataLoader, Loader };
+
+use crate::{
+    models::{ asset_type::AssetType, location::Location, manufacturer::Manufacturer },
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+
+/// Loads entities of type `T` by id
+pub struct EntityLoader<T> {
+    repo: Repository,
+    _entity: PhantomData<fn() -> T>,
+}
+
+impl<T> EntityLoader<T> {
+    pub fn new(repo: Repository) -> Self {
+        Self {
+            repo,
+            _entity: PhantomData,
+        }
+    }
+}
+
+impl<T: DynamoDbEntity + 'static> Loader<String> for EntityLoader<T> {
+    type Value = T;
+    type Error = AppError;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, T>, AppError> {
+        let entities = self.repo.batch_get::<T>(keys.to_vec()).await?;
+
+        Ok(
+            entities
+                .into_iter()
+                .map(|entity| (entity.primary_key(), entity))
+                .collect()
+        )
+    }
+}
+
+pub type AssetTypeLoader = DataLoader<EntityLoader<AssetType>>;
+pub type LocationLoader = DataLoader<EntityLoader<Location>>;
+pub type ManufacturerLoader = DataLoader<EntityLoader<Manufacturer>>;
+
+/// Creates a DataLoader for `T` that spawns its batch loads on the tokio runtime
+pub fn entity_loader<T: DynamoDbEntity + 'static>(
+    repo: Repository
+) -> DataLoader<EntityLoader<T>> {
+    DataLoader::new(EntityLoader::<T>::new(repo), tokio::spawn)
+}
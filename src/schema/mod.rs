@@ -1,5 +1,5 @@
+pub mod loaders;
 pub mod resolvers;
 pub mod types;
 
 pub use types::*;
-
@@ -1,5 +1,7 @@
 pub mod mutation;
 pub mod query;
+pub mod subscription;
 
 pub use mutation::MutationRoot;
 pub use query::QueryRoot;
+pub use subscription::SubscriptionRoot;
@@ -1,24 +1,42 @@
-use std::{ env, io::Read };
-use std::str::FromStr;
+use std::{ collections::HashSet, env, io::Read };
 
 use async_graphql::Upload;
 use aws_sdk_s3::primitives::ByteStream;
 use base64::Engine;
 use dotenvy::dotenv;
+use serde::Deserialize;
 use serde_json::from_str;
 
-use crate::models::asset::DocumentUploadsInput;
 use crate::{
+    context::ContextExtensions,
+    db::common::to_db_timestamp,
     models::{
-        asset::{ Asset, AssetCurrentStatusOptions, MaintenanceFrequencyOptions, DocumentUpload },
+        asset::{
+            Asset,
+            AssetCurrentStatusOptions,
+            MaintenanceFrequencyOptions,
+            DocumentUploadsInput,
+        },
+        asset_type::AssetType,
+        id::IdGenerator,
+        location::Location,
+        maintenance_schedule::MaintenanceSchedule,
+        manufacturer::Manufacturer,
         prelude::*,
     },
+    schema::asset::{ AssetImportError, ImportResult },
+    repository::{ TransactWriteOp, WriteCondition },
     AppError,
     DbClient,
+    DynamoDbEntity,
     Repository,
     S3Client,
 };
 
+/// Assets written per `import_assets` transaction; each may bring its schedule along and
+/// a transaction holds at most 100 items
+const IMPORT_ASSETS_PER_TRANSACTION: usize = 50;
+
 #[derive(Debug, Default)]
 pub struct AssetMutation;
 
@@ -62,7 +80,7 @@ impl AssetMutation {
         info!("new id: {}", &id);
 
         // Validate that dependencies exist
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Check if asset type exists
         repo
@@ -95,7 +113,7 @@ impl AssetMutation {
             })?;
 
         // Create the asset
-        let asset = Asset::new(
+        let mut asset = Asset::new(
             id,
             name,
             asset_type_id,
@@ -110,7 +128,79 @@ impl AssetMutation {
             warranty_end_date
         ).map_err(|e| e.to_graphql_error())?;
 
-        repo.create(asset).await.map_err(|e| e.to_graphql_error())
+        // Recurring assets get their maintenance schedule from day one
+        let Some(schedule) = MaintenanceSchedule::for_asset(
+            format!("ms-{}", Uuid::new_v4()),
+            &asset
+        ) else {
+            return repo.create(asset).await.map_err(|e| e.to_graphql_error());
+        };
+
+        asset.maintenance_schedule_id = Some(schedule.id.clone());
+
+        repo
+            .transact_write(vec![TransactWriteOp::create(&asset), TransactWriteOp::create(&schedule)]).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(asset)
+    }
+
+    /// Create many assets from a JSON array of `create_asset` style objects
+    ///
+    /// Every row is validated before anything is written; invalid rows are reported by
+    /// index and the rest are written, each asset in one transaction with its schedule.
+    /// Ids derive from each row's asset type and serial number, so retrying a payload
+    /// after a partial failure skips the assets already written instead of duplicating them.
+    async fn import_assets(
+        &self,
+        ctx: &Context<'_>,
+        payload: String
+    ) -> Result<ImportResult, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let rows: Vec<Json> = from_str(&payload).map_err(|e| {
+            AppError::ValidationError(
+                format!("Import payload must be a JSON array of assets: {}", e)
+            ).to_graphql_error()
+        })?;
+        info!("Importing {} assets", rows.len());
+
+        let parsed = parse_import_rows(rows);
+        let known = KnownReferences::load(&repo, &parsed).await.map_err(|e| e.to_graphql_error())?;
+        let (assets, errors) = prepare_import(parsed, &known);
+        let created = assets.len() as i32;
+
+        let already_imported: HashSet<String> = repo
+            .batch_get::<Asset>(
+                assets
+                    .iter()
+                    .map(|asset| asset.id.clone())
+                    .collect()
+            ).await
+            .map_err(|e| e.to_graphql_error())?
+            .into_iter()
+            .map(|asset| asset.id)
+            .collect();
+
+        let pending: Vec<Asset> = assets
+            .into_iter()
+            .filter(|asset| !already_imported.contains(&asset.id))
+            .collect();
+
+        for chunk in pending.chunks(IMPORT_ASSETS_PER_TRANSACTION) {
+            repo
+                .transact_write(chunk.iter().flat_map(import_writes).collect()).await
+                .map_err(|e| e.to_graphql_error())?;
+        }
+
+        Ok(ImportResult { created, errors })
     }
 
     /// Update an existing asset
@@ -139,7 +229,7 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut asset = repo
             .get::<Asset>(id.clone()).await
@@ -242,36 +332,67 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut asset = repo
-            .get::<Asset>(id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", id)))?;
-
-        // Validate status
         let new_status = AssetCurrentStatusOptions::from_string(&status).map_err(|e|
             e.to_graphql_error()
         )?;
 
-        // Update status and handle business logic
-        let old_status = asset.current_status.clone();
-        asset.current_status = new_status.clone();
-        asset.updated_at = Utc::now();
+        transition_asset_status(&repo, &id, |asset| asset.change_status(new_status)).await
+            .map_err(|e| e.to_graphql_error())
+    }
 
-        // Handle status-specific logic
-        match new_status {
-            AssetCurrentStatusOptions::Down => {
-                asset.last_downtime_date = Utc::now();
-            }
-            AssetCurrentStatusOptions::Operational => {
-                // If coming from Down status, could calculate downtime
-                // This is where you'd add business logic for status changes
-            }
-            _ => {}
-        }
+    /// Change asset status, accumulating downtime when it returns to service
+    async fn change_asset_status(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: String,
+        new_status: String,
+        note: Option<String>
+    ) -> Result<Asset, Error> {
+        info!(
+            "Changing asset {} status to {}: {}",
+            asset_id,
+            new_status,
+            note.as_deref().unwrap_or("no note")
+        );
 
-        repo.update(asset).await.map_err(|e| e.to_graphql_error())
+        let db_client = ctx
+            .data::<DbClient>()
+            .map_err(|_| {
+                AppError::InternalServerError("Database client not available".to_string())
+            })?;
+
+        let repo = ctx.repository(db_client);
+
+        let new_status = AssetCurrentStatusOptions::from_string(&new_status).map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        transition_asset_status(&repo, &asset_id, |asset| asset.change_status(new_status)).await
+            .map_err(|e| e.to_graphql_error())
+    }
+
+    /// Return a retired asset to service as operational
+    async fn unretire_asset(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: String,
+        note: Option<String>
+    ) -> Result<Asset, Error> {
+        info!("Un-retiring asset {}: {}", asset_id, note.as_deref().unwrap_or("no note"));
+
+        let db_client = ctx
+            .data::<DbClient>()
+            .map_err(|_| {
+                AppError::InternalServerError("Database client not available".to_string())
+            })?;
+
+        let repo = ctx.repository(db_client);
+
+        transition_asset_status(&repo, &asset_id, |asset| asset.unretire()).await.map_err(|e|
+            e.to_graphql_error()
+        )
     }
 
     /// Add work order to asset
@@ -289,9 +410,9 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut asset = repo
+        let asset = repo
             .get::<Asset>(asset_id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", asset_id)))?;
@@ -308,10 +429,9 @@ impl AssetMutation {
 
         // Add work order if not already present
         if !asset.work_order_ids.contains(&work_order_id) {
-            asset.work_order_ids.push(work_order_id);
-            asset.updated_at = Utc::now();
-
-            repo.update(asset).await.map_err(|e| e.to_graphql_error())
+            link_work_order(&repo, &asset_id, &work_order_id).await.map_err(|e| {
+                e.to_graphql_error()
+            })
         } else {
             Err(
                 AppError::ValidationError(
@@ -340,19 +460,18 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut asset = repo
+        let asset = repo
             .get::<Asset>(asset_id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", asset_id)))?;
 
         // Remove work order if present
-        if let Some(pos) = asset.work_order_ids.iter().position(|x| *x == work_order_id) {
-            asset.work_order_ids.remove(pos);
-            asset.updated_at = Utc::now();
-
-            repo.update(asset).await.map_err(|e| e.to_graphql_error())
+        if asset.work_order_ids.contains(&work_order_id) {
+            unlink_work_order(&repo, &asset_id, &work_order_id).await.map_err(|e| {
+                e.to_graphql_error()
+            })
         } else {
             Err(
                 AppError::ValidationError(
@@ -413,7 +532,7 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             )?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut asset = repo
             .get::<Asset>(asset_id.clone()).await
@@ -457,7 +576,7 @@ impl AssetMutation {
                 ).to_graphql_error()
             )?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify asset exists first
         let mut asset = repo
@@ -551,7 +670,7 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut asset = repo
             .get::<Asset>(asset_id.clone()).await
@@ -592,7 +711,7 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut asset = repo
             .get::<Asset>(asset_id.clone()).await
@@ -633,7 +752,7 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut asset = repo
             .get::<Asset>(asset_id.clone()).await
@@ -670,7 +789,7 @@ impl AssetMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify asset exists
         let asset = repo
@@ -700,3 +819,314 @@ impl AssetMutation {
         repo.delete::<Asset>(id).await.map_err(|e| e.to_graphql_error())
     }
 }
+
+/// Applies `transition` to the stored asset and writes the status and downtime fields back
+///
+/// The write is conditioned on the status read here, so a concurrent status change
+/// returns `AppError::Conflict` instead of double counting downtime.
+async fn transition_asset_status(
+    repo: &Repository,
+    asset_id: &str,
+    transition: impl FnOnce(&mut Asset) -> Result<(), AppError>
+) -> Result<Asset, AppError> {
+    let mut asset = repo
+        .get::<Asset>(asset_id.to_string()).await?
+        .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", asset_id)))?;
+
+    let previous_status = asset.current_status.to_str().to_string();
+    transition(&mut asset)?;
+
+    let mut item = asset.to_item();
+    let mut attribute = |name: &str| item.remove(name).unwrap_or(AttributeValue::Null(true));
+
+    let condition = WriteCondition::new("#current_status = :previous_status")
+        .name("#current_status", "current_status")
+        .name("#last_downtime_date", "last_downtime_date")
+        .name("#total_downtime_hours", "total_downtime_hours")
+        .name("#updated_at", "updated_at")
+        .value(":previous_status", AttributeValue::S(previous_status))
+        .value(":current_status", attribute("current_status"))
+        .value(":last_downtime_date", attribute("last_downtime_date"))
+        .value(":total_downtime_hours", attribute("total_downtime_hours"))
+        .value(":updated_at", attribute("updated_at"));
+
+    repo.update_attributes::<Asset>(
+        asset_id.to_string(),
+        "SET #current_status = :current_status, #last_downtime_date = :last_downtime_date, \
+         #total_downtime_hours = :total_downtime_hours, #updated_at = :updated_at",
+        condition
+    ).await
+}
+
+/// Adds `work_order_id` to the asset's `work_order_ids` set
+///
+/// Uses an `ADD` on the string set, so concurrent links never overwrite each other and
+/// linking twice is harmless.
+pub(crate) async fn link_work_order(
+    repo: &Repository,
+    asset_id: &str,
+    work_order_id: &str
+) -> Result<Asset, AppError> {
+    update_work_order_ids(repo, asset_id, work_order_id, "ADD").await
+}
+
+/// Removes `work_order_id` from the asset's `work_order_ids` set with a `DELETE`
+pub(crate) async fn unlink_work_order(
+    repo: &Repository,
+    asset_id: &str,
+    work_order_id: &str
+) -> Result<Asset, AppError> {
+    update_work_order_ids(repo, asset_id, work_order_id, "DELETE").await
+}
+
+async fn update_work_order_ids(
+    repo: &Repository,
+    asset_id: &str,
+    work_order_id: &str,
+    action: &str
+) -> Result<Asset, AppError> {
+    let condition = WriteCondition::default()
+        .name("#work_order_ids", "work_order_ids")
+        .name("#updated_at", "updated_at")
+        .value(":work_order_ids", AttributeValue::Ss(vec![work_order_id.to_string()]))
+        .value(":updated_at", AttributeValue::S(to_db_timestamp(&Utc::now())));
+
+    repo.update_attributes::<Asset>(
+        asset_id.to_string(),
+        &format!("{} #work_order_ids :work_order_ids SET #updated_at = :updated_at", action),
+        condition
+    ).await
+}
+
+/// One row of an `import_assets` payload, mirroring the `create_asset` arguments
+#[derive(Debug, Deserialize)]
+struct AssetImportRow {
+    name: String,
+    asset_type_id: String,
+    serial_number: String,
+    model_number: String,
+    purchase_date: DateTime<Utc>,
+    installation_date: DateTime<Utc>,
+    location_id: String,
+    manufacturer_id: String,
+    maintenance_frequency: String,
+    warranty_start_date: Option<DateTime<Utc>>,
+    warranty_end_date: Option<DateTime<Utc>>,
+}
+
+/// Decodes each payload row, keeping its index so failures can be reported
+fn parse_import_rows(rows: Vec<Json>) -> Vec<(usize, Result<AssetImportRow, String>)> {
+    rows.into_iter()
+        .enumerate()
+        .map(|(index, row)| (index, serde_json::from_value(row).map_err(|e| e.to_string())))
+        .collect()
+}
+
+/// Asset types, locations and manufacturers that import rows may reference
+#[derive(Debug, Default)]
+struct KnownReferences {
+    asset_types: HashSet<String>,
+    locations: HashSet<String>,
+    manufacturers: HashSet<String>,
+}
+
+impl KnownReferences {
+    /// Looks up every id referenced by the decoded rows with one batch get per table
+    async fn load(
+        repo: &Repository,
+        rows: &[(usize, Result<AssetImportRow, String>)]
+    ) -> Result<Self, AppError> {
+        let decoded = || rows.iter().filter_map(|(_, row)| row.as_ref().ok());
+
+        let asset_types = repo
+            .batch_get::<AssetType>(decoded().map(|r| r.asset_type_id.clone()).collect()).await?;
+        let locations = repo
+            .batch_get::<Location>(decoded().map(|r| r.location_id.clone()).collect()).await?;
+        let manufacturers = repo
+            .batch_get::<Manufacturer>(
+                decoded()
+                    .map(|r| r.manufacturer_id.clone())
+                    .collect()
+            ).await?;
+
+        Ok(Self {
+            asset_types: asset_types.into_iter().map(|t| t.id).collect(),
+            locations: locations.into_iter().map(|l| l.id).collect(),
+            manufacturers: manufacturers.into_iter().map(|m| m.id).collect(),
+        })
+    }
+
+    fn check(&self, row: &AssetImportRow) -> Result<(), String> {
+        if !self.asset_types.contains(&row.asset_type_id) {
+            return Err(format!("Asset type {} not found", row.asset_type_id));
+        }
+        if !self.locations.contains(&row.location_id) {
+            return Err(format!("Location {} not found", row.location_id));
+        }
+        if !self.manufacturers.contains(&row.manufacturer_id) {
+            return Err(format!("Manufacturer {} not found", row.manufacturer_id));
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits decoded rows into assets ready to write and per-row errors
+///
+/// Asset ids derive from the asset type and serial number, which must be unique within
+/// the payload.
+fn prepare_import(
+    rows: Vec<(usize, Result<AssetImportRow, String>)>,
+    known: &KnownReferences
+) -> (Vec<Asset>, Vec<AssetImportError>) {
+    let mut assets = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (index, row) in rows {
+        let asset = row.and_then(|row| {
+            known.check(&row)?;
+
+            let natural_key = format!("{}:{}", row.asset_type_id, row.serial_number);
+            if !seen.insert(natural_key.clone()) {
+                return Err(
+                    format!(
+                        "Serial number {} appears more than once for asset type {}",
+                        row.serial_number,
+                        row.asset_type_id
+                    )
+                );
+            }
+
+            Asset::new(
+                IdGenerator::derived_id("asset", &natural_key),
+                row.name,
+                row.asset_type_id,
+                row.serial_number,
+                row.model_number,
+                row.purchase_date,
+                row.installation_date,
+                row.location_id,
+                row.manufacturer_id,
+                row.maintenance_frequency,
+                row.warranty_start_date,
+                row.warranty_end_date
+            ).map_err(|e| e.to_string())
+        });
+
+        match asset {
+            Ok(asset) => assets.push(asset),
+            Err(message) => errors.push(AssetImportError { index: index as i32, message }),
+        }
+    }
+
+    (assets, errors)
+}
+
+/// Transaction items creating an imported asset and, if it recurs, its schedule
+fn import_writes(asset: &Asset) -> Vec<TransactWriteOp> {
+    let mut asset = asset.clone();
+
+    let Some(schedule) = MaintenanceSchedule::for_asset(
+        IdGenerator::derived_id("ms", &asset.id),
+        &asset
+    ) else {
+        return vec![TransactWriteOp::create(&asset)];
+    };
+
+    asset.maintenance_schedule_id = Some(schedule.id.clone());
+
+    vec![TransactWriteOp::create(&asset), TransactWriteOp::create(&schedule)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_reports_invalid_row_and_keeps_valid_one() {
+        let payload = r#"[
+            {
+                "name": "Boiler",
+                "asset_type_id": "type-1",
+                "serial_number": "SN-1",
+                "model_number": "B1",
+                "purchase_date": "2026-01-05T00:00:00Z",
+                "installation_date": "2026-01-10T00:00:00Z",
+                "location_id": "loc-1",
+                "manufacturer_id": "mfg-1",
+                "maintenance_frequency": "monthly"
+            },
+            {
+                "name": "Chiller",
+                "asset_type_id": "type-1",
+                "serial_number": "SN-2",
+                "model_number": "C1",
+                "purchase_date": "2026-01-05T00:00:00Z",
+                "installation_date": "2026-01-10T00:00:00Z",
+                "location_id": "loc-missing",
+                "manufacturer_id": "mfg-1",
+                "maintenance_frequency": "monthly"
+            }
+        ]"#;
+        let known = KnownReferences {
+            asset_types: HashSet::from(["type-1".to_string()]),
+            locations: HashSet::from(["loc-1".to_string()]),
+            manufacturers: HashSet::from(["mfg-1".to_string()]),
+        };
+
+        let rows: Vec<Json> = from_str(payload).unwrap();
+        let (assets, errors) = prepare_import(parse_import_rows(rows), &known);
+
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].name, "Boiler");
+        assert_eq!(errors, vec![AssetImportError {
+            index: 1,
+            message: "Location loc-missing not found".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn test_import_ids_repeat_across_runs_and_duplicates_are_rejected() {
+        let payload = r#"[
+            {
+                "name": "Boiler",
+                "asset_type_id": "type-1",
+                "serial_number": "SN-1",
+                "model_number": "B1",
+                "purchase_date": "2026-01-05T00:00:00Z",
+                "installation_date": "2026-01-10T00:00:00Z",
+                "location_id": "loc-1",
+                "manufacturer_id": "mfg-1",
+                "maintenance_frequency": "monthly"
+            },
+            {
+                "name": "Boiler again",
+                "asset_type_id": "type-1",
+                "serial_number": "SN-1",
+                "model_number": "B1",
+                "purchase_date": "2026-01-05T00:00:00Z",
+                "installation_date": "2026-01-10T00:00:00Z",
+                "location_id": "loc-1",
+                "manufacturer_id": "mfg-1",
+                "maintenance_frequency": "monthly"
+            }
+        ]"#;
+        let known = KnownReferences {
+            asset_types: HashSet::from(["type-1".to_string()]),
+            locations: HashSet::from(["loc-1".to_string()]),
+            manufacturers: HashSet::from(["mfg-1".to_string()]),
+        };
+        let import = || prepare_import(parse_import_rows(from_str(payload).unwrap()), &known);
+
+        let (first, errors) = import();
+        let (retry, _) = import();
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].id, retry[0].id);
+        assert_eq!(errors[0].index, 1);
+
+        let writes = import_writes(&first[0]);
+        assert_eq!(writes.len(), 2, "monthly assets are written with their schedule");
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
-    models::{ asset_type::{ AssetType, AssetTypeCategory }, prelude::* },
+    context::ContextExtensions,
+    models::{ asset::Asset, asset_type::{ AssetType, AssetTypeCategory }, prelude::* },
     AppError,
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -36,7 +36,7 @@ impl AssetTypeMutation {
         // info!("Creating new asset_type: {:?}", asset_type);
 
         asset_type.validate().map_err(|e| { AppError::ValidationError(e).to_graphql_error() })?;
-        Repository::new(db_client.clone())
+        ctx.repository(db_client)
             .create(asset_type).await
             .map_err(|e| e.to_graphql_error())
         // Validate before saving
@@ -59,7 +59,7 @@ impl AssetTypeMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut asset_type = repo
             .get::<AssetType>(id.clone()).await
@@ -93,13 +93,58 @@ impl AssetTypeMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _ = repo
             .get::<AssetType>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Asset type {} not found", id)))?;
 
+        let asset_count = repo
+            .count_index::<Asset>("TypeIndex", "asset_type_id", &id).await
+            .map_err(|e| e.to_graphql_error())?;
+        ensure_no_assets(&id, asset_count).map_err(|e| e.to_graphql_error())?;
+
         repo.delete::<AssetType>(id).await.map_err(|e| e.to_graphql_error())
     }
 }
+
+/// Rejects deleting an asset type while `asset_count` assets are still of that type
+fn ensure_no_assets(asset_type_id: &str, asset_count: i32) -> Result<(), AppError> {
+    if asset_count > 0 {
+        return Err(
+            AppError::Conflict(
+                format!(
+                    "Cannot delete asset type {} while {} asset(s) still use it",
+                    asset_type_id,
+                    asset_count
+                )
+            )
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_rejected_while_assets_use_asset_type() {
+        match ensure_no_assets("asset_type-1", 3) {
+            Err(AppError::Conflict(msg)) => {
+                assert_eq!(
+                    msg,
+                    "Cannot delete asset type asset_type-1 while 3 asset(s) still use it"
+                );
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_delete_allowed_for_unused_asset_type() {
+        assert!(ensure_no_assets("asset_type-1", 0).is_ok());
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use aws_sdk_s3::presigning::PresigningConfig;
+
+use crate::{
+    context::ContextExtensions,
+    models::{ asset::Asset, prelude::* },
+    schema::attachment::PresignedUpload,
+    AppError,
+    DbClient,
+    S3Client,
+};
+
+/// How long a presigned upload URL stays valid
+const UPLOAD_URL_EXPIRY: Duration = Duration::from_secs(15 * 60);
+
+/// Content types accepted for asset documentation
+const ALLOWED_DOCUMENT_CONTENT_TYPES: [&str; 3] = ["application/pdf", "image/png", "image/jpeg"];
+
+#[derive(Debug, Default)]
+pub struct AttachmentMutation;
+
+#[Object]
+impl AttachmentMutation {
+    /// Generate a presigned PUT URL for uploading a document to an asset
+    async fn generate_asset_document_upload_url(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: String,
+        file_name: String,
+        content_type: String
+    ) -> Result<PresignedUpload, Error> {
+        if !ALLOWED_DOCUMENT_CONTENT_TYPES.contains(&content_type.as_str()) {
+            return Err(
+                AppError::ValidationError(
+                    format!(
+                        "Unsupported content type {}; expected one of {}",
+                        content_type,
+                        ALLOWED_DOCUMENT_CONTENT_TYPES.join(", ")
+                    )
+                ).to_graphql_error()
+            );
+        }
+
+        if file_name.trim().is_empty() || file_name.contains('/') || file_name.contains('\\') {
+            return Err(
+                AppError::ValidationError(
+                    "File name must be non-empty and cannot contain path separators".to_string()
+                ).to_graphql_error()
+            );
+        }
+
+        let db_client = ctx
+            .data::<DbClient>()
+            .map_err(|_| {
+                AppError::InternalServerError("Database client not available".to_string())
+            })?;
+
+        let repo = ctx.repository(db_client);
+
+        // Verify asset exists before handing out a URL
+        repo
+            .get::<Asset>(asset_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", asset_id)))?;
+
+        let s3_client = ctx
+            .data::<S3Client>()
+            .map_err(|e| {
+                AppError::InternalServerError(
+                    format!("s3 client not available: {:?}", e)
+                ).to_graphql_error()
+            })?;
+
+        let bucket_name = ctx.s3_bucket().map_err(|e| e.to_graphql_error())?;
+
+        let key = format!("assets/{}/{}/{}", asset_id, Uuid::new_v4(), file_name);
+
+        let presigning_config = PresigningConfig::expires_in(UPLOAD_URL_EXPIRY).map_err(|e|
+            AppError::InternalServerError(
+                format!("Invalid presigning config: {:?}", e)
+            ).to_graphql_error()
+        )?;
+
+        let presigned = s3_client
+            .put_object()
+            .bucket(bucket_name)
+            .key(&key)
+            .content_type(content_type)
+            .presigned(presigning_config).await
+            .map_err(|e|
+                AppError::InternalServerError(
+                    format!("S3 presign error: {:?}", e)
+                ).to_graphql_error()
+            )?;
+
+        info!("Generated upload URL for asset {} document {}", asset_id, key);
+
+        Ok(PresignedUpload {
+            url: presigned.uri().to_string(),
+            key,
+            expires_at: Utc::now() + UPLOAD_URL_EXPIRY,
+        })
+    }
+
+    /// Record a completed presigned upload on the asset's documentation keys
+    async fn confirm_asset_document(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: String,
+        key: String
+    ) -> Result<Asset, Error> {
+        // Only keys minted for this asset may be attached to it
+        if !key.starts_with(&format!("assets/{}/", asset_id)) {
+            return Err(
+                AppError::ValidationError(
+                    format!("Key {} was not issued for asset {}", key, asset_id)
+                ).to_graphql_error()
+            );
+        }
+
+        let db_client = ctx
+            .data::<DbClient>()
+            .map_err(|_| {
+                AppError::InternalServerError("Database client not available".to_string())
+            })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut asset = repo
+            .get::<Asset>(asset_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", asset_id)))?;
+
+        if asset.documentation_keys.contains(&key) {
+            return Ok(asset);
+        }
+
+        info!("Confirming documentation {} on asset {}", key, asset_id);
+
+        asset.documentation_keys.push(key);
+        asset.updated_at = Utc::now();
+
+        repo.update(asset).await.map_err(|e| e.to_graphql_error())
+    }
+}
@@ -0,0 +1,37 @@
+//! Audit trail for sensitive mutations.
+//!
+//! Mutations that take access or business away from someone (terminating or
+//! suspending a user, revoking a role, blacklisting a vendor) call `record_audit`
+//! once their own write has succeeded. Recording is best effort: the change has
+//! already been made, so a failed audit write is logged rather than surfaced.
+
+use async_graphql::Context;
+use tracing::warn;
+
+use crate::{ context::ContextExtensions, models::audit_event::AuditEvent, Repository };
+
+/// Actor recorded when a request carried no valid bearer token
+const ANONYMOUS_ACTOR: &str = "anonymous";
+
+/// User id of the caller performing a mutation, for the audit trail
+pub(super) fn actor_user_id(ctx: &Context<'_>) -> String {
+    ctx.auth_user()
+        .map(|user| user.user_id.clone())
+        .unwrap_or_else(|| ANONYMOUS_ACTOR.to_string())
+}
+
+/// Writes an audit event for a completed mutation
+///
+/// Build `event` with `AuditEvent::new` only after the mutation's own write has
+/// succeeded, so the trail never records an action that did not happen.
+pub(super) async fn record_audit(repo: &Repository, event: AuditEvent) {
+    if let Err(e) = repo.create(event.clone()).await {
+        warn!(
+            "Failed to record audit event {} for {} {}: {}",
+            event.action,
+            event.target_type,
+            event.target_id,
+            e
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use crate::{
+    auth::{ guard::SET_USER_PASSWORD, require_permission },
+    config::AuthConfig,
+    DbClient,
+    models::{ prelude::*, credential::UserCredential, user::User },
+    context::ContextExtensions,
+    AppError,
+};
+
+#[derive(Debug, Default)]
+pub struct CredentialMutation;
+
+/// Message for every failed login, so callers can't probe which usernames exist
+const INVALID_LOGIN: &str = "Invalid username or password";
+
+#[Object]
+impl CredentialMutation {
+    /// Set a user's password
+    ///
+    /// Users may change their own password; setting anyone else's requires the
+    /// `user:set_password` permission.
+    async fn set_user_password(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+        new_password: String
+    ) -> Result<bool, Error> {
+        let auth_user = ctx
+            .auth_user()
+            .cloned()
+            .ok_or_else(|| {
+                AppError::Unauthorized("Authentication required".to_string()).to_graphql_error()
+            })?;
+
+        if auth_user.user_id != user_id {
+            require_permission(ctx, SET_USER_PASSWORD).await.map_err(|e| e.to_graphql_error())?;
+        }
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut user = repo
+            .get::<User>(user_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("User {} not found", user_id)).to_graphql_error()
+            })?;
+
+        if user.is_terminated() {
+            return Err(
+                AppError::ValidationError(
+                    "Cannot set password for terminated user".to_string()
+                ).to_graphql_error()
+            );
+        }
+
+        let existing = repo
+            .get::<UserCredential>(user_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let credential = UserCredential::new(user_id, &new_password).map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        if existing.is_some() {
+            repo.update(credential).await.map_err(|e| e.to_graphql_error())?;
+        } else {
+            repo.create(credential).await.map_err(|e| e.to_graphql_error())?;
+        }
+
+        user.record_password_change();
+        repo.update(user).await.map_err(|e| e.to_graphql_error())?;
+
+        Ok(true)
+    }
+
+    /// Check a username and password, recording the login attempt
+    ///
+    /// Failed attempts count toward the account lockout; a successful attempt
+    /// resets it. Returns the user on success and never exposes the hash.
+    async fn verify_password(
+        &self,
+        ctx: &Context<'_>,
+        username: String,
+        password: String
+    ) -> Result<User, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        let mut user = repo
+            .query_index::<User>("UsernameIndex", "username", &username, Some(1)).await
+            .map_err(|e| e.to_graphql_error())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::Unauthorized(INVALID_LOGIN.to_string()).to_graphql_error())?;
+
+        // Locked accounts are rejected before the credential is even loaded
+        if let Some(locked_until) = user.account_locked_until.filter(|_| user.is_account_locked()) {
+            return Err(account_locked(locked_until).to_graphql_error());
+        }
+
+        let credential = repo
+            .get::<UserCredential>(user.id.clone()).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let outcome = attempt_login(&mut user, credential.as_ref(), &password, &config.auth);
+
+        // A concurrent attempt bumped the version first; reporting the conflict would
+        // reveal that the username exists
+        let user = repo
+            .update(user).await
+            .map_err(|e| {
+                match e {
+                    AppError::Conflict(_) => AppError::Unauthorized(INVALID_LOGIN.to_string()),
+                    e => e,
+                }.to_graphql_error()
+            })?;
+
+        outcome.map(|_| user).map_err(|e| e.to_graphql_error())
+    }
+}
+
+fn account_locked(locked_until: DateTime<Utc>) -> AppError {
+    AppError::Forbidden(format!("Account locked until {}", locked_until.to_rfc3339()))
+}
+
+/// Checks a password against a user's credential and records the attempt on the user
+///
+/// # Arguments
+///
+/// * `user` - User attempting to log in; its login counters are updated in place
+/// * `credential` - Stored credential for the user, if one has been set
+/// * `password` - Plaintext password supplied by the caller
+/// * `auth_config` - Lockout thresholds
+///
+/// # Returns
+///
+/// `Ok(())` on success, `AppError::Forbidden` if the account is locked, or
+/// `AppError::Unauthorized` for an inactive account or a wrong password
+fn attempt_login(
+    user: &mut User,
+    credential: Option<&UserCredential>,
+    password: &str,
+    auth_config: &AuthConfig
+) -> Result<(), AppError> {
+    if let Some(locked_until) = user.account_locked_until {
+        if user.is_account_locked() {
+            return Err(account_locked(locked_until));
+        }
+
+        // Lockout has expired, so the user gets a fresh set of attempts
+        user.unlock_account();
+    }
+
+    if !user.is_active() {
+        return Err(AppError::Unauthorized(INVALID_LOGIN.to_string()));
+    }
+
+    if !credential.is_some_and(|c| c.verify(password)) {
+        user.record_failed_login(auth_config.max_attempts, auth_config.lockout_duration_minutes);
+
+        return match user.account_locked_until {
+            Some(locked_until) if user.is_account_locked() => Err(account_locked(locked_until)),
+            _ => Err(AppError::Unauthorized(INVALID_LOGIN.to_string())),
+        };
+    }
+
+    user.record_login();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    const PASSWORD: &str = "correct horse battery";
+
+    fn user() -> User {
+        User::new(
+            "user-1".to_string(),
+            "jdoe".to_string(),
+            "jdoe@example.com".to_string(),
+            "Jane".to_string(),
+            "Doe".to_string(),
+            None,
+            "employee".to_string(),
+            "active".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            serde_json::Value::Null,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_five_failures_lock_the_account() {
+        let auth_config = Config::default().auth;
+        let credential = UserCredential::new("user-1".to_string(), PASSWORD).unwrap();
+        let mut user = user();
+
+        for _ in 0..auth_config.max_attempts - 1 {
+            let result = attempt_login(&mut user, Some(&credential), "wrong password", &auth_config);
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+            assert!(!user.is_account_locked());
+        }
+
+        let result = attempt_login(&mut user, Some(&credential), "wrong password", &auth_config);
+
+        assert!(matches!(result, Err(AppError::Forbidden(msg)) if msg.starts_with("Account locked until")));
+        assert!(user.is_account_locked());
+        assert_eq!(user.failed_login_attempts, 5);
+    }
+
+    #[test]
+    fn test_locked_account_rejects_correct_password() {
+        let auth_config = Config::default().auth;
+        let credential = UserCredential::new("user-1".to_string(), PASSWORD).unwrap();
+        let mut user = user();
+        user.account_locked_until = Some(Utc::now() + chrono::Duration::minutes(10));
+
+        let result = attempt_login(&mut user, Some(&credential), PASSWORD, &auth_config);
+
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+        assert!(user.last_login_at.is_none());
+    }
+
+    #[test]
+    fn test_expired_lockout_is_cleared_on_next_login() {
+        let auth_config = Config::default().auth;
+        let credential = UserCredential::new("user-1".to_string(), PASSWORD).unwrap();
+        let mut user = user();
+        user.failed_login_attempts = auth_config.max_attempts;
+        user.account_locked_until = Some(Utc::now() - chrono::Duration::minutes(1));
+
+        assert!(attempt_login(&mut user, Some(&credential), PASSWORD, &auth_config).is_ok());
+        assert_eq!(user.failed_login_attempts, 0);
+        assert!(user.account_locked_until.is_none());
+    }
+}
@@ -0,0 +1,96 @@
+//! Client-supplied idempotency keys for create mutations.
+//!
+//! A create mutation calls `replay` before validating its input, so a retried
+//! request gets back the entity its first attempt created, then `claim`s the key
+//! with a conditional put before its first write, counter increments included.
+//! If a later step fails the claim is `release`d so the client can retry with
+//! the same key.
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::Utc;
+use tracing::warn;
+
+use crate::{
+    models::idempotency_key::IdempotencyKey,
+    repository::WriteCondition,
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+
+/// Entity previously created under `key`, if the key is known and unexpired
+pub(super) async fn replay<T: DynamoDbEntity>(
+    repo: &Repository,
+    scope: &str,
+    key: Option<&str>
+) -> Result<Option<T>, AppError> {
+    let Some(key) = key else {
+        return Ok(None);
+    };
+
+    // The key and its entity may have been written by a concurrent request moments ago
+    let record = repo.get_consistent::<IdempotencyKey>(IdempotencyKey::scoped(scope, key)).await?;
+
+    match record {
+        Some(record) if !record.is_expired() => repo.get_consistent::<T>(record.entity_id).await,
+        _ => Ok(None),
+    }
+}
+
+/// Outcome of claiming an idempotency key
+pub(super) enum Claim<T> {
+    /// No key was supplied
+    Unkeyed,
+    /// This request owns the key and should go on to create the entity
+    Claimed(IdempotencyKey),
+    /// A concurrent request with the same key already created this entity
+    Replayed(T),
+}
+
+/// Claims `key` for `entity_id`, unless a live claim for it already exists
+///
+/// # Returns
+///
+/// The claim outcome, or `AppError::Conflict` if another request holds the key
+/// but has not finished creating its entity yet
+pub(super) async fn claim<T: DynamoDbEntity>(
+    repo: &Repository,
+    scope: &str,
+    key: Option<&str>,
+    entity_id: &str
+) -> Result<Claim<T>, AppError> {
+    let Some(key) = key else {
+        return Ok(Claim::Unkeyed);
+    };
+
+    let record = IdempotencyKey::new(scope, key, entity_id.to_string());
+
+    // Expired rows can outlive their TTL until DynamoDB sweeps them, so they may be overwritten
+    let condition = WriteCondition::new("attribute_not_exists(id) OR #expires_at <= :now")
+        .name("#expires_at", "expires_at")
+        .value(":now", AttributeValue::N(Utc::now().timestamp().to_string()));
+
+    match repo.put_if(&record, condition).await {
+        Ok(()) => Ok(Claim::Claimed(record)),
+        Err(AppError::Conflict(_)) =>
+            match replay::<T>(repo, scope, Some(key)).await? {
+                Some(entity) => Ok(Claim::Replayed(entity)),
+                None =>
+                    Err(
+                        AppError::Conflict(
+                            "A request with this idempotency key is still in progress".to_string()
+                        )
+                    ),
+            }
+        Err(e) => Err(e),
+    }
+}
+
+/// Gives up a claim after the create it guarded failed
+pub(super) async fn release<T>(repo: &Repository, claim: Claim<T>) {
+    if let Claim::Claimed(record) = claim
+        && let Err(e) = repo.delete::<IdempotencyKey>(record.key.clone()).await
+    {
+        warn!("Failed to release idempotency key {}: {}", record.key, e);
+    }
+}
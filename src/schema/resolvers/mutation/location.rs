@@ -1,8 +1,11 @@
+use std::collections::HashMap;
+
 use crate::{
+    context::ContextExtensions,
     models::{
         prelude::*,
         address::AddressInput,
-        location::Location,
+        location::{ ensure_acyclic_parent, Location },
         location_type::LocationType,
         prelude::*,
     },
@@ -36,7 +39,7 @@ impl LocationMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
         let id = format!("location-{}", Uuid::new_v4());
 
         // Validate that location type exists
@@ -97,7 +100,7 @@ impl LocationMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut location = repo
             .get::<Location>(id.clone()).await
@@ -119,15 +122,6 @@ impl LocationMutation {
         // Validate new parent location if provided
         if let Some(ref new_parent_id) = parent_location_id {
             if !new_parent_id.is_empty() {
-                // Check that we're not creating a circular reference
-                if new_parent_id == &location.id {
-                    return Err(
-                        AppError::ValidationError(
-                            "Location cannot be its own parent".to_string()
-                        ).to_graphql_error()
-                    );
-                }
-
                 repo
                     .get::<Location>(new_parent_id.clone()).await
                     .map_err(|e| e.to_graphql_error())?
@@ -136,6 +130,11 @@ impl LocationMutation {
                             format!("Parent location {} not found", new_parent_id)
                         ).to_graphql_error()
                     })?;
+
+                let locations = locations_by_id(&repo).await.map_err(|e| e.to_graphql_error())?;
+                ensure_acyclic_parent(&location.id, new_parent_id, &locations).map_err(|e|
+                    e.to_graphql_error()
+                )?;
             }
         }
 
@@ -178,17 +177,14 @@ impl LocationMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut location = repo
-            .get::<Location>(id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Location {} not found", id)))?;
-
-        location.is_active = true;
-        location.updated_at = chrono::Utc::now();
-
-        repo.update(location).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<Location>(
+                id,
+                HashMap::from([("is_active".to_string(), AttributeValue::Bool(true))])
+            ).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     /// Deactivate a location
@@ -201,21 +197,18 @@ impl LocationMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
-
-        let mut location = repo
-            .get::<Location>(id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Location {} not found", id)))?;
+        let repo = ctx.repository(db_client);
 
         // Business rule: Check if location has active assets before deactivating
         // This would require scanning the assets table - for now we'll allow it
         // In production, you might want to add this validation
 
-        location.is_active = false;
-        location.updated_at = chrono::Utc::now();
-
-        repo.update(location).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<Location>(
+                id,
+                HashMap::from([("is_active".to_string(), AttributeValue::Bool(false))])
+            ).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     /// Move location to a new parent
@@ -233,7 +226,7 @@ impl LocationMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut location = repo
             .get::<Location>(id.clone()).await
@@ -242,15 +235,7 @@ impl LocationMutation {
 
         // Validate new parent if provided
         if let Some(ref parent_id) = new_parent_id {
-            if parent_id == &location.id {
-                return Err(
-                    AppError::ValidationError(
-                        "Location cannot be its own parent".to_string()
-                    ).to_graphql_error()
-                );
-            }
-
-            let parent_location = repo
+            repo
                 .get::<Location>(parent_id.clone()).await
                 .map_err(|e| e.to_graphql_error())?
                 .ok_or_else(|| {
@@ -259,17 +244,10 @@ impl LocationMutation {
                     ).to_graphql_error()
                 })?;
 
-            // Additional validation: Check for circular references
-            // This would require traversing the parent chain - simplified for now
-            if let Some(ref grandparent_id) = parent_location.parent_location_id {
-                if grandparent_id == &location.id {
-                    return Err(
-                        AppError::ValidationError(
-                            "Cannot create circular parent relationship".to_string()
-                        ).to_graphql_error()
-                    );
-                }
-            }
+            let locations = locations_by_id(&repo).await.map_err(|e| e.to_graphql_error())?;
+            ensure_acyclic_parent(&location.id, parent_id, &locations).map_err(|e|
+                e.to_graphql_error()
+            )?;
         }
 
         location.parent_location_id = new_parent_id;
@@ -288,7 +266,7 @@ impl LocationMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify location exists
         let location = repo
@@ -313,3 +291,17 @@ impl LocationMutation {
         repo.delete::<Location>(id).await.map_err(|e| e.to_graphql_error())
     }
 }
+
+/// Every location keyed by id, for walking the parent hierarchy
+async fn locations_by_id(
+    repo: &Repository
+) -> Result<HashMap<String, Location>, AppError> {
+    let locations = repo.list::<Location>(None).await?;
+
+    Ok(
+        locations
+            .into_iter()
+            .map(|l| (l.id.clone(), l))
+            .collect()
+    )
+}
@@ -1,4 +1,9 @@
-use crate::{ DbClient, models::{ prelude::*, location_type::LocationType }, AppError, Repository };
+use crate::{
+    context::ContextExtensions,
+    DbClient,
+    models::{ prelude::*, location_type::LocationType },
+    AppError,
+};
 
 #[derive(Debug, Default)]
 pub(crate) struct LocationTypeMutation;
@@ -31,7 +36,7 @@ impl LocationTypeMutation {
 
         location_type.validate().map_err(|e| { AppError::ValidationError(e).to_graphql_error() })?;
 
-        Repository::new(db_client.clone())
+        ctx.repository(db_client)
             .create(location_type).await
             .map_err(|e| e.to_graphql_error())
     }
@@ -52,7 +57,7 @@ impl LocationTypeMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut location_type = repo
             .get::<LocationType>(id.clone()).await
@@ -82,7 +87,7 @@ impl LocationTypeMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify location type exists
         let _ = repo
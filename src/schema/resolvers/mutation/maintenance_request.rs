@@ -1,15 +1,18 @@
+use std::collections::HashMap;
+
 use async_graphql::*;
+use aws_sdk_dynamodb::types::AttributeValue;
 use tracing::warn;
 use uuid::Uuid;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::{
         maintenance_request::{ MaintenanceRequest, MaintenanceRequestStatus },
         work_order::WorkOrderSeverity,
     },
     DbClient,
-    Repository,
 };
 
 #[derive(Default, Debug)]
@@ -72,7 +75,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Generate a unique ID for the maintenance request
         let id = format!("mr-{}", Uuid::new_v4());
@@ -105,7 +108,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Get the existing maintenance request
         let mut maintenance_request = repo
@@ -206,34 +209,22 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        // Get the existing maintenance request
-        let mut maintenance_request = repo
-            .get::<MaintenanceRequest>(input.id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| {
-                AppError::NotFound(
-                    format!("Maintenance request with id {} not found", input.id)
-                ).to_graphql_error()
-            })?;
-
-        // Update status
-        maintenance_request.status = input.status;
+        let mut updates = HashMap::from([
+            ("status".to_string(), AttributeValue::S(input.status.to_str().to_string())),
+        ]);
 
         // If marking as read and read_by_id is provided, set it
         if input.status == MaintenanceRequestStatus::Read {
             if let Some(read_by_id) = input.read_by_id {
-                maintenance_request.read_by_id = Some(read_by_id);
+                updates.insert("read_by_id".to_string(), AttributeValue::S(read_by_id));
             }
         }
 
-        // Update the timestamp
-        maintenance_request.updated_at = chrono::Utc::now();
-
-        repo.update(maintenance_request.clone()).await.map_err(|e| e.to_graphql_error())?;
-
-        Ok(maintenance_request)
+        repo
+            .update_fields::<MaintenanceRequest>(input.id, updates).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     /// Mark maintenance request as read
@@ -250,7 +241,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Get the existing maintenance request
         let mut maintenance_request = repo
@@ -286,7 +277,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Get the existing maintenance request
         let mut maintenance_request = repo
@@ -319,7 +310,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Get the existing maintenance request
         let mut maintenance_request = repo
@@ -369,7 +360,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Get the existing maintenance request
         let mut maintenance_request = repo
@@ -408,7 +399,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Check if the maintenance request exists
         let maintenance_request = repo
@@ -450,7 +441,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Get the existing maintenance request
         let mut maintenance_request = repo
@@ -492,7 +483,7 @@ impl MaintenanceRequestMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Get the existing maintenance request
         let mut maintenance_request = repo
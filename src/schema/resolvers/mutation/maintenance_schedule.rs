@@ -1,18 +1,110 @@
+use std::collections::{ HashMap, HashSet };
+
 use crate::{
     DbClient,
     models::{
         prelude::*,
         maintenance_schedule::{ MaintenanceSchedule, MaintenanceCadence, CadenceUnit },
-        asset::Asset,
+        asset::{ Asset, MaintenanceFrequencyOptions },
         user::User,
+        work_order::WorkOrder,
     },
+    context::ContextExtensions,
+    repository::TransactWriteOp,
+    schema::resolvers::query::maintenance_schedule::schedules_due_before,
     AppError,
     Repository,
 };
 
+use super::{ asset::link_work_order, work_order::next_work_order_number };
+
 #[derive(Debug, Default)]
 pub struct MaintenanceScheduleMutation;
 
+/// Creates a preventive work order for every schedule due by `now`
+///
+/// Each work order is written in one transaction with its schedule, which records the
+/// occurrence and moves `next_due_at` forward. Running again creates nothing for
+/// occurrences already generated, including ones claimed by a concurrent run. A schedule
+/// that fails is logged and skipped.
+///
+/// # Returns
+///
+/// The work orders created by this run
+pub(crate) async fn generate_due_work_orders(
+    repo: &Repository,
+    now: DateTime<Utc>
+) -> Result<Vec<WorkOrder>, AppError> {
+    let due_schedules = schedules_due_before(repo, now, None).await?;
+    let asset_ids: Vec<String> = due_schedules
+        .iter()
+        .map(|schedule| schedule.asset_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let assets: HashMap<String, Asset> = repo
+        .batch_get::<Asset>(asset_ids).await?
+        .into_iter()
+        .map(|asset| (asset.id.clone(), asset))
+        .collect();
+
+    let mut generated = Vec::new();
+
+    for mut schedule in due_schedules {
+        let Some(asset) = assets.get(&schedule.asset_id) else {
+            warn!("Skipping schedule {}: asset {} not found", schedule.id, schedule.asset_id);
+            continue;
+        };
+
+        // One schedule failing must not hold back the others; the next run retries it
+        match generate_for_schedule(repo, &mut schedule, asset, now).await {
+            Ok(Some(work_order)) => generated.push(work_order),
+            Ok(None) => {}
+            Err(e) => {
+                warn!("Failed to generate work order for schedule {}: {}", schedule.id, e);
+            }
+        }
+    }
+
+    Ok(generated)
+}
+
+/// Writes the work order `schedule` is due for, together with the advanced schedule
+///
+/// # Returns
+///
+/// The work order, or `None` if this occurrence was already generated
+async fn generate_for_schedule(
+    repo: &Repository,
+    schedule: &mut MaintenanceSchedule,
+    asset: &Asset,
+    now: DateTime<Utc>
+) -> Result<Option<WorkOrder>, AppError> {
+    let number = format!("{:06}", next_work_order_number(repo).await?);
+    let Some(work_order) = schedule.generate_work_order(asset, number, now)? else {
+        return Ok(None);
+    };
+
+    match
+        repo.transact_write(
+            vec![TransactWriteOp::create(&work_order), TransactWriteOp::update(schedule)]
+        ).await
+    {
+        Ok(()) => {
+            if let Err(e) = link_work_order(repo, &work_order.asset_id, &work_order.id).await {
+                warn!("Failed to link work order {} to its asset: {}", work_order.id, e);
+            }
+            Ok(Some(work_order))
+        }
+        // The work order id is per occurrence, so a conflict means it already exists
+        Err(AppError::Conflict(_)) => {
+            info!("Work order for schedule {} was already generated", schedule.id);
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
 #[Object]
 impl MaintenanceScheduleMutation {
     async fn create_maintenance_schedule(
@@ -35,7 +127,7 @@ impl MaintenanceScheduleMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
         let id = format!("ms-{}", Uuid::new_v4());
 
         let _asset = repo
@@ -79,6 +171,64 @@ impl MaintenanceScheduleMutation {
         repo.create(schedule).await.map_err(|e| e.to_graphql_error())
     }
 
+    /// Generate the recurring schedule for an asset from its maintenance frequency
+    ///
+    /// The schedule is created and linked via `asset.maintenance_schedule_id` in one
+    /// transaction. One-time and as-needed assets are rejected.
+    async fn generate_schedule_for_asset(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: String
+    ) -> Result<MaintenanceSchedule, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut asset = repo
+            .get::<Asset>(asset_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Asset {} not found", asset_id)).to_graphql_error()
+            })?;
+
+        if let Some(existing_id) = &asset.maintenance_schedule_id {
+            return Err(
+                AppError::ValidationError(
+                    format!("Asset {} already has maintenance schedule {}", asset_id, existing_id)
+                ).to_graphql_error()
+            );
+        }
+
+        let schedule = MaintenanceSchedule::for_asset(
+            format!("ms-{}", Uuid::new_v4()),
+            &asset
+        ).ok_or_else(|| {
+            AppError::ValidationError(
+                format!(
+                    "Asset {} has {} maintenance and needs no schedule",
+                    asset_id,
+                    asset.maintenance_frequency.to_str()
+                )
+            ).to_graphql_error()
+        })?;
+
+        asset.maintenance_schedule_id = Some(schedule.id.clone());
+        asset.updated_at = Utc::now();
+
+        repo
+            .transact_write(
+                vec![TransactWriteOp::create(&schedule), TransactWriteOp::update(&mut asset)]
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(schedule)
+    }
+
     async fn update_maintenance_schedule(
         &self,
         ctx: &Context<'_>,
@@ -98,7 +248,7 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedule = repo
             .get::<MaintenanceSchedule>(id.clone()).await
@@ -161,7 +311,7 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedule = repo
             .get::<MaintenanceSchedule>(id.clone()).await
@@ -179,25 +329,83 @@ impl MaintenanceScheduleMutation {
 
         let completion_time = completed_at.unwrap_or_else(|| Utc::now());
 
-        schedule.last_completed_at = Some(completion_time);
-        schedule.last_completed_by_user_id = Some(completed_by_user_id);
+        schedule.record_completion(completion_time, Some(completed_by_user_id), None, false);
 
-        if schedule.recurring {
-            if let Some(primary_cadence) = schedule.cadences.first() {
-                if let Ok(days_to_add) = primary_cadence.to_days() {
-                    schedule.next_due_at =
-                        completion_time + chrono::Duration::days(days_to_add as i64);
-                } else {
-                    schedule.next_due_at = completion_time + chrono::Duration::days(30);
-                }
-            }
-        } else {
-            schedule.active = false;
-        }
+        repo.update(schedule).await.map_err(|e| e.to_graphql_error())
+    }
 
-        schedule.updated_at = Utc::now();
+    /// Mark maintenance done and roll the schedule forward
+    ///
+    /// The linked asset's `last_downtime_date` is set to `completed_at` and both
+    /// records are written in one transaction. Quarterly and annual schedules stay
+    /// on their cycle boundaries; `missedCycles` on the result reports any cycles
+    /// that passed before this completion.
+    async fn complete_maintenance(
+        &self,
+        ctx: &Context<'_>,
+        schedule_id: String,
+        completed_at: DateTime<Utc>,
+        notes: Option<String>
+    ) -> Result<MaintenanceSchedule, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
 
-        repo.update(schedule).await.map_err(|e| e.to_graphql_error())
+        let repo = ctx.repository(db_client);
+
+        let mut schedule = repo
+            .get::<MaintenanceSchedule>(schedule_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(
+                    format!("Maintenance schedule {} not found", schedule_id)
+                ).to_graphql_error()
+            })?;
+
+        let mut asset = repo
+            .get::<Asset>(schedule.asset_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(
+                    format!("Asset {} not found", schedule.asset_id)
+                ).to_graphql_error()
+            })?;
+
+        let anchor_to_cycle = matches!(
+            asset.maintenance_frequency,
+            MaintenanceFrequencyOptions::Quarterly | MaintenanceFrequencyOptions::Annually
+        );
+        let completed_by = ctx.auth_user().map(|user| user.user_id.clone());
+
+        schedule.record_completion(completed_at, completed_by, notes, anchor_to_cycle);
+
+        asset.last_downtime_date = completed_at;
+        asset.updated_at = Utc::now();
+
+        repo
+            .transact_write(
+                vec![TransactWriteOp::update(&mut schedule), TransactWriteOp::update(&mut asset)]
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(schedule)
+    }
+
+    /// Create preventive work orders for every maintenance schedule that is now due
+    async fn generate_due_work_orders(&self, ctx: &Context<'_>) -> Result<Vec<WorkOrder>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        generate_due_work_orders(&repo, Utc::now()).await.map_err(|e| e.to_graphql_error())
     }
 
     async fn reschedule_maintenance(
@@ -214,7 +422,7 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedule = repo
             .get::<MaintenanceSchedule>(id.clone()).await
@@ -240,17 +448,17 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedule = repo
             .get::<MaintenanceSchedule>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Maintenance schedule {} not found", id)))?;
-
         schedule.active = true;
-        schedule.updated_at = Utc::now();
 
-        repo.update(schedule).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<MaintenanceSchedule>(id, schedule.activation_updates()).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     async fn deactivate_maintenance_schedule(
@@ -266,17 +474,17 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedule = repo
             .get::<MaintenanceSchedule>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Maintenance schedule {} not found", id)))?;
-
         schedule.active = false;
-        schedule.updated_at = Utc::now();
 
-        repo.update(schedule).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<MaintenanceSchedule>(id, schedule.activation_updates()).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     async fn add_cadence_to_schedule(
@@ -294,7 +502,7 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedule = repo
             .get::<MaintenanceSchedule>(id.clone()).await
@@ -330,7 +538,7 @@ impl MaintenanceScheduleMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let source_schedule = repo
             .get::<MaintenanceSchedule>(source_schedule_id.clone()).await
@@ -380,7 +588,7 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut results = Vec::new();
 
@@ -417,7 +625,7 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut results = Vec::new();
 
@@ -474,7 +682,7 @@ impl MaintenanceScheduleMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _source_asset = repo
             .get::<Asset>(source_asset_id.clone()).await
@@ -548,7 +756,7 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _schedule = repo
             .get::<MaintenanceSchedule>(id.clone()).await
@@ -571,7 +779,7 @@ impl MaintenanceScheduleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let all_schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -585,7 +793,7 @@ impl MaintenanceScheduleMutation {
         let mut deleted_count = 0;
 
         for schedule in asset_schedules {
-            if repo.delete::<MaintenanceSchedule>(schedule.id).await.is_ok() {
+            if matches!(repo.delete::<MaintenanceSchedule>(schedule.id).await, Ok(true)) {
                 deleted_count += 1;
             }
         }
@@ -1,13 +1,16 @@
+use std::collections::HashMap;
+
 use crate::{
+    context::ContextExtensions,
     DbClient,
     models::{
         prelude::*,
         manufacturer::Manufacturer,
         address::{ Address, AddressInput },
         asset::Asset,
+        validation::{ validate_email, validate_url },
     },
     AppError,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -37,7 +40,7 @@ impl ManufacturerMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
         let id = format!("manufacturer-{}", Uuid::new_v4());
 
         Address::from(address.clone())
@@ -101,7 +104,7 @@ impl ManufacturerMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturer = repo
             .get::<Manufacturer>(id.clone()).await
@@ -149,6 +152,7 @@ impl ManufacturerMutation {
                     ).to_graphql_error()
                 );
             }
+            validate_email(&new_email).map_err(|e| e.to_graphql_error())?;
             let existing_manufacturers = repo
                 .list::<Manufacturer>(None).await
                 .map_err(|e| e.to_graphql_error())?;
@@ -171,6 +175,9 @@ impl ManufacturerMutation {
         }
 
         if let Some(new_website) = website {
+            if !new_website.is_empty() {
+                validate_url(&new_website).map_err(|e| e.to_graphql_error())?;
+            }
             manufacturer.website = if new_website.is_empty() { None } else { Some(new_website) };
         }
 
@@ -223,17 +230,14 @@ impl ManufacturerMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
-
-        let mut manufacturer = repo
-            .get::<Manufacturer>(id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Manufacturer {} not found", id)))?;
-
-        manufacturer.active = true;
-        manufacturer.updated_at = chrono::Utc::now();
+        let repo = ctx.repository(db_client);
 
-        repo.update(manufacturer).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<Manufacturer>(
+                id,
+                HashMap::from([("active".to_string(), AttributeValue::Bool(true))])
+            ).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     async fn deactivate_manufacturer(
@@ -249,17 +253,14 @@ impl ManufacturerMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut manufacturer = repo
-            .get::<Manufacturer>(id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Manufacturer {} not found", id)))?;
-
-        manufacturer.active = false;
-        manufacturer.updated_at = chrono::Utc::now();
-
-        repo.update(manufacturer).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<Manufacturer>(
+                id,
+                HashMap::from([("active".to_string(), AttributeValue::Bool(false))])
+            ).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     async fn update_manufacturer_contact_info(
@@ -279,7 +280,7 @@ impl ManufacturerMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturer = repo
             .get::<Manufacturer>(id.clone()).await
@@ -298,6 +299,7 @@ impl ManufacturerMutation {
                     ).to_graphql_error()
                 );
             }
+            validate_email(&new_email).map_err(|e| e.to_graphql_error())?;
             let existing_manufacturers = repo
                 .list::<Manufacturer>(None).await
                 .map_err(|e| e.to_graphql_error())?;
@@ -356,7 +358,7 @@ impl ManufacturerMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let source_manufacturer = repo
             .get::<Manufacturer>(source_id.clone()).await
@@ -423,7 +425,7 @@ impl ManufacturerMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut results = Vec::new();
 
@@ -473,11 +475,15 @@ impl ManufacturerMutation {
         Ok(results)
     }
 
+    /// Delete a manufacturer no asset references any more
     async fn delete_manufacturer(
         &self,
         ctx: &Context<'_>,
         id: String,
-        force: Option<bool>
+        #[graphql(
+            name = "force",
+            deprecation = "Ignored: manufacturers referenced by assets are never deleted"
+        )] _force: Option<bool>
     ) -> Result<bool, Error> {
         // info!("Deleting manufacturer: {}", id);
 
@@ -487,24 +493,58 @@ impl ManufacturerMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _manufacturer = repo
             .get::<Manufacturer>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Manufacturer {} not found", id)))?;
 
-        let assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
-        let has_assets = assets.iter().any(|a| a.manufacturer_id == id);
+        let asset_count = repo
+            .count_index::<Asset>("ManufacturerIndex", "manufacturer_id", &id).await
+            .map_err(|e| e.to_graphql_error())?;
+        ensure_no_assets(&id, asset_count).map_err(|e| e.to_graphql_error())?;
 
-        if has_assets && !force.unwrap_or(false) {
-            return Err(
-                AppError::ValidationError(
-                    "Cannot delete manufacturer with existing assets. Use force=true to override.".to_string()
-                ).to_graphql_error()
-            );
+        repo.delete::<Manufacturer>(id).await.map_err(|e| e.to_graphql_error())
+    }
+}
+
+/// Rejects deleting a manufacturer while `asset_count` assets still reference it
+fn ensure_no_assets(manufacturer_id: &str, asset_count: i32) -> Result<(), AppError> {
+    if asset_count > 0 {
+        return Err(
+            AppError::Conflict(
+                format!(
+                    "Cannot delete manufacturer {} while {} asset(s) still reference it",
+                    manufacturer_id,
+                    asset_count
+                )
+            )
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_rejected_while_assets_reference_manufacturer() {
+        match ensure_no_assets("manufacturer-1", 2) {
+            Err(AppError::Conflict(msg)) => {
+                assert_eq!(
+                    msg,
+                    "Cannot delete manufacturer manufacturer-1 while 2 asset(s) still reference it"
+                );
+            }
+            other => panic!("expected a conflict, got {:?}", other),
         }
+    }
 
-        repo.delete::<Manufacturer>(id).await.map_err(|e| e.to_graphql_error())
+    #[test]
+    fn test_delete_allowed_without_referencing_assets() {
+        assert!(ensure_no_assets("manufacturer-1", 0).is_ok());
     }
 }
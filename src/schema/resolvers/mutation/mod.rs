@@ -18,6 +18,11 @@ mod notification_template;
 mod permission;
 mod task;
 mod maintenance_request;
+mod attachment;
+mod credential;
+mod user_preferences;
+mod idempotency;
+mod audit;
 
 #[derive(Debug, Default, MergedObject)]
 pub struct MutationRoot(
@@ -25,19 +30,21 @@ pub struct MutationRoot(
     asset::AssetMutation,
     asset_type::AssetTypeMutation,
     location::LocationMutation,
-    // manufacturer_mutation_root: manufacturer::ManufacturerMutationRoot,
     user::UserMutation,
     work_order::WorkOrderMutation,
     role::RoleMutation,
     permission::PermissionMutation,
     maintenance_schedule::MaintenanceScheduleMutation,
     manufacturer::ManufacturerMutation,
-    // notification_mutation_root: notification::NotificationMutationRoot,
-    // vendor_mutation_root: vendor::VendorMutationRoot,
-    // vendor_category_mutation_root: vendor_category::VendorCategoryMutationRoot,
+    notification::NotificationMutation,
+    vendor::VendorMutation,
+    vendor_category::VendorCategoryMutation,
     user_role::UserRoleMutation,
     task::TaskMutation,
-    maintenance_request::MaintenanceRequestMutation
+    maintenance_request::MaintenanceRequestMutation,
+    attachment::AttachmentMutation,
+    credential::CredentialMutation,
+    user_preferences::UserPreferencesMutation,
+    notification_template::NotificationTemplateMutation
     // user_notification_preferences_mutation_root: user_notification_preferences::UserNotificationPreferencesMutationRoot,
-    // notification_template_mutation_root: notification_template::NotificationTemplateMutationRoot,
 );
@@ -0,0 +1,90 @@
+use crate::{
+    context::ContextExtensions,
+    DbClient,
+    models::{
+        notification::{ Notification, NotificationChannels },
+        prelude::*,
+        user_preferences::UserPreferences,
+    },
+    AppError,
+    Repository,
+};
+
+#[derive(Debug, Default)]
+pub struct NotificationMutation;
+
+/// Stored preferences for `user_id`, `None` if the user has never saved any
+pub(super) async fn preferences_for(
+    repo: &Repository,
+    user_id: &str
+) -> Result<Option<UserPreferences>, AppError> {
+    let stored = repo.query_index::<UserPreferences>("UserIndex", "user_id", user_id, None).await?;
+
+    Ok(UserPreferences::first_created(stored))
+}
+
+/// Checks if a user with `preferences` may be notified over `channel`
+///
+/// Users without stored preferences get every channel, matching the defaults.
+pub(super) fn should_notify(
+    preferences: Option<&UserPreferences>,
+    channel: NotificationChannels
+) -> bool {
+    preferences.is_none_or(|preferences| preferences.allows_channel(channel))
+}
+
+#[Object]
+impl NotificationMutation {
+    /// Mark a notification as read
+    async fn mark_notification_read(
+        &self,
+        ctx: &Context<'_>,
+        id: String
+    ) -> Result<Notification, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut notification = repo
+            .get::<Notification>(id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Notification {} not found", id)).to_graphql_error()
+            })?;
+
+        if notification.read {
+            return Ok(notification);
+        }
+
+        notification.read = true;
+
+        repo.update(notification).await.map_err(|e| e.to_graphql_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_notify_respects_disabled_channels() {
+        let mut preferences = UserPreferences::create_default("user-1".to_string()).unwrap();
+        preferences.email_notifications_enabled = false;
+        preferences.mobile_notifications_enabled = true;
+
+        assert!(!should_notify(Some(&preferences), NotificationChannels::Email));
+        assert!(should_notify(Some(&preferences), NotificationChannels::Push));
+        assert!(should_notify(Some(&preferences), NotificationChannels::InApp));
+    }
+
+    #[test]
+    fn test_should_notify_defaults_to_enabled_without_preferences() {
+        assert!(should_notify(None, NotificationChannels::Email));
+        assert!(should_notify(None, NotificationChannels::Desktop));
+    }
+}
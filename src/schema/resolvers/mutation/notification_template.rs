@@ -0,0 +1,61 @@
+use crate::{
+    context::ContextExtensions,
+    DbClient,
+    models::{ id::IdGenerator, notification_template::NotificationTemplate, prelude::* },
+    schema::resolvers::query::notification_template::template_by_key,
+    AppError,
+};
+
+#[derive(Debug, Default)]
+pub struct NotificationTemplateMutation;
+
+#[Object]
+impl NotificationTemplateMutation {
+    /// Create a notification template
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Lookup key, unique across templates
+    /// * `subject_template` - Subject with `{{var}}` placeholders
+    /// * `body_template` - Body with `{{var}}` placeholders
+    /// * `variables` - Names of the variables the templates expect
+    async fn create_notification_template(
+        &self,
+        ctx: &Context<'_>,
+        key: String,
+        subject_template: String,
+        body_template: String,
+        variables: Vec<String>
+    ) -> Result<NotificationTemplate, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        if
+            template_by_key(&repo, &key).await
+                .map_err(|e| e.to_graphql_error())?
+                .is_some()
+        {
+            return Err(
+                AppError::ValidationError(
+                    format!("Notification template '{}' already exists", key)
+                ).to_graphql_error()
+            );
+        }
+
+        let template = NotificationTemplate::new(
+            IdGenerator::new_id("notification_template"),
+            key,
+            subject_template,
+            body_template,
+            variables
+        ).map_err(|e| e.to_graphql_error())?;
+
+        repo.create(template).await.map_err(|e| e.to_graphql_error())
+    }
+}
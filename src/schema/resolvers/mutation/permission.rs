@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use crate::{
+    context::ContextExtensions,
     DbClient,
     models::{
         prelude::*,
@@ -7,7 +10,6 @@ use crate::{
         role::Role,
     },
     AppError,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -37,7 +39,7 @@ impl PermissionMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
         let id = format!("permission-{}", Uuid::new_v4());
 
         let _role = repo
@@ -110,7 +112,7 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permission = repo
             .get::<Permission>(id.clone()).await
@@ -190,7 +192,7 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permission = repo
             .get::<Permission>(permission_id.clone()).await
@@ -221,7 +223,7 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permission = repo
             .get::<Permission>(permission_id.clone()).await
@@ -258,17 +260,14 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut permission = repo
-            .get::<Permission>(id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Permission {} not found", id)))?;
-
-        permission.active = true;
-        permission.updated_at = Utc::now();
-
-        repo.update(permission).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<Permission>(
+                id,
+                HashMap::from([("active".to_string(), AttributeValue::Bool(true))])
+            ).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     async fn deactivate_permission(
@@ -284,17 +283,14 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut permission = repo
-            .get::<Permission>(id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Permission {} not found", id)))?;
-
-        permission.active = false;
-        permission.updated_at = Utc::now();
-
-        repo.update(permission).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<Permission>(
+                id,
+                HashMap::from([("active".to_string(), AttributeValue::Bool(false))])
+            ).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     async fn extend_permission_expiration(
@@ -311,7 +307,7 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permission = repo
             .get::<Permission>(id.clone()).await
@@ -348,7 +344,7 @@ impl PermissionMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let source_permission = repo
             .get::<Permission>(source_permission_id.clone()).await
@@ -402,7 +398,7 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut results = Vec::new();
 
@@ -451,7 +447,7 @@ impl PermissionMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _source_role = repo
             .get::<Role>(source_role_id.clone()).await
@@ -525,7 +521,7 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _permission = repo
             .get::<Permission>(id.clone()).await
@@ -548,7 +544,7 @@ impl PermissionMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let all_permissions = repo
             .list::<Permission>(None).await
@@ -562,7 +558,7 @@ impl PermissionMutation {
         let mut deleted_count = 0;
 
         for permission in role_permissions {
-            if repo.delete::<Permission>(permission.id).await.is_ok() {
+            if matches!(repo.delete::<Permission>(permission.id).await, Ok(true)) {
                 deleted_count += 1;
             }
         }
@@ -1,6 +1,12 @@
+use std::collections::HashMap;
+
 use crate::{
+    context::ContextExtensions,
+    auth::{ guard::{ MANAGE_ROLES, MANAGE_SYSTEM_ROLES }, require_permission },
+    db::common::to_db_timestamp,
     DbClient,
     models::{ prelude::*, role::{ Role, RoleType }, permission::Permission, user_role::UserRole },
+    repository::WriteCondition,
     AppError,
     Repository,
 };
@@ -34,7 +40,7 @@ impl RoleMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
         let id = format!("role-{}", Uuid::new_v4());
 
         let existing_roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
@@ -53,6 +59,10 @@ impl RoleMutation {
                         format!("Parent role {} not found", parent_id)
                     ).to_graphql_error()
                 })?;
+
+            Role::ensure_acyclic_parent(&id, parent_id, &existing_roles).map_err(|e|
+                e.to_graphql_error()
+            )?;
         }
 
         let permission_ids = permission_ids.unwrap_or_default();
@@ -106,7 +116,7 @@ impl RoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut role = repo
             .get::<Role>(id.clone()).await
@@ -162,6 +172,12 @@ impl RoleMutation {
                             format!("Parent role {} not found", parent_id)
                         ).to_graphql_error()
                     })?;
+
+                let all_roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
+                Role::ensure_acyclic_parent(&role.id, &parent_id, &all_roles).map_err(|e|
+                    e.to_graphql_error()
+                )?;
+
                 role.parent_role_id = Some(parent_id);
             } else {
                 role.parent_role_id = None;
@@ -189,81 +205,71 @@ impl RoleMutation {
         repo.update(role).await.map_err(|e| e.to_graphql_error())
     }
 
+    #[graphql(deprecation = "Use grantPermissionToRole")]
     async fn add_permission_to_role(
         &self,
         ctx: &Context<'_>,
         role_id: String,
         permission_id: String
     ) -> Result<Role, Error> {
-        // info!("Adding permission {} to role {}", permission_id, role_id);
+        self.grant_permission_to_role(ctx, role_id, permission_id).await
+    }
+
+    #[graphql(deprecation = "Use revokePermissionFromRole")]
+    async fn remove_permission_from_role(
+        &self,
+        ctx: &Context<'_>,
+        role_id: String,
+        permission_id: String
+    ) -> Result<Role, Error> {
+        self.revoke_permission_from_role(ctx, role_id, permission_id).await
+    }
 
+    /// Grant a permission to a role
+    ///
+    /// The permission is `ADD`ed to the role's `permission_ids` set, so concurrent grants
+    /// never overwrite each other and granting twice is harmless. Requires `MANAGE_ROLES`,
+    /// and system roles also require `MANAGE_SYSTEM_ROLES`.
+    async fn grant_permission_to_role(
+        &self,
+        ctx: &Context<'_>,
+        role_id: String,
+        permission_id: String
+    ) -> Result<Role, Error> {
         let db_client = ctx
             .data::<DbClient>()
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
-
-        let mut role = repo
-            .get::<Role>(role_id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Role {} not found", role_id)))?;
-
-        if role.is_system_role {
-            return Err(
-                AppError::ValidationError(
-                    "Cannot modify permissions for system roles".to_string()
-                ).to_graphql_error()
-            );
-        }
-
-        let _permission = repo
-            .get::<Permission>(permission_id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| {
-                AppError::ValidationError(
-                    format!("Permission {} not found", permission_id)
-                ).to_graphql_error()
-            })?;
-
-        role.add_permission(permission_id);
+        let repo = ctx.repository(db_client);
 
-        repo.update(role).await.map_err(|e| e.to_graphql_error())
+        change_role_permission(ctx, &repo, &role_id, &permission_id, PermissionChange::Grant).await.map_err(|e|
+            e.to_graphql_error()
+        )
     }
 
-    async fn remove_permission_from_role(
+    /// Revoke a permission from a role with a `DELETE` on its `permission_ids` set
+    ///
+    /// Revoking a permission the role does not hold is harmless. Requires `MANAGE_ROLES`,
+    /// and system roles also require `MANAGE_SYSTEM_ROLES`.
+    async fn revoke_permission_from_role(
         &self,
         ctx: &Context<'_>,
         role_id: String,
         permission_id: String
     ) -> Result<Role, Error> {
-        // info!("Removing permission {} from role {}", permission_id, role_id);
-
         let db_client = ctx
             .data::<DbClient>()
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
-
-        let mut role = repo
-            .get::<Role>(role_id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Role {} not found", role_id)))?;
-
-        if role.is_system_role {
-            return Err(
-                AppError::ValidationError(
-                    "Cannot modify permissions for system roles".to_string()
-                ).to_graphql_error()
-            );
-        }
-
-        role.remove_permission(&permission_id);
+        let repo = ctx.repository(db_client);
 
-        repo.update(role).await.map_err(|e| e.to_graphql_error())
+        change_role_permission(ctx, &repo, &role_id, &permission_id, PermissionChange::Revoke).await.map_err(|e|
+            e.to_graphql_error()
+        )
     }
 
     async fn set_role_permissions(
@@ -280,7 +286,7 @@ impl RoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut role = repo
             .get::<Role>(role_id.clone()).await
@@ -321,17 +327,14 @@ impl RoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut role = repo
-            .get::<Role>(id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| AppError::NotFound(format!("Role {} not found", id)))?;
-
-        role.active = true;
-        role.updated_at = Utc::now();
-
-        repo.update(role).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<Role>(
+                id,
+                HashMap::from([("active".to_string(), AttributeValue::Bool(true))])
+            ).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     async fn deactivate_role(&self, ctx: &Context<'_>, id: String) -> Result<Role, Error> {
@@ -343,9 +346,9 @@ impl RoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        let mut role = repo
+        let role = repo
             .get::<Role>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Role {} not found", id)))?;
@@ -358,10 +361,12 @@ impl RoleMutation {
             );
         }
 
-        role.active = false;
-        role.updated_at = Utc::now();
-
-        repo.update(role).await.map_err(|e| e.to_graphql_error())
+        repo
+            .update_fields::<Role>(
+                id,
+                HashMap::from([("active".to_string(), AttributeValue::Bool(false))])
+            ).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     async fn extend_role_expiration(
@@ -378,7 +383,7 @@ impl RoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut role = repo
             .get::<Role>(id.clone()).await
@@ -416,7 +421,7 @@ impl RoleMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let source_role = repo
             .get::<Role>(source_role_id.clone()).await
@@ -467,7 +472,7 @@ impl RoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         if !["add", "remove", "set"].contains(&operation.as_str()) {
             return Err(
@@ -539,7 +544,7 @@ impl RoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let role = repo
             .get::<Role>(id.clone()).await
@@ -583,3 +588,68 @@ impl RoleMutation {
         repo.delete::<Role>(id).await.map_err(|e| e.to_graphql_error())
     }
 }
+
+/// Whether `change_role_permission` grants or revokes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PermissionChange {
+    Grant,
+    Revoke,
+}
+
+impl PermissionChange {
+    /// The `UpdateItem` set action applying the change
+    fn update_action(self) -> &'static str {
+        match self {
+            PermissionChange::Grant => "ADD",
+            PermissionChange::Revoke => "DELETE",
+        }
+    }
+}
+
+/// Adds or removes `permission_id` on the role's `permission_ids` set in place
+///
+/// Both the role and the permission must exist. The caller needs `MANAGE_ROLES`, plus
+/// `MANAGE_SYSTEM_ROLES` for a system role; without the latter the write is also
+/// conditioned on the role still not being a system role.
+async fn change_role_permission(
+    ctx: &Context<'_>,
+    repo: &Repository,
+    role_id: &str,
+    permission_id: &str,
+    change: PermissionChange
+) -> Result<Role, AppError> {
+    require_permission(ctx, MANAGE_ROLES).await?;
+
+    let role = repo
+        .get::<Role>(role_id.to_string()).await?
+        .ok_or_else(|| AppError::NotFound(format!("Role {} not found", role_id)))?;
+
+    let condition = if role.is_system_role {
+        require_permission(ctx, MANAGE_SYSTEM_ROLES).await?;
+        WriteCondition::default()
+    } else {
+        WriteCondition::new("#is_system_role = :false")
+            .name("#is_system_role", "is_system_role")
+            .value(":false", AttributeValue::Bool(false))
+    };
+
+    let permission_exists = repo.exists::<Permission>(permission_id.to_string()).await?;
+    if !permission_exists {
+        return Err(AppError::NotFound(format!("Permission {} not found", permission_id)));
+    }
+
+    let condition = condition
+        .name("#permission_ids", "permission_ids")
+        .name("#updated_at", "updated_at")
+        .value(":permission_ids", AttributeValue::Ss(vec![permission_id.to_string()]))
+        .value(":updated_at", AttributeValue::S(to_db_timestamp(&Utc::now())));
+
+    repo.update_attributes::<Role>(
+        role_id.to_string(),
+        &format!(
+            "{} #permission_ids :permission_ids SET #updated_at = :updated_at",
+            change.update_action()
+        ),
+        condition
+    ).await
+}
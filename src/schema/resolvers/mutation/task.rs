@@ -1,4 +1,9 @@
-use crate::{ DbClient, models::{ prelude::*, task::{ Task, TaskType } }, AppError, Repository };
+use crate::{
+    context::ContextExtensions,
+    DbClient,
+    models::{ prelude::*, task::{ Task, TaskType } },
+    AppError,
+};
 
 #[derive(Debug, Default)]
 pub struct TaskMutation;
@@ -26,7 +31,7 @@ impl TaskMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
         let id = format!("task-{}", Uuid::new_v4());
         let latest_tasks = repo.list::<Task>(Some(1)).await.map_err(|e| e.to_graphql_error())?;
         let next_number = if let Some(latest) = latest_tasks.first() {
@@ -73,7 +78,7 @@ impl TaskMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut task = repo
             .get::<Task>(id.clone()).await
@@ -134,7 +139,7 @@ impl TaskMutation {
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut task = repo
             .get::<Task>(id.clone()).await
@@ -157,7 +162,7 @@ impl TaskMutation {
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut task = repo
             .get::<Task>(id.clone()).await
@@ -185,7 +190,7 @@ impl TaskMutation {
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut task = repo
             .get::<Task>(id.clone()).await
@@ -208,7 +213,7 @@ impl TaskMutation {
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify task exists
         let _task = repo
@@ -1,46 +1,196 @@
 use crate::{
+    auth::{
+        guard::{ MANAGE_USER_LOGINS, PERMANENTLY_DELETE_USER, TERMINATE_USER },
+        require_permission,
+        AuthUser,
+    },
     DbClient,
-    models::{ prelude::*, user::{ User, UserStatus, UserType }, role::Role },
+    models::{
+        audit_event::{
+            AuditEvent,
+            USER_DELETED,
+            USER_STATUS_CHANGED,
+            USER_SUSPENDED,
+            USER_TERMINATED,
+        },
+        id::IdGenerator,
+        prelude::*,
+        user::{ User, UserStatus, UserType },
+        role::Role,
+        validation::{ normalize_phone, validate_email },
+    },
+    context::ContextExtensions,
     AppError,
-    Repository,
 };
 
+use super::{ audit, idempotency::{ self, Claim } };
+
 #[derive(Debug, Default)]
 pub struct UserMutation;
 
+/// Idempotency key scope for `create_user`
+const CREATE_USER_SCOPE: &str = "create_user";
+
+/// Fields accepted by `create_user`; JSON-valued fields are passed as JSON strings
+#[derive(Debug, InputObject)]
+pub struct CreateUserInput {
+    pub username: String,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub display_name: Option<String>,
+    pub user_type: String,
+    pub primary_role_id: Option<String>,
+    pub department: Option<String>,
+    pub job_title: Option<String>,
+    pub manager_id: Option<String>,
+    pub contact_number: Option<String>,
+    pub secondary_email: Option<String>,
+    pub hire_date: Option<DateTime<Utc>>,
+    pub certification_levels: Option<String>,
+    pub profile_image_url: Option<String>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    pub emergency_contact: Option<String>,
+    pub address: Option<String>,
+    pub employee_id: Option<String>,
+    pub cost_center: Option<String>,
+    pub security_clearance: Option<String>,
+    pub notes: Option<String>,
+    pub metadata: Option<String>,
+    pub created_by: Option<String>,
+}
+
+/// Fields accepted by `update_user`
+///
+/// Omitted fields are left unchanged; an empty string clears an optional field.
+#[derive(Debug, InputObject)]
+pub struct UpdateUserInput {
+    pub id: String,
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub display_name: Option<String>,
+    pub user_type: Option<String>,
+    pub primary_role_id: Option<String>,
+    pub department: Option<String>,
+    pub job_title: Option<String>,
+    pub manager_id: Option<String>,
+    pub contact_number: Option<String>,
+    pub secondary_email: Option<String>,
+    pub certification_levels: Option<String>,
+    pub profile_image_url: Option<String>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    pub emergency_contact: Option<String>,
+    pub address: Option<String>,
+    pub employee_id: Option<String>,
+    pub cost_center: Option<String>,
+    pub security_clearance: Option<String>,
+    pub notes: Option<String>,
+    pub metadata: Option<String>,
+}
+
+/// Parses an optional JSON string argument, naming `label` in the error
+fn parse_json_field(value: Option<&str>, label: &str) -> Result<Option<Json>, AppError> {
+    value
+        .map(|raw| {
+            serde_json
+                ::from_str::<Json>(raw)
+                .map_err(|_| AppError::ValidationError(format!("Invalid {} JSON", label)))
+        })
+        .transpose()
+}
+
+impl CreateUserInput {
+    /// Builds an active user with id `id`, parsing the JSON-valued fields
+    fn into_user(self, id: String) -> Result<User, AppError> {
+        let certification_levels = parse_json_field(
+            self.certification_levels.as_deref(),
+            "certification levels"
+        )?.unwrap_or_else(|| Json::Object(serde_json::Map::new()));
+        let emergency_contact = parse_json_field(
+            self.emergency_contact.as_deref(),
+            "emergency contact"
+        )?;
+        let address = parse_json_field(self.address.as_deref(), "address")?;
+        let metadata = parse_json_field(self.metadata.as_deref(), "metadata")?;
+
+        User::new(
+            id,
+            self.username,
+            self.email,
+            self.first_name,
+            self.last_name,
+            self.display_name,
+            self.user_type,
+            "active".to_string(), // Default to active status
+            self.primary_role_id,
+            self.department,
+            self.job_title,
+            self.manager_id,
+            self.contact_number,
+            self.secondary_email,
+            self.hire_date,
+            certification_levels,
+            self.profile_image_url,
+            self.timezone,
+            self.locale,
+            emergency_contact,
+            address,
+            self.employee_id,
+            self.cost_center,
+            self.security_clearance,
+            self.notes,
+            metadata,
+            self.created_by
+        )
+    }
+}
+
+/// Audit event for `actor` terminating `user`
+fn termination_audit_event(actor: &AuthUser, user: &User, reason: Option<&str>) -> AuditEvent {
+    AuditEvent::new(
+        actor.user_id.clone(),
+        USER_TERMINATED,
+        "user",
+        user.id.clone(),
+        serde_json::json!({
+            "reason": reason,
+            "termination_date": user.termination_date,
+        })
+    )
+}
+
+/// Normalizes an optional contact number to E.164 using the configured default region
+///
+/// Empty strings clear the number.
+fn normalize_contact_number(
+    ctx: &Context<'_>,
+    contact_number: Option<String>
+) -> Result<Option<String>, Error> {
+    let Some(contact) = contact_number.filter(|c| !c.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+    normalize_phone(&contact, &config.default_phone_region)
+        .map(Some)
+        .map_err(|e| e.to_graphql_error())
+}
+
 #[Object]
 impl UserMutation {
     /// Create a new user
     async fn create_user(
         &self,
         ctx: &Context<'_>,
-        username: String,
-        email: String,
-        first_name: String,
-        last_name: String,
-        display_name: Option<String>,
-        user_type: String,
-        primary_role_id: Option<String>,
-        department: Option<String>,
-        job_title: Option<String>,
-        manager_id: Option<String>,
-        contact_number: Option<String>,
-        secondary_email: Option<String>,
-        hire_date: Option<DateTime<Utc>>,
-        certification_levels: Option<String>, // JSON string
-        profile_image_url: Option<String>,
-        timezone: Option<String>,
-        locale: Option<String>,
-        emergency_contact: Option<String>, // JSON string
-        address: Option<String>, // JSON string
-        employee_id: Option<String>,
-        cost_center: Option<String>,
-        security_clearance: Option<String>,
-        notes: Option<String>,
-        metadata: Option<String>, // JSON string
-        created_by: Option<String>
+        mut input: CreateUserInput,
+        idempotency_key: Option<String>
     ) -> Result<User, Error> {
-        // info!("Creating new user: {}", username);
+        // info!("Creating new user: {}", input.username);
 
         let db_client = ctx.data::<DbClient>().map_err(|e| {
             warn!("Failed to get db_client from context: {:?}", e);
@@ -49,104 +199,88 @@ impl UserMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
-        let id = format!("user-{}", Uuid::new_v4());
+        let repo = ctx.repository(db_client);
+        let id = IdGenerator::new_id("user");
+
+        if
+            let Some(existing) = idempotency
+                ::replay::<User>(&repo, CREATE_USER_SCOPE, idempotency_key.as_deref()).await
+                .map_err(|e| e.to_graphql_error())?
+        {
+            return Ok(existing);
+        }
 
         // Check if username already exists
         let existing_users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
-        if existing_users.iter().any(|u| u.username.to_lowercase() == username.to_lowercase()) {
+        let username = input.username.to_lowercase();
+        if existing_users.iter().any(|u| u.username.to_lowercase() == username) {
             return Err(
                 AppError::ValidationError("Username already exists".to_string()).to_graphql_error()
             );
         }
 
         // Check if email already exists
-        if existing_users.iter().any(|u| u.email.to_lowercase() == email.to_lowercase()) {
+        if existing_users.iter().any(|u| u.email.to_lowercase() == input.email.to_lowercase()) {
             return Err(
                 AppError::ValidationError("Email already exists".to_string()).to_graphql_error()
             );
         }
 
         // Validate role exists if provided
-        if let Some(ref role_id) = primary_role_id {
-            let _role = repo
-                .get::<Role>(role_id.clone()).await
-                .map_err(|e| e.to_graphql_error())?
-                .ok_or_else(|| {
+        if let Some(ref role_id) = input.primary_role_id {
+            let exists = repo
+                .exists::<Role>(role_id.clone()).await
+                .map_err(|e| e.to_graphql_error())?;
+            if !exists {
+                return Err(
                     AppError::ValidationError(
                         format!("Role {} not found", role_id)
                     ).to_graphql_error()
-                })?;
+                );
+            }
         }
 
         // Validate manager exists if provided
-        if let Some(ref mgr_id) = manager_id {
-            let _manager = repo
-                .get::<User>(mgr_id.clone()).await
-                .map_err(|e| e.to_graphql_error())?
-                .ok_or_else(|| {
+        if let Some(ref mgr_id) = input.manager_id {
+            let exists = repo
+                .exists::<User>(mgr_id.clone()).await
+                .map_err(|e| e.to_graphql_error())?;
+            if !exists {
+                return Err(
                     AppError::ValidationError(
                         format!("Manager {} not found", mgr_id)
                     ).to_graphql_error()
-                })?;
+                );
+            }
         }
 
-        // Parse JSON fields
-        let cert_levels = if let Some(ref cert_str) = certification_levels {
-            serde_json
-                ::from_str::<Json>(cert_str)
-                .map_err(|_| {
-                    AppError::ValidationError(
-                        "Invalid certification levels JSON".to_string()
-                    ).to_graphql_error()
-                })?
-        } else {
-            Json::Object(serde_json::Map::new())
-        };
+        input.contact_number = normalize_contact_number(ctx, input.contact_number.take())?;
 
-        let emergency_contact_json = if let Some(ref ec_str) = emergency_contact {
-            Some(
-                serde_json
-                    ::from_str::<Json>(ec_str)
-                    .map_err(|_| {
-                        AppError::ValidationError(
-                            "Invalid emergency contact JSON".to_string()
-                        ).to_graphql_error()
-                    })?
-            )
-        } else {
-            None
-        };
+        let user = input.into_user(id).map_err(|e| e.to_graphql_error())?;
 
-        let address_json = if let Some(ref addr_str) = address {
-            Some(
-                serde_json
-                    ::from_str::<Json>(addr_str)
-                    .map_err(|_| {
-                        AppError::ValidationError(
-                            "Invalid address JSON".to_string()
-                        ).to_graphql_error()
-                    })?
-            )
-        } else {
-            None
-        };
+        let claim = idempotency
+            ::claim::<User>(&repo, CREATE_USER_SCOPE, idempotency_key.as_deref(), &user.id).await
+            .map_err(|e| e.to_graphql_error())?;
+        if let Claim::Replayed(existing) = claim {
+            return Ok(existing);
+        }
 
-        let metadata_json = if let Some(ref meta_str) = metadata {
-            Some(
-                serde_json
-                    ::from_str::<Json>(meta_str)
-                    .map_err(|_| {
-                        AppError::ValidationError(
-                            "Invalid metadata JSON".to_string()
-                        ).to_graphql_error()
-                    })?
-            )
-        } else {
-            None
-        };
+        match repo.create(user).await {
+            Ok(user) => Ok(user),
+            Err(e) => {
+                idempotency::release(&repo, claim).await;
+                Err(e.to_graphql_error())
+            }
+        }
+    }
 
-        let user = User::new(
+    /// Update an existing user
+    async fn update_user(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateUserInput
+    ) -> Result<User, Error> {
+        let UpdateUserInput {
             id,
             username,
             email,
@@ -154,60 +288,25 @@ impl UserMutation {
             last_name,
             display_name,
             user_type,
-            "active".to_string(), // Default to active status
             primary_role_id,
             department,
             job_title,
             manager_id,
             contact_number,
             secondary_email,
-            hire_date,
-            cert_levels,
+            certification_levels,
             profile_image_url,
             timezone,
             locale,
-            emergency_contact_json,
-            address_json,
+            emergency_contact,
+            address,
             employee_id,
             cost_center,
             security_clearance,
             notes,
-            metadata_json,
-            created_by
-        ).map_err(|e| e.to_graphql_error())?;
-
-        repo.create(user).await.map_err(|e| e.to_graphql_error())
-    }
+            metadata,
+        } = input;
 
-    /// Update an existing user
-    async fn update_user(
-        &self,
-        ctx: &Context<'_>,
-        id: String,
-        username: Option<String>,
-        email: Option<String>,
-        first_name: Option<String>,
-        last_name: Option<String>,
-        display_name: Option<String>,
-        user_type: Option<String>,
-        primary_role_id: Option<String>,
-        department: Option<String>,
-        job_title: Option<String>,
-        manager_id: Option<String>,
-        contact_number: Option<String>,
-        secondary_email: Option<String>,
-        certification_levels: Option<String>,
-        profile_image_url: Option<String>,
-        timezone: Option<String>,
-        locale: Option<String>,
-        emergency_contact: Option<String>,
-        address: Option<String>,
-        employee_id: Option<String>,
-        cost_center: Option<String>,
-        security_clearance: Option<String>,
-        notes: Option<String>,
-        metadata: Option<String>
-    ) -> Result<User, Error> {
         // info!("Updating user: {}", id);
 
         let db_client = ctx
@@ -216,7 +315,7 @@ impl UserMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
@@ -255,6 +354,8 @@ impl UserMutation {
         }
 
         if let Some(new_email) = email {
+            validate_email(&new_email).map_err(|e| e.to_graphql_error())?;
+
             // Check if new email already exists (excluding current user)
             let existing_users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
             if
@@ -320,9 +421,16 @@ impl UserMutation {
             }
         }
         if let Some(contact) = contact_number {
-            user.contact_number = if contact.is_empty() { None } else { Some(contact) };
+            user.contact_number = normalize_contact_number(ctx, Some(contact))?;
         }
         if let Some(sec_email) = secondary_email {
+            if !sec_email.is_empty() {
+                validate_email(&sec_email).map_err(|_| {
+                    AppError::ValidationError(
+                        "Invalid secondary email format".to_string()
+                    ).to_graphql_error()
+                })?;
+            }
             user.secondary_email = if sec_email.is_empty() { None } else { Some(sec_email) };
         }
         if let Some(cert_str) = certification_levels {
@@ -422,7 +530,7 @@ impl UserMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
@@ -430,18 +538,27 @@ impl UserMutation {
             .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
 
         let new_status = UserStatus::from_string(&status).map_err(|e| e.to_graphql_error())?;
+        let previous_status = user.status;
 
         user.status = new_status;
-
-        // Add reason to notes if provided
-        if let Some(reason_text) = reason {
-            let current_notes = user.notes.clone().unwrap_or_default();
-            user.notes = Some(format!("{}; STATUS CHANGE: {}", current_notes, reason_text));
-        }
-
         user.updated_at = Utc::now();
 
-        repo.update(user).await.map_err(|e| e.to_graphql_error())
+        let user = repo.update(user).await.map_err(|e| e.to_graphql_error())?;
+
+        let event = AuditEvent::new(
+            audit::actor_user_id(ctx),
+            USER_STATUS_CHANGED,
+            "user",
+            user.id.clone(),
+            serde_json::json!({
+                "previous_status": previous_status.to_str(),
+                "status": user.status.to_str(),
+                "reason": reason,
+            })
+        );
+        audit::record_audit(&repo, event).await;
+
+        Ok(user)
     }
 
     /// Suspend a user account
@@ -459,23 +576,27 @@ impl UserMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
 
-        user.status = UserStatus::Suspended;
+        user.suspend().map_err(|e| e.to_graphql_error())?;
 
-        if let Some(reason_text) = reason {
-            let current_notes = user.notes.clone().unwrap_or_default();
-            user.notes = Some(format!("{}; SUSPENDED: {}", current_notes, reason_text));
-        }
+        let user = repo.update(user).await.map_err(|e| e.to_graphql_error())?;
 
-        user.updated_at = Utc::now();
+        let event = AuditEvent::new(
+            audit::actor_user_id(ctx),
+            USER_SUSPENDED,
+            "user",
+            user.id.clone(),
+            serde_json::json!({ "reason": reason })
+        );
+        audit::record_audit(&repo, event).await;
 
-        repo.update(user).await.map_err(|e| e.to_graphql_error())
+        Ok(user)
     }
 
     /// Reactivate a suspended user account
@@ -488,7 +609,7 @@ impl UserMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
@@ -521,51 +642,49 @@ impl UserMutation {
     ) -> Result<User, Error> {
         // info!("Terminating user: {}", id);
 
+        let actor = require_permission(ctx, TERMINATE_USER).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
         let db_client = ctx
             .data::<DbClient>()
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
 
-        if matches!(user.status, UserStatus::Terminated) {
-            return Err(
-                AppError::ValidationError(
-                    "User is already terminated".to_string()
-                ).to_graphql_error()
-            );
-        }
+        user.terminate(termination_date).map_err(|e| e.to_graphql_error())?;
 
-        user.status = UserStatus::Terminated;
-        user.termination_date = termination_date.or_else(|| Some(Utc::now()));
+        let user = repo.update(user).await.map_err(|e| e.to_graphql_error())?;
 
-        if let Some(reason_text) = reason {
-            let current_notes = user.notes.clone().unwrap_or_default();
-            user.notes = Some(format!("{}; TERMINATED: {}", current_notes, reason_text));
-        }
+        let event = termination_audit_event(&actor, &user, reason.as_deref());
+        audit::record_audit(&repo, event).await;
 
-        user.updated_at = Utc::now();
-
-        repo.update(user).await.map_err(|e| e.to_graphql_error())
+        Ok(user)
     }
 
     /// Unlock a user account
+    ///
+    /// Requires the `user:manage_logins` permission, since clearing the lockout
+    /// would otherwise let anyone reset it between password guesses.
     async fn unlock_user_account(&self, ctx: &Context<'_>, id: String) -> Result<User, Error> {
         // info!("Unlocking user account: {}", id);
 
+        require_permission(ctx, MANAGE_USER_LOGINS).await.map_err(|e| e.to_graphql_error())?;
+
         let db_client = ctx
             .data::<DbClient>()
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
@@ -580,16 +699,21 @@ impl UserMutation {
     }
 
     /// Record user login
+    ///
+    /// Requires the `user:manage_logins` permission; interactive logins are
+    /// recorded by `verify_password`.
     async fn record_user_login(&self, ctx: &Context<'_>, id: String) -> Result<User, Error> {
         // info!("Recording user login: {}", id);
 
+        require_permission(ctx, MANAGE_USER_LOGINS).await.map_err(|e| e.to_graphql_error())?;
+
         let db_client = ctx
             .data::<DbClient>()
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
@@ -615,7 +739,7 @@ impl UserMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
@@ -643,7 +767,7 @@ impl UserMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user = repo
             .get::<User>(id.clone()).await
@@ -653,15 +777,18 @@ impl UserMutation {
         // Soft delete by terminating
         user.status = UserStatus::Terminated;
         user.termination_date = Some(Utc::now());
-
-        if let Some(reason_text) = reason {
-            let current_notes = user.notes.clone().unwrap_or_default();
-            user.notes = Some(format!("{}; DELETED: {}", current_notes, reason_text));
-        }
-
         user.updated_at = Utc::now();
 
-        repo.update(user).await.map_err(|e| e.to_graphql_error())?;
+        let user = repo.update(user).await.map_err(|e| e.to_graphql_error())?;
+
+        let event = AuditEvent::new(
+            audit::actor_user_id(ctx),
+            USER_DELETED,
+            "user",
+            user.id.clone(),
+            serde_json::json!({ "reason": reason })
+        );
+        audit::record_audit(&repo, event).await;
 
         Ok(true)
     }
@@ -670,13 +797,15 @@ impl UserMutation {
     async fn permanently_delete_user(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
         // info!("Permanently deleting user: {}", id);
 
+        require_permission(ctx, PERMANENTLY_DELETE_USER).await.map_err(|e| e.to_graphql_error())?;
+
         let db_client = ctx
             .data::<DbClient>()
             .map_err(|_| {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify user exists
         let user = repo
@@ -693,6 +822,121 @@ impl UserMutation {
             );
         }
 
-        repo.delete::<User>(id).await.map_err(|e| e.to_graphql_error())
+        // A concurrent delete may have removed the user since it was read
+        let deleted = repo.delete::<User>(id.clone()).await.map_err(|e| e.to_graphql_error())?;
+        if !deleted {
+            return Err(AppError::NotFound(format!("User {} not found", id)).to_graphql_error());
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> User {
+        User::new(
+            "user-1".to_string(),
+            "jdoe".to_string(),
+            "jdoe@example.com".to_string(),
+            "Jane".to_string(),
+            "Doe".to_string(),
+            None,
+            "employee".to_string(),
+            "terminated".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            serde_json::Value::Null,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None
+        ).unwrap()
+    }
+
+    fn create_input() -> CreateUserInput {
+        CreateUserInput {
+            username: "jdoe".to_string(),
+            email: "jdoe@example.com".to_string(),
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            display_name: None,
+            user_type: "employee".to_string(),
+            primary_role_id: None,
+            department: Some("Maintenance".to_string()),
+            job_title: None,
+            manager_id: None,
+            contact_number: None,
+            secondary_email: None,
+            hire_date: None,
+            certification_levels: Some(r#"{"electrical":"journeyman"}"#.to_string()),
+            profile_image_url: None,
+            timezone: None,
+            locale: None,
+            emergency_contact: None,
+            address: None,
+            employee_id: Some("E-100".to_string()),
+            cost_center: None,
+            security_clearance: None,
+            notes: None,
+            metadata: None,
+            created_by: None,
+        }
+    }
+
+    #[test]
+    fn test_create_input_builds_active_user() {
+        let user = create_input().into_user("user-1".to_string()).unwrap();
+
+        assert_eq!(user.id, "user-1");
+        assert_eq!(user.username, "jdoe");
+        assert_eq!(user.user_type, UserType::Employee);
+        assert_eq!(user.status, UserStatus::Active);
+        assert_eq!(user.department.as_deref(), Some("Maintenance"));
+        assert_eq!(user.employee_id.as_deref(), Some("E-100"));
+        assert_eq!(user.certification_levels["electrical"], "journeyman");
+        assert_eq!(user.metadata, None);
+    }
+
+    #[test]
+    fn test_create_input_rejects_invalid_json_field() {
+        let input = CreateUserInput {
+            address: Some("{not json".to_string()),
+            ..create_input()
+        };
+
+        match input.into_user("user-1".to_string()) {
+            Err(AppError::ValidationError(msg)) => assert_eq!(msg, "Invalid address JSON"),
+            other => panic!("expected a ValidationError, got {:?}", other.map(|u| u.id)),
+        }
+    }
+
+    #[test]
+    fn test_termination_audit_event_records_actor() {
+        let actor = AuthUser {
+            user_id: "user-admin".to_string(),
+            roles: vec!["admin".to_string()],
+        };
+
+        let event = termination_audit_event(&actor, &user(), Some("contract ended"));
+
+        assert_eq!(event.actor_user_id, "user-admin");
+        assert_eq!(event.action, USER_TERMINATED);
+        assert_eq!((event.target_type.as_str(), event.target_id.as_str()), ("user", "user-1"));
+        assert_eq!(event.metadata["reason"], "contract ended");
     }
 }
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use crate::{
+    context::ContextExtensions,
+    DbClient,
+    models::{ prelude::*, user_preferences::UserPreferences },
+    AppError,
+};
+
+#[derive(Debug, Default)]
+pub struct UserPreferencesMutation;
+
+#[Object]
+impl UserPreferencesMutation {
+    /// Update a user's preferences from a JSON object of field names to values
+    ///
+    /// Fields are applied by `UserPreferences::update_preferences`; unknown fields
+    /// and out-of-range values are ignored. Defaults are created first if the
+    /// user has no stored preferences yet.
+    async fn update_user_preferences(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+        updates: Json
+    ) -> Result<UserPreferences, Error> {
+        let Json::Object(updates) = updates else {
+            return Err(
+                AppError::invalid_field("updates", "Must be a JSON object").to_graphql_error()
+            );
+        };
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let stored = repo
+            .query_index::<UserPreferences>("UserIndex", "user_id", &user_id, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let existing = UserPreferences::first_created(stored);
+        let is_new = existing.is_none();
+
+        let mut preferences = match existing {
+            Some(preferences) => preferences,
+            None => UserPreferences::create_default(user_id).map_err(|e| e.to_graphql_error())?,
+        };
+
+        preferences
+            .update_preferences(updates.into_iter().collect::<HashMap<_, _>>())
+            .map_err(|e| e.to_graphql_error())?;
+
+        if is_new {
+            repo.create(preferences).await.map_err(|e| e.to_graphql_error())
+        } else {
+            repo.update(preferences).await.map_err(|e| e.to_graphql_error())
+        }
+    }
+}
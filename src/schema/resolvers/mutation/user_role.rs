@@ -1,10 +1,20 @@
 use crate::{
+    context::ContextExtensions,
     DbClient,
-    models::{ prelude::*, user_role::{ UserRole, RoleAssignmentStatus }, user::User, role::Role },
+    models::{
+        audit_event::{ AuditEvent, USER_ROLE_REVOKED, USER_ROLE_SUSPENDED },
+        id::IdGenerator,
+        prelude::*,
+        user_role::{ UserRole, RoleAssignmentStatus },
+        user::User,
+        role::Role,
+    },
+    repository::TransactWriteOp,
     AppError,
-    Repository,
 };
 
+use super::audit;
+
 #[derive(Debug, Default)]
 pub struct UserRoleMutation;
 
@@ -34,11 +44,11 @@ impl UserRoleMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
-        let id = format!("user_role-{}", Uuid::new_v4());
+        let repo = ctx.repository(db_client);
+        let id = IdGenerator::new_id("user_role");
 
         // Verify user exists
-        let _user = repo
+        let mut user = repo
             .get::<User>(user_id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| {
@@ -46,22 +56,26 @@ impl UserRoleMutation {
             })?;
 
         // Verify role exists
-        let _role = repo
-            .get::<Role>(role_id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| {
+        let role_exists = repo
+            .exists::<Role>(role_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?;
+        if !role_exists {
+            return Err(
                 AppError::ValidationError(format!("Role {} not found", role_id)).to_graphql_error()
-            })?;
+            );
+        }
 
         // Verify assigning user exists
-        let _assigning_user = repo
-            .get::<User>(assigned_by_user_id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| {
+        let assigning_user_exists = repo
+            .exists::<User>(assigned_by_user_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?;
+        if !assigning_user_exists {
+            return Err(
                 AppError::ValidationError(
                     format!("Assigning user {} not found", assigned_by_user_id)
                 ).to_graphql_error()
-            })?;
+            );
+        }
 
         // Check if assignment already exists and is active
         let existing_assignments = repo
@@ -113,7 +127,21 @@ impl UserRoleMutation {
             metadata
         ).map_err(|e| e.to_graphql_error())?;
 
-        repo.create(user_role).await.map_err(|e| e.to_graphql_error())
+        if !is_primary {
+            return repo.create(user_role).await.map_err(|e| e.to_graphql_error());
+        }
+
+        // Write the assignment and the user's primary role together so neither can land alone
+        user.primary_role_id = Some(user_role.role_id.clone());
+        user.updated_at = Utc::now();
+
+        repo
+            .transact_write(
+                vec![TransactWriteOp::create(&user_role), TransactWriteOp::update(&mut user)]
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(user_role)
     }
 
     /// Update an existing user role assignment
@@ -135,7 +163,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_role = repo
             .get::<UserRole>(id.clone()).await
@@ -213,7 +241,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_role = repo
             .get::<UserRole>(id.clone()).await
@@ -240,11 +268,27 @@ impl UserRoleMutation {
 
         user_role.status = RoleAssignmentStatus::Revoked;
         user_role.revoked_at = Some(Utc::now());
-        user_role.revoked_by_user_id = Some(revoked_by_user_id);
+        user_role.revoked_by_user_id = Some(revoked_by_user_id.clone());
         user_role.revocation_reason = revocation_reason;
         user_role.updated_at = Utc::now();
 
-        repo.update(user_role).await.map_err(|e| e.to_graphql_error())
+        let user_role = repo.update(user_role).await.map_err(|e| e.to_graphql_error())?;
+
+        let event = AuditEvent::new(
+            audit::actor_user_id(ctx),
+            USER_ROLE_REVOKED,
+            "user_role",
+            user_role.id.clone(),
+            serde_json::json!({
+                "user_id": user_role.user_id,
+                "role_id": user_role.role_id,
+                "revoked_by_user_id": revoked_by_user_id,
+                "reason": user_role.revocation_reason,
+            })
+        );
+        audit::record_audit(&repo, event).await;
+
+        Ok(user_role)
     }
 
     /// Suspend a user role assignment
@@ -263,7 +307,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_role = repo
             .get::<UserRole>(id.clone()).await
@@ -289,11 +333,27 @@ impl UserRoleMutation {
         }
 
         user_role.status = RoleAssignmentStatus::Suspended;
-        user_role.revoked_by_user_id = Some(suspended_by_user_id);
+        user_role.revoked_by_user_id = Some(suspended_by_user_id.clone());
         user_role.revocation_reason = suspension_reason;
         user_role.updated_at = Utc::now();
 
-        repo.update(user_role).await.map_err(|e| e.to_graphql_error())
+        let user_role = repo.update(user_role).await.map_err(|e| e.to_graphql_error())?;
+
+        let event = AuditEvent::new(
+            audit::actor_user_id(ctx),
+            USER_ROLE_SUSPENDED,
+            "user_role",
+            user_role.id.clone(),
+            serde_json::json!({
+                "user_id": user_role.user_id,
+                "role_id": user_role.role_id,
+                "suspended_by_user_id": suspended_by_user_id,
+                "reason": user_role.revocation_reason,
+            })
+        );
+        audit::record_audit(&repo, event).await;
+
+        Ok(user_role)
     }
 
     /// Reactivate a suspended user role assignment
@@ -306,7 +366,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_role = repo
             .get::<UserRole>(id.clone()).await
@@ -344,7 +404,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_role = repo
             .get::<UserRole>(id.clone()).await
@@ -379,7 +439,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_role = repo
             .get::<UserRole>(id.clone()).await
@@ -405,7 +465,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut target_role = repo
             .get::<UserRole>(id.clone()).await
@@ -464,25 +524,29 @@ impl UserRoleMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify role exists
-        let _role = repo
-            .get::<Role>(role_id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| {
+        let role_exists = repo
+            .exists::<Role>(role_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?;
+        if !role_exists {
+            return Err(
                 AppError::ValidationError(format!("Role {} not found", role_id)).to_graphql_error()
-            })?;
+            );
+        }
 
         // Verify assigning user exists
-        let _assigning_user = repo
-            .get::<User>(assigned_by_user_id.clone()).await
-            .map_err(|e| e.to_graphql_error())?
-            .ok_or_else(|| {
+        let assigning_user_exists = repo
+            .exists::<User>(assigned_by_user_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?;
+        if !assigning_user_exists {
+            return Err(
                 AppError::ValidationError(
                     format!("Assigning user {} not found", assigned_by_user_id)
                 ).to_graphql_error()
-            })?;
+            );
+        }
 
         let mut results = Vec::new();
         let effective_from = effective_from.unwrap_or_else(|| Utc::now());
@@ -511,7 +575,7 @@ impl UserRoleMutation {
                 continue; // Skip users who already have this role
             }
 
-            let id = format!("user_role-{}", Uuid::new_v4());
+            let id = IdGenerator::new_id("user_role");
 
             let user_role = UserRole::new(
                 id,
@@ -551,7 +615,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify revoking user exists
         let _revoking_user = repo
@@ -598,7 +662,7 @@ impl UserRoleMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify user role exists
         let user_role = repo
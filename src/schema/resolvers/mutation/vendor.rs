@@ -0,0 +1,689 @@
+use std::collections::HashMap;
+
+use crate::{
+    auth::{ guard::MANAGE_VENDORS, require_permission },
+    DbClient,
+    db::common::to_db_timestamp,
+    models::{
+        audit_event::{ AuditEvent, VENDOR_BLACKLISTED, VENDOR_SUSPENDED },
+        id::IdGenerator,
+        notification::{ Notification, NotificationCategory, NotificationChannels },
+        prelude::*,
+        vendor::{ expiring_contracts, VendorStatus, VendorTier },
+        work_order::{ average_vendor_rating, WorkOrder },
+        vendor_category::VendorCategory,
+        validation::{ normalize_phone, validate_email },
+    },
+    context::ContextExtensions,
+    repository::WriteCondition,
+    schema::vendor::CreateVendorResult,
+    AppError,
+    Repository,
+};
+
+use super::{ audit, notification::{ preferences_for, should_notify } };
+
+#[derive(Debug, Default)]
+pub struct VendorMutation;
+
+/// Fields accepted by `create_vendor`; JSON-valued fields are passed as JSON strings
+#[derive(Debug, InputObject)]
+pub struct CreateVendorInput {
+    pub name: String,
+    pub legal_name: Option<String>,
+    pub vendor_code: Option<String>,
+    pub vendor_category_id: String,
+    pub tier: Option<String>,
+    pub phone_number: String,
+    pub secondary_phone: Option<String>,
+    pub email_address: String,
+    pub secondary_email: Option<String>,
+    pub website: Option<String>,
+    pub tax_id: String,
+    pub registration_number: Option<String>,
+    pub payment_terms: String,
+    pub currency: String,
+    pub credit_limit: Option<f64>,
+    pub primary_contact_name: String,
+    pub primary_contact_title: String,
+    pub primary_contact_email: Option<String>,
+    pub primary_contact_phone: Option<String>,
+    pub billing_address: Option<String>,
+    pub shipping_address: Option<String>,
+    pub certifications: Option<Vec<String>>,
+    pub compliance_status: Option<String>,
+    pub insurance_info: Option<String>,
+    pub contract_start_date: Option<DateTime<Utc>>,
+    pub contract_end_date: Option<DateTime<Utc>>,
+    pub emergency_contact: Option<String>,
+    pub preferred_communication: Option<String>,
+    pub time_zone: Option<String>,
+    pub business_hours: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub custom_fields: Option<String>,
+    pub approval_required: Option<bool>,
+    pub auto_approval_limit: Option<f64>,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+}
+
+/// Fields accepted by `update_vendor`; JSON-valued fields are passed as JSON strings
+///
+/// Omitted fields are left unchanged; an empty string clears an optional field.
+#[derive(Debug, InputObject)]
+pub struct UpdateVendorInput {
+    pub id: String,
+    pub name: Option<String>,
+    pub legal_name: Option<String>,
+    pub vendor_code: Option<String>,
+    pub vendor_category_id: Option<String>,
+    pub tier: Option<String>,
+    pub phone_number: Option<String>,
+    pub email_address: Option<String>,
+    pub website: Option<String>,
+    pub payment_terms: Option<String>,
+    pub credit_limit: Option<f64>,
+    pub primary_contact_name: Option<String>,
+    pub primary_contact_title: Option<String>,
+    pub primary_contact_email: Option<String>,
+    pub billing_address: Option<String>,
+    pub shipping_address: Option<String>,
+    pub contract_start_date: Option<DateTime<Utc>>,
+    pub contract_end_date: Option<DateTime<Utc>>,
+    pub tags: Option<Vec<String>>,
+    pub notes: Option<String>,
+}
+
+/// How many days ahead of a contract's end `sweep_expiring_contracts` warns
+const CONTRACT_EXPIRY_WINDOW_DAYS: i64 = 30;
+
+/// Channels a contract expiry notification is sent over, subject to the recipient's preferences
+const CONTRACT_EXPIRY_CHANNELS: [NotificationChannels; 2] = [
+    NotificationChannels::InApp,
+    NotificationChannels::Email,
+];
+
+impl CreateVendorInput {
+    /// Builds a pending vendor with id `id`, parsing the JSON-valued fields
+    fn into_vendor(self, id: String, default_phone_region: &str) -> Result<Vendor, Error> {
+        Vendor::new(
+            id,
+            self.name,
+            self.legal_name,
+            self.vendor_code,
+            self.vendor_category_id,
+            "pending".to_string(), // New vendors start pending approval
+            self.tier.unwrap_or_else(|| "standard".to_string()),
+            self.phone_number,
+            self.secondary_phone,
+            self.email_address,
+            self.secondary_email,
+            self.website,
+            self.tax_id,
+            self.registration_number,
+            self.payment_terms,
+            self.currency,
+            self.credit_limit,
+            self.primary_contact_name,
+            self.primary_contact_title,
+            self.primary_contact_email,
+            self.primary_contact_phone,
+            parse_json_arg(self.billing_address, "billing_address")?,
+            parse_json_arg(self.shipping_address, "shipping_address")?,
+            self.certifications.unwrap_or_default(),
+            self.compliance_status.unwrap_or_else(|| "pending".to_string()),
+            parse_json_arg(self.insurance_info, "insurance_info")?,
+            self.contract_start_date,
+            self.contract_end_date,
+            parse_json_arg(self.emergency_contact, "emergency_contact")?,
+            self.preferred_communication.unwrap_or_else(|| "email".to_string()),
+            self.time_zone,
+            parse_json_arg(self.business_hours, "business_hours")?,
+            self.tags.unwrap_or_default(),
+            parse_json_arg(self.custom_fields, "custom_fields")?,
+            Vec::new(),
+            &self.approval_required.unwrap_or(false),
+            self.auto_approval_limit,
+            self.notes,
+            self.created_by,
+            default_phone_region
+        ).map_err(|e| e.to_graphql_error())
+    }
+}
+
+/// Parses an optional JSON string argument
+///
+/// Empty strings clear the value.
+fn parse_json_arg(value: Option<String>, field: &str) -> Result<Option<Json>, Error> {
+    match value.filter(|v| !v.trim().is_empty()) {
+        Some(raw) =>
+            serde_json
+                ::from_str::<Json>(&raw)
+                .map(Some)
+                .map_err(|_| {
+                    AppError::invalid_field(field, format!("Invalid {} JSON", field)).to_graphql_error()
+                }),
+        None => Ok(None),
+    }
+}
+
+/// Ensures `vendor_category_id` references an existing category
+async fn require_vendor_category(
+    repo: &Repository,
+    vendor_category_id: &str
+) -> Result<VendorCategory, Error> {
+    repo
+        .get::<VendorCategory>(vendor_category_id.to_string()).await
+        .map_err(|e| e.to_graphql_error())?
+        .ok_or_else(|| {
+            AppError::invalid_field(
+                "vendor_category_id",
+                format!("Vendor category {} not found", vendor_category_id)
+            ).to_graphql_error()
+        })
+}
+
+/// Vendor codes are stored trimmed and upper-cased, so codes differing only in case collide
+fn normalize_vendor_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// Rejects a vendor code already used by a different vendor
+///
+/// `vendor_code` must already be normalized; the lookup reads `VendorCodeIndex`.
+async fn ensure_unique_vendor_code(
+    repo: &Repository,
+    vendor_code: &str,
+    vendor_id: Option<&str>
+) -> Result<(), Error> {
+    // Two matches are enough to find one that isn't `vendor_id` itself
+    let existing_vendors = repo
+        .query_index::<Vendor>("VendorCodeIndex", "vendor_code", vendor_code, Some(2)).await
+        .map_err(|e| e.to_graphql_error())?;
+
+    let taken = existing_vendors.iter().any(|v| vendor_id.is_none_or(|id| v.id != id));
+
+    if taken {
+        return Err(
+            AppError::invalid_field(
+                "vendor_code",
+                "Vendor code already exists"
+            ).to_graphql_error()
+        );
+    }
+
+    Ok(())
+}
+
+/// Notification telling `user_id` that `vendor`'s contract is about to end
+///
+/// The id is derived from the vendor and its contract end date, so every sweep
+/// before that date produces the same notification.
+fn contract_expiry_notification(
+    user_id: &str,
+    vendor: &Vendor,
+    channels: Vec<NotificationChannels>
+) -> Result<Notification, AppError> {
+    let end_date = vendor.contract_end_date
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    Notification::new(
+        IdGenerator::derived_id(
+            "notification",
+            &format!("contract_expiry:{}:{}", vendor.id, end_date)
+        ),
+        user_id.to_string(),
+        format!("Vendor contract expiring: {}", vendor.name),
+        format!("The contract with {} ends on {}", vendor.name, end_date),
+        NotificationCategory::System,
+        channels
+    )
+}
+
+/// Store a contract expiry notification for the user who created `vendor`
+///
+/// Vendors without a `created_by` user are skipped, as are contracts an earlier
+/// sweep already notified about; both return `Ok(false)`.
+async fn notify_contract_expiry(repo: &Repository, vendor: &Vendor) -> Result<bool, AppError> {
+    let Some(user_id) = vendor.created_by.as_deref() else {
+        return Ok(false);
+    };
+
+    let preferences = preferences_for(repo, user_id).await?;
+    let channels: Vec<NotificationChannels> = CONTRACT_EXPIRY_CHANNELS.into_iter()
+        .filter(|channel| should_notify(preferences.as_ref(), *channel))
+        .collect();
+
+    let notification = contract_expiry_notification(user_id, vendor, channels)?;
+    match repo.create(notification).await {
+        Ok(_) => Ok(true),
+        Err(AppError::Conflict(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[Object]
+impl VendorMutation {
+    /// Create a new vendor
+    async fn create_vendor(
+        &self,
+        ctx: &Context<'_>,
+        mut input: CreateVendorInput
+    ) -> Result<CreateVendorResult, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        let repo = ctx.repository(db_client);
+        let id = format!("vendor-{}", Uuid::new_v4());
+
+        require_vendor_category(&repo, &input.vendor_category_id).await?;
+
+        input.vendor_code = input.vendor_code
+            .map(|code| normalize_vendor_code(&code))
+            .filter(|code| !code.is_empty());
+        if let Some(ref code) = input.vendor_code {
+            ensure_unique_vendor_code(&repo, code, None).await?;
+        }
+
+        let vendor = input.into_vendor(id, &config.default_phone_region)?;
+
+        let vendor = repo.create(vendor).await.map_err(|e| e.to_graphql_error())?;
+
+        repo
+            .add_to_count::<VendorCategory>(vendor.vendor_category_id.clone(), "vendor_count", 1).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let warnings = vendor.validate_warnings();
+        Ok(CreateVendorResult { vendor, warnings })
+    }
+
+    /// Update an existing vendor
+    ///
+    /// Empty strings clear optional fields.
+    async fn update_vendor(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateVendorInput
+    ) -> Result<Vendor, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut vendor = repo
+            .get::<Vendor>(input.id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Vendor {} not found", input.id)).to_graphql_error())?;
+
+        if let Some(new_name) = input.name {
+            if new_name.trim().is_empty() {
+                return Err(
+                    AppError::invalid_field("name", "Vendor name cannot be empty").to_graphql_error()
+                );
+            }
+            vendor.name = new_name;
+        }
+        if let Some(legal) = input.legal_name {
+            vendor.legal_name = if legal.is_empty() { None } else { Some(legal) };
+        }
+        if let Some(code) = input.vendor_code {
+            let code = normalize_vendor_code(&code);
+            if code.is_empty() {
+                vendor.vendor_code = None;
+            } else {
+                ensure_unique_vendor_code(&repo, &code, Some(&vendor.id)).await?;
+                vendor.vendor_code = Some(code);
+            }
+        }
+        if let Some(category_id) = input.vendor_category_id
+            && category_id != vendor.vendor_category_id
+        {
+            require_vendor_category(&repo, &category_id).await?;
+
+            // Keep vendor counts in step when moving between categories; a missing or
+            // already empty old category has nothing to take the vendor out of
+            match
+                repo.add_to_count::<VendorCategory>(
+                    vendor.vendor_category_id.clone(),
+                    "vendor_count",
+                    -1
+                ).await
+            {
+                Ok(()) | Err(AppError::Conflict(_)) => {}
+                Err(e) => {
+                    return Err(e.to_graphql_error());
+                }
+            }
+
+            repo
+                .add_to_count::<VendorCategory>(category_id.clone(), "vendor_count", 1).await
+                .map_err(|e| e.to_graphql_error())?;
+
+            vendor.vendor_category_id = category_id;
+        }
+        if let Some(new_tier) = input.tier {
+            vendor.tier = VendorTier::from_string(&new_tier).map_err(|e| e.to_graphql_error())?;
+        }
+        if let Some(phone) = input.phone_number {
+            let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+            vendor.phone_number = normalize_phone(&phone, &config.default_phone_region).map_err(
+                |e| e.to_graphql_error()
+            )?;
+        }
+        if let Some(email) = input.email_address {
+            validate_email(&email).map_err(|e| e.to_graphql_error())?;
+            vendor.email_address = email;
+        }
+        if let Some(site) = input.website {
+            vendor.website = if site.is_empty() { None } else { Some(site) };
+        }
+        if let Some(terms) = input.payment_terms {
+            vendor.payment_terms = terms;
+        }
+        if let Some(limit) = input.credit_limit {
+            if limit < 0.0 {
+                return Err(
+                    AppError::invalid_field(
+                        "credit_limit",
+                        "Credit limit cannot be negative"
+                    ).to_graphql_error()
+                );
+            }
+            vendor.credit_limit = Some(limit);
+        }
+        if let Some(contact_name) = input.primary_contact_name {
+            vendor.primary_contact_name = contact_name;
+        }
+        if let Some(contact_title) = input.primary_contact_title {
+            vendor.primary_contact_title = contact_title;
+        }
+        if let Some(contact_email) = input.primary_contact_email {
+            if !contact_email.is_empty() {
+                validate_email(&contact_email).map_err(|e| e.to_graphql_error())?;
+            }
+            vendor.primary_contact_email = if contact_email.is_empty() {
+                None
+            } else {
+                Some(contact_email)
+            };
+        }
+        if input.billing_address.is_some() {
+            vendor.billing_address = parse_json_arg(input.billing_address, "billing_address")?;
+        }
+        if input.shipping_address.is_some() {
+            vendor.shipping_address = parse_json_arg(input.shipping_address, "shipping_address")?;
+        }
+        vendor
+            .update_contract_dates(input.contract_start_date, input.contract_end_date)
+            .map_err(|e| e.to_graphql_error())?;
+        if let Some(new_tags) = input.tags {
+            vendor.tags = new_tags;
+        }
+        if let Some(new_notes) = input.notes {
+            vendor.notes = if new_notes.is_empty() { None } else { Some(new_notes) };
+        }
+
+        vendor.updated_at = Utc::now();
+
+        repo.update(vendor).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Suspend a vendor, appending the reason to its notes
+    async fn suspend_vendor(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        reason: Option<String>
+    ) -> Result<Vendor, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut vendor = repo
+            .get::<Vendor>(id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Vendor {} not found", id)).to_graphql_error())?;
+
+        vendor.suspend(reason.clone()).map_err(|e| e.to_graphql_error())?;
+
+        let vendor = repo.update(vendor).await.map_err(|e| e.to_graphql_error())?;
+
+        let event = AuditEvent::new(
+            audit::actor_user_id(ctx),
+            VENDOR_SUSPENDED,
+            "vendor",
+            vendor.id.clone(),
+            serde_json::json!({ "reason": reason })
+        );
+        audit::record_audit(&repo, event).await;
+
+        Ok(vendor)
+    }
+
+    /// Blacklist a vendor so it can no longer be used, appending the reason to its notes
+    async fn blacklist_vendor(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        reason: Option<String>
+    ) -> Result<Vendor, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut vendor = repo
+            .get::<Vendor>(id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Vendor {} not found", id)).to_graphql_error())?;
+
+        vendor.blacklist(reason.clone()).map_err(|e| e.to_graphql_error())?;
+
+        let vendor = repo.update(vendor).await.map_err(|e| e.to_graphql_error())?;
+
+        let event = AuditEvent::new(
+            audit::actor_user_id(ctx),
+            VENDOR_BLACKLISTED,
+            "vendor",
+            vendor.id.clone(),
+            serde_json::json!({ "reason": reason })
+        );
+        audit::record_audit(&repo, event).await;
+
+        Ok(vendor)
+    }
+
+    /// Reactivate a suspended or inactive vendor
+    async fn reactivate_vendor(&self, ctx: &Context<'_>, id: String) -> Result<Vendor, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut vendor = repo
+            .get::<Vendor>(id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Vendor {} not found", id)).to_graphql_error())?;
+
+        vendor.reactivate().map_err(|e| e.to_graphql_error())?;
+
+        let status = AttributeValue::S(vendor.status.to_string());
+        // Re-checked on write so a vendor blacklisted since the read stays blacklisted
+        let still_reactivatable = WriteCondition::new("#status IN (:suspended, :inactive)")
+            .name("#status", "status")
+            .value(":suspended", AttributeValue::S(VendorStatus::Suspended.to_string()))
+            .value(":inactive", AttributeValue::S(VendorStatus::Inactive.to_string()));
+
+        repo
+            .update_fields_if::<Vendor>(
+                id,
+                HashMap::from([("status".to_string(), status)]),
+                still_reactivatable
+            ).await
+            .map_err(|e| e.to_graphql_error())
+    }
+
+    /// Record an order placed with a vendor
+    ///
+    /// Order statistics are incremented with an atomic `UpdateItem` so concurrent
+    /// orders are never lost; the vendor is returned as stored after the update.
+    async fn record_vendor_order(
+        &self,
+        ctx: &Context<'_>,
+        vendor_id: String,
+        amount: f64
+    ) -> Result<Vendor, Error> {
+        if amount < 0.0 {
+            return Err(
+                AppError::invalid_field("amount", "Order amount cannot be negative").to_graphql_error()
+            );
+        }
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let vendor = repo
+            .get::<Vendor>(vendor_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Vendor {} not found", vendor_id)).to_graphql_error()
+            })?;
+
+        if !vendor.can_place_orders() {
+            return Err(
+                AppError::ValidationError(
+                    format!("Vendor {} cannot accept new orders", vendor_id)
+                ).to_graphql_error()
+            );
+        }
+
+        let now = to_db_timestamp(&Utc::now());
+
+        // Re-check the status in the write itself in case the vendor was suspended meanwhile
+        let condition = WriteCondition::new("#status = :active")
+            .name("#status", "status")
+            .value(":active", AttributeValue::S(VendorStatus::Active.to_string()))
+            .value(":one", AttributeValue::N("1".to_string()))
+            .value(":amount", AttributeValue::N(amount.to_string()))
+            .value(":now", AttributeValue::S(now));
+
+        repo
+            .update_attributes::<Vendor>(
+                vendor_id,
+                "ADD total_orders :one, total_spent :amount SET last_order_date = :now, updated_at = :now",
+                condition
+            ).await
+            .map_err(|e| e.to_graphql_error())
+    }
+
+    /// Recompute a vendor's average rating from its completed, rated work orders
+    ///
+    /// The rating is left unchanged when none of the vendor's work orders has been rated.
+    /// Requires `vendor:manage`.
+    async fn recompute_vendor_rating(
+        &self,
+        ctx: &Context<'_>,
+        vendor_id: String
+    ) -> Result<Vendor, Error> {
+        require_permission(ctx, MANAGE_VENDORS).await.map_err(|e| e.to_graphql_error())?;
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut vendor = repo
+            .get::<Vendor>(vendor_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Vendor {} not found", vendor_id)).to_graphql_error()
+            })?;
+
+        // VendorIndex is keyed on completed_date, so it only holds finished work orders
+        let completed = repo
+            .query_index::<WorkOrder>("VendorIndex", "vendor_id", &vendor_id, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let Some(rating) = average_vendor_rating(&completed, &vendor_id) else {
+            return Ok(vendor);
+        };
+
+        vendor.update_rating(rating).map_err(|e| e.to_graphql_error())?;
+
+        repo.update(vendor).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Notify vendor creators about contracts ending within the next 30 days
+    ///
+    /// Meant to be run on a schedule. Failures for one vendor are logged and do
+    /// not stop the sweep; vendors without a `created_by` user are skipped.
+    ///
+    /// Each contract is notified about once per end date, however often the sweep runs.
+    /// Requires `vendor:manage`.
+    ///
+    /// Returns the number of notifications created.
+    async fn sweep_expiring_contracts(&self, ctx: &Context<'_>) -> Result<i32, Error> {
+        require_permission(ctx, MANAGE_VENDORS).await.map_err(|e| e.to_graphql_error())?;
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let vendors = repo.list::<Vendor>(None).await.map_err(|e| e.to_graphql_error())?;
+
+        let mut notified = 0;
+
+        for vendor in expiring_contracts(vendors, CONTRACT_EXPIRY_WINDOW_DAYS, Utc::now()) {
+            match notify_contract_expiry(&repo, &vendor).await {
+                Ok(true) => {
+                    notified += 1;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("Failed to notify about expiring contract for vendor {}: {}", vendor.id, e);
+                }
+            }
+        }
+
+        Ok(notified)
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{
+    context::ContextExtensions,
+    DbClient,
+    models::{ prelude::*, vendor_category::VendorCategory },
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+
+#[derive(Debug, Default)]
+pub struct VendorCategoryMutation;
+
+/// Fields accepted by `create_vendor_category`
+#[derive(Debug, InputObject)]
+pub struct CreateVendorCategoryInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub category_type: String,
+    pub parent_category_id: Option<String>,
+    pub sort_order: Option<i32>,
+    pub code: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub required_fields: Option<Vec<String>>,
+    pub default_approval_workflow: Option<String>,
+    pub compliance_requirements: Option<Vec<String>>,
+    pub tax_category: Option<String>,
+    pub risk_level: Option<String>,
+    pub auto_approval_limit: Option<f64>,
+    pub created_by: Option<String>,
+}
+
+/// Fields accepted by `update_vendor_category`
+///
+/// Omitted fields are left unchanged; an empty string clears an optional field.
+#[derive(Debug, InputObject)]
+pub struct UpdateVendorCategoryInput {
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    pub sort_order: Option<i32>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub auto_approval_limit: Option<f64>,
+}
+
+/// Writes every attribute of `category` except `vendor_count`
+///
+/// The count is only changed through `Repository::add_to_count`, so a category read
+/// before a vendor joined it must not write its stale count back.
+async fn save_category(
+    repo: &Repository,
+    category: VendorCategory
+) -> Result<VendorCategory, AppError> {
+    let mut updates = category.to_item();
+    updates.remove("id");
+    updates.remove("vendor_count");
+
+    repo.update_fields::<VendorCategory>(category.id, updates).await
+}
+
+#[Object]
+impl VendorCategoryMutation {
+    /// Create a new vendor category
+    ///
+    /// The hierarchy level is derived from `parent_category_id`.
+    async fn create_vendor_category(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateVendorCategoryInput
+) -> Result<VendorCategory, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+        let id = format!("vendor-category-{}", Uuid::new_v4());
+
+        let existing_categories = repo
+            .list::<VendorCategory>(None).await
+            .map_err(|e| e.to_graphql_error())?;
+        if
+            existing_categories
+                .iter()
+                .any(|c| c.name.to_lowercase() == input.name.to_lowercase())
+        {
+            return Err(
+                AppError::ValidationError(
+                    "Vendor category name already exists".to_string()
+                ).to_graphql_error()
+            );
+        }
+
+        let level = match input.parent_category_id {
+            Some(ref parent_id) => {
+                let parent = repo
+                    .get::<VendorCategory>(parent_id.clone()).await
+                    .map_err(|e| e.to_graphql_error())?
+                    .ok_or_else(|| {
+                        AppError::ValidationError(
+                            format!("Parent category {} not found", parent_id)
+                        ).to_graphql_error()
+                    })?;
+                parent.level + 1
+            }
+            None => 0,
+        };
+
+        let category = VendorCategory::new(
+            id,
+            input.name,
+            input.description,
+            input.category_type,
+            "active".to_string(),
+            input.parent_category_id,
+            level,
+            input.sort_order.unwrap_or(0),
+            input.code,
+            input.color,
+            input.icon,
+            input.required_fields.unwrap_or_default(),
+            input.default_approval_workflow,
+            input.compliance_requirements.unwrap_or_default(),
+            input.tax_category,
+            input.risk_level.unwrap_or_else(|| "low".to_string()),
+            input.auto_approval_limit,
+            true,
+            input.created_by
+        ).map_err(|e| e.to_graphql_error())?;
+
+        repo.create(category).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Update an existing vendor category
+    async fn update_vendor_category(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateVendorCategoryInput
+) -> Result<VendorCategory, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut category = repo
+            .get::<VendorCategory>(input.id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(
+                    format!("Vendor category {} not found", input.id)
+                ).to_graphql_error()
+            })?;
+
+        if let Some(new_name) = input.name {
+            if new_name.trim().is_empty() {
+                return Err(
+                    AppError::ValidationError(
+                        "Category name cannot be empty".to_string()
+                    ).to_graphql_error()
+                );
+            }
+            category.name = new_name;
+        }
+        if let Some(desc) = input.description {
+            category.description = if desc.is_empty() { None } else { Some(desc) };
+        }
+        if let Some(new_status) = input.status {
+            category.update_status(new_status).map_err(|e| e.to_graphql_error())?;
+        }
+        if let Some(order) = input.sort_order {
+            if order < 0 {
+                return Err(
+                    AppError::ValidationError(
+                        "Sort order cannot be negative".to_string()
+                    ).to_graphql_error()
+                );
+            }
+            category.sort_order = order;
+        }
+        if let Some(new_color) = input.color {
+            category.color = if new_color.is_empty() { None } else { Some(new_color) };
+        }
+        if let Some(new_icon) = input.icon {
+            category.icon = if new_icon.is_empty() { None } else { Some(new_icon) };
+        }
+        if let Some(limit) = input.auto_approval_limit {
+            if limit < 0.0 {
+                return Err(
+                    AppError::ValidationError(
+                        "Auto approval limit cannot be negative".to_string()
+                    ).to_graphql_error()
+                );
+            }
+            category.auto_approval_limit = Some(limit);
+        }
+
+        category.updated_at = Utc::now();
+
+        save_category(&repo, category).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Archive a vendor category
+    async fn archive_vendor_category(
+        &self,
+        ctx: &Context<'_>,
+        id: String
+    ) -> Result<VendorCategory, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut category = repo
+            .get::<VendorCategory>(id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Vendor category {} not found", id)).to_graphql_error()
+            })?;
+
+        category.archive();
+
+        save_category(&repo, category).await.map_err(|e| e.to_graphql_error())
+    }
+}
@@ -1,28 +1,215 @@
-use rust_decimal::Decimal;
+use std::collections::HashMap;
 
 use crate::{
+    auth::{ guard::RATE_VENDORS, require_permission },
     models::{
         asset::Asset,
+        audit_event::{ AuditEvent, WORK_ORDER_STATUS_CHANGED },
+        counter::{ Counter, WORK_ORDER_NUMBER_COUNTER },
+        id::IdGenerator,
+        notification::{ Notification, NotificationCategory, NotificationChannels },
+        notification_template::NotificationTemplate,
         prelude::*,
         task::TaskType,
+        validation::parse_decimal,
         work_order::{
             WorkOrder,
             WorkOrderCost,
             WorkOrderDifficulty,
+            WorkOrderPart,
             WorkOrderPriority,
             WorkOrderSeverity,
             WorkOrderStatus,
         },
     },
-    schema::resolvers::mutation::task::TaskMutation,
+    schema::{
+        resolvers::{
+            mutation::task::TaskMutation,
+            query::notification_template::template_by_key,
+            subscription::{ WorkOrderStatusBroker, WorkOrderStatusChanged },
+        },
+        work_order::BulkUpdateResult,
+    },
+    context::ContextExtensions,
+    error::FieldError,
+    webhooks::emit_work_order_status_change,
     AppError,
     DbClient,
     Repository,
 };
 
+use super::{
+    asset::{ link_work_order, unlink_work_order },
+    audit,
+    idempotency::{ self, Claim },
+    notification::{ preferences_for, should_notify },
+};
+
 #[derive(Debug, Default)]
 pub struct WorkOrderMutation;
 
+/// A part or material line item; `quantity` and `unit_cost` are decimal strings
+#[derive(Debug, InputObject)]
+pub struct WorkOrderPartInput {
+    pub part_number: String,
+    pub description: String,
+    pub quantity: String,
+    pub unit_cost: String,
+}
+
+impl WorkOrderPartInput {
+    fn into_part(self) -> Result<WorkOrderPart, AppError> {
+        let quantity = parse_decimal(&self.quantity).map_err(|_| {
+            AppError::invalid_field("quantity", "Invalid quantity format")
+        })?;
+        let unit_cost = parse_decimal(&self.unit_cost).map_err(|_| {
+            AppError::invalid_field("unit_cost", "Invalid unit cost format")
+        })?;
+
+        WorkOrderPart::new(self.part_number, self.description, quantity, unit_cost)
+    }
+}
+
+/// Idempotency key scope for `create_work_order`
+const CREATE_WORK_ORDER_SCOPE: &str = "create_work_order";
+
+/// Notification template key for the message sent to a newly assigned technician
+const WORK_ORDER_ASSIGNED_TEMPLATE_KEY: &str = "work_order_assigned";
+
+/// Channels an assignment notification is sent over, subject to the assignee's preferences
+const ASSIGNMENT_CHANNELS: [NotificationChannels; 4] = [
+    NotificationChannels::InApp,
+    NotificationChannels::Email,
+    NotificationChannels::Push,
+    NotificationChannels::Desktop,
+];
+
+/// Maximum number of work orders accepted by a single bulk update
+const MAX_BULK_UPDATE_IDS: usize = 100;
+
+/// Number for the next work order, taken from the atomic work order counter
+///
+/// Every call gets a distinct number, so concurrent creates never share one. A number
+/// taken by a create that then fails is not reused.
+pub(crate) async fn next_work_order_number(repo: &Repository) -> Result<u64, AppError> {
+    repo.increment::<Counter>(WORK_ORDER_NUMBER_COUNTER.to_string(), "value").await
+}
+
+/// Ensures `vendor_id` references an existing vendor
+async fn require_vendor(repo: &Repository, vendor_id: &str) -> Result<(), Error> {
+    repo
+        .get::<Vendor>(vendor_id.to_string()).await
+        .map_err(|e| e.to_graphql_error())?
+        .ok_or_else(|| {
+            AppError::invalid_field(
+                "vendor_id",
+                format!("Vendor {} not found", vendor_id)
+            ).to_graphql_error()
+        })?;
+
+    Ok(())
+}
+
+/// Fire the status-change webhook and subscription event for a work order that was just written
+fn notify_status_change(ctx: &Context<'_>, work_order: &WorkOrder, old_status: WorkOrderStatus) {
+    let webhook = ctx
+        .config()
+        .ok()
+        .and_then(|config| config.webhook.as_ref());
+
+    emit_work_order_status_change(webhook, &work_order.id, old_status, work_order.status);
+
+    if let Ok(broker) = ctx.data::<WorkOrderStatusBroker>() {
+        broker.publish(WorkOrderStatusChanged::new(work_order, old_status));
+    }
+}
+
+/// Technician `work_order` is now assigned to, if the assignee changed
+fn newly_assigned_technician<'a>(
+    previous_technician_id: Option<&str>,
+    work_order: &'a WorkOrder
+) -> Option<&'a str> {
+    let technician_id = work_order.assigned_technician_id.as_deref()?;
+
+    (previous_technician_id != Some(technician_id)).then_some(technician_id)
+}
+
+/// Wording used when no `work_order_assigned` template has been stored
+fn default_assignment_template() -> NotificationTemplate {
+    NotificationTemplate {
+        id: WORK_ORDER_ASSIGNED_TEMPLATE_KEY.to_string(),
+        key: WORK_ORDER_ASSIGNED_TEMPLATE_KEY.to_string(),
+        subject_template: "Work order {{work_order_number}} assigned".to_string(),
+        body_template: "You have been assigned work order {{work_order_number}}: {{title}}".to_string(),
+        variables: vec!["work_order_number".to_string(), "title".to_string()],
+    }
+}
+
+/// Notification telling `technician_id` they were assigned `work_order`
+///
+/// The template can reference `work_order_id`, `work_order_number`, `title` and `technician_id`.
+fn assignment_notification(
+    template: &NotificationTemplate,
+    technician_id: &str,
+    work_order: &WorkOrder,
+    channels: Vec<NotificationChannels>
+) -> Result<Notification, AppError> {
+    let mut vars = serde_json::Map::new();
+    vars.insert("work_order_id".to_string(), work_order.id.clone().into());
+    vars.insert("work_order_number".to_string(), work_order.work_order_number.clone().into());
+    vars.insert("title".to_string(), work_order.title.clone().into());
+    vars.insert("technician_id".to_string(), technician_id.into());
+    let rendered = template.render(&vars)?;
+
+    Notification::new(
+        IdGenerator::new_id("notification"),
+        technician_id.to_string(),
+        rendered.subject,
+        rendered.body,
+        NotificationCategory::WorkOrder,
+        channels
+    )
+}
+
+/// Store the assignment notification for a work order that was just written
+///
+/// Failures are logged rather than returned so they never undo the assignment.
+async fn notify_assignment(
+    repo: &Repository,
+    previous_technician_id: Option<&str>,
+    work_order: &WorkOrder
+) {
+    let Some(technician_id) = newly_assigned_technician(previous_technician_id, work_order) else {
+        return;
+    };
+
+    let preferences = preferences_for(repo, technician_id).await.unwrap_or_else(|e| {
+        warn!("Failed to load notification preferences for {}: {}", technician_id, e);
+        None
+    });
+    let channels: Vec<NotificationChannels> = ASSIGNMENT_CHANNELS.into_iter()
+        .filter(|channel| should_notify(preferences.as_ref(), *channel))
+        .collect();
+
+    let template = match template_by_key(repo, WORK_ORDER_ASSIGNED_TEMPLATE_KEY).await {
+        Ok(Some(template)) => template,
+        Ok(None) => default_assignment_template(),
+        Err(e) => {
+            warn!("Failed to load {} template: {}", WORK_ORDER_ASSIGNED_TEMPLATE_KEY, e);
+            default_assignment_template()
+        }
+    };
+
+    let result = match assignment_notification(&template, technician_id, work_order, channels) {
+        Ok(notification) => repo.create(notification).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        warn!("Failed to create assignment notification for work order {}: {}", work_order.id, e);
+    }
+}
+
 #[Object]
 impl WorkOrderMutation {
     /// Create a new work order
@@ -38,9 +225,12 @@ impl WorkOrderMutation {
         severity: String,
         difficulty: String,
         assigned_technician_id: Option<String>,
+        vendor_id: Option<String>,
         estimated_duration_minutes: i32,
         estimated_cost: String,
-        created_by: String
+        scheduled_date: Option<DateTime<Utc>>,
+        created_by: String,
+        idempotency_key: Option<String>
     ) -> Result<WorkOrder, Error> {
         // info!("Creating new work order: {}", title);
 
@@ -51,22 +241,17 @@ impl WorkOrderMutation {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let id = format!("work_order-{}", Uuid::new_v4());
 
-        let latest_work_orders = repo
-            .list::<WorkOrder>(Some(1)).await
-            .map_err(|e| e.to_graphql_error())?;
-
-        let next_number = if let Some(latest) = latest_work_orders.first() {
-            // Assuming work_order_number is numeric
-            latest.work_order_number.parse::<u64>().unwrap_or(0) + 1
-        } else {
-            1
-        };
-
-        let work_order_number = format!("{:06}", next_number); // e.g., "000123"
+        if
+            let Some(existing) = idempotency
+                ::replay::<WorkOrder>(&repo, CREATE_WORK_ORDER_SCOPE, idempotency_key.as_deref()).await
+                .map_err(|e| e.to_graphql_error())?
+        {
+            return Ok(existing);
+        }
 
         // Validate that asset exists
         let _asset = repo
@@ -78,6 +263,10 @@ impl WorkOrderMutation {
                 ).to_graphql_error()
             })?;
 
+        if let Some(vendor_id) = &vendor_id {
+            require_vendor(&repo, vendor_id).await?;
+        }
+
         // Parse severity and difficulty enums
         let severity_enum = WorkOrderSeverity::from_string(&severity).map_err(|e|
             e.to_graphql_error()
@@ -91,6 +280,25 @@ impl WorkOrderMutation {
             e.to_graphql_error()
         )?;
 
+        // Claim the key before the counter moves, so concurrent retries cannot both take a number
+        let claim = idempotency
+            ::claim::<WorkOrder>(&repo, CREATE_WORK_ORDER_SCOPE, idempotency_key.as_deref(), &id).await
+            .map_err(|e| e.to_graphql_error())?;
+        if let Claim::Replayed(existing) = claim {
+            return Ok(existing);
+        }
+
+        // Only take a number once the input is known to be valid, so rejected requests leave no gap
+        let next_number = match next_work_order_number(&repo).await {
+            Ok(next_number) => next_number,
+            Err(e) => {
+                idempotency::release(&repo, claim).await;
+                return Err(e.to_graphql_error());
+            }
+        };
+
+        let work_order_number = format!("{:06}", next_number); // e.g., "000123"
+
         // Clone number, title, and technician id to allow use in Task creation
         let work_order = WorkOrder::new(
             id,
@@ -107,7 +315,16 @@ impl WorkOrderMutation {
             estimated_duration_minutes,
             estimated_cost_enum,
             created_by
-        ).map_err(|e| e.to_graphql_error())?;
+        );
+        let mut work_order = match work_order {
+            Ok(work_order) => work_order,
+            Err(e) => {
+                idempotency::release(&repo, claim).await;
+                return Err(e.to_graphql_error());
+            }
+        };
+        work_order.scheduled_date = scheduled_date;
+        work_order.vendor_id = vendor_id;
 
         let task_description = format!(
             "Complete work order number {}: {}",
@@ -115,7 +332,25 @@ impl WorkOrderMutation {
             &title
         );
 
-        let created_work_order = repo.create(work_order).await.map_err(|e| e.to_graphql_error())?;
+        let created_work_order = match repo.create(work_order).await {
+            Ok(work_order) => work_order,
+            Err(e) => {
+                idempotency::release(&repo, claim).await;
+                return Err(e.to_graphql_error());
+            }
+        };
+        notify_assignment(&repo, None, &created_work_order).await;
+
+        if
+            let Err(e) = link_work_order(
+                &repo,
+                &created_work_order.asset_id,
+                &created_work_order.id
+            ).await
+        {
+            warn!("Failed to link work order {} to its asset: {}", created_work_order.id, e);
+        }
+
         let task_type = TaskType::WorkOrder.to_string();
         let task_private = false;
 
@@ -147,8 +382,10 @@ impl WorkOrderMutation {
         severity: Option<String>,
         difficulty: Option<String>,
         assigned_technician_id: Option<String>,
+        vendor_id: Option<String>,
         estimated_duration_minutes: Option<i32>,
-        estimated_cost: Option<String>
+        estimated_cost: Option<String>,
+        scheduled_date: Option<DateTime<Utc>>
     ) -> Result<WorkOrder, Error> {
         // info!("Updating work order: {}", id);
 
@@ -158,7 +395,7 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_order = repo
             .get::<WorkOrder>(id.clone()).await
@@ -174,6 +411,8 @@ impl WorkOrderMutation {
             );
         }
 
+        let previous_technician_id = work_order.assigned_technician_id.clone();
+
         // Update fields
         if let Some(number) = work_order_number {
             work_order.work_order_number = number;
@@ -210,6 +449,14 @@ impl WorkOrderMutation {
                 Some(tech_id)
             };
         }
+        if let Some(vendor_id) = vendor_id {
+            work_order.vendor_id = if vendor_id.is_empty() {
+                None
+            } else {
+                require_vendor(&repo, &vendor_id).await?;
+                Some(vendor_id)
+            };
+        }
         if let Some(duration) = estimated_duration_minutes {
             work_order.estimated_duration_minutes = duration;
         }
@@ -218,12 +465,18 @@ impl WorkOrderMutation {
                 e.to_graphql_error()
             )?;
         }
+        if let Some(scheduled_date) = scheduled_date {
+            work_order.scheduled_date = Some(scheduled_date);
+        }
         if let Some(completed_date) = completed_date {
             work_order.completed_date = Some(completed_date);
         }
         work_order.updated_at = Utc::now();
 
-        repo.update(work_order).await.map_err(|e| e.to_graphql_error())
+        let work_order = repo.update(work_order).await.map_err(|e| e.to_graphql_error())?;
+        notify_assignment(&repo, previous_technician_id.as_deref(), &work_order).await;
+
+        Ok(work_order)
     }
 
     /// Update work order classification (severity and difficulty)
@@ -242,7 +495,7 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_order = repo
             .get::<WorkOrder>(id.clone()).await
@@ -276,16 +529,20 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_order = repo
             .get::<WorkOrder>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Work order {} not found", id)))?;
 
+        let old_status = work_order.status;
         work_order.start_work(technician_id).map_err(|e| e.to_graphql_error())?;
 
-        repo.update(work_order).await.map_err(|e| e.to_graphql_error())
+        let work_order = repo.update(work_order).await.map_err(|e| e.to_graphql_error())?;
+        notify_status_change(ctx, &work_order, old_status);
+
+        Ok(work_order)
     }
 
     /// Complete a work order
@@ -294,6 +551,7 @@ impl WorkOrderMutation {
         ctx: &Context<'_>,
         id: String,
         completion_notes: Option<String>,
+        actual_duration_minutes: Option<i32>,
         actual_cost: Option<String>,
         labor_hours: Option<f64>
     ) -> Result<WorkOrder, Error> {
@@ -305,30 +563,110 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_order = repo
             .get::<WorkOrder>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Work order {} not found", id)))?;
 
-        // Set additional completion data
-        if let Some(cost_str) = actual_cost {
-            work_order.actual_cost = Some(
-                cost_str
-                    .parse::<Decimal>()
-                    .map_err(|_| {
-                        AppError::ValidationError(
-                            "Invalid actual cost format".to_string()
-                        ).to_graphql_error()
-                    })?
-            );
-        }
-        if let Some(hours) = labor_hours {
-            work_order.labor_hours = Some(hours);
-        }
+        let actual_cost = actual_cost
+            .as_deref()
+            .map(parse_decimal)
+            .transpose()
+            .map_err(|_| {
+                AppError::invalid_field("actual_cost", "Invalid actual cost format").to_graphql_error()
+            })?;
+
+        let old_status = work_order.status;
+        work_order
+            .complete_work(completion_notes, actual_duration_minutes, actual_cost, labor_hours)
+            .map_err(|e| e.to_graphql_error())?;
+
+        let work_order = repo.update(work_order).await.map_err(|e| e.to_graphql_error())?;
+        notify_status_change(ctx, &work_order, old_status);
+
+        Ok(work_order)
+    }
+
+    /// Rate the vendor a completed work order was hired out to, from 0.0 to 5.0
+    ///
+    /// Run `recomputeVendorRating` afterwards to fold the rating into the vendor's average.
+    /// Requires `vendor:rate`.
+    async fn rate_work_order_vendor(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        rating: f64
+    ) -> Result<WorkOrder, Error> {
+        require_permission(ctx, RATE_VENDORS).await.map_err(|e| e.to_graphql_error())?;
 
-        work_order.complete_work(completion_notes).map_err(|e| e.to_graphql_error())?;
+        let db_client = ctx
+            .data::<DbClient>()
+            .map_err(|_| {
+                AppError::InternalServerError("Database client not available".to_string())
+            })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut work_order = repo
+            .get::<WorkOrder>(id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Work order {} not found", id)))?;
+
+        work_order.rate_vendor(rating).map_err(|e| e.to_graphql_error())?;
+
+        repo.update(work_order).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Add a part or material line item to a work order
+    async fn add_work_order_part(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        input: WorkOrderPartInput
+    ) -> Result<WorkOrder, Error> {
+        let db_client = ctx
+            .data::<DbClient>()
+            .map_err(|_| {
+                AppError::InternalServerError("Database client not available".to_string())
+            })?;
+
+        let repo = ctx.repository(db_client);
+
+        let part = input.into_part().map_err(|e| e.to_graphql_error())?;
+
+        let mut work_order = repo
+            .get::<WorkOrder>(id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Work order {} not found", id)))?;
+
+        work_order.add_part(part).map_err(|e| e.to_graphql_error())?;
+
+        repo.update(work_order).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Remove a part line item from a work order by part number
+    async fn remove_work_order_part(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        part_number: String
+    ) -> Result<WorkOrder, Error> {
+        let db_client = ctx
+            .data::<DbClient>()
+            .map_err(|_| {
+                AppError::InternalServerError("Database client not available".to_string())
+            })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut work_order = repo
+            .get::<WorkOrder>(id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Work order {} not found", id)))?;
+
+        work_order.remove_part(&part_number).map_err(|e| e.to_graphql_error())?;
 
         repo.update(work_order).await.map_err(|e| e.to_graphql_error())
     }
@@ -348,16 +686,20 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_order = repo
             .get::<WorkOrder>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Work order {} not found", id)))?;
 
+        let old_status = work_order.status;
         work_order.cancel_work(reason).map_err(|e| e.to_graphql_error())?;
 
-        repo.update(work_order).await.map_err(|e| e.to_graphql_error())
+        let work_order = repo.update(work_order).await.map_err(|e| e.to_graphql_error())?;
+        notify_status_change(ctx, &work_order, old_status);
+
+        Ok(work_order)
     }
 
     /// Put work order on hold
@@ -375,16 +717,20 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_order = repo
             .get::<WorkOrder>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Work order {} not found", id)))?;
 
+        let old_status = work_order.status;
         work_order.put_on_hold(reason).map_err(|e| e.to_graphql_error())?;
 
-        repo.update(work_order).await.map_err(|e| e.to_graphql_error())
+        let work_order = repo.update(work_order).await.map_err(|e| e.to_graphql_error())?;
+        notify_status_change(ctx, &work_order, old_status);
+
+        Ok(work_order)
     }
 
     /// Resume work order from hold
@@ -401,16 +747,20 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_order = repo
             .get::<WorkOrder>(id.clone()).await
             .map_err(|e| e.to_graphql_error())?
             .ok_or_else(|| AppError::NotFound(format!("Work order {} not found", id)))?;
 
+        let old_status = work_order.status;
         work_order.resume_from_hold().map_err(|e| e.to_graphql_error())?;
 
-        repo.update(work_order).await.map_err(|e| e.to_graphql_error())
+        let work_order = repo.update(work_order).await.map_err(|e| e.to_graphql_error())?;
+        notify_status_change(ctx, &work_order, old_status);
+
+        Ok(work_order)
     }
 
     /// Update work order status
@@ -428,7 +778,7 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_order = repo
             .get::<WorkOrder>(id.clone()).await
@@ -437,10 +787,97 @@ impl WorkOrderMutation {
 
         let new_status = WorkOrderStatus::from_string(&status).map_err(|e| e.to_graphql_error())?;
 
-        work_order.status = new_status;
-        work_order.updated_at = Utc::now();
+        let old_status = work_order.status;
+        work_order.transition_to(new_status).map_err(|e| e.to_graphql_error())?;
 
-        repo.update(work_order).await.map_err(|e| e.to_graphql_error())
+        let work_order = repo.update(work_order).await.map_err(|e| e.to_graphql_error())?;
+        notify_status_change(ctx, &work_order, old_status);
+
+        Ok(work_order)
+    }
+
+    /// Move many work orders to the same status
+    ///
+    /// Each work order is checked against the transition rules independently;
+    /// failures are reported per id instead of aborting the batch. Every change
+    /// is recorded as an audit event carrying `reason`; the notes are left alone.
+    async fn bulk_update_work_order_status(
+        &self,
+        ctx: &Context<'_>,
+        ids: Vec<String>,
+        new_status: String,
+        reason: Option<String>
+    ) -> Result<BulkUpdateResult, Error> {
+        if ids.len() > MAX_BULK_UPDATE_IDS {
+            return Err(
+                AppError::ValidationError(
+                    format!("Bulk updates are limited to {} work orders", MAX_BULK_UPDATE_IDS)
+                ).to_graphql_error()
+            );
+        }
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let status = WorkOrderStatus::from_string(&new_status).map_err(|e| e.to_graphql_error())?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut work_orders: HashMap<String, WorkOrder> = repo
+            .batch_get::<WorkOrder>(ids.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .into_iter()
+            .map(|wo| (wo.id.clone(), wo))
+            .collect();
+
+        let mut result = BulkUpdateResult::default();
+
+        for id in ids {
+            let Some(mut work_order) = work_orders.remove(&id) else {
+                // Either missing or a repeated id that was already processed
+                let already_reported =
+                    result.succeeded.contains(&id) || result.failed.iter().any(|f| f.field == id);
+                if !already_reported {
+                    let message = format!("Work order {} not found", id);
+                    result.failed.push(FieldError::new(id, message));
+                }
+                continue;
+            };
+
+            let old_status = work_order.status;
+            if let Err(e) = work_order.transition_to(status) {
+                result.failed.push(FieldError::new(id, e.to_string()));
+                continue;
+            }
+
+            match repo.update(work_order).await {
+                Ok(work_order) => {
+                    notify_status_change(ctx, &work_order, old_status);
+
+                    let event = AuditEvent::new(
+                        audit::actor_user_id(ctx),
+                        WORK_ORDER_STATUS_CHANGED,
+                        "work_order",
+                        work_order.id.clone(),
+                        serde_json::json!({
+                            "previous_status": old_status.to_str(),
+                            "status": work_order.status.to_str(),
+                            "reason": reason,
+                        })
+                    );
+                    audit::record_audit(&repo, event).await;
+
+                    result.succeeded.push(id);
+                }
+                Err(e) => result.failed.push(FieldError::new(id, e.to_string())),
+            }
+        }
+
+        Ok(result)
     }
 
     /// Delete a work order
@@ -453,7 +890,7 @@ impl WorkOrderMutation {
                 AppError::InternalServerError("Database client not available".to_string())
             })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify work order exists
         let work_order = repo
@@ -470,6 +907,84 @@ impl WorkOrderMutation {
             );
         }
 
-        repo.delete::<WorkOrder>(id).await.map_err(|e| e.to_graphql_error())
+        let deleted = repo.delete::<WorkOrder>(id.clone()).await.map_err(|e| e.to_graphql_error())?;
+
+        if deleted
+            && let Err(e) = unlink_work_order(&repo, &work_order.asset_id, &id).await
+        {
+            warn!("Failed to unlink work order {} from its asset: {}", id, e);
+        }
+
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work_order(assigned_technician_id: Option<&str>) -> WorkOrder {
+        WorkOrder::new(
+            "work_order-1".to_string(),
+            "000042".to_string(),
+            "Replace pump seal".to_string(),
+            "Seal is leaking".to_string(),
+            None,
+            "asset-1".to_string(),
+            "corrective".to_string(),
+            "normal".to_string(),
+            WorkOrderSeverity::Important,
+            WorkOrderDifficulty::Normal,
+            assigned_technician_id.map(str::to_string),
+            60,
+            WorkOrderCost::One,
+            "user-1".to_string()
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_assigning_work_order_creates_unread_notification_for_assignee() {
+        let work_order = work_order(Some("tech-1"));
+        let technician_id = newly_assigned_technician(None, &work_order).unwrap();
+
+        let notification = assignment_notification(
+            &default_assignment_template(),
+            technician_id,
+            &work_order,
+            ASSIGNMENT_CHANNELS.to_vec()
+        ).unwrap();
+
+        assert_eq!(notification.user_id, "tech-1");
+        assert_eq!(notification.category, NotificationCategory::WorkOrder);
+        assert!(!notification.read);
+        assert_eq!(notification.title, "Work order 000042 assigned");
+        assert_eq!(notification.body, "You have been assigned work order 000042: Replace pump seal");
+    }
+
+    #[test]
+    fn test_unchanged_or_missing_assignee_creates_no_notification() {
+        assert!(newly_assigned_technician(Some("tech-1"), &work_order(Some("tech-1"))).is_none());
+        assert!(newly_assigned_technician(Some("tech-1"), &work_order(None)).is_none());
+    }
+
+    #[test]
+    fn test_stored_template_controls_assignment_wording() {
+        let template = NotificationTemplate::new(
+            "notification_template-1".to_string(),
+            WORK_ORDER_ASSIGNED_TEMPLATE_KEY.to_string(),
+            "New job: {{title}}".to_string(),
+            "{{technician_id}}, please pick up #{{work_order_number}}".to_string(),
+            vec![]
+        ).unwrap();
+
+        let notification = assignment_notification(
+            &template,
+            "tech-1",
+            &work_order(Some("tech-1")),
+            vec![NotificationChannels::InApp]
+        ).unwrap();
+
+        assert_eq!(notification.title, "New job: Replace pump seal");
+        assert_eq!(notification.body, "tech-1, please pick up #000042");
     }
 }
@@ -1,13 +1,27 @@
-use async_graphql::*;
+use std::{ collections::{ HashMap, HashSet, VecDeque }, future::Future };
+
+use async_graphql::{ connection::{ self, Connection, Edge }, * };
+use aws_sdk_dynamodb::types::AttributeValue;
 use chrono::{ DateTime, Utc };
+use rust_decimal::prelude::ToPrimitive;
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
-    models::{ asset::{ Asset, AssetCurrentStatusOptions }, work_order::WorkOrder },
+    models::{
+        asset::{ assets_under_warranty, Asset, AssetCurrentStatusOptions },
+        location::Location,
+        work_order::WorkOrder,
+    },
+    repository::encode_cursor,
+    schema::asset::AssetPage,
     DbClient,
-    Repository,
 };
+
+/// Most assets `assets_page` and `assets_connection` return per page
+const MAX_ASSET_PAGE_SIZE: i32 = 100;
+
 #[derive(Debug, Default)]
 pub(crate) struct AssetQuery;
 
@@ -22,7 +36,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<Asset>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -44,7 +58,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(limit).await.map_err(|e| e.to_graphql_error())?;
 
@@ -86,6 +100,84 @@ impl AssetQuery {
         Ok(assets)
     }
 
+    /// Get a page of assets, resuming from the cursor of a previous page
+    ///
+    /// `limit` is capped at `MAX_ASSET_PAGE_SIZE`.
+    async fn assets_page(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<i32>,
+        cursor: Option<String>
+    ) -> Result<AssetPage, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let limit = limit.map(|limit| limit.clamp(1, MAX_ASSET_PAGE_SIZE));
+
+        let (items, next_cursor) = repo
+            .list_paginated::<Asset>(limit, cursor).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(AssetPage { items, next_cursor })
+    }
+
+    /// Relay connection over assets, for infinite-scroll grids
+    ///
+    /// `after` is the opaque DynamoDB cursor from a previous page's `endCursor`. With
+    /// `status` the `StatusIndex` is queried instead of scanning the table. `first`
+    /// is capped at `MAX_ASSET_PAGE_SIZE`.
+    async fn assets_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+        status: Option<String>
+    ) -> Result<Connection<String, Asset>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let status = status
+            .map(|s| AssetCurrentStatusOptions::from_string(&s))
+            .transpose()
+            .map_err(|e| e.to_graphql_error())?;
+
+        connection::query(after, None, first, None, |after, _before, first, _last| async move {
+            let limit = first.map(|first| (first as i32).clamp(1, MAX_ASSET_PAGE_SIZE));
+
+            let (assets, next_cursor) = match &status {
+                Some(status) =>
+                    repo.query_index_paginated::<Asset>(
+                        "StatusIndex",
+                        "current_status",
+                        status.to_str(),
+                        limit,
+                        after
+                    ).await?,
+                None => repo.list_paginated::<Asset>(limit, after).await?,
+            };
+
+            let mut connection = Connection::new(false, next_cursor.is_some());
+            for asset in assets {
+                let cursor = asset_cursor(&asset, status.is_some())?;
+                connection.edges.push(Edge::new(cursor, asset));
+            }
+
+            Ok::<_, AppError>(connection)
+        }).await
+    }
+
     /// Get assets by location
     async fn assets_by_location(
         &self,
@@ -101,7 +193,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -130,6 +222,45 @@ impl AssetQuery {
         Ok(assets)
     }
 
+    /// Get assets at a location, optionally including every location nested beneath it
+    async fn assets_in_location(
+        &self,
+        ctx: &Context<'_>,
+        location_id: String,
+        include_descendants: bool
+    ) -> Result<Vec<Asset>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        repo
+            .get::<Location>(location_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Location {} not found", location_id)))?;
+
+        let repo = &repo;
+        gather_location_assets(
+            location_id,
+            include_descendants,
+            |id| async move {
+                repo.query_index::<Location>(
+                    "ParentLocationIndex",
+                    "parent_location_id",
+                    &id,
+                    None
+                ).await
+            },
+            |id| async move {
+                repo.query_index::<Asset>("LocationIndex", "location_id", &id, None).await
+            }
+        ).await.map_err(|e| e.to_graphql_error())
+    }
+
     /// Get assets by type
     async fn assets_by_type(
         &self,
@@ -145,7 +276,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -189,7 +320,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -232,26 +363,15 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let status_enum = AssetCurrentStatusOptions::from_string(&status).map_err(|e|
             e.to_graphql_error()
         )?;
 
-        let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
-
-        // Filter by status
-        assets = assets
-            .into_iter()
-            .filter(|asset| asset.current_status.to_string() == status_enum.to_string())
-            .collect();
-
-        // Apply limit if provided
-        if let Some(limit_val) = limit {
-            assets.truncate(limit_val as usize);
-        }
-
-        Ok(assets)
+        repo
+            .query_index::<Asset>("StatusIndex", "current_status", status_enum.to_str(), limit).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     /// Get assets that are down (not operational)
@@ -267,7 +387,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -299,7 +419,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -336,7 +456,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -373,7 +493,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -402,10 +522,13 @@ impl AssetQuery {
         Ok(assets)
     }
 
-    /// Get assets under warranty
+    /// Get assets whose warranty is active, soonest expiry first
+    ///
+    /// `expiring_within_days` narrows the result to warranties ending within that window.
     async fn assets_under_warranty(
         &self,
         ctx: &Context<'_>,
+        expiring_within_days: Option<i32>,
         limit: Option<i32>
     ) -> Result<Vec<Asset>, Error> {
         let db_client = ctx.data::<DbClient>().map_err(|e| {
@@ -415,23 +538,15 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
-
-        let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
+        let repo = ctx.repository(db_client);
 
-        let now = Utc::now();
+        let assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
-        // Filter for assets under warranty
-        assets = assets
-            .into_iter()
-            .filter(|asset| {
-                if let Some(warranty_end) = asset.warranty_end_date {
-                    warranty_end > now
-                } else {
-                    false
-                }
-            })
-            .collect();
+        let mut assets = assets_under_warranty(
+            assets,
+            expiring_within_days.map(i64::from),
+            Utc::now()
+        );
 
         // Apply limit if provided
         if let Some(limit_val) = limit {
@@ -455,7 +570,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -497,7 +612,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify asset exists
         let _asset = repo
@@ -521,6 +636,29 @@ impl AssetQuery {
         Ok(work_orders)
     }
 
+    /// Work orders linked to an asset through its `work_order_ids`, loaded in one batch
+    async fn asset_work_orders(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: String
+    ) -> Result<Vec<WorkOrder>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let asset = repo
+            .get::<Asset>(asset_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", asset_id)))?;
+
+        repo.batch_get::<WorkOrder>(asset.work_order_ids).await.map_err(|e| e.to_graphql_error())
+    }
+
     /// Search assets by serial number
     async fn assets_by_serial_number(
         &self,
@@ -535,7 +673,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -572,7 +710,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -595,6 +733,132 @@ impl AssetQuery {
         Ok(filtered_assets)
     }
 
+    /// Get assets whose maintenance is overdue (or due within `within_days`), most overdue first
+    async fn assets_needing_maintenance(
+        &self,
+        ctx: &Context<'_>,
+        within_days: Option<i32>,
+        limit: Option<i32>
+    ) -> Result<Vec<Asset>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        // TODO: the due date is only computed in Rust today, so this is a full scan.
+        // Query MaintenanceFrequencyIndex per frequency (skipping one_time/as_needed)
+        // once due dates are stored on the item.
+        let assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
+
+        // One-time and as-needed assets (interval 0) have no recurring due date
+        let mut due_assets: Vec<(i64, Asset)> = assets
+            .into_iter()
+            .filter(|asset| asset.interval_days > 0)
+            .map(|asset| (asset.days_until_maintenance(), asset))
+            .filter(|(days_until, asset)| {
+                match within_days {
+                    Some(window) => *days_until <= (window as i64),
+                    None => asset.is_maintenance_overdue(),
+                }
+            })
+            .collect();
+
+        due_assets.sort_by(|(a_days, a), (b_days, b)| {
+            a_days.cmp(b_days).then_with(|| a.id.cmp(&b.id))
+        });
+
+        let mut assets: Vec<Asset> = due_assets
+            .into_iter()
+            .map(|(_, asset)| asset)
+            .collect();
+
+        if let Some(limit_val) = limit {
+            assets.truncate(limit_val as usize);
+        }
+
+        Ok(assets)
+    }
+
+    /// Search assets by a case-insensitive term across name, serial and model number
+    ///
+    /// Scan-based: every asset is loaded and matched in memory. Exact serial
+    /// matches rank first, then prefix matches, then substring matches.
+    async fn search_assets(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        status: Option<String>,
+        limit: Option<i32>
+    ) -> Result<Vec<Asset>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let status_enum = status
+            .map(|s| AssetCurrentStatusOptions::from_string(&s))
+            .transpose()
+            .map_err(|e| e.to_graphql_error())?;
+
+        let repo = ctx.repository(db_client);
+
+        let assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
+
+        let assets: Vec<Asset> = assets
+            .into_iter()
+            .filter(|asset| status_enum.is_none_or(|s| asset.current_status == s))
+            .collect();
+
+        let mut assets = rank_asset_matches(assets, &query);
+
+        if let Some(limit_val) = limit {
+            assets.truncate(limit_val as usize);
+        }
+
+        Ok(assets)
+    }
+
+    /// Uptime, downtime and MTBF for an asset from `since` (or its installation, if later)
+    ///
+    /// Downtime events are the corrective and emergency work orders raised against the
+    /// asset in the window.
+    async fn asset_availability(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: String,
+        since: DateTime<Utc>
+    ) -> Result<AssetAvailability, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let asset = repo
+            .get::<Asset>(asset_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", asset_id)))?;
+
+        let window_start = since.max(asset.installation_date);
+        let downtime_events = repo
+            .query_index::<WorkOrder>("AssetIndex", "asset_id", &asset_id, None).await
+            .map_err(|e| e.to_graphql_error())?
+            .iter()
+            .filter(|wo| wo.is_breakdown() && wo.created_at >= window_start)
+            .count() as i32;
+
+        Ok(AssetAvailability::compute(&asset, since, downtime_events, Utc::now()))
+    }
+
     /// Get asset maintenance history (placeholder - would need maintenance records model)
     async fn asset_maintenance_history(
         &self,
@@ -609,7 +873,7 @@ impl AssetQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify asset exists
         let _asset = repo
@@ -626,7 +890,7 @@ impl AssetQuery {
             .collect();
 
         // Sort by completion date (most recent first)
-        work_orders.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        work_orders.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then_with(|| a.id.cmp(&b.id)));
 
         // Apply limit if provided
         if let Some(limit_val) = limit {
@@ -636,3 +900,321 @@ impl AssetQuery {
         Ok(work_orders)
     }
 }
+
+/// Cursor resuming a scan, or a `StatusIndex` query, right after `asset`
+///
+/// Mirrors the `LastEvaluatedKey` DynamoDB would return had the page ended on this asset.
+fn asset_cursor(asset: &Asset, by_status: bool) -> Result<String, AppError> {
+    let mut key = HashMap::from([("id".to_string(), AttributeValue::S(asset.id.clone()))]);
+    if by_status {
+        key.insert(
+            "current_status".to_string(),
+            AttributeValue::S(asset.current_status.to_str().to_string())
+        );
+    }
+
+    encode_cursor(&key)
+}
+
+/// Assets at `location_id` and, when `include_descendants`, at every location below it
+///
+/// `children_of` and `assets_at` fetch one location's direct children and assets.
+/// Locations already visited are skipped so a looped hierarchy still terminates, and
+/// assets are deduplicated by id and ordered by name.
+async fn gather_location_assets<C, CF, A, AF>(
+    location_id: String,
+    include_descendants: bool,
+    mut children_of: C,
+    mut assets_at: A
+)
+    -> Result<Vec<Asset>, AppError>
+    where
+        C: FnMut(String) -> CF,
+        CF: Future<Output = Result<Vec<Location>, AppError>>,
+        A: FnMut(String) -> AF,
+        AF: Future<Output = Result<Vec<Asset>, AppError>>
+{
+    let mut visited = HashSet::from([location_id.clone()]);
+    let mut pending = VecDeque::from([location_id]);
+    let mut seen_assets = HashSet::new();
+    let mut assets = Vec::new();
+
+    while let Some(current) = pending.pop_front() {
+        if include_descendants {
+            for child in children_of(current.clone()).await? {
+                if visited.insert(child.id.clone()) {
+                    pending.push_back(child.id);
+                }
+            }
+        }
+
+        for asset in assets_at(current).await? {
+            if seen_assets.insert(asset.id.clone()) {
+                assets.push(asset);
+            }
+        }
+    }
+
+    assets.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    Ok(assets)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetAvailability {
+    pub uptime_hours: f64,
+    pub downtime_hours: f64,
+    pub availability_pct: f64,
+    pub downtime_events: i32,
+}
+
+impl AssetAvailability {
+    /// Availability of `asset` between `since` (or its installation, if later) and `now`
+    ///
+    /// Downtime is the asset's accumulated `total_downtime_hours` plus the open
+    /// window if it is currently down or in maintenance, capped at the elapsed time.
+    fn compute(
+        asset: &Asset,
+        since: DateTime<Utc>,
+        downtime_events: i32,
+        now: DateTime<Utc>
+    ) -> Self {
+        let window_start = since.max(asset.installation_date);
+        let elapsed_hours = hours_between(window_start, now);
+
+        let mut downtime_hours = asset.total_downtime_hours.to_f64().unwrap_or(0.0);
+        if asset.current_status.accrues_downtime() {
+            downtime_hours += hours_between(asset.last_downtime_date.max(window_start), now);
+        }
+        let downtime_hours = downtime_hours.min(elapsed_hours);
+
+        let uptime_hours = elapsed_hours - downtime_hours;
+        let availability_pct = if elapsed_hours == 0.0 {
+            100.0
+        } else {
+            (uptime_hours / elapsed_hours) * 100.0
+        };
+
+        Self { uptime_hours, downtime_hours, availability_pct, downtime_events }
+    }
+}
+
+#[Object]
+impl AssetAvailability {
+    async fn uptime_hours(&self) -> f64 {
+        self.uptime_hours
+    }
+
+    async fn downtime_hours(&self) -> f64 {
+        self.downtime_hours
+    }
+
+    async fn availability_pct(&self) -> f64 {
+        self.availability_pct
+    }
+
+    async fn downtime_events(&self) -> i32 {
+        self.downtime_events
+    }
+
+    /// Mean time between failures in hours, null when there were no downtime events
+    async fn mtbf_hours(&self) -> Option<f64> {
+        if self.downtime_events == 0 {
+            None
+        } else {
+            Some(self.uptime_hours / (self.downtime_events as f64))
+        }
+    }
+}
+
+/// Hours from `start` to `end`, zero if `end` is not after `start`
+fn hours_between(start: DateTime<Utc>, end: DateTime<Utc>) -> f64 {
+    ((end - start).num_seconds().max(0) as f64) / 3600.0
+}
+
+/// How closely an asset matches a search term, lower ranks first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SearchMatch {
+    ExactSerial,
+    Prefix,
+    Substring,
+}
+
+/// Keeps assets matching `query` and orders them by `SearchMatch`
+///
+/// Matching is case-insensitive; assets within the same rank keep their input order.
+fn rank_asset_matches(assets: Vec<Asset>, query: &str) -> Vec<Asset> {
+    let term = query.trim().to_lowercase();
+    if term.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranked: Vec<(SearchMatch, Asset)> = assets
+        .into_iter()
+        .filter_map(|asset| {
+            let fields = [
+                asset.serial_number.to_lowercase(),
+                asset.name.to_lowercase(),
+                asset.model_number.to_lowercase(),
+            ];
+
+            let rank = if fields[0] == term {
+                SearchMatch::ExactSerial
+            } else if fields.iter().any(|f| f.starts_with(&term)) {
+                SearchMatch::Prefix
+            } else if fields.iter().any(|f| f.contains(&term)) {
+                SearchMatch::Substring
+            } else {
+                return None;
+            };
+
+            Some((rank, asset))
+        })
+        .collect();
+
+    ranked.sort_by(|(a_rank, a), (b_rank, b)| a_rank.cmp(b_rank).then_with(|| a.id.cmp(&b.id)));
+
+    ranked
+        .into_iter()
+        .map(|(_, asset)| asset)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn asset_at(id: &str, location_id: &str) -> Asset {
+        let mut asset = asset(id, id, id, "M1");
+        asset.location_id = location_id.to_string();
+        asset
+    }
+
+    fn location(id: &str, parent_location_id: Option<&str>) -> Location {
+        Location::new(
+            id.to_string(),
+            id.to_string(),
+            format!("{} description", id),
+            "type-1".to_string(),
+            parent_location_id.map(|p| p.to_string()),
+            crate::Address::new(
+                "1 Main St".to_string(),
+                None,
+                "Denver".to_string(),
+                "CO".to_string(),
+                "US".to_string(),
+                "80202".to_string()
+            ),
+            None
+        )
+    }
+
+    fn asset(id: &str, name: &str, serial_number: &str, model_number: &str) -> Asset {
+        let now = Utc::now();
+        Asset::new(
+            id.to_string(),
+            name.to_string(),
+            "type-1".to_string(),
+            serial_number.to_string(),
+            model_number.to_string(),
+            now,
+            now,
+            "loc-1".to_string(),
+            "mfg-1".to_string(),
+            "monthly".to_string(),
+            None,
+            None
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_search_ranks_exact_serial_then_prefix_then_substring() {
+        let assets = vec![
+            asset("substring", "Boiler", "XAB-100", "B2"),
+            asset("prefix", "Pump", "AB-100-7", "P1"),
+            asset("exact", "Chiller", "ab-100", "C3"),
+            asset("miss", "Fan", "ZZ-9", "F4"),
+        ];
+
+        let ids: Vec<String> = rank_asset_matches(assets, "AB-100")
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+
+        assert_eq!(ids, vec!["exact", "prefix", "substring"]);
+    }
+
+    #[test]
+    fn test_availability_includes_recorded_and_in_progress_downtime() {
+        let installed = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let now = installed + chrono::Duration::hours(100);
+
+        let mut asset = asset("pump", "Pump", "SN-1", "P1");
+        asset.installation_date = installed;
+        asset.total_downtime_hours = Decimal::new(65, 1);
+
+        let availability = AssetAvailability::compute(&asset, installed, 2, now);
+        assert_eq!(availability.downtime_hours, 6.5);
+        assert_eq!(availability.uptime_hours, 93.5);
+        assert_eq!(availability.availability_pct, 93.5);
+
+        // Down for the last 3.5 hours and not yet back in service
+        asset.current_status = AssetCurrentStatusOptions::Down;
+        asset.last_downtime_date = now - chrono::Duration::minutes(210);
+
+        let availability = AssetAvailability::compute(&asset, installed, 2, now);
+        assert_eq!(availability.downtime_hours, 10.0);
+        assert_eq!(availability.uptime_hours, 90.0);
+        assert_eq!(availability.availability_pct, 90.0);
+        assert_eq!(availability.downtime_events, 2);
+    }
+
+    #[tokio::test]
+    async fn test_assets_in_location_unions_child_rooms_when_descendants_included() {
+        let locations = vec![
+            location("building", None),
+            location("room-a", Some("building")),
+            location("room-b", Some("building")),
+        ];
+        let assets = vec![
+            asset_at("boiler", "building"),
+            asset_at("pump", "room-a"),
+            asset_at("fan", "room-b"),
+        ];
+
+        let gather = |include_descendants| {
+            let (locations, assets) = (&locations, &assets);
+            gather_location_assets(
+                "building".to_string(),
+                include_descendants,
+                move |id| async move {
+                    Ok(
+                        locations
+                            .iter()
+                            .filter(|l| l.parent_location_id.as_deref() == Some(id.as_str()))
+                            .cloned()
+                            .collect()
+                    )
+                },
+                move |id| async move {
+                    Ok(
+                        assets
+                            .iter()
+                            .filter(|a| a.location_id == id)
+                            .cloned()
+                            .collect()
+                    )
+                }
+            )
+        };
+
+        let with_descendants = gather(true).await.unwrap();
+        assert_eq!(with_descendants.len(), 3);
+
+        let direct_only = gather(false).await.unwrap();
+        assert_eq!(direct_only.len(), 1);
+        assert_eq!(direct_only[0].id, "boiler");
+    }
+}
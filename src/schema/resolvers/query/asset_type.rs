@@ -1,10 +1,12 @@
 use async_graphql::*;
 use tracing::{ info, warn };
 
-use crate::{ error::AppError, models::asset_type::AssetType, DbClient, Repository };
-
-// Import for Asset_type::from_item() usage
-use crate::repository::DynamoDbEntity;
+use crate::{
+    context::ContextExtensions,
+    error::AppError,
+    models::{ asset::Asset, asset_type::AssetType },
+    DbClient,
+};
 
 #[derive(Debug, Default)]
 pub(crate) struct AssetTypeQuery;
@@ -23,7 +25,7 @@ impl AssetTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let asset_type = repo
             .get::<AssetType>(id.clone()).await
@@ -33,7 +35,6 @@ impl AssetTypeQuery {
         Ok(asset_type)
     }
     pub(crate) async fn asset_types(&self, ctx: &Context<'_>) -> Result<Vec<AssetType>, Error> {
-        let table_name = "AssetTypes";
         let db_client = ctx.data::<DbClient>().map_err(|e| {
             warn!("Failed to get db_client from context: {:?}", e);
             AppError::InternalServerError(
@@ -41,27 +42,26 @@ impl AssetTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let response = db_client
-            .scan()
-            .table_name(table_name)
-            .send().await
-            .map_err(|e| {
-                warn!("Failed to get db_client from context: {:?}", e);
-                AppError::DatabaseError(
-                    "Failed to get all users from db".to_string()
-                ).to_graphql_error()
-            })?;
-
-        // info!("get all users response: {:?}", response);
+        let repo = ctx.repository(db_client);
 
-        let asset_types = response
-            .items()
-            .iter()
-            .filter_map(|item| AssetType::from_item(item))
-            .collect::<Vec<AssetType>>();
+        repo.list::<AssetType>(None).await.map_err(|e| e.to_graphql_error())
+    }
 
-        // info!("asset_types from response items: {:?}", asset_types);
+    /// Number of assets of an asset type, counted without loading them
+    async fn asset_type_usage(
+        &self,
+        ctx: &Context<'_>,
+        asset_type_id: String
+    ) -> Result<i32, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
 
-        Ok(asset_types)
+        ctx.repository(db_client)
+            .count_index::<Asset>("TypeIndex", "asset_type_id", &asset_type_id).await
+            .map_err(|e| e.to_graphql_error())
     }
 }
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use async_graphql::*;
+use aws_sdk_s3::presigning::PresigningConfig;
+use tracing::{ info, warn };
+
+use crate::{
+    context::ContextExtensions,
+    error::AppError,
+    models::asset::Asset,
+    DbClient,
+    S3Client,
+};
+
+/// How long a presigned download URL stays valid
+const DOWNLOAD_URL_EXPIRY: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Default)]
+pub(crate) struct AttachmentQuery;
+
+#[Object]
+impl AttachmentQuery {
+    /// Get a short-lived presigned GET URL for one of an asset's documents
+    async fn asset_document_download_url(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: String,
+        key: String
+    ) -> Result<String, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let asset = repo
+            .get::<Asset>(asset_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::NotFound(format!("Asset {} not found", asset_id)))?;
+
+        // Only hand out URLs for keys recorded on the asset, so the bucket can't be probed
+        if !asset.documentation_keys.contains(&key) {
+            return Err(
+                AppError::Forbidden(
+                    format!("Key {} is not associated with asset {}", key, asset_id)
+                ).to_graphql_error()
+            );
+        }
+
+        let s3_client = ctx.data::<S3Client>().map_err(|e| {
+            warn!("Failed to get s3_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application s3_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let bucket_name = ctx.s3_bucket().map_err(|e| e.to_graphql_error())?;
+
+        let presigning_config = PresigningConfig::expires_in(DOWNLOAD_URL_EXPIRY).map_err(|e|
+            AppError::InternalServerError(
+                format!("Invalid presigning config: {:?}", e)
+            ).to_graphql_error()
+        )?;
+
+        let presigned = s3_client
+            .get_object()
+            .bucket(bucket_name)
+            .key(&key)
+            .presigned(presigning_config).await
+            .map_err(|e|
+                AppError::InternalServerError(
+                    format!("S3 presign error: {:?}", e)
+                ).to_graphql_error()
+            )?;
+
+        info!("Generated download URL for asset {} document {}", asset_id, key);
+
+        Ok(presigned.uri().to_string())
+    }
+}
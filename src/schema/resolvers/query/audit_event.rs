@@ -0,0 +1,50 @@
+use async_graphql::*;
+use tracing::warn;
+
+use crate::{
+    auth::{ guard::VIEW_AUDIT_EVENTS, require_permission },
+    context::ContextExtensions,
+    error::AppError,
+    models::audit_event::AuditEvent,
+    DbClient,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct AuditEventQuery;
+
+#[Object]
+impl AuditEventQuery {
+    /// Audit events recorded against an entity, newest first
+    ///
+    /// Requires `audit:view`.
+    async fn audit_events_for_target(
+        &self,
+        ctx: &Context<'_>,
+        target_id: String,
+        limit: Option<i32>
+    ) -> Result<Vec<AuditEvent>, Error> {
+        require_permission(ctx, VIEW_AUDIT_EVENTS).await.map_err(|e| e.to_graphql_error())?;
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        // TargetIndex has no sort key, so order and trim after reading every event
+        let mut events = repo
+            .query_index::<AuditEvent>("TargetIndex", "target_id", &target_id, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        events.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+
+        if let Some(limit) = limit {
+            events.truncate(limit.max(0) as usize);
+        }
+
+        Ok(events)
+    }
+}
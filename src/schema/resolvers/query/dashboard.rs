@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::{
         asset::AssetCurrentStatusOptions,
@@ -13,11 +14,11 @@ use crate::{
         AssetsByStatus,
         DashboardFilter,
         DashboardStatistics,
+        DashboardSummary,
         WorkOrdersByPriority,
         WorkOrdersByStatus,
     },
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -25,6 +26,37 @@ pub(crate) struct DashboardQuery;
 
 #[Object]
 impl DashboardQuery {
+    /// Get headline counts for the dashboard
+    ///
+    /// Loads each table once and folds the results, rather than querying per bucket.
+    async fn dashboard_summary(&self, ctx: &Context<'_>) -> Result<DashboardSummary, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
+        let work_orders = repo.list::<WorkOrder>(None).await.map_err(|e| e.to_graphql_error())?;
+        let maintenance_schedules = repo
+            .list::<MaintenanceSchedule>(None).await
+            .map_err(|e| e.to_graphql_error())?;
+        let vendors = repo.list::<Vendor>(None).await.map_err(|e| e.to_graphql_error())?;
+
+        Ok(
+            summarize_dashboard(
+                &assets,
+                &work_orders,
+                &maintenance_schedules,
+                &vendors,
+                Utc::now()
+            )
+        )
+    }
+
     /// Get comprehensive dashboard statistics
     async fn dashboard_statistics(
         &self,
@@ -38,7 +70,7 @@ impl DashboardQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let assets = repo.list::<Asset>(None).await.map_err(|e| e.to_graphql_error())?;
         let work_orders = repo.list::<WorkOrder>(None).await.map_err(|e| e.to_graphql_error())?;
@@ -155,13 +187,7 @@ impl DashboardQuery {
         // Assets under warranty
         let assets_under_warranty = filtered_assets
             .iter()
-            .filter(|asset| {
-                if let Some(warranty_end) = asset.warranty_end_date {
-                    warranty_end > now
-                } else {
-                    false
-                }
-            })
+            .filter(|asset| asset.is_under_warranty(now))
             .count() as i32;
 
         // Recent work order completions (last 7 days)
@@ -195,3 +221,120 @@ impl DashboardQuery {
         })
     }
 }
+
+/// Folds already-loaded records into the dashboard summary buckets
+fn summarize_dashboard(
+    assets: &[Asset],
+    work_orders: &[WorkOrder],
+    maintenance_schedules: &[MaintenanceSchedule],
+    vendors: &[Vendor],
+    now: DateTime<Utc>
+) -> DashboardSummary {
+    let assets_by_status = assets.iter().fold(AssetsByStatus::default(), |mut acc, asset| {
+        match asset.current_status {
+            AssetCurrentStatusOptions::Operational => {
+                acc.operational += 1;
+            }
+            AssetCurrentStatusOptions::Down => {
+                acc.down += 1;
+            }
+            AssetCurrentStatusOptions::Maintenance => {
+                acc.maintenance += 1;
+            }
+            AssetCurrentStatusOptions::Retired => {
+                acc.retired += 1;
+            }
+            AssetCurrentStatusOptions::NeedsAttention => {
+                acc.needs_attention += 1;
+            }
+        }
+        acc
+    });
+
+    let open_work_orders_by_priority = work_orders
+        .iter()
+        .filter(|wo| wo.is_open())
+        .fold(WorkOrdersByPriority::default(), |mut acc, wo| {
+            match wo.priority {
+                WorkOrderPriority::Low => {
+                    acc.low += 1;
+                }
+                WorkOrderPriority::Normal => {
+                    acc.normal += 1;
+                }
+                WorkOrderPriority::High => {
+                    acc.high += 1;
+                }
+                WorkOrderPriority::Urgent => {
+                    acc.urgent += 1;
+                }
+                WorkOrderPriority::Emergency => {
+                    acc.emergency += 1;
+                }
+            }
+            acc
+        });
+
+    let overdue_maintenance = maintenance_schedules
+        .iter()
+        .filter(|ms| ms.active && ms.next_due_at < now)
+        .count() as i32;
+
+    let active_vendors = vendors
+        .iter()
+        .filter(|v| v.is_active())
+        .count() as i32;
+
+    DashboardSummary {
+        assets_by_status,
+        open_work_orders_by_priority,
+        overdue_maintenance,
+        active_vendors,
+        inactive_vendors: (vendors.len() as i32) - active_vendors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(id: &str, status: AssetCurrentStatusOptions) -> Asset {
+        let now = Utc::now();
+        let mut asset = Asset::new(
+            id.to_string(),
+            format!("Asset {}", id),
+            "type-1".to_string(),
+            format!("SN-{}", id),
+            "M-1".to_string(),
+            now,
+            now,
+            "loc-1".to_string(),
+            "mfg-1".to_string(),
+            "monthly".to_string(),
+            None,
+            None
+        ).unwrap();
+        asset.current_status = status;
+        asset
+    }
+
+    #[test]
+    fn test_summary_counts_assets_by_status() {
+        let assets = vec![
+            asset("a1", AssetCurrentStatusOptions::Operational),
+            asset("a2", AssetCurrentStatusOptions::Operational),
+            asset("a3", AssetCurrentStatusOptions::Down),
+            asset("a4", AssetCurrentStatusOptions::NeedsAttention),
+            asset("a5", AssetCurrentStatusOptions::Operational),
+        ];
+
+        let summary = summarize_dashboard(&assets, &[], &[], &[], Utc::now());
+
+        assert_eq!(summary.assets_by_status.operational, 3);
+        assert_eq!(summary.assets_by_status.down, 1);
+        assert_eq!(summary.assets_by_status.needs_attention, 1);
+        assert_eq!(summary.assets_by_status.maintenance, 0);
+        assert_eq!(summary.assets_by_status.retired, 0);
+        assert_eq!(summary.overdue_maintenance, 0);
+    }
+}
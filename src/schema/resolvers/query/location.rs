@@ -1,11 +1,13 @@
+use std::collections::HashMap;
+
 use async_graphql::*;
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
-    models::location::Location,
+    models::location::{ location_path, Location },
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -26,7 +28,7 @@ impl LocationQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<Location>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -45,7 +47,7 @@ impl LocationQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut locations = repo.list::<Location>(limit).await.map_err(|e| e.to_graphql_error())?;
 
@@ -79,7 +81,7 @@ impl LocationQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // This would ideally use a GSI on location_type_id
         // For now, we'll scan and filter (not ideal for production)
@@ -106,6 +108,31 @@ impl LocationQuery {
         Ok(locations)
     }
 
+    /// Ancestor chain from the root location down to `location_id`, inclusive
+    async fn location_path(
+        &self,
+        ctx: &Context<'_>,
+        location_id: String
+    ) -> Result<Vec<Location>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let locations: HashMap<String, Location> = repo
+            .list::<Location>(None).await
+            .map_err(|e| e.to_graphql_error())?
+            .into_iter()
+            .map(|l| (l.id.clone(), l))
+            .collect();
+
+        location_path(&location_id, &locations).map_err(|e| e.to_graphql_error())
+    }
+
     /// Get child locations of a parent
     async fn child_locations(
         &self,
@@ -120,16 +147,12 @@ impl LocationQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
-        // This would ideally use a GSI on parent_location_id
-        // For now, we'll scan and filter (not ideal for production)
-        let mut locations = repo.list::<Location>(None).await.map_err(|e| e.to_graphql_error())?;
-
-        locations = locations
-            .into_iter()
-            .filter(|l| l.parent_location_id.as_ref() == Some(&parent_id))
-            .collect();
+        let mut locations = repo
+            .query_index::<Location>("ParentLocationIndex", "parent_location_id", &parent_id, None)
+            .await
+            .map_err(|e| e.to_graphql_error())?;
 
         // Filter by active status if requested
         if let Some(true) = active_only {
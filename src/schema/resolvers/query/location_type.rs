@@ -2,10 +2,10 @@ use async_graphql::*;
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::{ location_type::LocationType, location::Location },
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -26,7 +26,7 @@ impl LocationTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<LocationType>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -44,7 +44,7 @@ impl LocationTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.list::<LocationType>(limit).await.map_err(|e| e.to_graphql_error())
     }
@@ -64,7 +64,7 @@ impl LocationTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let location_types = repo
             .list::<LocationType>(None).await
@@ -106,7 +106,7 @@ impl LocationTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let location_types = repo
             .list::<LocationType>(None).await
@@ -142,7 +142,7 @@ impl LocationTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify location type exists
         let _location_type = repo
@@ -196,7 +196,7 @@ impl LocationTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify location type exists
         let location_type = repo
@@ -244,7 +244,7 @@ impl LocationTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut location_types = repo
             .list::<LocationType>(limit).await
@@ -289,7 +289,7 @@ impl LocationTypeQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let location_types = repo
             .list::<LocationType>(None).await
@@ -2,13 +2,13 @@ use async_graphql::*;
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::{
         maintenance_request::{ MaintenanceRequest, MaintenanceRequestStatus },
         work_order::WorkOrderSeverity,
     },
     DbClient,
-    Repository,
 };
 
 #[derive(Default, Debug)]
@@ -29,7 +29,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<MaintenanceRequest>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -50,7 +50,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut requests = repo
             .list::<MaintenanceRequest>(limit).await
@@ -87,7 +87,7 @@ impl MaintenanceRequestQuery {
         }
 
         // Sort by creation date (newest first)
-        requests.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        requests.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
 
         Ok(requests)
     }
@@ -106,7 +106,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let status_enum = MaintenanceRequestStatus::from_string(&status).map_err(|e|
             e.to_graphql_error()
@@ -121,7 +121,7 @@ impl MaintenanceRequestQuery {
             .filter(|req| req.status == status_enum)
             .collect();
 
-        requests.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        requests.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             requests.truncate(limit_val as usize);
@@ -145,7 +145,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let severity_enum = WorkOrderSeverity::from_string(&severity).map_err(|e|
             e.to_graphql_error()
@@ -171,7 +171,7 @@ impl MaintenanceRequestQuery {
                 .collect();
         }
 
-        requests.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        requests.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             requests.truncate(limit_val as usize);
@@ -195,7 +195,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut requests = repo
             .list::<MaintenanceRequest>(None).await
@@ -217,7 +217,7 @@ impl MaintenanceRequestQuery {
                 .collect();
         }
 
-        requests.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        requests.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             requests.truncate(limit_val as usize);
@@ -239,7 +239,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut requests = repo
             .list::<MaintenanceRequest>(None).await
@@ -250,7 +250,9 @@ impl MaintenanceRequestQuery {
             .filter(|req| req.status == MaintenanceRequestStatus::Submitted)
             .collect();
 
-        requests.sort_by(|a, b| a.created_at.cmp(&b.created_at)); // Oldest first for unread
+        // Oldest first for unread
+
+        requests.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             requests.truncate(limit_val as usize);
@@ -272,7 +274,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut requests = repo
             .list::<MaintenanceRequest>(None).await
@@ -283,7 +285,9 @@ impl MaintenanceRequestQuery {
             .filter(|req| req.status == MaintenanceRequestStatus::Read)
             .collect();
 
-        requests.sort_by(|a, b| a.created_at.cmp(&b.created_at)); // Oldest first for pending
+        // Oldest first for pending
+
+        requests.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             requests.truncate(limit_val as usize);
@@ -305,7 +309,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut requests = repo
             .list::<MaintenanceRequest>(None).await
@@ -316,7 +320,7 @@ impl MaintenanceRequestQuery {
             .filter(|req| !req.work_order_ids.is_empty())
             .collect();
 
-        requests.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        requests.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             requests.truncate(limit_val as usize);
@@ -337,7 +341,7 @@ impl MaintenanceRequestQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let requests = repo
             .list::<MaintenanceRequest>(None).await
@@ -3,12 +3,38 @@ use chrono::{ DateTime, Utc };
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
-    models::{ maintenance_schedule::{ MaintenanceSchedule, CadenceUnit }, asset::Asset },
+    models::{
+        maintenance_schedule::{
+            due_date_sort_key,
+            MaintenanceSchedule,
+            CadenceUnit,
+            DUE_DATE_PARTITION,
+        },
+        asset::Asset,
+    },
+    repository::SortKeyCondition,
     DbClient,
     Repository,
 };
 
+/// Schedules due on or before `cutoff`, soonest first, read from `DueDateIndex`
+pub(crate) async fn schedules_due_before(
+    repo: &Repository,
+    cutoff: DateTime<Utc>,
+    limit: Option<i32>
+) -> Result<Vec<MaintenanceSchedule>, AppError> {
+    repo.query_index_range::<MaintenanceSchedule>(
+        "DueDateIndex",
+        "due_partition",
+        DUE_DATE_PARTITION,
+        "next_due_date",
+        SortKeyCondition::LessThanOrEqual(due_date_sort_key(&cutoff)),
+        limit
+    ).await
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct MaintenanceScheduleQuery;
 
@@ -26,7 +52,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<MaintenanceSchedule>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -45,7 +71,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedules = repo
             .list::<MaintenanceSchedule>(limit).await
@@ -82,7 +108,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _asset = repo
             .get::<Asset>(asset_id.clone()).await
@@ -107,7 +133,7 @@ impl MaintenanceScheduleQuery {
                 .collect();
         }
 
-        schedules.sort_by(|a, b| a.next_due_at.cmp(&b.next_due_at));
+        schedules.sort_by(|a, b| a.next_due_at.cmp(&b.next_due_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             schedules.truncate(limit_val as usize);
@@ -116,6 +142,27 @@ impl MaintenanceScheduleQuery {
         Ok(schedules)
     }
 
+    /// Get schedules due on or before `cutoff`, soonest first, via `DueDateIndex`
+    ///
+    /// Schedules without a fixed interval are not indexed and never appear here.
+    async fn maintenance_due_before(
+        &self,
+        ctx: &Context<'_>,
+        cutoff: DateTime<Utc>,
+        limit: Option<i32>
+    ) -> Result<Vec<MaintenanceSchedule>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        schedules_due_before(&repo, cutoff, limit).await.map_err(|e| e.to_graphql_error())
+    }
+
     async fn overdue_maintenance_schedules(
         &self,
         ctx: &Context<'_>,
@@ -128,7 +175,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -141,7 +188,7 @@ impl MaintenanceScheduleQuery {
             .filter(|s| s.active && s.next_due_at < now)
             .collect();
 
-        schedules.sort_by(|a, b| a.next_due_at.cmp(&b.next_due_at));
+        schedules.sort_by(|a, b| a.next_due_at.cmp(&b.next_due_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             schedules.truncate(limit_val as usize);
@@ -163,7 +210,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -178,7 +225,7 @@ impl MaintenanceScheduleQuery {
             .filter(|s| s.active && s.next_due_at >= now && s.next_due_at <= cutoff_date)
             .collect();
 
-        schedules.sort_by(|a, b| a.next_due_at.cmp(&b.next_due_at));
+        schedules.sort_by(|a, b| a.next_due_at.cmp(&b.next_due_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             schedules.truncate(limit_val as usize);
@@ -200,7 +247,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -239,7 +286,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let cadence_unit_enum = CadenceUnit::from_string(&cadence_unit).map_err(|e|
             e.to_graphql_error()
@@ -289,7 +336,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -307,7 +354,7 @@ impl MaintenanceScheduleQuery {
                 .collect();
         }
 
-        schedules.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        schedules.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             schedules.truncate(limit_val as usize);
@@ -329,7 +376,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -349,10 +396,12 @@ impl MaintenanceScheduleQuery {
             })
             .collect();
 
+        let now = Utc::now();
         schedules.sort_by(|a, b| {
             b.last_completed_at
-                .unwrap_or_else(|| Utc::now())
-                .cmp(&a.last_completed_at.unwrap_or_else(|| Utc::now()))
+                .unwrap_or(now)
+                .cmp(&a.last_completed_at.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         if let Some(limit_val) = limit {
@@ -377,7 +426,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -405,6 +454,7 @@ impl MaintenanceScheduleQuery {
 
         schedules.sort_by(|a, b| {
             a.duration_estimate.unwrap_or(0).cmp(&b.duration_estimate.unwrap_or(0))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         if let Some(limit_val) = limit {
@@ -425,7 +475,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -478,7 +528,7 @@ impl MaintenanceScheduleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut schedules = repo
             .list::<MaintenanceSchedule>(None).await
@@ -496,7 +546,7 @@ impl MaintenanceScheduleQuery {
                 .collect();
         }
 
-        schedules.sort_by(|a, b| a.next_due_at.cmp(&b.next_due_at));
+        schedules.sort_by(|a, b| a.next_due_at.cmp(&b.next_due_at).then_with(|| a.id.cmp(&b.id)));
 
         Ok(schedules)
     }
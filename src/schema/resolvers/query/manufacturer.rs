@@ -2,13 +2,13 @@ use async_graphql::*;
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::{
         manufacturer::Manufacturer,
         asset::Asset,
     },
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -28,7 +28,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<Manufacturer>(id)
             .await
@@ -48,7 +48,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturers = repo
             .list::<Manufacturer>(limit)
@@ -62,7 +62,7 @@ impl ManufacturerQuery {
                 .collect();
         }
 
-        manufacturers.sort_by(|a, b| a.name.cmp(&b.name));
+        manufacturers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         Ok(manufacturers)
     }
@@ -82,7 +82,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturers = repo
             .list::<Manufacturer>(None)
@@ -110,7 +110,7 @@ impl ManufacturerQuery {
                 .collect();
         }
 
-        manufacturers.sort_by(|a, b| a.name.cmp(&b.name));
+        manufacturers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             manufacturers.truncate(limit_val as usize);
@@ -133,7 +133,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturers = repo
             .list::<Manufacturer>(None)
@@ -152,7 +152,7 @@ impl ManufacturerQuery {
                 .collect();
         }
 
-        manufacturers.sort_by(|a, b| a.name.cmp(&b.name));
+        manufacturers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             manufacturers.truncate(limit_val as usize);
@@ -175,7 +175,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturers = repo
             .list::<Manufacturer>(None)
@@ -194,7 +194,7 @@ impl ManufacturerQuery {
                 .collect();
         }
 
-        manufacturers.sort_by(|a, b| a.name.cmp(&b.name));
+        manufacturers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             manufacturers.truncate(limit_val as usize);
@@ -217,7 +217,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturers = repo
             .list::<Manufacturer>(None)
@@ -236,7 +236,7 @@ impl ManufacturerQuery {
                 .collect();
         }
 
-        manufacturers.sort_by(|a, b| a.name.cmp(&b.name));
+        manufacturers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             manufacturers.truncate(limit_val as usize);
@@ -258,7 +258,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturers = repo
             .list::<Manufacturer>(None)
@@ -277,7 +277,7 @@ impl ManufacturerQuery {
                 .collect();
         }
 
-        manufacturers.sort_by(|a, b| a.name.cmp(&b.name));
+        manufacturers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             manufacturers.truncate(limit_val as usize);
@@ -299,7 +299,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturers = repo
             .list::<Manufacturer>(None)
@@ -318,7 +318,7 @@ impl ManufacturerQuery {
                 .collect();
         }
 
-        manufacturers.sort_by(|a, b| a.name.cmp(&b.name));
+        manufacturers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             manufacturers.truncate(limit_val as usize);
@@ -340,7 +340,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut manufacturers = repo
             .list::<Manufacturer>(None)
@@ -359,7 +359,7 @@ impl ManufacturerQuery {
                 .collect();
         }
 
-        manufacturers.sort_by(|a, b| a.name.cmp(&b.name));
+        manufacturers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             manufacturers.truncate(limit_val as usize);
@@ -379,7 +379,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let manufacturers = repo
             .list::<Manufacturer>(None)
@@ -421,7 +421,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _manufacturer = repo
             .get::<Manufacturer>(manufacturer_id.clone())
@@ -433,16 +433,11 @@ impl ManufacturerQuery {
             })?;
 
         let mut assets = repo
-            .list::<Asset>(None)
+            .query_index::<Asset>("ManufacturerIndex", "manufacturer_id", &manufacturer_id, None)
             .await
             .map_err(|e| e.to_graphql_error())?;
 
-        assets = assets
-            .into_iter()
-            .filter(|a| a.manufacturer_id == manufacturer_id)
-            .collect();
-
-        assets.sort_by(|a, b| a.name.cmp(&b.name));
+        assets.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             assets.truncate(limit_val as usize);
@@ -451,6 +446,7 @@ impl ManufacturerQuery {
         Ok(assets)
     }
 
+    /// Number of assets referencing a manufacturer, counted without loading them
     async fn manufacturer_asset_count(
         &self,
         ctx: &Context<'_>,
@@ -463,7 +459,7 @@ impl ManufacturerQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _manufacturer = repo
             .get::<Manufacturer>(manufacturer_id.clone())
@@ -474,17 +470,9 @@ impl ManufacturerQuery {
                     .to_graphql_error()
             })?;
 
-        let assets = repo
-            .list::<Asset>(None)
+        repo.count_index::<Asset>("ManufacturerIndex", "manufacturer_id", &manufacturer_id)
             .await
-            .map_err(|e| e.to_graphql_error())?;
-
-        let count = assets
-            .iter()
-            .filter(|a| a.manufacturer_id == manufacturer_id)
-            .count() as i32;
-
-        Ok(count)
+            .map_err(|e| e.to_graphql_error())
     }
 }
 
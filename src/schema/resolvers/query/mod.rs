@@ -2,6 +2,7 @@ use async_graphql::MergedObject;
 
 mod asset_type;
 mod asset;
+mod audit_event;
 mod location;
 mod location_type;
 mod user;
@@ -10,15 +11,21 @@ mod role;
 mod permission;
 mod work_order;
 mod manufacturer;
-mod maintenance_schedule;
+pub(crate) mod maintenance_schedule;
 mod dashboard;
 mod task;
 mod maintenance_request;
+mod attachment;
+mod vendor;
+mod user_preferences;
+mod notification;
+pub(crate) mod notification_template;
 
 #[derive(Debug, Default, MergedObject)]
 pub struct QueryRoot(
     asset_type::AssetTypeQuery,
     asset::AssetQuery,
+    audit_event::AuditEventQuery,
     location::LocationQuery,
     location_type::LocationTypeQuery,
     user::UserQuery,
@@ -31,4 +38,9 @@ pub struct QueryRoot(
     dashboard::DashboardQuery,
     task::TaskQuery,
     maintenance_request::MaintenanceRequestQuery,
+    attachment::AttachmentQuery,
+    vendor::VendorQuery,
+    user_preferences::UserPreferencesQuery,
+    notification::NotificationQuery,
+    notification_template::NotificationTemplateQuery,
 );
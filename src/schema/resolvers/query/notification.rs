@@ -0,0 +1,51 @@
+use async_graphql::*;
+use tracing::warn;
+
+use crate::{
+    context::ContextExtensions,
+    error::AppError,
+    models::notification::Notification,
+    DbClient,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct NotificationQuery;
+
+#[Object]
+impl NotificationQuery {
+    /// Get a user's notifications, newest first
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User whose notifications to return
+    /// * `unread_only` - Only return notifications the user has not read yet
+    async fn notifications_for_user(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+        unread_only: Option<bool>
+    ) -> Result<Vec<Notification>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let mut notifications = repo
+            .query_index::<Notification>("UserIndex", "user_id", &user_id, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        if unread_only.unwrap_or(false) {
+            notifications.retain(|n| !n.read);
+        }
+
+        notifications.sort_by(|a, b| {
+            b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id))
+        });
+
+        Ok(notifications)
+    }
+}
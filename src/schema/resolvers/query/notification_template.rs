@@ -0,0 +1,62 @@
+use async_graphql::*;
+use tracing::warn;
+
+use crate::{
+    context::ContextExtensions,
+    error::AppError,
+    models::notification_template::{ NotificationTemplate, RenderedNotification },
+    DbClient,
+    Repository,
+};
+
+/// Template stored under `key`, if any
+pub(crate) async fn template_by_key(
+    repo: &Repository,
+    key: &str
+) -> Result<Option<NotificationTemplate>, AppError> {
+    let templates = repo.query_index::<NotificationTemplate>("KeyIndex", "key", key, Some(1)).await?;
+
+    Ok(templates.into_iter().next())
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct NotificationTemplateQuery;
+
+#[Object]
+impl NotificationTemplateQuery {
+    /// Render the template stored under `template_key` with `vars` substituted
+    ///
+    /// # Arguments
+    ///
+    /// * `template_key` - Key of the template to render
+    /// * `vars` - JSON object of variable values by name
+    async fn render_notification(
+        &self,
+        ctx: &Context<'_>,
+        template_key: String,
+        vars: serde_json::Value
+    ) -> Result<RenderedNotification, Error> {
+        let serde_json::Value::Object(vars) = vars else {
+            return Err(AppError::invalid_field("vars", "Must be a JSON object").to_graphql_error());
+        };
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let template = template_by_key(&repo, &template_key).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(
+                    format!("Notification template '{}' not found", template_key)
+                ).to_graphql_error()
+            })?;
+
+        template.render(&vars).map_err(|e| e.to_graphql_error())
+    }
+}
@@ -3,14 +3,17 @@ use chrono::{ DateTime, Utc };
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
+    auth::guard::effective_permission_ids,
     error::AppError,
     models::{
         permission::{ Permission, PermissionScope },
         permission_log::{ PermissionAction, ResourceType },
         role::Role,
+        user::User,
+        user_role::UserRole,
     },
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -30,7 +33,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<Permission>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -50,7 +53,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permissions = repo
             .list::<Permission>(limit).await
@@ -100,7 +103,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _role = repo
             .get::<Role>(role_id.clone()).await
@@ -132,6 +135,46 @@ impl PermissionQuery {
         Ok(permissions)
     }
 
+    /// Get every permission a user holds, directly or inherited through parent roles
+    ///
+    /// Only effective role assignments and usable roles count. Each permission
+    /// appears once, the user's own role permissions before inherited ones.
+    async fn effective_permissions_for_user(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String
+    ) -> Result<Vec<Permission>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let _user = repo
+            .get::<User>(user_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("User {} not found", user_id)).to_graphql_error()
+            })?;
+
+        let role_ids: Vec<String> = repo
+            .list::<UserRole>(None).await
+            .map_err(|e| e.to_graphql_error())?
+            .into_iter()
+            .filter(|ur| ur.user_id == user_id && ur.is_effective())
+            .map(|ur| ur.role_id)
+            .collect();
+
+        let permission_ids = effective_permission_ids(role_ids, |ids| {
+            repo.batch_get::<Role>(ids)
+        }).await.map_err(|e| e.to_graphql_error())?;
+
+        repo.batch_get::<Permission>(permission_ids).await.map_err(|e| e.to_graphql_error())
+    }
+
     async fn permissions_by_scope(
         &self,
         ctx: &Context<'_>,
@@ -146,7 +189,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let scope_enum = PermissionScope::from_string(&scope).map_err(|e| e.to_graphql_error())?;
 
@@ -187,7 +230,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let resource_type_enum = ResourceType::from_string(&resource_type).map_err(|e|
             e.to_graphql_error()
@@ -230,7 +273,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let action_enum = PermissionAction::from_string(&action).map_err(|e| e.to_graphql_error())?;
 
@@ -269,7 +312,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permissions = repo
             .list::<Permission>(None).await
@@ -280,10 +323,12 @@ impl PermissionQuery {
             .filter(|p| p.is_expired())
             .collect();
 
+        let now = Utc::now();
         permissions.sort_by(|a, b| {
             b.expires_at
-                .unwrap_or_else(|| Utc::now())
-                .cmp(&a.expires_at.unwrap_or_else(|| Utc::now()))
+                .unwrap_or(now)
+                .cmp(&a.expires_at.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         if let Some(limit_val) = limit {
@@ -306,7 +351,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permissions = repo
             .list::<Permission>(None).await
@@ -327,10 +372,12 @@ impl PermissionQuery {
             })
             .collect();
 
+        let now = Utc::now();
         permissions.sort_by(|a, b| {
             a.expires_at
-                .unwrap_or_else(|| Utc::now())
-                .cmp(&b.expires_at.unwrap_or_else(|| Utc::now()))
+                .unwrap_or(now)
+                .cmp(&b.expires_at.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         if let Some(limit_val) = limit {
@@ -354,7 +401,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permissions = repo
             .list::<Permission>(None).await
@@ -372,7 +419,7 @@ impl PermissionQuery {
                 .collect();
         }
 
-        permissions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        permissions.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             permissions.truncate(limit_val as usize);
@@ -394,7 +441,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut permissions = repo
             .list::<Permission>(None).await
@@ -430,7 +477,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let permissions = repo.list::<Permission>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -491,7 +538,7 @@ impl PermissionQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let resource_type_enum = ResourceType::from_string(&resource_type).map_err(|e|
             e.to_graphql_error()
@@ -1,12 +1,18 @@
+use std::collections::HashSet;
+
 use async_graphql::*;
 use chrono::{ DateTime, Utc };
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
-    models::{ role::{ Role, RoleType }, user_role::UserRole, permission::Permission },
+    models::{
+        role::{ sort_roles_by_priority, Role, RoleType },
+        user_role::UserRole,
+        permission::Permission,
+    },
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -22,7 +28,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<Role>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -42,7 +48,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut roles = repo.list::<Role>(limit).await.map_err(|e| e.to_graphql_error())?;
 
@@ -90,7 +96,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let type_enum = RoleType::from_string(&role_type).map_err(|e| e.to_graphql_error())?;
 
@@ -108,7 +114,7 @@ impl RoleQuery {
                 .collect();
         }
 
-        roles.sort_by(|a, b| b.priority.cmp(&a.priority));
+        sort_roles_by_priority(&mut roles);
 
         if let Some(limit_val) = limit {
             roles.truncate(limit_val as usize);
@@ -131,7 +137,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -169,7 +175,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -185,7 +191,7 @@ impl RoleQuery {
                 .collect();
         }
 
-        roles.sort_by(|a, b| b.priority.cmp(&a.priority));
+        sort_roles_by_priority(&mut roles);
 
         if let Some(limit_val) = limit {
             roles.truncate(limit_val as usize);
@@ -207,7 +213,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -223,7 +229,7 @@ impl RoleQuery {
                 .collect();
         }
 
-        roles.sort_by(|a, b| b.priority.cmp(&a.priority));
+        sort_roles_by_priority(&mut roles);
 
         if let Some(limit_val) = limit {
             roles.truncate(limit_val as usize);
@@ -246,7 +252,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -264,7 +270,7 @@ impl RoleQuery {
                 .collect();
         }
 
-        roles.sort_by(|a, b| b.priority.cmp(&a.priority));
+        sort_roles_by_priority(&mut roles);
 
         if let Some(limit_val) = limit {
             roles.truncate(limit_val as usize);
@@ -285,7 +291,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -294,10 +300,12 @@ impl RoleQuery {
             .filter(|role| role.is_expired())
             .collect();
 
+        let now = Utc::now();
         roles.sort_by(|a, b| {
             b.expires_at
-                .unwrap_or_else(|| Utc::now())
-                .cmp(&a.expires_at.unwrap_or_else(|| Utc::now()))
+                .unwrap_or(now)
+                .cmp(&a.expires_at.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         if let Some(limit_val) = limit {
@@ -320,7 +328,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -339,10 +347,12 @@ impl RoleQuery {
             })
             .collect();
 
+        let now = Utc::now();
         roles.sort_by(|a, b| {
             a.expires_at
-                .unwrap_or_else(|| Utc::now())
-                .cmp(&b.expires_at.unwrap_or_else(|| Utc::now()))
+                .unwrap_or(now)
+                .cmp(&b.expires_at.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         if let Some(limit_val) = limit {
@@ -366,7 +376,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _permission = repo
             .get::<Permission>(permission_id.clone()).await
@@ -391,7 +401,7 @@ impl RoleQuery {
                 .collect();
         }
 
-        roles.sort_by(|a, b| b.priority.cmp(&a.priority));
+        sort_roles_by_priority(&mut roles);
 
         if let Some(limit_val) = limit {
             roles.truncate(limit_val as usize);
@@ -414,7 +424,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _parent_role = repo
             .get::<Role>(parent_role_id.clone()).await
@@ -441,7 +451,7 @@ impl RoleQuery {
                 .collect();
         }
 
-        roles.sort_by(|a, b| b.priority.cmp(&a.priority));
+        sort_roles_by_priority(&mut roles);
 
         if let Some(limit_val) = limit {
             roles.truncate(limit_val as usize);
@@ -464,7 +474,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -482,7 +492,7 @@ impl RoleQuery {
                 .collect();
         }
 
-        roles.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        roles.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             roles.truncate(limit_val as usize);
@@ -504,7 +514,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut all_roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -518,8 +528,14 @@ impl RoleQuery {
         if let Some(root_id) = root_role_id {
             let mut result = Vec::new();
             let mut to_process = vec![root_id];
+            // Guards against cyclic parent links already in storage
+            let mut visited = HashSet::new();
 
             while let Some(current_id) = to_process.pop() {
+                if !visited.insert(current_id.clone()) {
+                    continue;
+                }
+
                 if let Some(role) = all_roles.iter().find(|r| r.id == current_id) {
                     result.push(role.clone());
 
@@ -533,7 +549,7 @@ impl RoleQuery {
                 }
             }
 
-            result.sort_by(|a, b| b.priority.cmp(&a.priority));
+            sort_roles_by_priority(&mut result);
             Ok(result)
         } else {
             let root_roles: Vec<Role> = all_roles
@@ -559,7 +575,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let _role = repo
             .get::<Role>(role_id.clone()).await
@@ -582,7 +598,7 @@ impl RoleQuery {
                 .collect();
         }
 
-        user_roles.sort_by(|a, b| b.assigned_at.cmp(&a.assigned_at));
+        user_roles.sort_by(|a, b| b.assigned_at.cmp(&a.assigned_at).then_with(|| a.id.cmp(&b.id)));
 
         if let Some(limit_val) = limit {
             user_roles.truncate(limit_val as usize);
@@ -603,7 +619,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let role = repo
             .get::<Role>(role_id.clone()).await
@@ -645,7 +661,7 @@ impl RoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let roles = repo.list::<Role>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -2,10 +2,10 @@ use async_graphql::*;
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::task::{Task, TaskType},
     DbClient,
-    Repository,
 };
 
 #[derive(Default, Debug)]
@@ -26,7 +26,7 @@ impl TaskQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<Task>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -47,7 +47,7 @@ impl TaskQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut tasks = repo
             .list::<Task>(limit).await
@@ -94,7 +94,7 @@ impl TaskQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut tasks = repo.list::<Task>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -132,7 +132,7 @@ impl TaskQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let type_enum = TaskType::from_string(&task_type).map_err(|e| e.to_graphql_error())?;
 
@@ -172,7 +172,7 @@ impl TaskQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut tasks = repo.list::<Task>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -3,10 +3,10 @@ use chrono::{ DateTime, Utc };
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::{ user::{ User, UserStatus, UserType }, role::Role, work_order::WorkOrder },
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -23,7 +23,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<User>(id).await.map_err(|e| e.to_graphql_error())
     }
@@ -41,7 +41,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -59,7 +59,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -81,7 +81,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -109,7 +109,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut users = repo.list::<User>(limit).await.map_err(|e| e.to_graphql_error())?;
 
@@ -166,7 +166,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let status_enum = UserStatus::from_string(&status).map_err(|e| e.to_graphql_error())?;
 
@@ -200,7 +200,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -240,7 +240,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify manager exists
         let _manager = repo
@@ -290,7 +290,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify role exists
         let _role = repo
@@ -337,7 +337,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let days_back = days.unwrap_or(30);
         let cutoff_date = Utc::now() - chrono::Duration::days(days_back as i64);
@@ -356,10 +356,12 @@ impl UserQuery {
             .collect();
 
         // Sort by last login (most recent first)
+        let now = Utc::now();
         users.sort_by(|a, b| {
             b.last_login_at
-                .unwrap_or_else(|| Utc::now())
-                .cmp(&a.last_login_at.unwrap_or_else(|| Utc::now()))
+                .unwrap_or(now)
+                .cmp(&a.last_login_at.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         // Apply limit if provided
@@ -383,7 +385,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -413,7 +415,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -423,10 +425,12 @@ impl UserQuery {
             .collect();
 
         // Sort by termination date (most recent first)
+        let now = Utc::now();
         users.sort_by(|a, b| {
             b.termination_date
-                .unwrap_or_else(|| Utc::now())
-                .cmp(&a.termination_date.unwrap_or_else(|| Utc::now()))
+                .unwrap_or(now)
+                .cmp(&a.termination_date.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         // Apply limit if provided
@@ -452,7 +456,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -507,7 +511,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify user exists
         let _user = repo
@@ -528,7 +532,7 @@ impl UserQuery {
             .collect();
 
         // Sort by creation date (most recent first)
-        work_orders.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        work_orders.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
 
         // Apply limit if provided
         if let Some(limit_val) = limit {
@@ -547,7 +551,7 @@ impl UserQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let users = repo.list::<User>(None).await.map_err(|e| e.to_graphql_error())?;
 
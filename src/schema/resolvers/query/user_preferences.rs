@@ -0,0 +1,43 @@
+use async_graphql::*;
+use tracing::warn;
+
+use crate::{
+    context::ContextExtensions,
+    error::AppError,
+    models::user_preferences::UserPreferences,
+    DbClient,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct UserPreferencesQuery;
+
+#[Object]
+impl UserPreferencesQuery {
+    /// Get a user's preferences, creating and storing the defaults on first access
+    async fn user_preferences(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String
+    ) -> Result<UserPreferences, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let stored = repo
+            .query_index::<UserPreferences>("UserIndex", "user_id", &user_id, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        if let Some(preferences) = UserPreferences::first_created(stored) {
+            return Ok(preferences);
+        }
+
+        let defaults = UserPreferences::create_default(user_id).map_err(|e| e.to_graphql_error())?;
+
+        repo.create(defaults).await.map_err(|e| e.to_graphql_error())
+    }
+}
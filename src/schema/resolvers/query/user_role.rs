@@ -3,6 +3,7 @@ use chrono::{DateTime, Utc};
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::{
         user_role::{UserRole, RoleAssignmentStatus, AssignmentSource},
@@ -10,7 +11,6 @@ use crate::{
         role::Role,
     },
     DbClient,
-    Repository,
 };
 
 #[derive(Debug, Default)]
@@ -31,7 +31,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<UserRole>(id)
             .await
@@ -55,7 +55,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_roles = repo
             .list::<UserRole>(limit)
@@ -116,7 +116,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify user exists
         let _user = repo
@@ -149,13 +149,10 @@ impl UserRoleQuery {
 
         // Sort by priority (primary role first, then by assigned date)
         user_roles.sort_by(|a, b| {
-            if a.is_primary_role && !b.is_primary_role {
-                std::cmp::Ordering::Less
-            } else if !a.is_primary_role && b.is_primary_role {
-                std::cmp::Ordering::Greater
-            } else {
-                b.assigned_at.cmp(&a.assigned_at)
-            }
+            b.is_primary_role
+                .cmp(&a.is_primary_role)
+                .then_with(|| b.assigned_at.cmp(&a.assigned_at))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         // Apply limit if provided
@@ -181,7 +178,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify role exists
         let _role = repo
@@ -213,7 +210,7 @@ impl UserRoleQuery {
         }
 
         // Sort by assignment date (most recent first)
-        user_roles.sort_by(|a, b| b.assigned_at.cmp(&a.assigned_at));
+        user_roles.sort_by(|a, b| b.assigned_at.cmp(&a.assigned_at).then_with(|| a.id.cmp(&b.id)));
 
         // Apply limit if provided
         if let Some(limit_val) = limit {
@@ -237,7 +234,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_roles = repo
             .list::<UserRole>(None)
@@ -280,7 +277,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let status_enum = RoleAssignmentStatus::from_string(&status)
             .map_err(|e| e.to_graphql_error())?;
@@ -317,7 +314,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let source_enum = AssignmentSource::from_string(&assignment_source)
             .map_err(|e| e.to_graphql_error())?;
@@ -353,7 +350,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_roles = repo
             .list::<UserRole>(None)
@@ -375,9 +372,11 @@ impl UserRoleQuery {
             .collect();
 
         // Sort by expiration date (most recently expired first)
+        let now = Utc::now();
         user_roles.sort_by(|a, b| {
-            b.expires_at.unwrap_or_else(|| Utc::now())
-                .cmp(&a.expires_at.unwrap_or_else(|| Utc::now()))
+            b.expires_at.unwrap_or(now)
+                .cmp(&a.expires_at.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         // Apply limit if provided
@@ -402,7 +401,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_roles = repo
             .list::<UserRole>(None)
@@ -426,9 +425,11 @@ impl UserRoleQuery {
             .collect();
 
         // Sort by expiration date (soonest first)
+        let now = Utc::now();
         user_roles.sort_by(|a, b| {
-            a.expires_at.unwrap_or_else(|| Utc::now())
-                .cmp(&b.expires_at.unwrap_or_else(|| Utc::now()))
+            a.expires_at.unwrap_or(now)
+                .cmp(&b.expires_at.unwrap_or(now))
+                .then_with(|| a.id.cmp(&b.id))
         });
 
         // Apply limit if provided
@@ -453,7 +454,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_roles = repo
             .list::<UserRole>(None)
@@ -478,7 +479,7 @@ impl UserRoleQuery {
         }
 
         // Sort by assignment date (most recent first)
-        user_roles.sort_by(|a, b| b.assigned_at.cmp(&a.assigned_at));
+        user_roles.sort_by(|a, b| b.assigned_at.cmp(&a.assigned_at).then_with(|| a.id.cmp(&b.id)));
 
         // Apply limit if provided
         if let Some(limit_val) = limit {
@@ -501,7 +502,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut user_roles = repo
             .list::<UserRole>(None)
@@ -534,7 +535,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let user_roles = repo
             .list::<UserRole>(None)
@@ -576,7 +577,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         // Verify user exists
         let _user = repo
@@ -600,7 +601,7 @@ impl UserRoleQuery {
             .collect();
 
         // Sort by assignment date (most recent first)
-        user_roles.sort_by(|a, b| b.assigned_at.cmp(&a.assigned_at));
+        user_roles.sort_by(|a, b| b.assigned_at.cmp(&a.assigned_at).then_with(|| a.id.cmp(&b.id)));
 
         // Apply limit if provided
         if let Some(limit_val) = limit {
@@ -624,7 +625,7 @@ impl UserRoleQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let user_roles = repo
             .list::<UserRole>(None)
@@ -637,6 +638,84 @@ impl UserRoleQuery {
 
         Ok(has_role)
     }
+
+    /// Get the roles currently in effect for a user, highest priority first
+    ///
+    /// Only effective assignments of usable roles are returned; the assignment
+    /// flagged as the user's primary role is marked with `is_primary_role`.
+    async fn active_roles_for_user(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+    ) -> Result<Vec<ActiveRole>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let user_roles = repo
+            .list::<UserRole>(None)
+            .await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let assignments = effective_assignments_for(&user_id, user_roles);
+
+        let roles = repo
+            .batch_get::<Role>(assignments.iter().map(|ur| ur.role_id.clone()).collect())
+            .await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(rank_active_roles(&assignments, roles))
+    }
+}
+
+/// A role currently in effect for a user
+#[derive(Debug)]
+pub struct ActiveRole {
+    pub role: Role,
+    pub is_primary_role: bool,
+}
+
+#[Object]
+impl ActiveRole {
+    async fn role(&self) -> &Role {
+        &self.role
+    }
+
+    async fn is_primary_role(&self) -> bool {
+        self.is_primary_role
+    }
+}
+
+/// The user's assignments that are currently effective
+fn effective_assignments_for(user_id: &str, user_roles: Vec<UserRole>) -> Vec<UserRole> {
+    user_roles
+        .into_iter()
+        .filter(|ur| ur.user_id == user_id && ur.is_effective())
+        .collect()
+}
+
+/// Pairs usable roles with their assignments, sorted by priority descending
+fn rank_active_roles(assignments: &[UserRole], roles: Vec<Role>) -> Vec<ActiveRole> {
+    let mut active_roles: Vec<ActiveRole> = roles
+        .into_iter()
+        .filter(|role| role.is_usable())
+        .map(|role| ActiveRole {
+            is_primary_role: assignments
+                .iter()
+                .any(|ur| ur.role_id == role.id && ur.is_primary_role),
+            role,
+        })
+        .collect();
+
+    active_roles.sort_by(|a, b| {
+        b.role.priority.cmp(&a.role.priority).then_with(|| a.role.id.cmp(&b.role.id))
+    });
+    active_roles
 }
 
 /// User role assignment statistics
@@ -697,4 +776,69 @@ impl UserRoleStatistics {
             (self.active_assignments as f64 / self.total_assignments as f64) * 100.0
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn assignment(role_id: &str, is_primary_role: bool, expires_at: Option<DateTime<Utc>>) -> UserRole {
+        UserRole::new(
+            format!("user_role-{}", role_id),
+            "user-1".to_string(),
+            role_id.to_string(),
+            "manual".to_string(),
+            is_primary_role,
+            None,
+            Utc::now() - Duration::days(30),
+            expires_at,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn role(id: &str, priority: i32) -> Role {
+        Role::new(
+            id.to_string(),
+            id.to_string(),
+            None,
+            "custom".to_string(),
+            false,
+            vec![],
+            None,
+            priority,
+            true,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_only_effective_assignments_resolve_to_active_roles() {
+        let user_roles = vec![
+            assignment("expired", true, Some(Utc::now() - Duration::days(1))),
+            assignment("technician", false, None),
+            assignment("supervisor", true, Some(Utc::now() + Duration::days(1))),
+        ];
+
+        let assignments = effective_assignments_for("user-1", user_roles);
+        let role_ids: Vec<&str> = assignments.iter().map(|ur| ur.role_id.as_str()).collect();
+        assert_eq!(role_ids, vec!["technician", "supervisor"]);
+
+        let active_roles = rank_active_roles(
+            &assignments,
+            vec![role("technician", 10), role("supervisor", 50)],
+        );
+
+        let ranked: Vec<(&str, bool)> = active_roles
+            .iter()
+            .map(|ar| (ar.role.id.as_str(), ar.is_primary_role))
+            .collect();
+        assert_eq!(ranked, vec![("supervisor", true), ("technician", false)]);
+    }
+}
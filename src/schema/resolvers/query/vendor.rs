@@ -0,0 +1,117 @@
+use async_graphql::*;
+use chrono::Utc;
+use tracing::warn;
+
+use crate::{
+    context::ContextExtensions,
+    error::AppError,
+    models::vendor::{ expiring_contracts, Vendor, VendorStatus, VendorTier },
+    DbClient,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct VendorQuery;
+
+#[Object]
+impl VendorQuery {
+    /// Get vendor by ID
+    async fn vendor_by_id(&self, ctx: &Context<'_>, id: String) -> Result<Option<Vendor>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        repo.get::<Vendor>(id).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Search vendors by status, tier, tag and a case-insensitive name/code fragment
+    async fn vendors(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<String>,
+        tier: Option<String>,
+        tag: Option<String>,
+        name_contains: Option<String>,
+        limit: Option<i32>
+    ) -> Result<Vec<Vendor>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let status_enum = status
+            .map(|s| VendorStatus::from_string(&s))
+            .transpose()
+            .map_err(|e| e.to_graphql_error())?;
+
+        let tier_enum = tier
+            .map(|t| VendorTier::from_string(&t))
+            .transpose()
+            .map_err(|e| e.to_graphql_error())?;
+
+        let name_term = name_contains.map(|n| n.to_lowercase());
+
+        let repo = ctx.repository(db_client);
+
+        // Filters are applied after the scan, so the limit is applied afterwards too
+        let vendors = repo.list::<Vendor>(None).await.map_err(|e| e.to_graphql_error())?;
+
+        let mut vendors: Vec<Vendor> = vendors
+            .into_iter()
+            .filter(|vendor| status_enum.is_none_or(|s| vendor.status == s))
+            .filter(|vendor| tier_enum.is_none_or(|t| vendor.tier == t))
+            .filter(|vendor| tag.as_ref().is_none_or(|t| vendor.tags.contains(t)))
+            .filter(|vendor| {
+                name_term.as_ref().is_none_or(|term| {
+                    vendor.name.to_lowercase().contains(term) ||
+                        vendor.legal_name
+                            .as_ref()
+                            .is_some_and(|n| n.to_lowercase().contains(term)) ||
+                        vendor.vendor_code
+                            .as_ref()
+                            .is_some_and(|c| c.to_lowercase().contains(term))
+                })
+            })
+            .collect();
+
+        if let Some(limit_val) = limit {
+            vendors.truncate(limit_val as usize);
+        }
+
+        Ok(vendors)
+    }
+
+    /// Get vendors whose contract ends within `within_days`, soonest first
+    ///
+    /// Expired contracts and vendors without a contract end date are excluded.
+    async fn vendors_with_expiring_contracts(
+        &self,
+        ctx: &Context<'_>,
+        within_days: i32
+    ) -> Result<Vec<Vendor>, Error> {
+        if within_days < 0 {
+            return Err(
+                AppError::invalid_field("within_days", "Window cannot be negative").to_graphql_error()
+            );
+        }
+
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let vendors = repo.list::<Vendor>(None).await.map_err(|e| e.to_graphql_error())?;
+
+        Ok(expiring_contracts(vendors, within_days as i64, Utc::now()))
+    }
+}
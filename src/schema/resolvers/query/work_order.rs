@@ -1,9 +1,14 @@
 use async_graphql::*;
+use chrono::Utc;
+use futures_util::future::try_join_all;
 use tracing::warn;
 
 use crate::{
+    context::ContextExtensions,
     error::AppError,
     models::work_order::{
+        work_orders_breaching_sla,
+        MAX_SLA_HOURS,
         WorkOrder,
         WorkOrderStatus,
         WorkOrderPriority,
@@ -12,7 +17,6 @@ use crate::{
         WorkOrderDifficulty,
     },
     DbClient,
-    Repository,
 };
 
 #[derive(Default, Debug)]
@@ -33,21 +37,28 @@ impl WorkOrderQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         repo.get::<WorkOrder>(id).await.map_err(|e| e.to_graphql_error())
     }
 
-    /// Get all work orders with filtering
+    /// Get work orders matching every provided filter
+    ///
+    /// `overdue_only` keeps work that has run past its estimate, and `sort_by`
+    /// defaults to newest first. `limit` applies after filtering and sorting.
     async fn work_orders(
         &self,
         ctx: &Context<'_>,
-        limit: Option<i32>,
-        status_filter: Option<String>,
-        priority_filter: Option<String>,
-        type_filter: Option<String>,
-        severity_filter: Option<String>,
-        difficulty_filter: Option<String>
+        status: Option<String>,
+        priority: Option<String>,
+        severity: Option<String>,
+        work_order_type: Option<String>,
+        difficulty: Option<String>,
+        asset_id: Option<String>,
+        assigned_technician_id: Option<String>,
+        overdue_only: Option<bool>,
+        sort_by: Option<WorkOrderSortBy>,
+        limit: Option<i32>
     ) -> Result<Vec<WorkOrder>, Error> {
         let db_client = ctx.data::<DbClient>().map_err(|e| {
             warn!("Failed to get db_client from context: {:?}", e);
@@ -56,62 +67,81 @@ impl WorkOrderQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
-
-        let mut work_orders = repo
-            .list::<WorkOrder>(limit).await
-            .map_err(|e| e.to_graphql_error())?;
-
-        // Apply filters
-        if let Some(status) = status_filter {
-            let status_enum = WorkOrderStatus::from_string(&status).map_err(|e|
-                e.to_graphql_error()
-            )?;
-            work_orders = work_orders
-                .into_iter()
-                .filter(|wo| wo.status.to_string() == status_enum.to_string())
-                .collect();
-        }
-
-        if let Some(priority) = priority_filter {
-            let priority_enum = WorkOrderPriority::from_string(&priority).map_err(|e|
-                e.to_graphql_error()
-            )?;
-            work_orders = work_orders
-                .into_iter()
-                .filter(|wo| wo.priority.to_string() == priority_enum.to_string())
-                .collect();
-        }
+        let filter = WorkOrderFilter {
+            status: status
+                .map(|s| WorkOrderStatus::from_string(&s))
+                .transpose()
+                .map_err(|e| e.to_graphql_error())?,
+            priority: priority
+                .map(|p| WorkOrderPriority::from_string(&p))
+                .transpose()
+                .map_err(|e| e.to_graphql_error())?,
+            severity: severity
+                .map(|s| WorkOrderSeverity::from_string(&s))
+                .transpose()
+                .map_err(|e| e.to_graphql_error())?,
+            work_order_type: work_order_type
+                .map(|t| WorkOrderType::from_string(&t))
+                .transpose()
+                .map_err(|e| e.to_graphql_error())?,
+            difficulty: difficulty
+                .map(|d| WorkOrderDifficulty::from_string(&d))
+                .transpose()
+                .map_err(|e| e.to_graphql_error())?,
+            asset_id,
+            assigned_technician_id,
+            overdue_only: overdue_only.unwrap_or(false),
+        };
+
+        let repo = ctx.repository(db_client);
+
+        // An asset has few work orders and a status partition is bounded by the open
+        // workload, so either GSI narrows the read enough to query it directly; the asset
+        // partition is the smaller of the two when both are given. Priority, severity and
+        // technician filters select too broadly to be worth an index read of their own,
+        // so without status or asset_id the table is scanned. Every filter is re-applied
+        // in memory either way.
+        let candidates = if let Some(asset_id) = &filter.asset_id {
+            repo.query_index::<WorkOrder>("AssetIndex", "asset_id", asset_id, None).await
+        } else if let Some(status) = filter.status {
+            repo.query_index::<WorkOrder>("StatusIndex", "status", status.to_str(), None).await
+        } else {
+            repo.list::<WorkOrder>(None).await
+        }.map_err(|e| e.to_graphql_error())?;
+
+        Ok(
+            filter_and_sort_work_orders(
+                candidates,
+                &filter,
+                sort_by.unwrap_or(WorkOrderSortBy::CreatedAt),
+                limit
+            )
+        )
+    }
 
-        if let Some(wo_type) = type_filter {
-            let type_enum = WorkOrderType::from_string(&wo_type).map_err(|e| e.to_graphql_error())?;
-            work_orders = work_orders
-                .into_iter()
-                .filter(|wo| wo.work_order_type.to_string() == type_enum.to_string())
-                .collect();
-        }
+    /// Get work orders by status
+    async fn work_orders_by_status(
+        &self,
+        ctx: &Context<'_>,
+        status: String,
+        limit: Option<i32>
+    ) -> Result<Vec<WorkOrder>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
 
-        if let Some(severity) = severity_filter {
-            let severity_enum = WorkOrderSeverity::from_string(&severity).map_err(|e|
-                e.to_graphql_error()
-            )?;
-            work_orders = work_orders
-                .into_iter()
-                .filter(|wo| wo.severity.to_string() == severity_enum.to_string())
-                .collect();
-        }
+        let repo = ctx.repository(db_client);
 
-        if let Some(difficulty) = difficulty_filter {
-            let difficulty_enum = WorkOrderDifficulty::from_string(&difficulty).map_err(|e|
-                e.to_graphql_error()
-            )?;
-            work_orders = work_orders
-                .into_iter()
-                .filter(|wo| wo.difficulty.to_string() == difficulty_enum.to_string())
-                .collect();
-        }
+        let status_enum = WorkOrderStatus::from_string(&status).map_err(|e|
+            e.to_graphql_error()
+        )?;
 
-        Ok(work_orders)
+        repo
+            .query_index::<WorkOrder>("StatusIndex", "status", status_enum.to_str(), limit).await
+            .map_err(|e| e.to_graphql_error())
     }
 
     /// Get work orders by severity level
@@ -129,7 +159,7 @@ impl WorkOrderQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let severity_enum = WorkOrderSeverity::from_string(&severity).map_err(|e|
             e.to_graphql_error()
@@ -176,7 +206,7 @@ impl WorkOrderQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let difficulty_enum = WorkOrderDifficulty::from_string(&difficulty).map_err(|e|
             e.to_graphql_error()
@@ -226,7 +256,7 @@ impl WorkOrderQuery {
             ).to_graphql_error()
         })?;
 
-        let repo = Repository::new(db_client.clone());
+        let repo = ctx.repository(db_client);
 
         let mut work_orders = repo.list::<WorkOrder>(None).await.map_err(|e| e.to_graphql_error())?;
 
@@ -254,5 +284,278 @@ impl WorkOrderQuery {
         Ok(work_orders)
     }
 
+    /// Get work orders assigned to a technician via the AssignedToIndex GSI
+    ///
+    /// `open_only` excludes completed and cancelled work orders.
+    async fn work_orders_for_technician(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+        open_only: Option<bool>,
+        limit: Option<i32>
+    ) -> Result<Vec<WorkOrder>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        // The open filter runs after the query, so the limit is applied afterwards too
+        let mut work_orders = repo
+            .query_index::<WorkOrder>(
+                "AssignedToIndex",
+                "assigned_technician_id",
+                &user_id,
+                None
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        if open_only.unwrap_or(false) {
+            work_orders.retain(|wo| wo.is_open());
+        }
+
+        if let Some(limit_val) = limit {
+            work_orders.truncate(limit_val as usize);
+        }
+
+        Ok(work_orders)
+    }
+
+    /// Open work orders past their SLA deadline or due within `within_hours`, soonest first
+    ///
+    /// `within_hours` defaults to 0, which lists only orders already in breach, and may be
+    /// at most a year. Only open statuses are read, one `StatusIndex` partition each.
+    async fn work_orders_breaching_sla(
+        &self,
+        ctx: &Context<'_>,
+        within_hours: Option<i64>
+    ) -> Result<Vec<WorkOrder>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        let within_hours = within_hours.unwrap_or(0);
+        if !(0..=MAX_SLA_HOURS).contains(&within_hours) {
+            return Err(
+                AppError::invalid_field(
+                    "within_hours",
+                    format!("within_hours must be between 0 and {}", MAX_SLA_HOURS)
+                ).to_graphql_error()
+            );
+        }
+
+        let repo = ctx.repository(db_client);
+
+        let partitions = try_join_all(
+            WorkOrderStatus::OPEN.iter().map(|status| {
+                repo.query_index::<WorkOrder>("StatusIndex", "status", status.to_str(), None)
+            })
+        ).await.map_err(|e| e.to_graphql_error())?;
+        let work_orders = partitions.into_iter().flatten().collect();
+
+        Ok(work_orders_breaching_sla(work_orders, &config.sla, within_hours, Utc::now()))
+    }
+
+    /// Number of open work orders assigned to a technician
+    async fn technician_workload(&self, ctx: &Context<'_>, user_id: String) -> Result<i32, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        let work_orders = repo
+            .query_index::<WorkOrder>(
+                "AssignedToIndex",
+                "assigned_technician_id",
+                &user_id,
+                None
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(open_work_order_count(&work_orders))
+    }
+
     // ... rest of the existing queries, but remove references to removed fields
 }
+
+/// Orderings accepted by `work_orders`
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WorkOrderSortBy {
+    /// Most urgent priority first
+    Priority,
+    /// Newest first
+    CreatedAt,
+    /// Most severe first
+    Severity,
+}
+
+/// Filters accepted by `work_orders`; `None` matches everything
+#[derive(Debug, Default)]
+struct WorkOrderFilter {
+    status: Option<WorkOrderStatus>,
+    priority: Option<WorkOrderPriority>,
+    severity: Option<WorkOrderSeverity>,
+    work_order_type: Option<WorkOrderType>,
+    difficulty: Option<WorkOrderDifficulty>,
+    asset_id: Option<String>,
+    assigned_technician_id: Option<String>,
+    overdue_only: bool,
+}
+
+impl WorkOrderFilter {
+    fn matches(&self, work_order: &WorkOrder) -> bool {
+        self.status.is_none_or(|status| work_order.status == status) &&
+            self.priority.is_none_or(|priority| work_order.priority == priority) &&
+            self.severity.is_none_or(|severity| work_order.severity == severity) &&
+            self.work_order_type.is_none_or(|t| work_order.work_order_type == t) &&
+            self.difficulty.is_none_or(|difficulty| work_order.difficulty == difficulty) &&
+            self.asset_id.as_ref().is_none_or(|id| &work_order.asset_id == id) &&
+            self.assigned_technician_id
+                .as_ref()
+                .is_none_or(|id| work_order.assigned_technician_id.as_ref() == Some(id)) &&
+            (!self.overdue_only || work_order.is_overdue())
+    }
+}
+
+/// Keeps the work orders matching `filter`, ordered by `sort_by` with ties broken by id
+fn filter_and_sort_work_orders(
+    work_orders: Vec<WorkOrder>,
+    filter: &WorkOrderFilter,
+    sort_by: WorkOrderSortBy,
+    limit: Option<i32>
+) -> Vec<WorkOrder> {
+    let mut work_orders: Vec<WorkOrder> = work_orders
+        .into_iter()
+        .filter(|wo| filter.matches(wo))
+        .collect();
+
+    work_orders.sort_by(|a, b| {
+        (
+            match sort_by {
+                WorkOrderSortBy::Priority =>
+                    a.priority.numeric_level().cmp(&b.priority.numeric_level()),
+                WorkOrderSortBy::CreatedAt => b.created_at.cmp(&a.created_at),
+                WorkOrderSortBy::Severity =>
+                    a.severity.numeric_level().cmp(&b.severity.numeric_level()),
+            }
+        ).then_with(|| a.id.cmp(&b.id))
+    });
+
+    if let Some(limit_val) = limit {
+        work_orders.truncate(limit_val.max(0) as usize);
+    }
+
+    work_orders
+}
+
+/// Counts work orders that are neither completed nor cancelled
+fn open_work_order_count(work_orders: &[WorkOrder]) -> i32 {
+    work_orders
+        .iter()
+        .filter(|wo| wo.is_open())
+        .count() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    use crate::models::work_order::WorkOrderCost;
+
+    fn work_order(id: &str, status: WorkOrderStatus) -> WorkOrder {
+        let mut work_order = WorkOrder::new(
+            id.to_string(),
+            id.to_uppercase(),
+            "Inspect pump".to_string(),
+            "Quarterly inspection".to_string(),
+            None,
+            "asset-1".to_string(),
+            "preventive".to_string(),
+            "normal".to_string(),
+            WorkOrderSeverity::Nice,
+            WorkOrderDifficulty::Normal,
+            Some("tech-1".to_string()),
+            60,
+            WorkOrderCost::One,
+            "user-1".to_string()
+        ).unwrap();
+        work_order.status = status;
+        work_order
+    }
+
+    #[test]
+    fn test_status_and_overdue_filters_combine() {
+        let mut overdue_in_progress = work_order("wo-1", WorkOrderStatus::InProgress);
+        overdue_in_progress.created_at = Utc::now() - chrono::Duration::hours(3);
+        let mut overdue_scheduled = work_order("wo-2", WorkOrderStatus::Scheduled);
+        overdue_scheduled.created_at = Utc::now() - chrono::Duration::hours(3);
+        let on_time_in_progress = work_order("wo-3", WorkOrderStatus::InProgress);
+
+        let filter = WorkOrderFilter {
+            status: Some(WorkOrderStatus::InProgress),
+            overdue_only: true,
+            ..WorkOrderFilter::default()
+        };
+
+        let matches = filter_and_sort_work_orders(
+            vec![on_time_in_progress, overdue_scheduled, overdue_in_progress],
+            &filter,
+            WorkOrderSortBy::CreatedAt,
+            None
+        );
+
+        let ids: Vec<&str> = matches
+            .iter()
+            .map(|wo| wo.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["wo-1"]);
+    }
+
+    #[test]
+    fn test_priority_sort_puts_most_urgent_first() {
+        let mut low = work_order("wo-1", WorkOrderStatus::Scheduled);
+        low.priority = WorkOrderPriority::Low;
+        let mut emergency = work_order("wo-2", WorkOrderStatus::Scheduled);
+        emergency.priority = WorkOrderPriority::Emergency;
+        let mut high = work_order("wo-3", WorkOrderStatus::Scheduled);
+        high.priority = WorkOrderPriority::High;
+
+        let sorted = filter_and_sort_work_orders(
+            vec![low, emergency, high],
+            &WorkOrderFilter::default(),
+            WorkOrderSortBy::Priority,
+            Some(2)
+        );
+
+        let ids: Vec<&str> = sorted
+            .iter()
+            .map(|wo| wo.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["wo-2", "wo-3"]);
+    }
+
+    #[test]
+    fn test_workload_counts_only_open_work_orders() {
+        let work_orders = vec![
+            work_order("wo-1", WorkOrderStatus::Scheduled),
+            work_order("wo-2", WorkOrderStatus::InProgress),
+            work_order("wo-3", WorkOrderStatus::Completed),
+        ];
+
+        assert_eq!(open_work_order_count(&work_orders), 2);
+    }
+}
+
@@ -0,0 +1,8 @@
+use async_graphql::MergedSubscription;
+
+mod work_order;
+
+pub use work_order::{ WorkOrderStatusBroker, WorkOrderStatusChanged };
+
+#[derive(Debug, Default, MergedSubscription)]
+pub struct SubscriptionRoot(work_order::WorkOrderSubscription);
@@ -0,0 +1,135 @@
+use async_graphql::*;
+use chrono::{ DateTime, Utc };
+use futures_util::{ stream, Stream, StreamExt };
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::{
+    auth::{ guard::VIEW_WORK_ORDERS, require_permission },
+    error::AppError,
+    models::work_order::{ WorkOrder, WorkOrderStatus },
+    schema::timestamp::Timestamp,
+};
+
+/// Events buffered per subscriber before the slowest ones start skipping
+const BROKER_CAPACITY: usize = 256;
+
+/// A work order moved from `previous_status` to `status`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkOrderStatusChanged {
+    pub work_order_id: String,
+    pub asset_id: String,
+    pub previous_status: WorkOrderStatus,
+    pub status: WorkOrderStatus,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl WorkOrderStatusChanged {
+    pub fn new(work_order: &WorkOrder, previous_status: WorkOrderStatus) -> Self {
+        Self {
+            work_order_id: work_order.id.clone(),
+            asset_id: work_order.asset_id.clone(),
+            previous_status,
+            status: work_order.status,
+            changed_at: work_order.updated_at,
+        }
+    }
+}
+
+#[Object]
+impl WorkOrderStatusChanged {
+    async fn work_order_id(&self) -> &str {
+        &self.work_order_id
+    }
+
+    async fn asset_id(&self) -> &str {
+        &self.asset_id
+    }
+
+    async fn previous_status(&self) -> WorkOrderStatus {
+        self.previous_status
+    }
+
+    async fn status(&self) -> WorkOrderStatus {
+        self.status
+    }
+
+    async fn changed_at(&self) -> Timestamp {
+        self.changed_at.into()
+    }
+}
+
+/// Fans work order status changes out to every open subscription
+///
+/// Registered as schema data by `create_schema`. Events are only delivered to
+/// subscriptions open at the time they are published; nothing is persisted.
+#[derive(Debug, Clone)]
+pub struct WorkOrderStatusBroker {
+    sender: broadcast::Sender<WorkOrderStatusChanged>,
+}
+
+impl Default for WorkOrderStatusBroker {
+    fn default() -> Self {
+        Self { sender: broadcast::channel(BROKER_CAPACITY).0 }
+    }
+}
+
+impl WorkOrderStatusBroker {
+    pub fn publish(&self, event: WorkOrderStatusChanged) {
+        // An error only means nobody is subscribed right now
+        let _ = self.sender.send(event);
+    }
+
+    /// Stream of events published from now on
+    ///
+    /// A subscriber that falls more than `BROKER_CAPACITY` events behind skips the
+    /// ones it missed rather than ending the stream.
+    pub fn subscribe(&self) -> impl Stream<Item = WorkOrderStatusChanged> + use<> {
+        stream::unfold(self.sender.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        return Some((event, receiver));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Work order status subscriber skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return None;
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct WorkOrderSubscription;
+
+#[Subscription]
+impl WorkOrderSubscription {
+    /// Work order status changes, limited to one asset when `asset_id` is given
+    ///
+    /// Requires `work_order:view`.
+    async fn work_order_status_changed(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: Option<String>
+    ) -> Result<impl Stream<Item = WorkOrderStatusChanged>, Error> {
+        require_permission(ctx, VIEW_WORK_ORDERS).await.map_err(|e| e.to_graphql_error())?;
+
+        let broker = ctx.data::<WorkOrderStatusBroker>().map_err(|e| {
+            warn!("Failed to get work order status broker from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access work order status broker".to_string()
+            ).to_graphql_error()
+        })?;
+
+        Ok(
+            broker.subscribe().filter(move |event| {
+                let matches = asset_id.as_ref().is_none_or(|id| *id == event.asset_id);
+                std::future::ready(matches)
+            })
+        )
+    }
+}
@@ -1,9 +1,15 @@
-use async_graphql::Upload;
-
-use crate::models::{
-    asset::{ AssetCurrentStatusOptions, DocumentUpload, MaintenanceFrequencyOptions },
-    prelude::*,
+use crate::{
+    context::ContextExtensions,
+    models::{
+        asset::{ AssetCurrentStatusOptions, MaintenanceFrequencyOptions },
+        prelude::*,
+    },
+    models::{ asset_type::AssetType, location::Location, manufacturer::Manufacturer },
+    schema::{ loaders::{ AssetTypeLoader, LocationLoader, ManufacturerLoader }, timestamp::Timestamp },
+    AppError,
+    DbClient,
 };
+
 #[Object]
 impl Asset {
     async fn id(&self) -> &str {
@@ -18,6 +24,18 @@ impl Asset {
         &self.asset_type_id
     }
 
+    /// Asset type resolved through the request's `AssetTypeLoader`
+    async fn asset_type(&self, ctx: &Context<'_>) -> Result<Option<AssetType>, Error> {
+        let loader = ctx.data::<AssetTypeLoader>().map_err(|e| {
+            warn!("Failed to get asset type loader from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access asset type loader".to_string()
+            ).to_graphql_error()
+        })?;
+
+        loader.load_one(self.asset_type_id.clone()).await.map_err(|e| e.to_graphql_error())
+    }
+
     async fn serial_number(&self) -> &str {
         &self.serial_number
     }
@@ -26,12 +44,12 @@ impl Asset {
         &self.model_number
     }
 
-    async fn purchase_date(&self) -> &DateTime<Utc> {
-        &self.purchase_date
+    async fn purchase_date(&self) -> Timestamp {
+        self.purchase_date.into()
     }
 
-    async fn installation_date(&self) -> &DateTime<Utc> {
-        &self.installation_date
+    async fn installation_date(&self) -> Timestamp {
+        self.installation_date.into()
     }
 
     async fn current_status(&self) -> AssetCurrentStatusOptions {
@@ -42,10 +60,34 @@ impl Asset {
         &self.location_id
     }
 
+    /// Location resolved through the request's `LocationLoader`
+    async fn location(&self, ctx: &Context<'_>) -> Result<Option<Location>, Error> {
+        let loader = ctx.data::<LocationLoader>().map_err(|e| {
+            warn!("Failed to get location loader from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access location loader".to_string()
+            ).to_graphql_error()
+        })?;
+
+        loader.load_one(self.location_id.clone()).await.map_err(|e| e.to_graphql_error())
+    }
+
     async fn manufacturer_id(&self) -> &str {
         &self.manufacturer_id
     }
 
+    /// Manufacturer resolved through the request's `ManufacturerLoader`
+    async fn manufacturer(&self, ctx: &Context<'_>) -> Result<Option<Manufacturer>, Error> {
+        let loader = ctx.data::<ManufacturerLoader>().map_err(|e| {
+            warn!("Failed to get manufacturer loader from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access manufacturer loader".to_string()
+            ).to_graphql_error()
+        })?;
+
+        loader.load_one(self.manufacturer_id.clone()).await.map_err(|e| e.to_graphql_error())
+    }
+
     async fn maintenance_frequency(&self) -> MaintenanceFrequencyOptions {
         self.maintenance_frequency
     }
@@ -62,28 +104,176 @@ impl Asset {
         &self.work_order_ids
     }
 
-    async fn warranty_start_date(&self) -> Option<&DateTime<Utc>> {
-        self.warranty_start_date.as_ref()
+    /// Work orders referenced by `work_order_ids`, loaded in a single batch
+    async fn work_orders(&self, ctx: &Context<'_>) -> Result<Vec<WorkOrder>, Error> {
+        let db_client = ctx.data::<DbClient>().map_err(|e| {
+            warn!("Failed to get db_client from context: {:?}", e);
+            AppError::InternalServerError(
+                "Failed to access application db_client".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let repo = ctx.repository(db_client);
+
+        repo
+            .batch_get::<WorkOrder>(self.work_order_ids.clone()).await
+            .map_err(|e| e.to_graphql_error())
+    }
+
+    async fn warranty_start_date(&self) -> Option<Timestamp> {
+        self.warranty_start_date.map(Timestamp)
     }
 
-    async fn warranty_end_date(&self) -> Option<&DateTime<Utc>> {
-        self.warranty_end_date.as_ref()
+    async fn warranty_end_date(&self) -> Option<Timestamp> {
+        self.warranty_end_date.map(Timestamp)
     }
 
     async fn total_downtime_hours(&self) -> String {
         self.total_downtime_hours.to_string()
     }
 
-    async fn last_downtime_date(&self) -> &DateTime<Utc> {
-        &self.last_downtime_date
+    async fn last_downtime_date(&self) -> Timestamp {
+        self.last_downtime_date.into()
+    }
+
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
+    }
+
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
+    }
+
+    /// When maintenance is next due, based on the last downtime or installation date
+    #[graphql(name = "nextMaintenanceDue")]
+    async fn resolve_next_maintenance_due(&self) -> Timestamp {
+        self.next_maintenance_due().into()
+    }
+
+    #[graphql(name = "isMaintenanceOverdue")]
+    async fn resolve_is_maintenance_overdue(&self) -> bool {
+        self.is_maintenance_overdue()
+    }
+
+    /// Days until maintenance is due, negative when overdue
+    #[graphql(name = "daysUntilMaintenance")]
+    async fn resolve_days_until_maintenance(&self) -> i64 {
+        self.days_until_maintenance()
+    }
+
+    /// Whether the warranty is currently active
+    #[graphql(name = "isUnderWarranty")]
+    async fn resolve_is_under_warranty(&self) -> bool {
+        self.is_under_warranty(Utc::now())
+    }
+
+    /// Days until the warranty ends, negative once expired and null without a warranty
+    #[graphql(name = "warrantyDaysRemaining")]
+    async fn resolve_warranty_days_remaining(&self) -> Option<i64> {
+        self.warranty_days_remaining(Utc::now())
+    }
+}
+
+
+/// A single page of assets returned by cursor-based listing
+#[derive(Debug)]
+pub struct AssetPage {
+    pub items: Vec<Asset>,
+    pub next_cursor: Option<String>,
+}
+
+#[Object]
+impl AssetPage {
+    async fn items(&self) -> &Vec<Asset> {
+        &self.items
+    }
+
+    /// Opaque cursor to pass as `cursor` to fetch the next page
+    async fn next_cursor(&self) -> Option<&str> {
+        self.next_cursor.as_deref()
+    }
+
+    async fn has_next_page(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+}
+
+/// A rejected row of an asset import
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetImportError {
+    pub index: i32,
+    pub message: String,
+}
+
+#[Object]
+impl AssetImportError {
+    /// Zero-based position of the row in the payload
+    async fn index(&self) -> i32 {
+        self.index
+    }
+
+    async fn message(&self) -> &str {
+        &self.message
     }
+}
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+/// Outcome of a bulk asset import; valid rows are created even when others fail
+#[derive(Debug, Clone, Default)]
+pub struct ImportResult {
+    pub created: i32,
+    pub errors: Vec<AssetImportError>,
+}
+
+#[Object]
+impl ImportResult {
+    async fn created(&self) -> i32 {
+        self.created
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn errors(&self) -> &Vec<AssetImportError> {
+        &self.errors
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{ EmptyMutation, EmptySubscription, Schema };
+
+    struct TestQuery;
+
+    #[Object]
+    impl TestQuery {
+        async fn asset(&self) -> Asset {
+            let installed = Utc::now() - chrono::Duration::days(60);
+            Asset::new(
+                "asset-1".to_string(),
+                "Pump".to_string(),
+                "type-1".to_string(),
+                "SN1".to_string(),
+                "M1".to_string(),
+                installed,
+                installed,
+                "loc-1".to_string(),
+                "mfg-1".to_string(),
+                "monthly".to_string(),
+                None,
+                None
+            ).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_monthly_asset_installed_60_days_ago_is_overdue() {
+        let schema = Schema::new(TestQuery, EmptyMutation, EmptySubscription);
+
+        let response = schema.execute(
+            "{ asset { isMaintenanceOverdue daysUntilMaintenance nextMaintenanceDue } }"
+        ).await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["asset"]["isMaintenanceOverdue"], true);
+        assert!(data["asset"]["daysUntilMaintenance"].as_i64().unwrap() < 0);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::models::{ asset_type::{ AssetType, AssetTypeCategory }, prelude::* };
+use crate::{
+    models::{ asset_type::{ AssetType, AssetTypeCategory }, prelude::* },
+    schema::timestamp::Timestamp,
+};
+
 #[Object]
 impl AssetType {
     async fn id(&self) -> &str {
@@ -16,11 +20,11 @@ impl AssetType {
         self.category
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 }
@@ -0,0 +1,31 @@
+//! GraphQL output types for S3-backed attachments.
+
+use async_graphql::*;
+use chrono::{ DateTime, Utc };
+use crate::schema::timestamp::Timestamp;
+
+/// Presigned S3 PUT target for a client-side upload
+#[derive(Debug, Clone)]
+pub struct PresignedUpload {
+    pub url: String,
+    pub key: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[Object]
+impl PresignedUpload {
+    /// URL the client should PUT the file body to.
+    async fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Object key to pass to `confirmAssetDocument` once the upload finishes.
+    async fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Time after which the URL is no longer accepted by S3.
+    async fn expires_at(&self) -> Timestamp {
+        self.expires_at.into()
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    models::{ audit_event::AuditEvent, prelude::* },
+    schema::timestamp::Timestamp,
+};
+
+#[Object]
+impl AuditEvent {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn actor_user_id(&self) -> &str {
+        &self.actor_user_id
+    }
+
+    async fn action(&self) -> &str {
+        &self.action
+    }
+
+    async fn target_type(&self) -> &str {
+        &self.target_type
+    }
+
+    async fn target_id(&self) -> &str {
+        &self.target_id
+    }
+
+    async fn metadata(&self) -> String {
+        serde_json::to_string(&self.metadata).unwrap_or_default()
+    }
+
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
+    }
+}
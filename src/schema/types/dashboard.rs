@@ -127,6 +127,38 @@ impl DashboardStatistics {
 }
 
 #[derive(Debug)]
+pub struct DashboardSummary {
+    pub assets_by_status: AssetsByStatus,
+    pub open_work_orders_by_priority: WorkOrdersByPriority,
+    pub overdue_maintenance: i32,
+    pub active_vendors: i32,
+    pub inactive_vendors: i32,
+}
+
+#[Object]
+impl DashboardSummary {
+    async fn assets_by_status(&self) -> &AssetsByStatus {
+        &self.assets_by_status
+    }
+
+    async fn open_work_orders_by_priority(&self) -> &WorkOrdersByPriority {
+        &self.open_work_orders_by_priority
+    }
+
+    async fn overdue_maintenance(&self) -> i32 {
+        self.overdue_maintenance
+    }
+
+    async fn active_vendors(&self) -> i32 {
+        self.active_vendors
+    }
+
+    async fn inactive_vendors(&self) -> i32 {
+        self.inactive_vendors
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct AssetsByStatus {
     pub operational: i32,
     pub down: i32,
@@ -158,7 +190,7 @@ impl AssetsByStatus {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct WorkOrdersByPriority {
     pub low: i32,
     pub normal: i32,
@@ -0,0 +1,19 @@
+//! GraphQL schema implementation for field-level errors.
+
+use async_graphql::*;
+
+use crate::error::FieldError;
+
+/// GraphQL Object implementation for FieldError.
+#[Object]
+impl FieldError {
+    /// Input field, or entity id, the error applies to.
+    async fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Human-readable description of the failure.
+    async fn message(&self) -> &str {
+        &self.message
+    }
+}
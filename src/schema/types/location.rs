@@ -1,4 +1,7 @@
-use crate::models::prelude::*;
+use crate::{
+    models::prelude::*,
+    schema::timestamp::Timestamp,
+};
 
 #[Object]
 impl Location {
@@ -26,11 +29,11 @@ impl Location {
         &self.description
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 }
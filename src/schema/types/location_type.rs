@@ -1,4 +1,8 @@
-use crate::models::{ prelude::*, location_type::LocationType };
+use crate::{
+    models::{ prelude::*, location_type::LocationType },
+    schema::timestamp::Timestamp,
+};
+
 #[Object]
 impl LocationType {
     async fn id(&self) -> &str {
@@ -13,11 +17,11 @@ impl LocationType {
         &self.description
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 }
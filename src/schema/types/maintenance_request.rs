@@ -1,7 +1,10 @@
-use crate::models::{
-    maintenance_request::{ MaintenanceRequest, MaintenanceRequestStatus },
-    prelude::*,
-    work_order::WorkOrderSeverity,
+use crate::{
+    models::{
+        maintenance_request::{ MaintenanceRequest, MaintenanceRequestStatus },
+        prelude::*,
+        work_order::WorkOrderSeverity,
+    },
+    schema::timestamp::Timestamp,
 };
 
 #[Object]
@@ -56,12 +59,12 @@ impl MaintenanceRequest {
     }
 
     /// Creation timestamp.
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
     /// Last modification timestamp.
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 }
@@ -1,7 +1,11 @@
-use crate::models::{
-    maintenance_schedule::{ CadenceUnit, MaintenanceCadence, MaintenanceSchedule },
-    prelude::*,
+use crate::{
+    models::{
+        maintenance_schedule::{ CadenceUnit, MaintenanceCadence, MaintenanceSchedule },
+        prelude::*,
+    },
+    schema::timestamp::Timestamp,
 };
+
 #[Object]
 impl MaintenanceSchedule {
     async fn id(&self) -> &str {
@@ -16,16 +20,29 @@ impl MaintenanceSchedule {
         &self.cadences
     }
 
-    async fn last_completed_at(&self) -> Option<&DateTime<Utc>> {
-        self.last_completed_at.as_ref()
+    async fn last_completed_at(&self) -> Option<Timestamp> {
+        self.last_completed_at.map(Timestamp)
     }
 
     async fn last_completed_by_user_id(&self) -> Option<&str> {
         self.last_completed_by_user_id.as_deref()
     }
 
-    async fn next_due_at(&self) -> &DateTime<Utc> {
-        &self.next_due_at
+    async fn last_completion_notes(&self) -> Option<&str> {
+        self.last_completion_notes.as_deref()
+    }
+
+    /// Due date of the last occurrence a preventive work order was generated for
+    async fn last_generated_due_at(&self) -> Option<Timestamp> {
+        self.last_generated_due_at.map(Timestamp)
+    }
+
+    async fn missed_cycles(&self) -> i32 {
+        self.missed_cycles
+    }
+
+    async fn next_due_at(&self) -> Timestamp {
+        self.next_due_at.into()
     }
 
     async fn duration_estimate(&self) -> Option<i32> {
@@ -40,12 +57,12 @@ impl MaintenanceSchedule {
         self.active
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 }
 
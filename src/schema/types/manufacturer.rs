@@ -1,4 +1,8 @@
-use crate::models::{ prelude::*, manufacturer::Manufacturer };
+use crate::{
+    models::{ prelude::*, manufacturer::Manufacturer },
+    schema::timestamp::Timestamp,
+};
+
 #[Object]
 impl Manufacturer {
     async fn id(&self) -> &str {
@@ -41,11 +45,11 @@ impl Manufacturer {
         self.active
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 }
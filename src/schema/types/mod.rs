@@ -1,6 +1,7 @@
 pub mod address;
 pub mod asset_type;
 pub mod asset;
+pub mod audit_event;
 pub mod location_type;
 pub mod location;
 pub mod maintenance_schedule;
@@ -23,3 +24,6 @@ pub mod work_order;
 pub mod dashboard;
 pub mod task;
 pub mod maintenance_request;
+pub mod attachment;
+pub mod error;
+pub mod timestamp;
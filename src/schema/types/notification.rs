@@ -1,6 +1,9 @@
-use crate::models::{
-  notification::{ Notification, NotificationChannels, NotificationStatus, SeverityLevel },
-  prelude::*,
+use crate::{
+    models::{
+        notification::{ Notification, NotificationCategory, NotificationChannels },
+        prelude::*,
+    },
+    schema::timestamp::Timestamp,
 };
 
 #[Object]
@@ -9,73 +12,31 @@ impl Notification {
       &self.id
   }
 
-  async fn template_id(&self) -> &str {
-      &self.template_id
+  async fn user_id(&self) -> &str {
+      &self.user_id
   }
 
-  async fn recipient_id(&self) -> &str {
-      &self.recipient_id
+  async fn title(&self) -> &str {
+      &self.title
   }
 
-  async fn subject(&self) -> &str {
-      &self.subject
+  async fn body(&self) -> &str {
+      &self.body
   }
 
-  async fn message(&self) -> &str {
-      &self.message
+  async fn category(&self) -> NotificationCategory {
+      self.category
   }
 
-  async fn context(&self) -> Option<String> {
-      self.context.as_ref().and_then(|c| serde_json::to_string(c).ok())
+  async fn channels(&self) -> &Vec<NotificationChannels> {
+      &self.channels
   }
 
-  async fn severity(&self) -> SeverityLevel {
-      self.severity
+  async fn read(&self) -> bool {
+      self.read
   }
 
-  async fn status(&self) -> NotificationStatus {
-      self.status
-  }
-
-  async fn scheduled_at(&self) -> &DateTime<Utc> {
-      &self.scheduled_at
-  }
-
-  async fn sent_at(&self) -> Option<&DateTime<Utc>> {
-      self.sent_at.as_ref()
-  }
-
-  async fn delivered_channels(&self) -> Vec<NotificationChannels> {
-      self.delivered_channels
-          .iter()
-          .map(|c| c.clone())
-          .collect()
-  }
-
-  async fn failed_channels(&self) -> Vec<NotificationChannels> {
-      self.failed_channels
-          .iter()
-          .map(|c| c.clone())
-          .collect()
-  }
-
-  async fn read_at(&self) -> Option<&DateTime<Utc>> {
-      self.read_at.as_ref()
-  }
-
-  async fn expires_at(&self) -> Option<&DateTime<Utc>> {
-      self.expires_at.as_ref()
-  }
-
-  async fn retry_count(&self) -> i32 {
-      self.retry_count
-  }
-
-  async fn created_at(&self) -> &DateTime<Utc> {
-      &self.created_at
-  }
-
-  async fn updated_at(&self) -> &DateTime<Utc> {
-      &self.updated_at
+  async fn created_at(&self) -> Timestamp {
+      self.created_at.into()
   }
 }
@@ -1,8 +1,12 @@
-use crate::models::{
-    notification::NotificationChannels,
-    notification_delivery_log::{ DeliveryStatus, NotificationDeliveryLog },
-    prelude::*,
+use crate::{
+    models::{
+        notification::NotificationChannels,
+        notification_delivery_log::{ DeliveryStatus, NotificationDeliveryLog },
+        prelude::*,
+    },
+    schema::timestamp::Timestamp,
 };
+
 #[Object]
 impl NotificationDeliveryLog {
     async fn id(&self) -> &str {
@@ -21,12 +25,12 @@ impl NotificationDeliveryLog {
         self.delivery_status
     }
 
-    async fn attempted_at(&self) -> &DateTime<Utc> {
-        &self.attempted_at
+    async fn attempted_at(&self) -> Timestamp {
+        self.attempted_at.into()
     }
 
-    async fn delivered_at(&self) -> Option<&DateTime<Utc>> {
-        self.delivered_at.as_ref()
+    async fn delivered_at(&self) -> Option<Timestamp> {
+        self.delivered_at.map(Timestamp)
     }
 
     async fn error_message(&self) -> Option<&str> {
@@ -41,11 +45,11 @@ impl NotificationDeliveryLog {
         self.recipient_address.as_deref()
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 }
@@ -1,4 +1,12 @@
-use crate::models::{ permission::{Permission, PermissionScope}, permission_log::{PermissionAction, ResourceType}, prelude::* };
+use crate::{
+    models::{
+        permission::{ Permission, PermissionScope },
+        permission_log::{ PermissionAction, ResourceType },
+        prelude::*,
+    },
+    schema::timestamp::Timestamp,
+};
+
 #[Object]
 impl Permission {
     async fn id(&self) -> &str {
@@ -36,20 +44,20 @@ impl Permission {
         self.active
     }
 
-    async fn expires_at(&self) -> Option<&DateTime<Utc>> {
-        self.expires_at.as_ref()
+    async fn expires_at(&self) -> Option<Timestamp> {
+        self.expires_at.map(Timestamp)
     }
 
     async fn created_by(&self) -> &str {
         &self.created_by
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
   // Domain entity methods - expose if needed
     // #[graphql(name = "is_expired")]
@@ -1,7 +1,11 @@
-use crate::models::{
-    permission_log::{ PermissionAction, PermissionLog, PermissionStatus, ResourceType },
-    prelude::*,
+use crate::{
+    models::{
+        permission_log::{ PermissionAction, PermissionLog, PermissionStatus, ResourceType },
+        prelude::*,
+    },
+    schema::timestamp::Timestamp,
 };
+
 #[Object]
 impl PermissionLog {
     async fn id(&self) -> &str {
@@ -28,12 +32,12 @@ impl PermissionLog {
         self.status
     }
 
-    async fn attempted_at(&self) -> &DateTime<Utc> {
-        &self.attempted_at
+    async fn attempted_at(&self) -> Timestamp {
+        self.attempted_at.into()
     }
 
-    async fn granted_at(&self) -> Option<&DateTime<Utc>> {
-        self.granted_at.as_ref()
+    async fn granted_at(&self) -> Option<Timestamp> {
+        self.granted_at.map(Timestamp)
     }
 
     async fn denied_reason(&self) -> Option<&str> {
@@ -56,11 +60,11 @@ impl PermissionLog {
         self.role_at_time.as_deref()
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 }
@@ -1,4 +1,7 @@
-use crate::models::{ prelude::*, role::Role };
+use crate::{
+    models::{ prelude::*, role::Role },
+    schema::timestamp::Timestamp,
+};
 
 #[Object]
 impl Role {
@@ -38,8 +41,8 @@ impl Role {
         self.active
     }
 
-    async fn expires_at(&self) -> Option<&DateTime<Utc>> {
-        self.expires_at.as_ref()
+    async fn expires_at(&self) -> Option<Timestamp> {
+        self.expires_at.map(Timestamp)
     }
 
     async fn max_users(&self) -> Option<i32> {
@@ -50,12 +53,12 @@ impl Role {
         self.created_by.as_deref()
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
     // Domain entity methods - expose if needed
 
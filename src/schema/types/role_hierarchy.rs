@@ -1,4 +1,8 @@
-use crate::models::{ prelude::*, role_hierarchy::{HierarchyType, RoleHierarchy} };
+use crate::{
+    models::{ prelude::*, role_hierarchy::{HierarchyType, RoleHierarchy} },
+    schema::timestamp::Timestamp,
+};
+
 #[Object]
 impl RoleHierarchy {
     async fn id(&self) -> &str {
@@ -41,20 +45,20 @@ impl RoleHierarchy {
         self.conditions.as_deref()
     }
 
-    async fn delegation_expires_at(&self) -> Option<&DateTime<Utc>> {
-        self.delegation_expires_at.as_ref()
+    async fn delegation_expires_at(&self) -> Option<Timestamp> {
+        self.delegation_expires_at.map(Timestamp)
     }
 
     async fn created_by(&self) -> &str {
         &self.created_by
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
     // Domain entity methods - expose if needed
     // #[graphql(name = "is_expired")]
@@ -1,8 +1,10 @@
 //! GraphQL schema implementation for Task entity.
 
 use async_graphql::*;
-use chrono::{DateTime, Utc};
-use crate::models::task::{Task, TaskType};
+use crate::{
+    models::task::{Task, TaskType},
+    schema::timestamp::Timestamp,
+};
 
 /// GraphQL Object implementation for Task.
 #[Object]
@@ -58,13 +60,13 @@ impl Task {
     }
 
     /// Creation timestamp.
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
     /// Last modification timestamp.
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 
     /// Computed field: checks if task is completed.
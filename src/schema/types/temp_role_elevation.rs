@@ -1,6 +1,9 @@
-use crate::models::{
-    prelude::*,
-    temp_role_elevation::{ ElevationPriority, ElevationStatus, TempRoleElevation },
+use crate::{
+    models::{
+        prelude::*,
+        temp_role_elevation::{ ElevationPriority, ElevationStatus, TempRoleElevation },
+    },
+    schema::timestamp::Timestamp,
 };
 
 #[Object]
@@ -33,20 +36,20 @@ impl TempRoleElevation {
         self.approved_by_user_id.as_deref()
     }
 
-    async fn start_time(&self) -> &DateTime<Utc> {
-        &self.start_time
+    async fn start_time(&self) -> Timestamp {
+        self.start_time.into()
     }
 
-    async fn end_time(&self) -> &DateTime<Utc> {
-        &self.end_time
+    async fn end_time(&self) -> Timestamp {
+        self.end_time.into()
     }
 
-    async fn actual_start_time(&self) -> Option<&DateTime<Utc>> {
-        self.actual_start_time.as_ref()
+    async fn actual_start_time(&self) -> Option<Timestamp> {
+        self.actual_start_time.map(Timestamp)
     }
 
-    async fn actual_end_time(&self) -> Option<&DateTime<Utc>> {
-        self.actual_end_time.as_ref()
+    async fn actual_end_time(&self) -> Option<Timestamp> {
+        self.actual_end_time.map(Timestamp)
     }
 
     async fn status(&self) -> ElevationStatus {
@@ -69,8 +72,8 @@ impl TempRoleElevation {
         self.approval_required
     }
 
-    async fn approval_deadline(&self) -> Option<&DateTime<Utc>> {
-        self.approval_deadline.as_ref()
+    async fn approval_deadline(&self) -> Option<Timestamp> {
+        self.approval_deadline.map(Timestamp)
     }
 
     async fn revoked_by_user_id(&self) -> Option<&str> {
@@ -81,12 +84,12 @@ impl TempRoleElevation {
         self.revocation_reason.as_deref()
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
     // Domain entity methods - expose if needed
     // #[graphql(name = "is_active")]
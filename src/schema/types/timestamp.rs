@@ -0,0 +1,57 @@
+//! GraphQL scalar for the timestamps the schema returns.
+
+use async_graphql::{ InputValueError, InputValueResult, Scalar, ScalarType, Value };
+use chrono::{ DateTime, SecondsFormat, Utc };
+
+use crate::db::common::from_db_timestamp;
+
+/// A UTC timestamp serialized as RFC3339 with a `Z` suffix, e.g. `2024-03-15T08:00:00Z`
+///
+/// async-graphql's own `DateTime<Utc>` scalar writes a `+00:00` offset instead, so
+/// this is a separate `Timestamp` scalar; arguments may keep using `DateTime<Utc>`
+/// since both parse either form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timestamp(pub DateTime<Utc>);
+
+#[Scalar(name = "Timestamp")]
+impl ScalarType for Timestamp {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(s) =>
+                from_db_timestamp(s)
+                    .map(Timestamp)
+                    .ok_or_else(|| InputValueError::custom(format!("Invalid timestamp {}", s))),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(value: DateTime<Utc>) -> Self {
+        Timestamp(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_timestamp_round_trips_with_z_suffix() {
+        let timestamp = Timestamp(Utc.with_ymd_and_hms(2024, 3, 15, 8, 0, 0).unwrap());
+
+        let value = timestamp.to_value();
+
+        assert_eq!(value, Value::from("2024-03-15T08:00:00Z"));
+        assert_eq!(Timestamp::parse(value).unwrap(), timestamp);
+        assert_eq!(
+            Timestamp::parse(Value::from("2024-03-15T08:00:00+00:00")).unwrap(),
+            timestamp
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::models::{ prelude::*, user::{ UserStatus, UserType } };
+use crate::{
+    models::{ prelude::*, user::{ UserStatus, UserType } },
+    schema::timestamp::Timestamp,
+};
 
 #[Object]
 impl User {
@@ -58,28 +61,28 @@ impl User {
         self.secondary_email.as_deref()
     }
 
-    async fn hire_date(&self) -> Option<&DateTime<Utc>> {
-        self.hire_date.as_ref()
+    async fn hire_date(&self) -> Option<Timestamp> {
+        self.hire_date.map(Timestamp)
     }
 
-    async fn termination_date(&self) -> Option<&DateTime<Utc>> {
-        self.termination_date.as_ref()
+    async fn termination_date(&self) -> Option<Timestamp> {
+        self.termination_date.map(Timestamp)
     }
 
-    async fn last_login_at(&self) -> Option<&DateTime<Utc>> {
-        self.last_login_at.as_ref()
+    async fn last_login_at(&self) -> Option<Timestamp> {
+        self.last_login_at.map(Timestamp)
     }
 
-    async fn password_changed_at(&self) -> Option<&DateTime<Utc>> {
-        self.password_changed_at.as_ref()
+    async fn password_changed_at(&self) -> Option<Timestamp> {
+        self.password_changed_at.map(Timestamp)
     }
 
     async fn failed_login_attempts(&self) -> i32 {
         self.failed_login_attempts
     }
 
-    async fn account_locked_until(&self) -> Option<&DateTime<Utc>> {
-        self.account_locked_until.as_ref()
+    async fn account_locked_until(&self) -> Option<Timestamp> {
+        self.account_locked_until.map(Timestamp)
     }
 
     async fn certification_levels(&self) -> String {
@@ -130,12 +133,16 @@ impl User {
         self.created_by.as_deref()
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn version(&self) -> u64 {
+        self.version
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
+    }
+
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 
     #[graphql(name = "full_name")]
@@ -1,8 +1,12 @@
-use crate::models::{
-    notification::{ NotificationChannels, SeverityLevel },
-    prelude::*,
-    user_notification_preferences::{ PreferenceScope, UserNotificationPreferences },
+use crate::{
+    models::{
+        notification::{ NotificationChannels, SeverityLevel },
+        prelude::*,
+        user_notification_preferences::{ PreferenceScope, UserNotificationPreferences },
+    },
+    schema::timestamp::Timestamp,
 };
+
 #[Object]
 impl UserNotificationPreferences {
     async fn id(&self) -> &str {
@@ -83,12 +87,12 @@ impl UserNotificationPreferences {
         self.active
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 
     #[graphql(name = "is_notification_allowed_now")]
@@ -1,8 +1,9 @@
-use chrono::Timelike;
-
-use crate::models::{
-    prelude::*,
-    user_preferences::{ LanguageOptions, ThemeOptions, TimezoneFormat, UserPreferences },
+use crate::{
+    models::{
+        prelude::*,
+        user_preferences::{ LanguageOptions, ThemeOptions, TimezoneFormat, UserPreferences },
+    },
+    schema::timestamp::Timestamp,
 };
 
 #[Object]
@@ -87,33 +88,25 @@ impl UserPreferences {
         self.custom_shortcuts.as_ref().and_then(|s| serde_json::to_string(s).ok())
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
+    }
+
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
+    }
+
+    async fn dark_mode_start_hour(&self) -> u32 {
+        self.dark_mode_start_hour
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn dark_mode_end_hour(&self) -> u32 {
+        self.dark_mode_end_hour
     }
 
+    /// Theme to display now, resolving `auto` against the user's local time
     async fn effective_theme(&self) -> &str {
-        // If theme is auto, could determine based on system time or other logic
-        match self.theme {
-            ThemeOptions::Auto => {
-                // Simple logic: dark theme between 6 PM and 6 AM
-                let hour12 = Utc::now().hour12();
-                let mut hour = hour12.1;
-
-                if hour12.0 == true {
-                    hour += 12;
-                }
-                if hour >= 18 || hour < 6 {
-                    return "dark";
-                } else {
-                    return "light";
-                }
-            }
-            _ => self.theme.to_str(),
-        }
+        self.effective_theme_at(Utc::now())
     }
 
     async fn is_auto_refresh_enabled(&self) -> bool {
@@ -1,4 +1,8 @@
-use crate::models::{ prelude::*, user_role::{ AssignmentSource, RoleAssignmentStatus, UserRole } };
+use crate::{
+    models::{ prelude::*, user_role::{ AssignmentSource, RoleAssignmentStatus, UserRole } },
+    schema::timestamp::Timestamp,
+};
+
 #[Object]
 impl UserRole {
     async fn id(&self) -> &str {
@@ -25,24 +29,24 @@ impl UserRole {
         self.is_primary_role
     }
 
-    async fn assigned_at(&self) -> &DateTime<Utc> {
-        &self.assigned_at
+    async fn assigned_at(&self) -> Timestamp {
+        self.assigned_at.into()
     }
 
     async fn assigned_by_user_id(&self) -> Option<&str> {
         self.assigned_by_user_id.as_deref()
     }
 
-    async fn effective_from(&self) -> &DateTime<Utc> {
-        &self.effective_from
+    async fn effective_from(&self) -> Timestamp {
+        self.effective_from.into()
     }
 
-    async fn expires_at(&self) -> Option<&DateTime<Utc>> {
-        self.expires_at.as_ref()
+    async fn expires_at(&self) -> Option<Timestamp> {
+        self.expires_at.map(Timestamp)
     }
 
-    async fn last_used_at(&self) -> Option<&DateTime<Utc>> {
-        self.last_used_at.as_ref()
+    async fn last_used_at(&self) -> Option<Timestamp> {
+        self.last_used_at.map(Timestamp)
     }
 
     async fn conditions(&self) -> Option<&str> {
@@ -53,8 +57,8 @@ impl UserRole {
         self.elevation_request_id.as_deref()
     }
 
-    async fn revoked_at(&self) -> Option<&DateTime<Utc>> {
-        self.revoked_at.as_ref()
+    async fn revoked_at(&self) -> Option<Timestamp> {
+        self.revoked_at.map(Timestamp)
     }
 
     async fn revoked_by_user_id(&self) -> Option<&str> {
@@ -69,12 +73,12 @@ impl UserRole {
         self.metadata.as_deref()
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
     // Domain entity methods - expose if needed
 
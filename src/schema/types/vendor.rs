@@ -1,4 +1,7 @@
-use crate::models::{ prelude::*, vendor::{ VendorStatus, VendorTier } };
+use crate::{
+    models::{ prelude::*, vendor::{ VendorStatus, VendorTier } },
+    schema::timestamp::Timestamp,
+};
 
 #[Object]
 impl Vendor {
@@ -106,16 +109,16 @@ impl Vendor {
         self.insurance_info.as_ref().and_then(|ins| serde_json::to_string(ins).ok())
     }
 
-    async fn contract_start_date(&self) -> Option<&DateTime<Utc>> {
-        self.contract_start_date.as_ref()
+    async fn contract_start_date(&self) -> Option<Timestamp> {
+        self.contract_start_date.map(Timestamp)
     }
 
-    async fn contract_end_date(&self) -> Option<&DateTime<Utc>> {
-        self.contract_end_date.as_ref()
+    async fn contract_end_date(&self) -> Option<Timestamp> {
+        self.contract_end_date.map(Timestamp)
     }
 
-    async fn last_order_date(&self) -> Option<&DateTime<Utc>> {
-        self.last_order_date.as_ref()
+    async fn last_order_date(&self) -> Option<Timestamp> {
+        self.last_order_date.map(Timestamp)
     }
 
     async fn total_orders(&self) -> i32 {
@@ -178,34 +181,35 @@ impl Vendor {
         self.created_by.as_deref()
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 
-    // Domain entity methods - expose if needed
-    // #[graphql(name = "is_active")]
-    // async fn check_is_active(&self) -> bool {
-    //     self.is_active()
-    // }
+    #[graphql(name = "isActive")]
+    async fn resolve_is_active(&self) -> bool {
+        self.is_active()
+    }
 
-    // #[graphql(name = "is_contract_expired")]
-    // async fn check_is_contract_expired(&self) -> bool {
-    //     self.is_contract_expired()
-    // }
+    #[graphql(name = "isContractExpired")]
+    async fn resolve_is_contract_expired(&self) -> bool {
+        self.is_contract_expired()
+    }
 
-    // #[graphql(name = "can_place_orders")]
-    // async fn check_can_place_orders(&self) -> bool {
-    //     self.can_place_orders()
-    // }
+    /// Whether the vendor is active, under contract and not suspended
+    #[graphql(name = "canPlaceOrders")]
+    async fn resolve_can_place_orders(&self) -> bool {
+        self.can_place_orders()
+    }
 
-    // #[graphql(name = "average_order_value")]
-    // async fn check_average_order_value(&self) -> f64 {
-    //     self.average_order_value()
-    // }
+    /// Total spent divided by total orders, 0 when no orders were placed
+    #[graphql(name = "averageOrderValue")]
+    async fn resolve_average_order_value(&self) -> f64 {
+        self.average_order_value()
+    }
 
     // async fn certification_count(&self) -> i32 {
     //     self.certifications.len() as i32
@@ -253,3 +257,98 @@ impl Vendor {
     //     }
     // }
 }
+
+/// A newly created vendor plus advisories about details worth completing
+#[derive(Debug, Clone)]
+pub struct CreateVendorResult {
+    pub vendor: Vendor,
+    pub warnings: Vec<String>,
+}
+
+#[Object]
+impl CreateVendorResult {
+    async fn vendor(&self) -> &Vendor {
+        &self.vendor
+    }
+
+    /// Non-blocking suggestions, e.g. "Consider adding a website"
+    async fn warnings(&self) -> &Vec<String> {
+        &self.warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{ EmptyMutation, EmptySubscription, Schema };
+
+    struct TestQuery;
+
+    #[Object]
+    impl TestQuery {
+        async fn vendor(&self) -> Vendor {
+            let mut vendor = Vendor::new(
+                "vendor-1".to_string(),
+                "Acme Supply".to_string(),
+                None,
+                Some("ACME".to_string()),
+                "vendor-category-1".to_string(),
+                "active".to_string(),
+                "standard".to_string(),
+                "+15555550100".to_string(),
+                None,
+                "orders@acme.example".to_string(),
+                None,
+                None,
+                "12-3456789".to_string(),
+                None,
+                "Net 30".to_string(),
+                "USD".to_string(),
+                None,
+                "Jane Doe".to_string(),
+                "Account Manager".to_string(),
+                None,
+                None,
+                Some(serde_json::json!({ "city": "Denver" })),
+                None,
+                vec![],
+                "verified".to_string(),
+                None,
+                None,
+                None,
+                None,
+                "email".to_string(),
+                None,
+                None,
+                vec![],
+                None,
+                vec![],
+                &false,
+                None,
+                None,
+                None,
+                "US"
+            ).unwrap();
+            vendor.record_order(100.0).unwrap();
+            vendor.record_order(50.0).unwrap();
+            vendor
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vendor_exposes_computed_fields_and_json_strings() {
+        let schema = Schema::new(TestQuery, EmptyMutation, EmptySubscription);
+
+        let response = schema.execute(
+            "{ vendor { isActive canPlaceOrders isContractExpired averageOrderValue billingAddress } }"
+        ).await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["vendor"]["isActive"], true);
+        assert_eq!(data["vendor"]["canPlaceOrders"], true);
+        assert_eq!(data["vendor"]["isContractExpired"], false);
+        assert_eq!(data["vendor"]["averageOrderValue"], 75.0);
+        assert_eq!(data["vendor"]["billingAddress"], r#"{"city":"Denver"}"#);
+    }
+}
@@ -1,6 +1,9 @@
-use crate::models::{
-    prelude::*,
-    vendor_category::{ CategoryStatus, CategoryType, VendorCategory },
+use crate::{
+    models::{
+        prelude::*,
+        vendor_category::{ CategoryStatus, CategoryType, VendorCategory },
+    },
+    schema::timestamp::Timestamp,
 };
 
 #[Object]
@@ -85,12 +88,12 @@ impl VendorCategory {
         self.created_by.as_deref()
     }
 
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 
     // Domain entity methods - expose if needed
@@ -1,15 +1,22 @@
 //! GraphQL schema implementation for WorkOrder entity.
 
 use async_graphql::*;
-use chrono::{ DateTime, Utc };
-use crate::models::work_order::{
-    WorkOrder,
-    WorkOrderCost,
-    WorkOrderDifficulty,
-    WorkOrderPriority,
-    WorkOrderSeverity,
-    WorkOrderStatus,
-    WorkOrderType,
+use crate::{
+    error::FieldError,
+    models::{
+        validation::canonical_decimal,
+        work_order::{
+            WorkOrder,
+            WorkOrderCost,
+            WorkOrderDifficulty,
+            WorkOrderPart,
+            WorkOrderPriority,
+            WorkOrderSeverity,
+            WorkOrderStatus,
+            WorkOrderType,
+        },
+    },
+    schema::timestamp::Timestamp,
 };
 
 /// GraphQL Object implementation for WorkOrder.
@@ -95,6 +102,11 @@ impl WorkOrder {
         self.assigned_technician_id.as_deref()
     }
 
+    /// Vendor the work is hired out to, if any.
+    async fn vendor_id(&self) -> Option<&str> {
+        self.vendor_id.as_deref()
+    }
+
     /// Estimated duration in minutes.
     async fn estimated_duration_minutes(&self) -> i32 {
         self.estimated_duration_minutes
@@ -125,19 +137,52 @@ impl WorkOrder {
         self.actual_cost.as_ref().map(|c| c.to_string())
     }
 
+    /// Parts and materials consumed by this work order.
+    async fn parts(&self) -> &Vec<WorkOrderPart> {
+        &self.parts
+    }
+
+    /// Sum of quantity times unit cost over the parts list, as a decimal string.
+    #[graphql(name = "partsTotalCost")]
+    async fn resolve_parts_total_cost(&self) -> String {
+        canonical_decimal(&self.parts_total_cost())
+    }
+
     /// Total labor hours invested in this work order.
     async fn labor_hours(&self) -> Option<f64> {
         self.labor_hours
     }
 
+    /// Actual cost minus the estimated cost band midpoint; positive means over budget.
+    #[graphql(name = "costVariance")]
+    async fn resolve_cost_variance(&self) -> Option<String> {
+        self.cost_variance().map(|v| canonical_decimal(&v))
+    }
+
+    /// Actual minus estimated duration in minutes; positive means the work ran long.
+    #[graphql(name = "durationVariance")]
+    async fn resolve_duration_variance(&self) -> Option<i32> {
+        self.duration_variance()
+    }
+
     /// Notes provided upon work order completion.
     async fn completion_notes(&self) -> Option<&str> {
         self.completion_notes.as_deref()
     }
 
+    /// Rating from 0.0 to 5.0 given to the vendor once the work was completed.
+    async fn vendor_rating(&self) -> Option<f64> {
+        self.vendor_rating
+    }
+
+    /// Date the work is scheduled to start.
+    async fn scheduled_date(&self) -> Option<Timestamp> {
+        self.scheduled_date.map(Timestamp)
+    }
+
     /// Date of completion.
-    async fn completed_date(&self) -> Option<DateTime<Utc>> {
-        self.completed_date.clone()
+    async fn completed_date(&self) -> Option<Timestamp> {
+        self.completed_date.map(Timestamp)
     }
 
     /// User who created this work order.
@@ -145,14 +190,19 @@ impl WorkOrder {
         &self.created_by
     }
 
+    /// Optimistic concurrency version, incremented on every update.
+    async fn version(&self) -> u64 {
+        self.version
+    }
+
     /// Creation timestamp.
-    async fn created_at(&self) -> &DateTime<Utc> {
-        &self.created_at
+    async fn created_at(&self) -> Timestamp {
+        self.created_at.into()
     }
 
     /// Last modification timestamp.
-    async fn updated_at(&self) -> &DateTime<Utc> {
-        &self.updated_at
+    async fn updated_at(&self) -> Timestamp {
+        self.updated_at.into()
     }
 
     /// Computed field: checks if work order is currently in progress.
@@ -173,3 +223,132 @@ impl WorkOrder {
         self.is_overdue()
     }
 }
+
+#[Object]
+impl WorkOrderPart {
+    /// Manufacturer or catalog part number, unique within the work order.
+    async fn part_number(&self) -> &str {
+        &self.part_number
+    }
+
+    /// Part description.
+    async fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Quantity used as a decimal string.
+    async fn quantity(&self) -> String {
+        canonical_decimal(&self.quantity)
+    }
+
+    /// Cost per unit as a decimal string.
+    async fn unit_cost(&self) -> String {
+        canonical_decimal(&self.unit_cost)
+    }
+
+    /// Quantity times unit cost as a decimal string.
+    #[graphql(name = "lineTotal")]
+    async fn resolve_line_total(&self) -> String {
+        canonical_decimal(&self.line_total())
+    }
+}
+
+/// Outcome of a bulk work order update, reported per id.
+#[derive(Debug, Clone, Default)]
+pub struct BulkUpdateResult {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<FieldError>,
+}
+
+#[Object]
+impl BulkUpdateResult {
+    /// Ids of work orders that were updated.
+    async fn succeeded(&self) -> &Vec<String> {
+        &self.succeeded
+    }
+
+    /// Per-id failures; `field` holds the work order id.
+    async fn failed(&self) -> &Vec<FieldError> {
+        &self.failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{ EmptyMutation, EmptySubscription, Schema };
+    use chrono::{ DateTime, Utc };
+
+    fn work_order(id: &str, status: WorkOrderStatus) -> WorkOrder {
+        let mut work_order = WorkOrder::new(
+            id.to_string(),
+            format!("WO-{}", id),
+            "Replace seal".to_string(),
+            "Pump seal is leaking".to_string(),
+            None,
+            "asset-1".to_string(),
+            "corrective".to_string(),
+            "high".to_string(),
+            WorkOrderSeverity::Important,
+            WorkOrderDifficulty::Normal,
+            None,
+            60,
+            WorkOrderCost::Two,
+            "user-1".to_string()
+        ).unwrap();
+        // Started two hours ago against a one hour estimate
+        work_order.created_at = Utc::now() - chrono::Duration::hours(2);
+        work_order.status = status;
+        work_order
+    }
+
+    struct TestQuery;
+
+    #[Object]
+    impl TestQuery {
+        async fn work_orders(&self) -> Vec<WorkOrder> {
+            vec![
+                work_order("wo-1", WorkOrderStatus::Completed),
+                work_order("wo-2", WorkOrderStatus::InProgress)
+            ]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_unfinished_work_past_its_estimate_is_overdue() {
+        let schema = Schema::new(TestQuery, EmptyMutation, EmptySubscription);
+
+        let response = schema.execute(
+            "{ workOrders { id is_overdue is_in_progress is_completed severity severityLevel \
+             estimatedCost estimatedCostDescription } }"
+        ).await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        let (completed, in_progress) = (&data["workOrders"][0], &data["workOrders"][1]);
+
+        assert_eq!(completed["is_overdue"], false);
+        assert_eq!(completed["is_completed"], true);
+        assert_eq!(in_progress["is_overdue"], true);
+        assert_eq!(in_progress["is_in_progress"], true);
+        assert_eq!(in_progress["severity"], "IMPORTANT");
+        assert_eq!(in_progress["severityLevel"], 2);
+        assert_eq!(in_progress["estimatedCost"], "TWO");
+        assert_eq!(in_progress["estimatedCostDescription"], "$250 - $499");
+    }
+
+    #[tokio::test]
+    async fn test_timestamps_serialize_as_rfc3339_utc() {
+        let schema = Schema::new(TestQuery, EmptyMutation, EmptySubscription);
+
+        let response = schema.execute("{ workOrders { createdAt updatedAt } }").await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        for field in ["createdAt", "updatedAt"] {
+            let timestamp = data["workOrders"][0][field].as_str().unwrap();
+            assert!(timestamp.ends_with('Z'), "{} = {}", field, timestamp);
+            assert!(DateTime::parse_from_rfc3339(timestamp).is_ok(), "{} = {}", field, timestamp);
+        }
+    }
+}
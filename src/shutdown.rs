@@ -0,0 +1,91 @@
+use std::{ future::Future, net::SocketAddr, time::Duration };
+
+use axum::Router;
+use tokio::{ net::TcpListener, sync::oneshot };
+use tracing::{ info, warn };
+
+/// How long in-flight requests get to finish once shutdown begins
+///
+/// Kept under the 30 second grace period container schedulers allow between
+/// SIGTERM and SIGKILL.
+pub const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(25);
+
+/// Resolves on Ctrl+C or, on Unix, SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl+C"),
+        _ = terminate => info!("Received SIGTERM"),
+    }
+}
+
+/// Serve `app` until `signal` resolves, then drain in-flight requests
+///
+/// Once `signal` resolves the listener stops accepting connections and open
+/// requests are given `drain_timeout` to complete; any still running after that
+/// are dropped. Handlers can extract the peer address as `ConnectInfo<SocketAddr>`.
+///
+/// # Arguments
+///
+/// * `listener` - Bound listener to accept connections on
+/// * `app` - Router to serve
+/// * `signal` - Future that starts shutdown when it resolves, e.g. `shutdown_signal()`
+/// * `drain_timeout` - Upper bound on waiting for in-flight requests
+pub async fn serve_with_graceful_shutdown(
+    listener: TcpListener,
+    app: Router,
+    signal: impl Future<Output = ()> + Send + 'static,
+    drain_timeout: Duration
+) -> std::io::Result<()> {
+    let (draining_tx, draining_rx) = oneshot::channel::<()>();
+
+    let graceful = async move {
+        signal.await;
+        info!("Shutdown started, waiting up to {:?} for in-flight requests", drain_timeout);
+        let _ = draining_tx.send(());
+    };
+
+    let deadline = async move {
+        match draining_rx.await {
+            Ok(()) => tokio::time::sleep(drain_timeout).await,
+            // Server finished without shutting down; let it report its own result
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    tokio::select! {
+        result = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>()
+        ).with_graceful_shutdown(graceful) => {
+            info!("Server stopped");
+            result
+        }
+        _ = deadline => {
+            warn!("Requests still in flight after {:?}, shutting down anyway", drain_timeout);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Prometheus metrics for GraphQL traffic, DynamoDB calls and application errors.
+//!
+//! `install_recorder` registers the process-wide recorder once at startup; the
+//! `/metrics` route renders it with `metrics_handler`.
+
+use std::{ sync::{ Arc, Mutex }, time::{ Duration, Instant } };
+
+use async_graphql::{
+    extensions::{ Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery },
+    parser::types::{ ExecutableDocument, OperationType },
+    Response,
+    ServerResult,
+    Variables,
+};
+use axum::{
+    extract::Extension as AxumExtension,
+    http::header,
+    response::{ IntoResponse, Response as HttpResponse },
+};
+use metrics::{ counter, histogram };
+use metrics_exporter_prometheus::{ PrometheusBuilder, PrometheusHandle };
+
+use crate::{
+    auth::{ guard::{ ensure_permission, VIEW_METRICS }, AuthUser },
+    config::Config,
+    AppError,
+    DbClient,
+    Repository,
+};
+
+/// GraphQL operations executed, labeled by `operation_type`
+pub const GRAPHQL_REQUESTS_TOTAL: &str = "graphql_requests_total";
+
+/// GraphQL operation latency in seconds, labeled by `operation_type`
+pub const GRAPHQL_REQUEST_DURATION_SECONDS: &str = "graphql_request_duration_seconds";
+
+/// DynamoDB calls made by the repository, labeled by `operation` and `table`
+pub const DYNAMODB_CALLS_TOTAL: &str = "dynamodb_calls_total";
+
+/// Errors returned to GraphQL clients, labeled by `AppError` `variant`
+pub const APP_ERRORS_TOTAL: &str = "app_errors_total";
+
+/// How often histogram summaries are compacted
+pub const METRICS_UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Installs the global Prometheus recorder
+///
+/// # Returns
+///
+/// The handle `metrics_handler` renders from, or `AppError::ConfigError` if a recorder
+/// is already installed
+pub fn install_recorder() -> Result<PrometheusHandle, AppError> {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .map_err(|e| AppError::ConfigError(format!("Failed to install metrics recorder: {}", e)))
+}
+
+/// Runs the recorder's periodic upkeep in the background
+pub fn spawn_upkeep(handle: PrometheusHandle, every: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            handle.run_upkeep();
+        }
+    });
+}
+
+/// Serves every recorded metric in the Prometheus text format
+///
+/// The scraper authenticates like any other caller and needs `VIEW_METRICS`.
+pub async fn metrics_handler(
+    AxumExtension(handle): AxumExtension<PrometheusHandle>,
+    AxumExtension(db_client): AxumExtension<DbClient>,
+    AxumExtension(config): AxumExtension<Config>,
+    auth_user: Option<AxumExtension<AuthUser>>
+) -> HttpResponse {
+    let Some(AxumExtension(auth_user)) = auth_user else {
+        let error = AppError::Unauthorized("Authentication required".to_string());
+        return (error.status_code(), error.to_string()).into_response();
+    };
+
+    let repo = Repository::from_config(db_client, &config.database);
+    if let Err(e) = ensure_permission(&repo, &auth_user, VIEW_METRICS).await {
+        return (e.status_code(), e.to_string()).into_response();
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], handle.render()).into_response()
+}
+
+/// Counts one DynamoDB call, e.g. `("GetItem", "Users")`
+pub fn record_dynamodb_call(operation: &'static str, table: &str) {
+    counter!(DYNAMODB_CALLS_TOTAL, "operation" => operation, "table" => table.to_string())
+        .increment(1);
+}
+
+/// Counts one error surfaced to a client
+pub(crate) fn record_error(error: &AppError) {
+    counter!(APP_ERRORS_TOTAL, "variant" => error_variant(error)).increment(1);
+}
+
+/// Name of the `AppError` variant, used as the `variant` label
+fn error_variant(error: &AppError) -> &'static str {
+    match error {
+        AppError::DatabaseError(_) => "DatabaseError",
+        AppError::ValidationError(_) => "ValidationError",
+        AppError::InvalidField { .. } => "InvalidField",
+        AppError::ValidationErrors(_) => "ValidationErrors",
+        AppError::NotFound(_) => "NotFound",
+        AppError::Unauthorized(_) => "Unauthorized",
+        AppError::Forbidden(_) => "Forbidden",
+        AppError::InternalServerError(_) => "InternalServerError",
+        AppError::ConfigError(_) => "ConfigError",
+        AppError::AuthError(_) => "AuthError",
+        AppError::Conflict(_) => "Conflict",
+    }
+}
+
+/// Schema extension counting and timing every executed GraphQL operation
+///
+/// Operations are labeled by type rather than by the client-chosen operation name,
+/// which would let any caller mint new time series.
+pub struct GraphQLMetrics;
+
+impl ExtensionFactory for GraphQLMetrics {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GraphQLMetricsExtension::default())
+    }
+}
+
+/// Created per request; remembers the type of each operation in the parsed document
+#[derive(Default)]
+struct GraphQLMetricsExtension {
+    operation_types: Mutex<Vec<(Option<String>, &'static str)>>,
+}
+
+impl GraphQLMetricsExtension {
+    /// The `operation_type` label for the operation `operation_name` selects
+    ///
+    /// `unknown` when the document didn't parse or has no such operation.
+    fn operation_type(&self, operation_name: Option<&str>) -> &'static str {
+        let operation_types = match self.operation_types.lock() {
+            Ok(operation_types) => operation_types,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        match operation_types.as_slice() {
+            [(_, operation_type)] => operation_type,
+            operation_types =>
+                operation_types
+                    .iter()
+                    .find(|(name, _)| name.as_deref() == operation_name)
+                    .map_or("unknown", |(_, operation_type)| operation_type),
+        }
+    }
+}
+
+/// Label value for an operation type
+fn operation_type_label(operation_type: OperationType) -> &'static str {
+    match operation_type {
+        OperationType::Query => "query",
+        OperationType::Mutation => "mutation",
+        OperationType::Subscription => "subscription",
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for GraphQLMetricsExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+
+        let mut operation_types = match self.operation_types.lock() {
+            Ok(operation_types) => operation_types,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *operation_types = document.operations
+            .iter()
+            .map(|(name, operation)| {
+                (name.map(|name| name.to_string()), operation_type_label(operation.node.ty))
+            })
+            .collect();
+
+        Ok(document)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>
+    ) -> Response {
+        let started = Instant::now();
+        let response = next.run(ctx, operation_name).await;
+
+        let operation_type = self.operation_type(operation_name);
+        counter!(GRAPHQL_REQUESTS_TOTAL, "operation_type" => operation_type).increment(1);
+        histogram!(GRAPHQL_REQUEST_DURATION_SECONDS, "operation_type" => operation_type).record(
+            started.elapsed().as_secs_f64()
+        );
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_variant_names_each_variant() {
+        assert_eq!(error_variant(&AppError::NotFound("x".to_string())), "NotFound");
+        assert_eq!(error_variant(&AppError::invalid_field("email", "x")), "InvalidField");
+        assert_eq!(error_variant(&AppError::Conflict("x".to_string())), "Conflict");
+    }
+
+    #[test]
+    fn test_operation_type_ignores_unknown_operation_names() {
+        let extension = GraphQLMetricsExtension {
+            operation_types: Mutex::new(
+                vec![(Some("Load".to_string()), "query"), (Some("Save".to_string()), "mutation")]
+            ),
+        };
+
+        assert_eq!(extension.operation_type(Some("Save")), "mutation");
+        assert_eq!(extension.operation_type(Some("RandomName123")), "unknown");
+        assert_eq!(GraphQLMetricsExtension::default().operation_type(None), "unknown");
+    }
+}
@@ -0,0 +1,174 @@
+use std::{ sync::OnceLock, time::Duration };
+
+use chrono::{ DateTime, Utc };
+use hmac::{ Hmac, Mac };
+use serde::Serialize;
+use sha2::Sha256;
+use tracing::{ info, warn };
+
+use crate::{ config::WebhookConfig, models::work_order::WorkOrderStatus };
+
+/// Header carrying the hex HMAC-SHA256 of the request body
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Delivery attempts per event before giving up
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubled for each later attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Per-attempt timeout so a slow receiver can't pin the task
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Payload POSTed when a work order changes status
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkOrderStatusEvent {
+    pub event: String,
+    pub work_order_id: String,
+    pub old_status: String,
+    pub new_status: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl WorkOrderStatusEvent {
+    /// Builds the event for a transition; the event name is `work_order.<new_status>`
+    pub fn new(work_order_id: &str, old_status: WorkOrderStatus, new_status: WorkOrderStatus) -> Self {
+        Self {
+            event: format!("work_order.{}", new_status.to_str()),
+            work_order_id: work_order_id.to_string(),
+            old_status: old_status.to_string(),
+            new_status: new_status.to_string(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Queue a work order status webhook if the configuration subscribes to it
+///
+/// Delivery runs on a spawned task so the calling mutation isn't blocked; failures
+/// are logged and never reach the caller. No-op when webhooks are not configured
+/// or the status did not change.
+///
+/// # Arguments
+///
+/// * `config` - Webhook configuration, `None` when webhooks are disabled
+/// * `work_order_id` - ID of the work order that changed
+/// * `old_status` - Status before the update was written
+/// * `new_status` - Status after the update was written
+pub fn emit_work_order_status_change(
+    config: Option<&WebhookConfig>,
+    work_order_id: &str,
+    old_status: WorkOrderStatus,
+    new_status: WorkOrderStatus
+) {
+    let Some(config) = config else {
+        return;
+    };
+
+    if old_status == new_status {
+        return;
+    }
+
+    let event = WorkOrderStatusEvent::new(work_order_id, old_status, new_status);
+    if !config.events.iter().any(|e| e == &event.event) {
+        return;
+    }
+
+    let body = match serde_json::to_vec(&event) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to serialize webhook event {}: {}", event.event, e);
+            return;
+        }
+    };
+
+    let url = config.url.clone();
+    let signature = sign(&config.secret, &body);
+
+    tokio::spawn(async move {
+        deliver(&url, &event.event, body, &signature).await;
+    });
+}
+
+/// Hex-encoded HMAC-SHA256 of `body` keyed with `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>
+        ::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build().unwrap_or_default()
+    })
+}
+
+/// POST the signed body, retrying with exponential backoff on errors and non-2xx responses
+async fn deliver(url: &str, event: &str, body: Vec<u8>, signature: &str) {
+    for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+        let result = http_client()
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .body(body.clone())
+            .send().await;
+
+        match result {
+            Ok(response) if response.status().is_success() => {
+                info!("Delivered webhook {} on attempt {}", event, attempt);
+                return;
+            }
+            Ok(response) => {
+                warn!(
+                    "Webhook {} attempt {}/{} got status {}",
+                    event,
+                    attempt,
+                    MAX_DELIVERY_ATTEMPTS,
+                    response.status()
+                );
+            }
+            Err(e) => {
+                warn!("Webhook {} attempt {}/{} failed: {}", event, attempt, MAX_DELIVERY_ATTEMPTS, e);
+            }
+        }
+
+        if attempt < MAX_DELIVERY_ATTEMPTS {
+            tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+        }
+    }
+
+    warn!("Giving up on webhook {} after {} attempts", event, MAX_DELIVERY_ATTEMPTS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_matches_rfc_4231_vector() {
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_event_payload_shape() {
+        let event = WorkOrderStatusEvent::new(
+            "wo-1",
+            WorkOrderStatus::InProgress,
+            WorkOrderStatus::WaitingParts
+        );
+
+        let payload = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(payload["event"], "work_order.waiting_parts");
+        assert_eq!(payload["work_order_id"], "wo-1");
+        assert_eq!(payload["old_status"], "in_progress");
+        assert_eq!(payload["new_status"], "waiting_parts");
+        assert!(payload["timestamp"].is_string());
+    }
+}
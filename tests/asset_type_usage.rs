@@ -0,0 +1,140 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::sync::{ Arc, Mutex };
+
+use async_graphql::{ Request, Response };
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{ config::GraphQLConfig, create_schema, DbClient };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+/// Answers as a table holding `asset_type-1` and `asset_count` assets of that type, split
+/// over two query pages. Every `Query` and `DeleteItem` request body is recorded.
+fn dynamodb_stub(asset_count: i32, requests: Arc<Mutex<Vec<(String, Value)>>>) -> Router {
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let requests = requests.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+
+                let response = match operation.as_str() {
+                    "GetItem" =>
+                        json!({
+                            "Item": {
+                                "id": { "S": "asset_type-1" },
+                                "name": { "S": "Walk-in cooler" },
+                                "description": { "S": "Cold storage" },
+                                "category": { "S": "refrigeration" }
+                            }
+                        }),
+                    "Query" if request.get("ExclusiveStartKey").is_none() && asset_count > 1 =>
+                        json!({
+                            "Count": asset_count - 1,
+                            "ScannedCount": asset_count - 1,
+                            "LastEvaluatedKey": { "id": { "S": "asset-1" } }
+                        }),
+                    "Query" if request.get("ExclusiveStartKey").is_none() =>
+                        json!({ "Count": asset_count, "ScannedCount": asset_count }),
+                    "Query" => json!({ "Count": 1, "ScannedCount": 1 }),
+                    "DeleteItem" => json!({ "Attributes": { "id": { "S": "asset_type-1" } } }),
+                    _ => json!({}),
+                };
+                if operation == "Query" || operation == "DeleteItem" {
+                    requests.lock().unwrap().push((operation, request));
+                }
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+async fn execute(asset_count: i32, query: &str) -> (Response, Vec<(String, Value)>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(asset_count, requests.clone());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let response = schema.execute(Request::new(query)).await;
+
+    server.abort();
+
+    let requests = requests.lock().unwrap().clone();
+    (response, requests)
+}
+
+#[tokio::test]
+async fn test_asset_type_usage_counts_without_loading_items() {
+    let (response, requests) = execute(
+        3,
+        r#"{ assetTypeUsage(assetTypeId: "asset_type-1") }"#
+    ).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.data.into_json().unwrap(), json!({ "assetTypeUsage": 3 }));
+
+    assert_eq!(requests.len(), 2);
+    for (_, query) in &requests {
+        assert_eq!(query["IndexName"], "TypeIndex");
+        assert_eq!(query["Select"], "COUNT");
+        assert_eq!(query["ExpressionAttributeNames"]["#pk"], "asset_type_id");
+    }
+}
+
+#[tokio::test]
+async fn test_unused_asset_type_is_deleted() {
+    let (response, requests) = execute(
+        0,
+        r#"mutation { deleteAssetType(id: "asset_type-1") }"#
+    ).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.data.into_json().unwrap(), json!({ "deleteAssetType": true }));
+    assert!(requests.iter().any(|(operation, _)| operation == "DeleteItem"));
+}
+
+#[tokio::test]
+async fn test_asset_type_in_use_is_not_deleted() {
+    let (response, requests) = execute(
+        2,
+        r#"mutation { deleteAssetType(id: "asset_type-1") }"#
+    ).await;
+
+    assert_eq!(response.errors.len(), 1);
+    let extensions = response.errors[0].extensions.as_ref().expect("code extension");
+    assert_eq!(extensions.get("code"), Some(&async_graphql::Value::from("CONFLICT")));
+    assert!(requests.iter().all(|(operation, _)| operation != "DeleteItem"));
+}
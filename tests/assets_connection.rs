@@ -0,0 +1,203 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::{ collections::HashSet, sync::{ Arc, Mutex } };
+
+use async_graphql::Request;
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{ config::GraphQLConfig, create_schema, DbClient, GraphQLSchema };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+fn asset_item(i: usize) -> Value {
+    json!({
+        "id": { "S": format!("asset-{:02}", i) },
+        "name": { "S": format!("Pump {}", i) },
+        "asset_type_id": { "S": "type-1" },
+        "serial_number": { "S": format!("SN-{}", i) },
+        "model_number": { "S": "P1" },
+        "location_id": { "S": "loc-1" },
+        "manufacturer_id": { "S": "mfg-1" },
+        "current_status": { "S": if i.is_multiple_of(2) { "operational" } else { "down" } },
+        "maintenance_frequency": { "S": "monthly" }
+    })
+}
+
+/// Answers `Scan` and `Query` pages over `count` assets the way DynamoDB does: resuming
+/// after `ExclusiveStartKey` and returning a `LastEvaluatedKey` whenever `Limit` is reached.
+/// Every request body is recorded.
+fn dynamodb_stub(count: usize, requests: Arc<Mutex<Vec<Value>>>) -> Router {
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let requests = requests.clone();
+            async move {
+                let target = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+                requests.lock().unwrap().push(request.clone());
+
+                let mut items: Vec<Value> = (0..count).map(asset_item).collect();
+                if target.ends_with(".Query") {
+                    let status = &request["ExpressionAttributeValues"][":pk"];
+                    items.retain(|item| item["current_status"] == *status);
+                }
+
+                let start = match request["ExclusiveStartKey"]["id"]["S"].as_str() {
+                    Some(id) => items.iter().position(|item| item["id"]["S"] == id).unwrap() + 1,
+                    None => 0,
+                };
+                let limit = request["Limit"].as_u64().map_or(items.len(), |l| l as usize);
+                let page: Vec<Value> = items.iter().skip(start).take(limit).cloned().collect();
+
+                let mut response = json!({ "Items": page, "Count": page.len() });
+                if page.len() == limit {
+                    let last = page.last().unwrap();
+                    let mut key = json!({ "id": last["id"] });
+                    if target.ends_with(".Query") {
+                        key["current_status"] = last["current_status"].clone();
+                    }
+                    response["LastEvaluatedKey"] = key;
+                }
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+fn schema_for(address: std::net::SocketAddr) -> GraphQLSchema {
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish()
+}
+
+/// Follows `endCursor` until `hasNextPage` is false, returning the ids of every page
+async fn page_through(schema: &GraphQLSchema, status: Option<&str>) -> Vec<Vec<String>> {
+    let mut pages = Vec::new();
+    let mut after: Option<String> = None;
+
+    loop {
+        let mut arguments = vec!["first: 10".to_string()];
+        if let Some(after) = &after {
+            arguments.push(format!("after: \"{}\"", after));
+        }
+        if let Some(status) = status {
+            arguments.push(format!("status: \"{}\"", status));
+        }
+        let query = format!(
+            "{{ assetsConnection({}) {{ \
+                edges {{ node {{ id }} }} pageInfo {{ hasNextPage endCursor }} \
+            }} }}",
+            arguments.join(", ")
+        );
+
+        let response = schema.execute(Request::new(query)).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        let connection = &data["assetsConnection"];
+
+        pages.push(
+            connection["edges"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|edge| edge["node"]["id"].as_str().unwrap().to_string())
+                .collect()
+        );
+
+        if connection["pageInfo"]["hasNextPage"] != true {
+            break;
+        }
+        after = Some(connection["pageInfo"]["endCursor"].as_str().unwrap().to_string());
+    }
+
+    pages
+}
+
+#[tokio::test]
+async fn test_paging_through_assets_visits_each_once() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(30, requests.clone());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let pages = page_through(&schema_for(address), None).await;
+
+    server.abort();
+
+    assert!(pages.iter().all(|page| page.len() <= 10));
+    let ids: Vec<String> = pages.into_iter().flatten().collect();
+    let expected: Vec<String> = (0..30).map(|i| format!("asset-{:02}", i)).collect();
+    assert_eq!(ids, expected);
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 30);
+}
+
+#[tokio::test]
+async fn test_status_filter_pages_through_status_index() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(30, requests.clone());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let pages = page_through(&schema_for(address), Some("down")).await;
+
+    server.abort();
+
+    let ids: Vec<String> = pages.into_iter().flatten().collect();
+    let expected: Vec<String> = (0..30_usize)
+        .filter(|i| !i.is_multiple_of(2))
+        .map(|i| format!("asset-{:02}", i))
+        .collect();
+    assert_eq!(ids, expected);
+
+    let requests = requests.lock().unwrap();
+    assert!(requests.iter().all(|request| request["IndexName"] == "StatusIndex"));
+}
+
+#[tokio::test]
+async fn test_page_size_is_capped() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(150, requests.clone());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let response = schema_for(address).execute(
+        Request::new("{ assetsConnection(first: 1000000) { edges { node { id } } } }")
+    ).await;
+
+    server.abort();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+    assert_eq!(data["assetsConnection"]["edges"].as_array().unwrap().len(), 100);
+    assert_eq!(requests.lock().unwrap()[0]["Limit"], 100);
+}
@@ -0,0 +1,169 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use async_graphql::{ Request, Response, Value as GraphQLValue };
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{
+    auth::{ guard::VIEW_AUDIT_EVENTS, AuthUser },
+    config::GraphQLConfig,
+    create_schema,
+    DbClient,
+};
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+fn audit_item(id: &str, created_at: &str) -> Value {
+    json!({
+        "id": { "S": id },
+        "actor_user_id": { "S": "admin-1" },
+        "action": { "S": "user.suspended" },
+        "target_type": { "S": "user" },
+        "target_id": { "S": "user-7" },
+        "created_at": { "S": created_at }
+    })
+}
+
+/// Answers like a table holding three events against `user-7`, stored out of order,
+/// with only the `auditor` role granted `VIEW_AUDIT_EVENTS`
+fn dynamodb_stub() -> Router {
+    Router::new().route(
+        "/",
+        post(|headers: HeaderMap, body: String| async move {
+            let operation = headers
+                .get("x-amz-target")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.rsplit('.').next())
+                .unwrap_or_default()
+                .to_string();
+            let request: Value = serde_json::from_str(&body).unwrap();
+
+            let response = match operation.as_str() {
+                "Query" => {
+                    assert_eq!(request["IndexName"], "TargetIndex");
+                    assert_eq!(request["ExpressionAttributeValues"][":pk"]["S"], "user-7");
+                    json!({
+                        "Items": [
+                            audit_item("audit-2", "2026-03-02T09:00:00.000000000Z"),
+                            audit_item("audit-3", "2026-03-03T09:00:00.000000000Z"),
+                            audit_item("audit-1", "2026-03-01T09:00:00.000000000Z")
+                        ],
+                        "Count": 3
+                    })
+                }
+                "BatchGetItem" => {
+                    let roles: Vec<Value> = request["RequestItems"]["Roles"]["Keys"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|key| key["id"]["S"].as_str().unwrap())
+                        .map(|id| {
+                            let mut role = json!({
+                                "id": { "S": id },
+                                "name": { "S": id },
+                                "role_type": { "S": "custom" },
+                                "is_system_role": { "BOOL": false }
+                            });
+                            if id == "auditor" {
+                                role["permission_ids"] = json!({ "SS": [VIEW_AUDIT_EVENTS] });
+                            }
+                            role
+                        })
+                        .collect();
+                    json!({ "Responses": { "Roles": roles }, "UnprocessedKeys": {} })
+                }
+                _ => json!({}),
+            };
+
+            ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+        })
+    )
+}
+
+async fn execute(requests: Vec<Request>) -> Vec<Response> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move { axum::serve(listener, dynamodb_stub()).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let mut responses = Vec::new();
+    for request in requests {
+        responses.push(schema.execute(request).await);
+    }
+
+    server.abort();
+    responses
+}
+
+fn query(limit: Option<i32>, caller_role: Option<&str>) -> Request {
+    let limit = limit.map(|limit| format!(", limit: {}", limit)).unwrap_or_default();
+    let request = Request::new(
+        format!(r#"{{ auditEventsForTarget(targetId: "user-7"{}) {{ id }} }}"#, limit)
+    );
+    match caller_role {
+        Some(role) =>
+            request.data(AuthUser {
+                user_id: "user-1".to_string(),
+                roles: vec![role.to_string()],
+            }),
+        None => request,
+    }
+}
+
+fn ids(response: &Response) -> Vec<String> {
+    response.data
+        .clone()
+        .into_json()
+        .unwrap()["auditEventsForTarget"].as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_events_for_target_are_newest_first() {
+    let responses = execute(
+        vec![query(None, Some("auditor")), query(Some(2), Some("auditor"))]
+    ).await;
+    let (all, latest) = (&responses[0], &responses[1]);
+
+    assert!(all.errors.is_empty(), "{:?}", all.errors);
+    assert_eq!(ids(all), vec!["audit-3", "audit-2", "audit-1"]);
+    assert_eq!(ids(latest), vec!["audit-3", "audit-2"]);
+}
+
+#[tokio::test]
+async fn test_events_for_target_require_permission() {
+    let code = |response: &Response| {
+        response.errors[0].extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code").cloned())
+    };
+
+    let responses = execute(vec![query(None, None), query(None, Some("viewer"))]).await;
+
+    assert_eq!(code(&responses[0]), Some(GraphQLValue::from("UNAUTHORIZED")));
+    assert_eq!(code(&responses[1]), Some(GraphQLValue::from("FORBIDDEN")));
+}
@@ -0,0 +1,98 @@
+//! Runs against local DynamoDB; skipped unless `DB_URL` points at an instance.
+
+use std::{ collections::HashMap, sync::LazyLock };
+
+use aws_sdk_dynamodb::types::{
+    AttributeDefinition,
+    AttributeValue,
+    BillingMode,
+    KeySchemaElement,
+    KeyType,
+    ScalarAttributeType,
+};
+use ore_dock_cmms_lambda::{
+    db::{ common::wait_until_active, local::setup_local_client },
+    repository::BatchWriteSummary,
+    DynamoDbEntity,
+    Repository,
+};
+use uuid::Uuid;
+
+static TABLE_NAME: LazyLock<String> = LazyLock::new(|| {
+    format!("BatchWriteTest-{}", Uuid::new_v4())
+});
+
+#[derive(Clone, Debug)]
+struct Item {
+    id: String,
+}
+
+impl DynamoDbEntity for Item {
+    fn table_name() -> &'static str {
+        TABLE_NAME.as_str()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        Some(Self { id: item.get("id")?.as_s().ok()?.clone() })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        HashMap::from([("id".to_string(), AttributeValue::S(self.id.clone()))])
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+}
+
+#[tokio::test]
+async fn test_batch_write_chunks_sixty_items_and_all_land() {
+    if std::env::var("DB_URL").is_err() {
+        eprintln!("DB_URL not set, skipping local DynamoDB test");
+        return;
+    }
+
+    let client = setup_local_client().await.unwrap();
+
+    client
+        .create_table()
+        .table_name(Item::table_name())
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(
+            AttributeDefinition::builder()
+                .attribute_name("id")
+                .attribute_type(ScalarAttributeType::S)
+                .build()
+                .unwrap()
+        )
+        .key_schema(
+            KeySchemaElement::builder()
+                .attribute_name("id")
+                .key_type(KeyType::Hash)
+                .build()
+                .unwrap()
+        )
+        .send().await
+        .unwrap();
+    wait_until_active(&client, Item::table_name()).await.unwrap();
+
+    let repo = Repository::new(client.clone());
+    let ids: Vec<String> = (0..60).map(|i| format!("item-{:02}", i)).collect();
+    let items: Vec<Item> = ids
+        .iter()
+        .map(|id| Item { id: id.clone() })
+        .collect();
+
+    let written = repo.batch_write(items, Vec::new()).await;
+    let stored = repo.batch_get::<Item>(ids.clone()).await;
+
+    let deleted = repo.batch_write(Vec::<Item>::new(), ids.clone()).await;
+    let remaining = repo.batch_get::<Item>(ids).await;
+
+    client.delete_table().table_name(Item::table_name()).send().await.unwrap();
+
+    assert_eq!(written.unwrap(), BatchWriteSummary { put: 60, deleted: 0 });
+    assert_eq!(stored.unwrap().len(), 60);
+    assert_eq!(deleted.unwrap(), BatchWriteSummary { put: 0, deleted: 60 });
+    assert!(remaining.unwrap().is_empty());
+}
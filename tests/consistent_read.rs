@@ -0,0 +1,136 @@
+use std::{ collections::HashMap, sync::{ Arc, LazyLock, Mutex } };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    types::{
+        AttributeDefinition,
+        AttributeValue,
+        BillingMode,
+        KeySchemaElement,
+        KeyType,
+        ScalarAttributeType,
+    },
+    Client,
+};
+use axum::{ routing::post, Router };
+use ore_dock_cmms_lambda::{
+    db::{ common::wait_until_active, local::setup_local_client },
+    DynamoDbEntity,
+    Repository,
+};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+static TABLE_NAME: LazyLock<String> = LazyLock::new(|| {
+    format!("ConsistentReadTest-{}", Uuid::new_v4())
+});
+
+#[derive(Clone, Debug)]
+struct Item {
+    id: String,
+    label: String,
+}
+
+impl DynamoDbEntity for Item {
+    fn table_name() -> &'static str {
+        TABLE_NAME.as_str()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        Some(Self {
+            id: item.get("id")?.as_s().ok()?.clone(),
+            label: item.get("label")?.as_s().ok()?.clone(),
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S(self.id.clone())),
+            ("label".to_string(), AttributeValue::S(self.label.clone())),
+        ])
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+}
+
+#[tokio::test]
+async fn test_get_consistent_sends_consistent_read() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |body: String| async move {
+            recorded.lock().unwrap().push(body);
+            ([("content-type", "application/x-amz-json-1.0")], "{}")
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let repo = Repository::new(Client::from_conf(config));
+
+    let consistent = repo.get_consistent::<Item>("item-1".to_string()).await;
+    let eventual = repo.get::<Item>("item-1".to_string()).await;
+
+    server.abort();
+
+    assert!(consistent.unwrap().is_none());
+    assert!(eventual.unwrap().is_none());
+    let requests = requests.lock().unwrap();
+    assert!(requests[0].contains(r#""ConsistentRead":true"#), "{}", requests[0]);
+    assert!(!requests[1].contains(r#""ConsistentRead":true"#), "{}", requests[1]);
+}
+
+/// Runs against local DynamoDB; skipped unless `DB_URL` points at an instance.
+#[tokio::test]
+async fn test_consistent_read_after_update_returns_new_value() {
+    if std::env::var("DB_URL").is_err() {
+        eprintln!("DB_URL not set, skipping local DynamoDB test");
+        return;
+    }
+
+    let client = setup_local_client().await.unwrap();
+
+    client
+        .create_table()
+        .table_name(Item::table_name())
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(
+            AttributeDefinition::builder()
+                .attribute_name("id")
+                .attribute_type(ScalarAttributeType::S)
+                .build()
+                .unwrap()
+        )
+        .key_schema(
+            KeySchemaElement::builder()
+                .attribute_name("id")
+                .key_type(KeyType::Hash)
+                .build()
+                .unwrap()
+        )
+        .send().await
+        .unwrap();
+    wait_until_active(&client, Item::table_name()).await.unwrap();
+
+    let repo = Repository::new(client.clone());
+    let item = Item { id: "item-1".to_string(), label: "before".to_string() };
+
+    repo.create(item.clone()).await.unwrap();
+    repo.update(Item { label: "after".to_string(), ..item }).await.unwrap();
+    let stored = repo.get_consistent::<Item>("item-1".to_string()).await;
+
+    client.delete_table().table_name(Item::table_name()).send().await.unwrap();
+
+    assert_eq!(stored.unwrap().unwrap().label, "after");
+}
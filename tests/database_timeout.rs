@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use ore_dock_cmms_lambda::{
+    config::DatabaseConfig,
+    db::connect::apply_database_config,
+    models::role::Role,
+    AppError,
+    Repository,
+};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn test_slow_endpoint_times_out_instead_of_hanging() {
+    // Accepts connections but never answers, like a DynamoDB endpoint that has stalled
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let stalled = tokio::spawn(async move {
+        let mut open = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            open.push(socket);
+        }
+    });
+
+    let database = DatabaseConfig {
+        region: "us-east-2".to_string(),
+        endpoint: Some(format!("http://{}", address)),
+        max_retries: 0,
+        operation_timeout_ms: 200,
+        max_scan_items: 10_000,
+        table_prefix: None,
+    };
+
+    let builder = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new(database.region.clone()))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(database.endpoint.clone().unwrap());
+    let client = Client::from_conf(apply_database_config(builder, &database).build());
+
+    let result = tokio::time
+        ::timeout(
+            Duration::from_secs(5),
+            Repository::new(client).get::<Role>("role-1".to_string())
+        ).await
+        .expect("DynamoDB call hung past the operation timeout");
+
+    stalled.abort();
+
+    match result {
+        Err(AppError::DatabaseError(msg)) => assert!(msg.contains("timeout"), "{}", msg),
+        other => panic!("expected a DatabaseError timeout, got {:?}", other),
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::{ Arc, Mutex };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::{ HeaderMap, StatusCode }, routing::post, Router };
+use ore_dock_cmms_lambda::{ db::migrations::migrate_due_date_sort_keys, Repository };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+const CONDITIONAL_CHECK_FAILED: &str =
+    "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException";
+
+fn schedule_item(id: &str, next_due_date: &str) -> Value {
+    json!({
+        "id": { "S": id },
+        "asset_id": { "S": "asset-1" },
+        "cadences": { "L": [] },
+        "active": { "BOOL": true },
+        "next_due_at": { "S": "2026-03-01T09:00:00.000000000Z" },
+        "due_partition": { "S": "due" },
+        "next_due_date": { "S": next_due_date }
+    })
+}
+
+#[tokio::test]
+async fn test_only_whole_second_sort_keys_are_rewritten() {
+    let stored = vec![
+        schedule_item("old-schedule", "2026-03-01T09:00:00Z"),
+        schedule_item("new-schedule", "2026-03-01T09:00:00.000000000Z")
+    ];
+    let updates = Arc::new(Mutex::new(Vec::new()));
+    let recorded = updates.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let stored = stored.clone();
+            let recorded = recorded.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+
+                let (status, response) = match operation.as_str() {
+                    "Query" => {
+                        assert_eq!(request["IndexName"], "DueDateIndex");
+                        (StatusCode::OK, json!({ "Items": stored, "Count": stored.len() }))
+                    }
+                    "UpdateItem" => {
+                        let item = stored
+                            .iter()
+                            .find(|item| item["id"] == request["Key"]["id"])
+                            .unwrap();
+                        let sort_key = &request["ExpressionAttributeValues"][":next_due_date"];
+                        if item["next_due_date"] == *sort_key {
+                            (
+                                StatusCode::BAD_REQUEST,
+                                json!({
+                                    "__type": CONDITIONAL_CHECK_FAILED,
+                                    "message": "The conditional request failed"
+                                }),
+                            )
+                        } else {
+                            let item = item.clone();
+                            recorded.lock().unwrap().push(request);
+                            (StatusCode::OK, json!({ "Attributes": item }))
+                        }
+                    }
+                    _ => (StatusCode::OK, json!({})),
+                };
+
+                (status, [("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let migrated = migrate_due_date_sort_keys(
+        &Repository::new(Client::from_conf(config))
+    ).await.unwrap();
+
+    server.abort();
+
+    assert_eq!(migrated, 1);
+    let updates = updates.lock().unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0]["Key"]["id"]["S"], "old-schedule");
+    assert_eq!(
+        updates[0]["ExpressionAttributeValues"][":next_due_date"]["S"],
+        "2026-03-01T09:00:00.000000000Z"
+    );
+}
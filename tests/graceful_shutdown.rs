@@ -0,0 +1,60 @@
+use std::{ sync::Arc, time::Duration };
+
+use axum::{ routing::get, Router };
+use ore_dock_cmms_lambda::shutdown::serve_with_graceful_shutdown;
+use tokio::{ net::{ TcpListener, TcpStream }, sync::{ oneshot, Notify } };
+
+#[tokio::test]
+async fn test_shutdown_refuses_new_connections_but_finishes_in_flight_request() {
+    let entered = Arc::new(Notify::new());
+    let release = Arc::new(Notify::new());
+
+    let app = {
+        let (entered, release) = (entered.clone(), release.clone());
+        Router::new().route(
+            "/slow",
+            get(move || async move {
+                entered.notify_one();
+                release.notified().await;
+                "done"
+            })
+        )
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(
+        serve_with_graceful_shutdown(
+            listener,
+            app,
+            async {
+                let _ = shutdown_rx.await;
+            },
+            Duration::from_secs(5)
+        )
+    );
+
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+    let in_flight = tokio::spawn(client.get(format!("http://{}/slow", address)).send());
+
+    entered.notified().await;
+    shutdown_tx.send(()).unwrap();
+
+    // The accept loop stops asynchronously after the signal
+    let refused = tokio::time::timeout(Duration::from_secs(2), async {
+        while TcpStream::connect(address).await.is_ok() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }).await;
+    assert!(refused.is_ok(), "server kept accepting connections after shutdown");
+
+    release.notify_one();
+
+    let response = in_flight.await.unwrap().unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), "done");
+
+    tokio::time::timeout(Duration::from_secs(2), server).await.unwrap().unwrap().unwrap();
+}
@@ -0,0 +1,46 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use ore_dock_cmms_lambda::{ config::GraphQLConfig, create_schema };
+
+fn graphql_config(introspection: bool) -> GraphQLConfig {
+    GraphQLConfig {
+        playground: false,
+        introspection,
+        complexity_limit: None,
+        depth_limit: None,
+    }
+}
+
+const SCHEMA_QUERY: &str = "{ __schema { queryType { name } } }";
+
+// async-graphql resolves disabled introspection fields to null instead of raising an error
+#[tokio::test]
+async fn test_schema_query_fails_with_introspection_disabled() {
+    let schema = create_schema(&graphql_config(false)).finish();
+
+    let response = schema.execute(SCHEMA_QUERY).await;
+
+    assert!(response.data.into_json().unwrap()["__schema"].is_null());
+}
+
+#[tokio::test]
+async fn test_schema_query_succeeds_with_introspection_enabled() {
+    let schema = create_schema(&graphql_config(true)).finish();
+
+    let response = schema.execute(SCHEMA_QUERY).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap()["__schema"]["queryType"]["name"],
+        "QueryRoot"
+    );
+}
+
+#[tokio::test]
+async fn test_timestamp_scalar_does_not_shadow_datetime() {
+    let sdl = create_schema(&graphql_config(true)).finish().sdl();
+
+    assert!(sdl.contains("scalar Timestamp"), "{}", sdl);
+    assert!(sdl.contains("scalar DateTime"), "{}", sdl);
+}
@@ -0,0 +1,71 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use async_graphql::Value;
+use ore_dock_cmms_lambda::{
+    config::GraphQLConfig,
+    create_schema,
+    error::{ QUERY_TOO_COMPLEX, QUERY_TOO_DEEP },
+};
+
+fn graphql_config(depth_limit: Option<usize>, complexity_limit: Option<usize>) -> GraphQLConfig {
+    GraphQLConfig {
+        playground: false,
+        introspection: true,
+        complexity_limit,
+        depth_limit,
+    }
+}
+
+// Six levels deep: __schema > types > fields > type > ofType > name
+const DEEP_QUERY: &str = "{ __schema { types { fields { type { ofType { name } } } } } }";
+
+#[tokio::test]
+async fn test_query_deeper_than_limit_is_rejected() {
+    let schema = create_schema(&graphql_config(Some(3), None)).finish();
+
+    let response = schema.execute(DEEP_QUERY).await;
+
+    assert!(response.data.into_json().unwrap().is_null());
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("nested too deep"), "{:?}", response.errors);
+}
+
+#[tokio::test]
+async fn test_query_deeper_than_limit_carries_code() {
+    let schema = create_schema(&graphql_config(Some(3), None)).finish();
+
+    let response = schema.execute(DEEP_QUERY).await;
+
+    let extensions = response.errors[0].extensions.as_ref().expect("code extension");
+    assert_eq!(extensions.get("code"), Some(&Value::from(QUERY_TOO_DEEP)));
+}
+
+#[tokio::test]
+async fn test_query_more_complex_than_limit_is_rejected() {
+    let schema = create_schema(&graphql_config(None, Some(3))).finish();
+
+    let response = schema.execute(DEEP_QUERY).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("too complex"), "{:?}", response.errors);
+}
+
+#[tokio::test]
+async fn test_query_more_complex_than_limit_carries_code() {
+    let schema = create_schema(&graphql_config(None, Some(3))).finish();
+
+    let response = schema.execute(DEEP_QUERY).await;
+
+    let extensions = response.errors[0].extensions.as_ref().expect("code extension");
+    assert_eq!(extensions.get("code"), Some(&Value::from(QUERY_TOO_COMPLEX)));
+}
+
+#[tokio::test]
+async fn test_query_within_limits_executes() {
+    let schema = create_schema(&graphql_config(Some(10), Some(1000))).finish();
+
+    let response = schema.execute(DEEP_QUERY).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+}
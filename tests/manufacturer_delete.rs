@@ -0,0 +1,124 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::sync::{ Arc, Mutex };
+
+use async_graphql::{ Response, Value as GraphQLValue };
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{ config::GraphQLConfig, create_schema, DbClient };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+/// Answers like a table holding `manufacturer-1` and `asset_count` assets made by it,
+/// recording every operation called
+fn dynamodb_stub(asset_count: u64, operations: Arc<Mutex<Vec<String>>>) -> Router {
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let operations = operations.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+                operations.lock().unwrap().push(operation.clone());
+
+                let response = match operation.as_str() {
+                    "GetItem" =>
+                        json!({
+                            "Item": {
+                                "id": { "S": "manufacturer-1" },
+                                "name": { "S": "Acme Pumps" },
+                                "phone": { "S": "+15555550100" },
+                                "email": { "S": "support@acme.example" },
+                                "address": {
+                                    "M": {
+                                        "street": { "S": "1 Main St" },
+                                        "city": { "S": "Duluth" },
+                                        "state": { "S": "MN" },
+                                        "country": { "S": "US" },
+                                        "zip": { "S": "55802" }
+                                    }
+                                }
+                            }
+                        }),
+                    "Query" => {
+                        assert_eq!(request["IndexName"], "ManufacturerIndex");
+                        json!({ "Count": asset_count, "ScannedCount": asset_count })
+                    }
+                    _ => json!({}),
+                };
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+async fn execute(asset_count: u64, query: &str) -> (Response, Vec<String>) {
+    let operations = Arc::new(Mutex::new(Vec::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(asset_count, operations.clone());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let response = schema.execute(query).await;
+
+    server.abort();
+
+    let operations = operations.lock().unwrap().clone();
+    (response, operations)
+}
+
+#[tokio::test]
+async fn test_manufacturer_with_stored_assets_is_not_deleted() {
+    // `force` is still accepted but no longer overrides the guard
+    let (response, operations) = execute(
+        2,
+        r#"mutation { deleteManufacturer(id: "manufacturer-1", force: true) }"#
+    ).await;
+
+    assert_eq!(
+        response.errors[0].extensions.as_ref().and_then(|extensions| extensions.get("code").cloned()),
+        Some(GraphQLValue::from("CONFLICT"))
+    );
+    assert!(!operations.contains(&"DeleteItem".to_string()));
+}
+
+#[tokio::test]
+async fn test_unreferenced_manufacturer_is_deleted() {
+    let (response, operations) = execute(
+        0,
+        r#"mutation { deleteManufacturer(id: "manufacturer-1") }"#
+    ).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert!(operations.contains(&"DeleteItem".to_string()));
+}
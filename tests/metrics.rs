@@ -0,0 +1,107 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use async_graphql::Request;
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ extract::Extension, http::StatusCode, routing::{ get, post }, Router };
+use ore_dock_cmms_lambda::{
+    auth::{ guard::VIEW_METRICS, AuthUser },
+    config::{ Config, GraphQLConfig },
+    create_schema,
+    telemetry,
+    DbClient,
+};
+use serde_json::json;
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn test_metrics_endpoint_counts_graphql_operations() {
+    let handle = telemetry::install_recorder().unwrap();
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    ).finish();
+    let response = schema.execute(
+        Request::new("query MetricsProbe { __typename }").operation_name("MetricsProbe")
+    ).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    // The scraper's role grants VIEW_METRICS
+    let roles = json!({
+        "Responses": {
+            "Roles": [{
+                "id": { "S": "scraper" },
+                "name": { "S": "scraper" },
+                "role_type": { "S": "custom" },
+                "permission_ids": { "SS": [VIEW_METRICS] }
+            }]
+        },
+        "UnprocessedKeys": {}
+    }).to_string();
+    let dynamodb_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let dynamodb_address = dynamodb_listener.local_addr().unwrap();
+    let dynamodb = Router::new().route(
+        "/",
+        post(move || async move { ([("content-type", "application/x-amz-json-1.0")], roles) })
+    );
+    let dynamodb_server = tokio::spawn(async move {
+        axum::serve(dynamodb_listener, dynamodb).await
+    });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", dynamodb_address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let scraper = Router::new()
+        .route("/scraper/metrics", get(telemetry::metrics_handler))
+        .layer(
+            Extension(AuthUser {
+                user_id: "prometheus".to_string(),
+                roles: vec!["scraper".to_string()],
+            })
+        );
+    let app = Router::new()
+        .route("/metrics", get(telemetry::metrics_handler))
+        .merge(scraper)
+        .layer(Extension(handle))
+        .layer(Extension(db_client))
+        .layer(Extension(Config::default()));
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+    let anonymous = client.get(format!("http://{}/metrics", address)).send().await.unwrap();
+    let body = client
+        .get(format!("http://{}/scraper/metrics", address))
+        .send().await
+        .unwrap()
+        .text().await
+        .unwrap();
+
+    server.abort();
+    dynamodb_server.abort();
+
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+    assert!(
+        body.contains(
+            &format!(r#"{}{{operation_type="query"}} 1"#, telemetry::GRAPHQL_REQUESTS_TOTAL)
+        ),
+        "{}",
+        body
+    );
+    assert!(!body.contains("MetricsProbe"), "{}", body);
+    assert!(body.contains(telemetry::GRAPHQL_REQUEST_DURATION_SECONDS), "{}", body);
+}
@@ -0,0 +1,89 @@
+use std::{ collections::{ HashMap, HashSet }, sync::{ Arc, Mutex } };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    types::AttributeValue,
+    Client,
+};
+use axum::{ http::StatusCode, routing::post, Router };
+use ore_dock_cmms_lambda::{
+    models::vendor_category::VendorCategory,
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+const CONDITIONAL_CHECK_FAILED: &str =
+    "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException";
+
+/// Answers `PutItem` like a table honouring `attribute_not_exists(id)`
+fn dynamodb_stub(stored_ids: Arc<Mutex<HashSet<String>>>) -> Router {
+    Router::new().route(
+        "/",
+        post(move |body: String| {
+            let stored_ids = stored_ids.clone();
+            async move {
+                let request: Value = serde_json::from_str(&body).unwrap();
+                assert_eq!(request["ConditionExpression"], "attribute_not_exists(id)");
+
+                let id = request["Item"]["id"]["S"].as_str().unwrap().to_string();
+                let (status, response) = if stored_ids.lock().unwrap().insert(id) {
+                    (StatusCode::OK, json!({}))
+                } else {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        json!({
+                            "__type": CONDITIONAL_CHECK_FAILED,
+                            "message": "The conditional request failed"
+                        }),
+                    )
+                };
+
+                (status, [("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+#[tokio::test]
+async fn test_create_with_existing_id_is_conflict() {
+    let stored_ids = Arc::new(Mutex::new(HashSet::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(stored_ids.clone());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let repo = Repository::new(Client::from_conf(config));
+
+    let category = VendorCategory::from_item(
+        &HashMap::from([
+            ("id".to_string(), AttributeValue::S("category-1".to_string())),
+            ("name".to_string(), AttributeValue::S("Hydraulics".to_string())),
+            ("category_type".to_string(), AttributeValue::S("service".to_string())),
+            ("status".to_string(), AttributeValue::S("active".to_string())),
+        ])
+    ).unwrap();
+
+    let first = repo.create(category.clone()).await;
+    let second = repo.create(category).await;
+
+    server.abort();
+
+    assert_eq!(first.unwrap().id, "category-1");
+    match second {
+        Err(AppError::Conflict(msg)) => {
+            assert_eq!(msg, "VendorCategories category-1 already exists");
+        }
+        other => panic!("expected a conflict, got {:?}", other.map(|c| c.id)),
+    }
+    assert_eq!(stored_ids.lock().unwrap().len(), 1);
+}
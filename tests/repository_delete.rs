@@ -0,0 +1,64 @@
+use std::sync::{ Arc, Mutex };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ routing::post, Router };
+use ore_dock_cmms_lambda::{ models::vendor_category::VendorCategory, Repository };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+/// Deletes `category-1` against a stub answering `DeleteItem` with `response`
+///
+/// # Returns
+///
+/// The result of `delete` and the `DeleteItem` request body the stub received
+async fn delete_with_response(response: Value) -> (bool, Value) {
+    let request = Arc::new(Mutex::new(Value::Null));
+    let received = request.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |body: String| {
+            *received.lock().unwrap() = serde_json::from_str(&body).unwrap();
+            let response = response.to_string();
+            async move { ([("content-type", "application/x-amz-json-1.0")], response) }
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let deleted = Repository::new(Client::from_conf(config))
+        .delete::<VendorCategory>("category-1".to_string()).await
+        .unwrap();
+
+    server.abort();
+    let request = request.lock().unwrap().clone();
+    (deleted, request)
+}
+
+#[tokio::test]
+async fn test_delete_reports_removed_item() {
+    let (deleted, request) = delete_with_response(
+        json!({ "Attributes": { "id": { "S": "category-1" } } })
+    ).await;
+
+    assert!(deleted);
+    assert_eq!(request["ReturnValues"], "ALL_OLD");
+}
+
+#[tokio::test]
+async fn test_delete_of_missing_id_is_false() {
+    // DynamoDB answers a delete of a missing key with no old attributes
+    let (deleted, _) = delete_with_response(json!({})).await;
+
+    assert!(!deleted);
+}
@@ -0,0 +1,65 @@
+use std::sync::{ Arc, Mutex };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ routing::post, Router };
+use ore_dock_cmms_lambda::{ models::vendor_category::VendorCategory, Repository };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+/// Checks `category-1` against a stub answering `GetItem` with `response`
+///
+/// # Returns
+///
+/// The result of `exists` and the `GetItem` request body the stub received
+async fn exists_with_response(response: Value) -> (bool, Value) {
+    let request = Arc::new(Mutex::new(Value::Null));
+    let received = request.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |body: String| {
+            *received.lock().unwrap() = serde_json::from_str(&body).unwrap();
+            let response = response.to_string();
+            async move { ([("content-type", "application/x-amz-json-1.0")], response) }
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let exists = Repository::new(Client::from_conf(config))
+        .exists::<VendorCategory>("category-1".to_string()).await
+        .unwrap();
+
+    server.abort();
+    let request = request.lock().unwrap().clone();
+    (exists, request)
+}
+
+#[tokio::test]
+async fn test_exists_projects_only_the_key() {
+    // Only the key comes back, which `VendorCategory::from_item` could not parse
+    let (exists, request) = exists_with_response(
+        json!({ "Item": { "id": { "S": "category-1" } } })
+    ).await;
+
+    assert!(exists);
+    assert_eq!(request["ProjectionExpression"], "#id");
+    assert_eq!(request["ExpressionAttributeNames"]["#id"], "id");
+}
+
+#[tokio::test]
+async fn test_exists_is_false_for_missing_item() {
+    let (exists, _) = exists_with_response(json!({})).await;
+
+    assert!(!exists);
+}
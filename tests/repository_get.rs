@@ -0,0 +1,58 @@
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ routing::post, Router };
+use ore_dock_cmms_lambda::{ models::vendor_category::VendorCategory, AppError, Repository };
+use serde_json::json;
+use tokio::net::TcpListener;
+
+/// Fetches `category-1` from a stub that stores it with the given `status`
+async fn get_category_with_status(status: &str) -> Result<Option<VendorCategory>, AppError> {
+    let body = json!({
+        "Item": {
+            "id": { "S": "category-1" },
+            "name": { "S": "Hydraulics" },
+            "category_type": { "S": "service" },
+            "status": { "S": status }
+        }
+    }).to_string();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move || async move { ([("content-type", "application/x-amz-json-1.0")], body) })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let result = Repository::new(Client::from_conf(config)).get::<VendorCategory>(
+        "category-1".to_string()
+    ).await;
+
+    server.abort();
+    result
+}
+
+#[tokio::test]
+async fn test_get_parses_stored_category() {
+    let category = get_category_with_status("active").await.unwrap();
+
+    assert_eq!(category.unwrap().id, "category-1");
+}
+
+#[tokio::test]
+async fn test_get_reports_unparseable_item_instead_of_none() {
+    match get_category_with_status("on_fire").await {
+        Err(AppError::DatabaseError(msg)) => {
+            assert_eq!(msg, "Failed to deserialize VendorCategories item category-1");
+        }
+        other => panic!("expected a deserialization error, got {:?}", other.map(|c| c.is_some())),
+    }
+}
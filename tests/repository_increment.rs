@@ -0,0 +1,50 @@
+use std::sync::{ Arc, Mutex };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ routing::post, Router };
+use ore_dock_cmms_lambda::{
+    models::counter::{ Counter, WORK_ORDER_NUMBER_COUNTER },
+    Repository,
+};
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn test_increment_adds_atomically_and_returns_new_value() {
+    let request = Arc::new(Mutex::new(Value::Null));
+    let received = request.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |body: String| {
+            *received.lock().unwrap() = serde_json::from_str(&body).unwrap();
+            let response = json!({ "Attributes": { "value": { "N": "42" } } }).to_string();
+            async move { ([("content-type", "application/x-amz-json-1.0")], response) }
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let value = Repository::new(Client::from_conf(config))
+        .increment::<Counter>(WORK_ORDER_NUMBER_COUNTER.to_string(), "value").await
+        .unwrap();
+
+    server.abort();
+
+    assert_eq!(value, 42);
+    let request = request.lock().unwrap();
+    assert_eq!(request["TableName"], "Counters");
+    assert_eq!(request["Key"]["id"]["S"], WORK_ORDER_NUMBER_COUNTER);
+    assert_eq!(request["UpdateExpression"], "ADD #attribute :one");
+    assert_eq!(request["ReturnValues"], "UPDATED_NEW");
+}
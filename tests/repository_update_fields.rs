@@ -0,0 +1,112 @@
+use std::{ collections::HashMap, sync::{ Arc, Mutex } };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    types::AttributeValue,
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{
+    models::vendor_category::{ CategoryStatus, VendorCategory },
+    Repository,
+};
+use serde_json::{ json, Map, Value };
+use tokio::net::TcpListener;
+
+/// Applies an `update_fields` request to `item` the way DynamoDB would
+///
+/// Only understands the `#fN = :fN` / `REMOVE #fN` / `ADD #version` shape the repository sends.
+fn apply_update(item: &mut Map<String, Value>, request: &Value) {
+    let names = request["ExpressionAttributeNames"].as_object().unwrap();
+    let values = request["ExpressionAttributeValues"].as_object().unwrap();
+
+    for (placeholder, attribute) in names {
+        let attribute = attribute.as_str().unwrap();
+        if attribute == "version" {
+            let version = item
+                .get("version")
+                .and_then(|v| v["N"].as_str())
+                .map_or(0, |n| n.parse::<u64>().unwrap());
+            item.insert("version".to_string(), json!({ "N": (version + 1).to_string() }));
+            continue;
+        }
+
+        match values.get(&placeholder.replacen('#', ":", 1)) {
+            Some(value) => {
+                item.insert(attribute.to_string(), value.clone());
+            }
+            None => {
+                item.remove(attribute);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_update_fields_keeps_attributes_written_by_others() {
+    let stored = Arc::new(
+        Mutex::new(
+            json!({
+                "id": { "S": "category-1" },
+                "name": { "S": "Hydraulics" },
+                "category_type": { "S": "service" },
+                "status": { "S": "active" }
+            })
+                .as_object()
+                .unwrap()
+                .clone()
+        )
+    );
+
+    let table = stored.clone();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let table = table.clone();
+            async move {
+                assert!(
+                    headers["x-amz-target"].to_str().unwrap().ends_with(".UpdateItem"),
+                    "update_fields must not rewrite the whole item"
+                );
+                let request: Value = serde_json::from_str(&body).unwrap();
+                let mut item = table.lock().unwrap();
+                apply_update(&mut item, &request);
+                let response = json!({ "Attributes": Value::Object(item.clone()) });
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+
+    // Someone else sets the description after our caller read the category
+    stored
+        .lock()
+        .unwrap()
+        .insert("description".to_string(), json!({ "S": "Pumps and cylinders" }));
+
+    let category = Repository::new(Client::from_conf(config))
+        .update_fields::<VendorCategory>(
+            "category-1".to_string(),
+            HashMap::from([("status".to_string(), AttributeValue::S("inactive".to_string()))])
+        ).await
+        .unwrap();
+
+    server.abort();
+
+    assert_eq!(category.description.as_deref(), Some("Pumps and cylinders"));
+    assert_eq!(category.status, CategoryStatus::Inactive);
+    assert!(
+        !stored.lock().unwrap().contains_key("version"),
+        "vendor categories are not versioned"
+    );
+}
@@ -0,0 +1,77 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use async_graphql::Request;
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ routing::post, Router };
+use ore_dock_cmms_lambda::{ config::GraphQLConfig, create_schema, DbClient, Repository };
+use serde_json::json;
+use tokio::net::TcpListener;
+
+/// One scan page holding a binary tree of `size` roles rooted at `role-0`
+fn role_tree_page(size: usize) -> String {
+    let items: Vec<_> = (0..size)
+        .map(|i| {
+            let mut item = json!({
+                "id": { "S": format!("role-{}", i) },
+                "name": { "S": format!("Role {}", i) },
+                "role_type": { "S": "custom" },
+            });
+            if i > 0 {
+                item["parent_role_id"] = json!({ "S": format!("role-{}", (i - 1) / 2) });
+            }
+            item
+        })
+        .collect();
+
+    json!({ "Items": items, "Count": size, "ScannedCount": size }).to_string()
+}
+
+async fn role_hierarchy_calls(size: usize) -> (usize, usize) {
+    let page = role_tree_page(size);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move || async move { ([("content-type", "application/x-amz-json-1.0")], page) })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let (response, calls) = Repository::with_call_counter(
+        schema.execute(Request::new(r#"{ roleHierarchy(rootRoleId: "role-0") { id } }"#))
+    ).await;
+
+    server.abort();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let returned = response.data.into_json().unwrap()["roleHierarchy"].as_array().unwrap().len();
+    (returned, calls)
+}
+
+#[tokio::test]
+async fn test_role_hierarchy_loads_roles_once_regardless_of_tree_size() {
+    assert_eq!(role_hierarchy_calls(3).await, (3, 1));
+    assert_eq!(role_hierarchy_calls(25).await, (25, 1));
+}
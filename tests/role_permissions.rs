@@ -0,0 +1,283 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::{ collections::BTreeSet, sync::{ Arc, Mutex } };
+
+use async_graphql::{ Request, Response, Value as GraphQLValue };
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{
+    auth::{ guard::{ MANAGE_ROLES, MANAGE_SYSTEM_ROLES }, AuthUser },
+    config::GraphQLConfig,
+    create_schema,
+    DbClient,
+};
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+/// `role-1` as stored, plus every `UpdateItem` expression and condition applied to it
+#[derive(Default)]
+struct Table {
+    is_system_role: bool,
+    permission_ids: BTreeSet<String>,
+    update_expressions: Vec<String>,
+    condition_expressions: Vec<String>,
+}
+
+fn role_item(id: &str, is_system_role: bool, permission_ids: &BTreeSet<String>) -> Value {
+    let mut item = json!({
+        "id": { "S": id },
+        "name": { "S": id },
+        "role_type": { "S": "custom" },
+        "is_system_role": { "BOOL": is_system_role }
+    });
+    if !permission_ids.is_empty() {
+        item["permission_ids"] = json!({ "SS": permission_ids });
+    }
+    item
+}
+
+/// Answers like a table holding `role-1`, permission `perm-1` and the caller roles
+/// `admin` (holding `MANAGE_ROLES` and `MANAGE_SYSTEM_ROLES`), `manager` (holding only
+/// `MANAGE_ROLES`) and `viewer` (holding nothing)
+fn dynamodb_stub(table: Arc<Mutex<Table>>) -> Router {
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let table = table.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+                let mut table = table.lock().unwrap();
+
+                let response = match operation.as_str() {
+                    "GetItem" if request["TableName"] == "Roles" =>
+                        json!({
+                            "Item": role_item("role-1", table.is_system_role, &table.permission_ids)
+                        }),
+                    "GetItem" if request["Key"]["id"]["S"] == "perm-1" => {
+                        json!({ "Item": { "id": { "S": "perm-1" } } })
+                    }
+                    "UpdateItem" => {
+                        let expression = request["UpdateExpression"].as_str().unwrap().to_string();
+                        let values = &request["ExpressionAttributeValues"][":permission_ids"]["SS"];
+                        for id in values.as_array().unwrap() {
+                            let id = id.as_str().unwrap().to_string();
+                            if expression.starts_with("ADD") {
+                                table.permission_ids.insert(id);
+                            } else {
+                                table.permission_ids.remove(&id);
+                            }
+                        }
+                        table.update_expressions.push(expression);
+                        table.condition_expressions.push(
+                            request["ConditionExpression"].as_str().unwrap().to_string()
+                        );
+                        json!({
+                            "Attributes": role_item(
+                                "role-1",
+                                table.is_system_role,
+                                &table.permission_ids
+                            )
+                        })
+                    }
+                    "BatchGetItem" => {
+                        let admin = BTreeSet::from([
+                            MANAGE_ROLES.to_string(),
+                            MANAGE_SYSTEM_ROLES.to_string(),
+                        ]);
+                        let manager = BTreeSet::from([MANAGE_ROLES.to_string()]);
+                        let roles: Vec<Value> = request["RequestItems"]["Roles"]["Keys"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|key| key["id"]["S"].as_str().unwrap())
+                            .map(|id| {
+                                match id {
+                                    "admin" => role_item(id, true, &admin),
+                                    "manager" => role_item(id, false, &manager),
+                                    _ => role_item(id, false, &BTreeSet::new()),
+                                }
+                            })
+                            .collect();
+                        json!({ "Responses": { "Roles": roles }, "UnprocessedKeys": {} })
+                    }
+                    _ => json!({}),
+                };
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+async fn execute(table: Arc<Mutex<Table>>, requests: Vec<Request>) -> Vec<Response> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(table);
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let mut responses = Vec::new();
+    for request in requests {
+        responses.push(schema.execute(request).await);
+    }
+
+    server.abort();
+    responses
+}
+
+fn grant(caller_role: Option<&str>) -> Request {
+    as_caller(
+        Request::new(
+            r#"mutation {
+                grantPermissionToRole(roleId: "role-1", permissionId: "perm-1") { permissionIds }
+            }"#
+        ),
+        caller_role
+    )
+}
+
+fn as_caller(request: Request, caller_role: Option<&str>) -> Request {
+    match caller_role {
+        Some(role) =>
+            request.data(AuthUser {
+                user_id: "user-1".to_string(),
+                roles: vec![role.to_string()],
+            }),
+        None => request,
+    }
+}
+
+#[tokio::test]
+async fn test_grant_adds_permission_to_role_set() {
+    let table = Arc::new(Mutex::new(Table::default()));
+
+    let responses = execute(table.clone(), vec![grant(Some("manager"))]).await;
+
+    assert!(responses[0].errors.is_empty(), "{:?}", responses[0].errors);
+    assert_eq!(
+        responses[0].data.clone().into_json().unwrap(),
+        json!({ "grantPermissionToRole": { "permissionIds": ["perm-1"] } })
+    );
+    let table = table.lock().unwrap();
+    assert!(table.update_expressions[0].starts_with("ADD #permission_ids"));
+    // Without MANAGE_SYSTEM_ROLES the write must not land if the role became a system role
+    assert!(table.condition_expressions[0].contains("#is_system_role = :false"));
+}
+
+#[tokio::test]
+async fn test_regranting_permission_is_idempotent() {
+    let table = Arc::new(Mutex::new(Table::default()));
+
+    let responses = execute(
+        table.clone(),
+        vec![grant(Some("manager")), grant(Some("manager"))]
+    ).await;
+
+    for response in &responses {
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+    }
+    assert_eq!(
+        responses[1].data.clone().into_json().unwrap(),
+        json!({ "grantPermissionToRole": { "permissionIds": ["perm-1"] } })
+    );
+    assert_eq!(table.lock().unwrap().update_expressions.len(), 2);
+}
+
+#[tokio::test]
+async fn test_system_role_requires_elevated_permission() {
+    let table = Arc::new(Mutex::new(Table {
+        is_system_role: true,
+        ..Table::default()
+    }));
+
+    let responses = execute(
+        table.clone(),
+        vec![grant(None), grant(Some("manager")), grant(Some("admin"))]
+    ).await;
+
+    let code = |response: &Response| {
+        response.errors[0].extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code").cloned())
+    };
+    assert_eq!(code(&responses[0]), Some(GraphQLValue::from("UNAUTHORIZED")));
+    assert_eq!(code(&responses[1]), Some(GraphQLValue::from("FORBIDDEN")));
+    assert!(responses[2].errors.is_empty(), "{:?}", responses[2].errors);
+
+    assert_eq!(table.lock().unwrap().update_expressions.len(), 1);
+}
+
+#[tokio::test]
+async fn test_revoke_deletes_permission_from_role_set() {
+    let table = Arc::new(Mutex::new(Table {
+        permission_ids: BTreeSet::from(["perm-1".to_string()]),
+        ..Table::default()
+    }));
+
+    let responses = execute(
+        table.clone(),
+        vec![
+            as_caller(
+                Request::new(
+                    r#"mutation {
+                        revokePermissionFromRole(roleId: "role-1", permissionId: "perm-1") {
+                            permissionIds
+                        }
+                    }"#
+                ),
+                Some("manager")
+            )
+        ]
+    ).await;
+
+    assert!(responses[0].errors.is_empty(), "{:?}", responses[0].errors);
+    assert_eq!(
+        responses[0].data.clone().into_json().unwrap(),
+        json!({ "revokePermissionFromRole": { "permissionIds": [] } })
+    );
+    assert!(table.lock().unwrap().update_expressions[0].starts_with("DELETE #permission_ids"));
+}
+
+#[tokio::test]
+async fn test_changing_role_permissions_requires_manage_roles() {
+    let table = Arc::new(Mutex::new(Table::default()));
+
+    let responses = execute(table.clone(), vec![grant(None), grant(Some("viewer"))]).await;
+
+    let code = |response: &Response| {
+        response.errors[0].extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code").cloned())
+    };
+    assert_eq!(code(&responses[0]), Some(GraphQLValue::from("UNAUTHORIZED")));
+    assert_eq!(code(&responses[1]), Some(GraphQLValue::from("FORBIDDEN")));
+    assert!(table.lock().unwrap().update_expressions.is_empty());
+}
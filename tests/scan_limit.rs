@@ -0,0 +1,47 @@
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ routing::post, Router };
+use ore_dock_cmms_lambda::{ models::role::Role, AppError, Repository };
+use tokio::net::TcpListener;
+
+// Every page holds three items and points at another page, like a table that never ends
+const ENDLESS_SCAN_PAGE: &str = r#"{
+    "Items": [
+        { "id": { "S": "role-1" } },
+        { "id": { "S": "role-2" } },
+        { "id": { "S": "role-3" } }
+    ],
+    "Count": 3,
+    "ScannedCount": 3,
+    "LastEvaluatedKey": { "id": { "S": "role-3" } }
+}"#;
+
+#[tokio::test]
+async fn test_scan_past_max_items_is_refused() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(|| async { ([("content-type", "application/x-amz-json-1.0")], ENDLESS_SCAN_PAGE) })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let repo = Repository::new(Client::from_conf(config)).with_max_scan_items(5);
+
+    let result = repo.list::<Role>(None).await;
+
+    server.abort();
+
+    match result {
+        Err(AppError::DatabaseError(msg)) => assert!(msg.contains("safety limit"), "{}", msg),
+        other => panic!("expected the scan to be refused, got {:?}", other.map(|r| r.len())),
+    }
+}
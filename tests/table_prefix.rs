@@ -0,0 +1,181 @@
+use std::{ collections::HashMap, net::SocketAddr, sync::{ Arc, Mutex } };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    types::AttributeValue,
+    Client,
+};
+use axum::{ http::{ HeaderMap, StatusCode }, routing::post, Router };
+use ore_dock_cmms_lambda::{
+    config::Config,
+    db::ensure_all_tables_exist,
+    models::vendor_category::VendorCategory,
+    AppError,
+    DbClient,
+    Repository,
+};
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+const CONDITIONAL_CHECK_FAILED: &str =
+    "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException";
+
+fn staging_config() -> Config {
+    let mut config = Config::default();
+    config.database.table_prefix = Some("staging".to_string());
+    config
+}
+
+fn client_for(address: SocketAddr) -> DbClient {
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    Client::from_conf(config)
+}
+
+/// Runs `operation` against a stub that stores every request body and answers with `response`
+async fn table_names_requested<F, Fut>(response: Value, operation: F) -> Vec<String>
+    where F: FnOnce(Repository) -> Fut, Fut: Future<Output = ()>
+{
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = requests.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |body: String| {
+            received.lock().unwrap().push(serde_json::from_str::<Value>(&body).unwrap());
+            let response = response.to_string();
+            async move { ([("content-type", "application/x-amz-json-1.0")], response) }
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    operation(Repository::from_config(client_for(address), &staging_config().database)).await;
+
+    server.abort();
+    let requests = requests.lock().unwrap();
+    requests
+        .iter()
+        .map(|request| request["TableName"].as_str().unwrap_or_default().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_prefixed_repository_reads_and_writes_prefixed_table() {
+    let response = json!({
+        "Item": {
+            "id": { "S": "category-1" },
+            "name": { "S": "Hydraulics" },
+            "category_type": { "S": "service" },
+            "status": { "S": "active" }
+        }
+    });
+
+    let tables = table_names_requested(response, |repo| async move {
+        let category = repo.get::<VendorCategory>("category-1".to_string()).await.unwrap();
+        repo.create(category.unwrap()).await.unwrap();
+    }).await;
+
+    assert_eq!(tables, vec!["staging_VendorCategories", "staging_VendorCategories"]);
+}
+
+#[tokio::test]
+async fn test_errors_name_the_prefixed_table() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(|| async {
+            let response = json!({
+                "__type": CONDITIONAL_CHECK_FAILED,
+                "message": "The conditional request failed"
+            });
+            (
+                StatusCode::BAD_REQUEST,
+                [("content-type", "application/x-amz-json-1.0")],
+                response.to_string(),
+            )
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let repo = Repository::from_config(client_for(address), &staging_config().database);
+    let error = repo
+        .update_fields::<VendorCategory>(
+            "category-1".to_string(),
+            HashMap::from([("status".to_string(), AttributeValue::S("inactive".to_string()))])
+        ).await
+        .unwrap_err();
+
+    server.abort();
+
+    assert!(
+        matches!(&error, AppError::NotFound(message) if message.ends_with("staging_VendorCategories")),
+        "unexpected error: {:?}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn test_existing_tables_are_found_past_the_first_page() {
+    let created = Arc::new(Mutex::new(Vec::new()));
+    let recorded = created.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let recorded = recorded.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+
+                let response = match operation.as_str() {
+                    // DynamoDB returns at most 100 names per page; split ours over two
+                    "ListTables" if request.get("ExclusiveStartTableName").is_none() => {
+                        json!({
+                            "TableNames": ["staging_Assets"],
+                            "LastEvaluatedTableName": "staging_Assets"
+                        })
+                    }
+                    "ListTables" => json!({ "TableNames": ["staging_Users"] }),
+                    "CreateTable" => {
+                        let table_name = request["TableName"].as_str().unwrap().to_string();
+                        recorded.lock().unwrap().push(table_name.clone());
+                        json!({
+                            "TableDescription": {
+                                "TableName": table_name,
+                                "TableStatus": "CREATING"
+                            }
+                        })
+                    }
+                    "DescribeTable" => json!({ "Table": { "TableStatus": "ACTIVE" } }),
+                    _ => json!({}),
+                };
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    ensure_all_tables_exist(&client_for(address), Some("staging")).await.unwrap();
+
+    server.abort();
+
+    let created = created.lock().unwrap();
+    assert!(created.contains(&"staging_Locations".to_string()));
+    assert!(!created.contains(&"staging_Assets".to_string()));
+    assert!(!created.contains(&"staging_Users".to_string()));
+}
@@ -0,0 +1,71 @@
+//! Runs against local DynamoDB; skipped unless `DB_URL` points at an instance.
+
+use aws_sdk_dynamodb::types::{
+    AttributeDefinition,
+    AttributeValue,
+    BillingMode,
+    GlobalSecondaryIndex,
+    KeySchemaElement,
+    KeyType,
+    Projection,
+    ProjectionType,
+    ScalarAttributeType,
+};
+use ore_dock_cmms_lambda::db::{ common::wait_until_active, local::setup_local_client };
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_fresh_table_is_queryable_once_active() {
+    if std::env::var("DB_URL").is_err() {
+        eprintln!("DB_URL not set, skipping local DynamoDB test");
+        return;
+    }
+
+    let client = setup_local_client().await.unwrap();
+    let table_name = format!("ReadinessTest-{}", Uuid::new_v4());
+
+    let attribute = |name: &str| {
+        AttributeDefinition::builder()
+            .attribute_name(name)
+            .attribute_type(ScalarAttributeType::S)
+            .build()
+            .unwrap()
+    };
+    let hash_key = |name: &str| {
+        KeySchemaElement::builder().attribute_name(name).key_type(KeyType::Hash).build().unwrap()
+    };
+
+    client
+        .create_table()
+        .table_name(&table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(attribute("id"))
+        .attribute_definitions(attribute("status"))
+        .key_schema(hash_key("id"))
+        .global_secondary_indexes(
+            GlobalSecondaryIndex::builder()
+                .index_name("StatusIndex")
+                .key_schema(hash_key("status"))
+                .projection(Projection::builder().projection_type(ProjectionType::All).build())
+                .build()
+                .unwrap()
+        )
+        .send().await
+        .unwrap();
+
+    let result = wait_until_active(&client, &table_name).await;
+
+    let query = client
+        .query()
+        .table_name(&table_name)
+        .index_name("StatusIndex")
+        .key_condition_expression("#status = :status")
+        .expression_attribute_names("#status", "status")
+        .expression_attribute_values(":status", AttributeValue::S("open".to_string()))
+        .send().await;
+
+    client.delete_table().table_name(&table_name).send().await.unwrap();
+
+    result.unwrap();
+    assert_eq!(query.unwrap().count(), 0);
+}
@@ -0,0 +1,100 @@
+use std::sync::{ Arc, Mutex };
+
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{ db::migrations::run_migrations, Repository };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+fn user_item(id: &str, notes: &str) -> Value {
+    json!({
+        "id": { "S": id },
+        "username": { "S": id },
+        "email": { "S": format!("{}@example.com", id) },
+        "first_name": { "S": "Jane" },
+        "last_name": { "S": "Doe" },
+        "user_type": { "S": "employee" },
+        "status": { "S": "suspended" },
+        "notes": { "S": notes },
+        "version": { "N": "3" }
+    })
+}
+
+#[tokio::test]
+async fn test_only_users_with_status_notes_are_migrated() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let recorded = recorded.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+
+                let response = match operation.as_str() {
+                    "Scan" =>
+                        json!({
+                            "Items": [
+                                user_item(
+                                    "old-user",
+                                    "Night shift; SUSPENDED: Missed audits; TERMINATED: Policy"
+                                ),
+                                user_item("new-user", "Night shift")
+                            ],
+                            "Count": 2
+                        }),
+                    "BatchWriteItem" => json!({ "UnprocessedItems": {} }),
+                    _ => json!({}),
+                };
+                recorded.lock().unwrap().push((operation, request));
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    );
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    run_migrations(&Repository::new(Client::from_conf(config))).await.unwrap();
+
+    server.abort();
+
+    let requests = requests.lock().unwrap();
+
+    let events: Vec<&Value> = requests
+        .iter()
+        .filter(|(operation, _)| operation == "BatchWriteItem")
+        .flat_map(|(_, request)| request["RequestItems"]["AuditEvents"].as_array().unwrap())
+        .map(|write| &write["PutRequest"]["Item"])
+        .collect();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event["target_id"]["S"] == "old-user"));
+    assert_eq!(events[0]["action"]["S"], "user.suspended");
+    assert_eq!(events[1]["action"]["S"], "user.terminated");
+
+    let rewritten: Vec<&Value> = requests
+        .iter()
+        .filter(|(operation, _)| operation == "PutItem")
+        .map(|(_, request)| &request["Item"])
+        .collect();
+    assert_eq!(rewritten.len(), 1);
+    assert_eq!(rewritten[0]["id"]["S"], "old-user");
+    assert_eq!(rewritten[0]["notes"]["S"], "Night shift");
+}
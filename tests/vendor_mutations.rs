@@ -0,0 +1,236 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::sync::{ Arc, Mutex };
+
+use async_graphql::{ Request, Response };
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::{ HeaderMap, StatusCode }, routing::post, Router };
+use ore_dock_cmms_lambda::{ config::{ Config, GraphQLConfig }, create_schema, DbClient };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+const CONDITIONAL_CHECK_FAILED: &str =
+    "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException";
+
+/// `category-1`, holding `vendor_count` vendors
+fn category_item(vendor_count: u64) -> Value {
+    json!({
+        "id": { "S": "category-1" },
+        "name": { "S": "Hydraulics" },
+        "category_type": { "S": "service" },
+        "status": { "S": "active" },
+        "vendor_count": { "N": vendor_count.to_string() }
+    })
+}
+
+/// A vendor already holding code `ACME-01`
+fn vendor_item(id: &str) -> Value {
+    json!({
+        "id": { "S": id },
+        "name": { "S": "Acme Hydraulics" },
+        "vendor_code": { "S": "ACME-01" },
+        "vendor_category_id": { "S": "category-1" },
+        "status": { "S": "active" },
+        "tier": { "S": "preferred" },
+        "phone_number": { "S": "+15555550100" },
+        "email_address": { "S": "orders@acme.example" },
+        "tax_id": { "S": "12-3456789" },
+        "payment_terms": { "S": "net30" },
+        "currency": { "S": "USD" },
+        "primary_contact_name": { "S": "Pat Lee" },
+        "primary_contact_title": { "S": "Account Manager" },
+        "compliance_status": { "S": "compliant" },
+        "preferred_communication": { "S": "email" }
+    })
+}
+
+/// Answers like a table holding `category-1` and the vendors in `code_holders`,
+/// recording every request in `calls`
+///
+/// `UpdateItem` only succeeds on `category-1`, as the `attribute_exists(id)` condition asks.
+fn dynamodb_stub(code_holders: Vec<Value>, calls: Arc<Mutex<Vec<(String, Value)>>>) -> Router {
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let code_holders = code_holders.clone();
+            let calls = calls.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+                calls.lock().unwrap().push((operation.clone(), request.clone()));
+
+                let (status, response) = match operation.as_str() {
+                    "GetItem" if request["Key"]["id"]["S"] == "category-1" => {
+                        (StatusCode::OK, json!({ "Item": category_item(3) }))
+                    }
+                    "GetItem" if request["Key"]["id"]["S"] == "vendor-9" => {
+                        let mut vendor = vendor_item("vendor-9");
+                        vendor["vendor_code"] = json!({ "S": "OLD-09" });
+                        vendor["vendor_category_id"] = json!({ "S": "category-0" });
+                        (StatusCode::OK, json!({ "Item": vendor }))
+                    }
+                    "Query" => {
+                        (
+                            StatusCode::OK,
+                            json!({ "Items": code_holders, "Count": code_holders.len() }),
+                        )
+                    }
+                    "UpdateItem" if request["Key"]["id"]["S"] != "category-1" => {
+                        (
+                            StatusCode::BAD_REQUEST,
+                            json!({
+                                "__type": CONDITIONAL_CHECK_FAILED,
+                                "message": "The conditional request failed"
+                            }),
+                        )
+                    }
+                    _ => (StatusCode::OK, json!({})),
+                };
+
+                (status, [("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+async fn execute(
+    code_holders: Vec<Value>,
+    calls: Arc<Mutex<Vec<(String, Value)>>>,
+    request: Request
+) -> Response {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(code_holders, calls);
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let response = schema.execute(request).await;
+
+    server.abort();
+    response
+}
+
+fn create_vendor(vendor_code: &str) -> Request {
+    Request::new(
+        format!(
+            r#"mutation {{
+                createVendor(input: {{
+                    name: "Bolt Supply",
+                    vendorCode: "{}",
+                    vendorCategoryId: "category-1",
+                    phoneNumber: "+15555550111",
+                    emailAddress: "sales@bolt.example",
+                    taxId: "98-7654321",
+                    paymentTerms: "net30",
+                    currency: "USD",
+                    primaryContactName: "Sam Roe",
+                    primaryContactTitle: "Sales Lead"
+                }}) {{ vendor {{ id vendorCode }} }}
+            }}"#,
+            vendor_code
+        )
+    ).data(Config::default())
+}
+
+fn operations(calls: &Arc<Mutex<Vec<(String, Value)>>>, operation: &str) -> Vec<Value> {
+    calls
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(name, _)| name == operation)
+        .map(|(_, request)| request.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_create_vendor_reads_code_index_and_adds_to_category_count() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let response = execute(Vec::new(), calls.clone(), create_vendor(" acme-02 ")).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert!(operations(&calls, "Scan").is_empty(), "vendor codes must not be checked by scanning");
+
+    let queries = operations(&calls, "Query");
+    assert_eq!(queries.len(), 1);
+    assert_eq!(queries[0]["IndexName"], "VendorCodeIndex");
+    assert_eq!(queries[0]["ExpressionAttributeValues"][":pk"]["S"], "ACME-02");
+
+    let updates = operations(&calls, "UpdateItem");
+    assert_eq!(updates.len(), 1, "the category must be updated in place, not rewritten");
+    assert_eq!(updates[0]["Key"]["id"]["S"], "category-1");
+    assert!(updates[0]["UpdateExpression"].as_str().unwrap().starts_with("ADD #attribute :amount"));
+    assert_eq!(updates[0]["ExpressionAttributeNames"]["#attribute"], "vendor_count");
+    assert_eq!(updates[0]["ExpressionAttributeValues"][":amount"]["N"], "1");
+}
+
+#[tokio::test]
+async fn test_create_vendor_rejects_a_code_in_use() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let response = execute(
+        vec![vendor_item("vendor-1")],
+        calls.clone(),
+        create_vendor("acme-01")
+    ).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("Vendor code already exists"));
+    assert!(operations(&calls, "PutItem").is_empty());
+    assert!(operations(&calls, "UpdateItem").is_empty());
+}
+
+#[tokio::test]
+async fn test_moving_a_vendor_tolerates_a_missing_old_category() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let response = execute(
+        Vec::new(),
+        calls.clone(),
+        Request::new(
+            r#"mutation {
+                updateVendor(input: { id: "vendor-9", vendorCategoryId: "category-1" }) {
+                    vendorCategoryId
+                }
+            }"#
+        )
+    ).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let amounts: Vec<(Value, Value)> = operations(&calls, "UpdateItem")
+        .iter()
+        .map(|update| (
+            update["Key"]["id"]["S"].clone(),
+            update["ExpressionAttributeValues"][":amount"]["N"].clone(),
+        ))
+        .collect();
+    assert_eq!(
+        amounts,
+        vec![(json!("category-0"), json!("-1")), (json!("category-1"), json!("1"))]
+    );
+}
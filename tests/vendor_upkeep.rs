@@ -0,0 +1,252 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::{ collections::HashSet, sync::{ Arc, Mutex } };
+
+use async_graphql::{ Request, Response, Value as GraphQLValue };
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::{ HeaderMap, StatusCode }, routing::post, Router };
+use chrono::{ Duration, Utc };
+use ore_dock_cmms_lambda::{
+    auth::{ guard::{ MANAGE_VENDORS, RATE_VENDORS }, AuthUser },
+    config::GraphQLConfig,
+    create_schema,
+    db::common::to_db_timestamp,
+    DbClient,
+};
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+const CONDITIONAL_CHECK_FAILED: &str =
+    "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException";
+
+/// `vendor-1`, created by `user-1`, whose contract ends in ten days
+fn vendor_item() -> Value {
+    json!({
+        "id": { "S": "vendor-1" },
+        "name": { "S": "Acme Hydraulics" },
+        "vendor_category_id": { "S": "category-1" },
+        "status": { "S": "active" },
+        "tier": { "S": "preferred" },
+        "phone_number": { "S": "+15555550100" },
+        "email_address": { "S": "orders@acme.example" },
+        "tax_id": { "S": "12-3456789" },
+        "payment_terms": { "S": "net30" },
+        "currency": { "S": "USD" },
+        "primary_contact_name": { "S": "Pat Lee" },
+        "primary_contact_title": { "S": "Account Manager" },
+        "compliance_status": { "S": "compliant" },
+        "preferred_communication": { "S": "email" },
+        "created_by": { "S": "user-1" },
+        "contract_end_date": { "S": to_db_timestamp(&(Utc::now() + Duration::days(10))) }
+    })
+}
+
+/// Answers like a table holding `vendor-1`, with `vendor-manager` granted
+/// `MANAGE_VENDORS` and `RATE_VENDORS`; every other operation is recorded in `calls`
+///
+/// Puts are conditional on the id being new, as `Repository::create` asks.
+fn dynamodb_stub(calls: Arc<Mutex<Vec<(String, Value)>>>) -> Router {
+    let stored_ids = Arc::new(Mutex::new(HashSet::new()));
+
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let calls = calls.clone();
+            let stored_ids = stored_ids.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+
+                if operation != "BatchGetItem" {
+                    calls.lock().unwrap().push((operation.clone(), request.clone()));
+                }
+                let (status, response) = match operation.as_str() {
+                    "BatchGetItem" => {
+                        let roles: Vec<Value> = request["RequestItems"]["Roles"]["Keys"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|key| key["id"]["S"].as_str().unwrap())
+                            .map(|id| {
+                                let mut role = json!({
+                                    "id": { "S": id },
+                                    "name": { "S": id },
+                                    "role_type": { "S": "custom" },
+                                    "is_system_role": { "BOOL": false }
+                                });
+                                if id == "vendor-manager" {
+                                    role["permission_ids"] = json!({
+                                        "SS": [MANAGE_VENDORS, RATE_VENDORS]
+                                    });
+                                }
+                                role
+                            })
+                            .collect();
+                        (
+                            StatusCode::OK,
+                            json!({ "Responses": { "Roles": roles }, "UnprocessedKeys": {} }),
+                        )
+                    }
+                    "GetItem" => (StatusCode::OK, json!({ "Item": vendor_item() })),
+                    "Scan" => (StatusCode::OK, json!({ "Items": [vendor_item()], "Count": 1 })),
+                    "Query" => (StatusCode::OK, json!({ "Items": [], "Count": 0 })),
+                    "PutItem" => {
+                        let id = request["Item"]["id"]["S"].as_str().unwrap().to_string();
+                        if stored_ids.lock().unwrap().insert(id) {
+                            (StatusCode::OK, json!({}))
+                        } else {
+                            (
+                                StatusCode::BAD_REQUEST,
+                                json!({
+                                    "__type": CONDITIONAL_CHECK_FAILED,
+                                    "message": "The conditional request failed"
+                                }),
+                            )
+                        }
+                    }
+                    _ => (StatusCode::OK, json!({})),
+                };
+
+                (status, [("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+async fn execute(calls: Arc<Mutex<Vec<(String, Value)>>>, requests: Vec<Request>) -> Vec<Response> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(calls);
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let mut responses = Vec::new();
+    for request in requests {
+        responses.push(schema.execute(request).await);
+    }
+
+    server.abort();
+    responses
+}
+
+fn as_caller(request: &str, caller_role: Option<&str>) -> Request {
+    let request = Request::new(request);
+    match caller_role {
+        Some(role) =>
+            request.data(AuthUser {
+                user_id: "user-1".to_string(),
+                roles: vec![role.to_string()],
+            }),
+        None => request,
+    }
+}
+
+#[tokio::test]
+async fn test_recompute_vendor_rating_reads_vendor_index() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let responses = execute(
+        calls.clone(),
+        vec![
+            as_caller(
+                r#"mutation { recomputeVendorRating(vendorId: "vendor-1") { id } }"#,
+                Some("vendor-manager")
+            )
+        ]
+    ).await;
+
+    assert!(responses[0].errors.is_empty(), "{:?}", responses[0].errors);
+
+    let calls = calls.lock().unwrap();
+    assert!(calls.iter().all(|(operation, _)| operation != "Scan"));
+    let (_, query) = calls
+        .iter()
+        .find(|(operation, _)| operation == "Query")
+        .unwrap();
+    assert_eq!(query["IndexName"], "VendorIndex");
+    assert_eq!(query["ExpressionAttributeValues"][":pk"]["S"], "vendor-1");
+}
+
+#[tokio::test]
+async fn test_vendor_ratings_require_permission() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let code = |response: &Response| {
+        response.errors[0].extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code").cloned())
+    };
+
+    let recompute = r#"mutation { recomputeVendorRating(vendorId: "vendor-1") { id } }"#;
+    let rate = r#"mutation { rateWorkOrderVendor(id: "wo-1", rating: 4.5) { id } }"#;
+
+    let responses = execute(
+        calls.clone(),
+        vec![
+            as_caller(recompute, None),
+            as_caller(recompute, Some("viewer")),
+            as_caller(rate, Some("viewer"))
+        ]
+    ).await;
+
+    assert_eq!(code(&responses[0]), Some(GraphQLValue::from("UNAUTHORIZED")));
+    assert_eq!(code(&responses[1]), Some(GraphQLValue::from("FORBIDDEN")));
+    assert_eq!(code(&responses[2]), Some(GraphQLValue::from("FORBIDDEN")));
+    assert!(calls.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_repeated_contract_sweeps_notify_once() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let sweep = "mutation { sweepExpiringContracts }";
+
+    let mut responses = execute(
+        calls.clone(),
+        vec![
+            as_caller(sweep, Some("vendor-manager")),
+            as_caller(sweep, Some("vendor-manager")),
+            as_caller(sweep, Some("viewer"))
+        ]
+    ).await;
+    let viewer = responses.pop().unwrap();
+    let second = responses.pop().unwrap();
+    let first = responses.pop().unwrap();
+
+    assert_eq!(first.data.into_json().unwrap(), json!({ "sweepExpiringContracts": 1 }));
+    assert_eq!(second.data.into_json().unwrap(), json!({ "sweepExpiringContracts": 0 }));
+    assert!(!viewer.errors.is_empty());
+
+    let calls = calls.lock().unwrap();
+    let put_ids: Vec<&Value> = calls
+        .iter()
+        .filter(|(operation, _)| operation == "PutItem")
+        .map(|(_, request)| &request["Item"]["id"])
+        .collect();
+    assert_eq!(put_ids.len(), 2);
+    assert_eq!(put_ids[0], put_ids[1]);
+}
@@ -0,0 +1,137 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::sync::{ Arc, Mutex };
+
+use async_graphql::Request;
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{ config::GraphQLConfig, create_schema, DbClient };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+fn asset_item(work_order_ids: &[&str]) -> Value {
+    let mut item = json!({
+        "id": { "S": "asset-1" },
+        "name": { "S": "Boiler" },
+        "asset_type_id": { "S": "type-1" },
+        "serial_number": { "S": "SN-1" },
+        "model_number": { "S": "B1" },
+        "location_id": { "S": "loc-1" },
+        "manufacturer_id": { "S": "mfg-1" },
+        "current_status": { "S": "operational" },
+        "maintenance_frequency": { "S": "monthly" }
+    });
+    if !work_order_ids.is_empty() {
+        item["work_order_ids"] = json!({ "SS": work_order_ids });
+    }
+    item
+}
+
+/// Answers each DynamoDB operation the way an empty table holding `asset-1` would,
+/// recording every `UpdateItem` request body
+fn dynamodb_stub(updates: Arc<Mutex<Vec<Value>>>) -> Router {
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let updates = updates.clone();
+            async move {
+                let target = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+
+                let response = match target.rsplit('.').next().unwrap_or_default() {
+                    "Scan" => json!({ "Items": [], "Count": 0, "ScannedCount": 0 }),
+                    "GetItem" if request["TableName"] == "Assets" => {
+                        json!({ "Item": asset_item(&[]) })
+                    }
+                    "UpdateItem" if request["TableName"] == "Counters" => {
+                        json!({ "Attributes": { "value": { "N": "7" } } })
+                    }
+                    "UpdateItem" => {
+                        let values = &request["ExpressionAttributeValues"];
+                        let linked = values[":work_order_ids"]["SS"][0]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string();
+                        updates.lock().unwrap().push(request);
+                        json!({ "Attributes": asset_item(&[&linked]) })
+                    }
+                    _ => json!({}),
+                };
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+#[tokio::test]
+async fn test_creating_work_order_adds_it_to_asset_work_order_ids() {
+    let updates = Arc::new(Mutex::new(Vec::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(updates.clone());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let response = schema.execute(
+        Request::new(
+            r#"mutation {
+                createWorkOrder(
+                    title: "Replace burner"
+                    description: "Burner fails to ignite"
+                    assetId: "asset-1"
+                    workOrderType: "corrective"
+                    priority: "high"
+                    severity: "important"
+                    difficulty: "normal"
+                    estimatedDurationMinutes: 90
+                    estimatedCost: "two"
+                    createdBy: "user-1"
+                ) { id }
+            }"#
+        )
+    ).await;
+
+    server.abort();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+    let work_order_id = data["createWorkOrder"]["id"].as_str().unwrap();
+
+    let updates = updates.lock().unwrap();
+    let link = updates
+        .iter()
+        .find(|update| update["TableName"] == "Assets")
+        .expect("the asset should be updated");
+
+    assert_eq!(link["Key"]["id"]["S"], "asset-1");
+    assert!(link["UpdateExpression"].as_str().unwrap().starts_with("ADD #work_order_ids"));
+    assert_eq!(link["ExpressionAttributeValues"][":work_order_ids"]["SS"], json!([work_order_id]));
+}
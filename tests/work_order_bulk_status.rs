@@ -0,0 +1,145 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::sync::{ Arc, Mutex };
+
+use async_graphql::Request;
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::HeaderMap, routing::post, Router };
+use ore_dock_cmms_lambda::{ config::GraphQLConfig, create_schema, DbClient };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+/// Scheduled work order `wo-1`, carrying a technician's note
+fn work_order_item() -> Value {
+    json!({
+        "id": { "S": "wo-1" },
+        "work_order_number": { "S": "000042" },
+        "title": { "S": "Replace pump seal" },
+        "description": { "S": "Seal is leaking" },
+        "notes": { "S": "Bring the long ladder" },
+        "asset_id": { "S": "asset-1" },
+        "work_order_type": { "S": "corrective" },
+        "status": { "S": "scheduled" },
+        "priority": { "S": "normal" },
+        "severity": { "S": "important" },
+        "difficulty": { "S": "normal" },
+        "estimated_cost": { "S": "one" },
+        "created_by": { "S": "user-1" },
+        "created_at": { "S": "2026-03-01T09:00:00.000000000Z" },
+        "updated_at": { "S": "2026-03-01T09:00:00.000000000Z" },
+        "version": { "N": "1" }
+    })
+}
+
+/// Answers like a table holding `wo-1`, recording every `PutItem` request body
+fn dynamodb_stub(puts: Arc<Mutex<Vec<Value>>>) -> Router {
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let puts = puts.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+
+                let response = match operation.as_str() {
+                    "BatchGetItem" =>
+                        json!({
+                            "Responses": { "WorkOrders": [work_order_item()] },
+                            "UnprocessedKeys": {}
+                        }),
+                    "PutItem" => {
+                        puts.lock().unwrap().push(request);
+                        json!({})
+                    }
+                    _ => json!({}),
+                };
+
+                ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+#[tokio::test]
+async fn test_bulk_status_reason_is_audited_not_appended_to_notes() {
+    let puts = Arc::new(Mutex::new(Vec::new()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(puts.clone());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let response = schema.execute(
+        Request::new(
+            r#"mutation {
+                bulkUpdateWorkOrderStatus(
+                    ids: ["wo-1"],
+                    newStatus: "in_progress",
+                    reason: "Crew arrived early"
+                ) { succeeded }
+            }"#
+        )
+    ).await;
+
+    server.abort();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap()["bulkUpdateWorkOrderStatus"]["succeeded"],
+        json!(["wo-1"])
+    );
+
+    let puts = puts.lock().unwrap();
+    let work_order = puts
+        .iter()
+        .find(|put| put["TableName"] == "WorkOrders")
+        .map(|put| &put["Item"])
+        .unwrap();
+    assert_eq!(work_order["status"]["S"], "in_progress");
+    assert_eq!(work_order["notes"]["S"], "Bring the long ladder");
+
+    let event = puts
+        .iter()
+        .find(|put| put["TableName"] == "AuditEvents")
+        .map(|put| &put["Item"])
+        .unwrap();
+    assert_eq!(event["action"]["S"], "work_order.status_changed");
+    assert_eq!(event["target_id"]["S"], "wo-1");
+    let metadata: Value = serde_json::from_str(event["metadata"]["S"].as_str().unwrap()).unwrap();
+    assert_eq!(
+        metadata,
+        json!({
+            "previous_status": "scheduled",
+            "status": "in_progress",
+            "reason": "Crew arrived early"
+        })
+    );
+}
@@ -0,0 +1,174 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::sync::{ Arc, Mutex };
+
+use async_graphql::{ Request, Response, Value as GraphQLValue };
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ http::{ HeaderMap, StatusCode }, routing::post, Router };
+use ore_dock_cmms_lambda::{ config::GraphQLConfig, create_schema, DbClient };
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+const CONDITIONAL_CHECK_FAILED: &str =
+    "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException";
+
+const CREATE_WORK_ORDER: &str =
+    r#"mutation {
+    createWorkOrder(
+        title: "Replace burner"
+        description: "Burner fails to ignite"
+        assetId: "asset-1"
+        workOrderType: "corrective"
+        priority: "high"
+        severity: "important"
+        difficulty: "normal"
+        estimatedDurationMinutes: 90
+        estimatedCost: "two"
+        createdBy: "user-1"
+    ) { id workOrderNumber }
+}"#;
+
+fn asset_item() -> Value {
+    json!({
+        "id": { "S": "asset-1" },
+        "name": { "S": "Boiler" },
+        "asset_type_id": { "S": "type-1" },
+        "serial_number": { "S": "SN-1" },
+        "model_number": { "S": "B1" },
+        "location_id": { "S": "loc-1" },
+        "manufacturer_id": { "S": "mfg-1" },
+        "current_status": { "S": "operational" },
+        "maintenance_frequency": { "S": "monthly" }
+    })
+}
+
+/// Answers like a table holding `asset-1` if `asset_exists`, recording every operation
+/// with its request body
+///
+/// With `key_in_use`, every idempotency key is held by a request that has not finished yet:
+/// claiming one fails its condition and no entity is stored under it.
+fn dynamodb_stub(
+    asset_exists: bool,
+    key_in_use: bool,
+    calls: Arc<Mutex<Vec<(String, Value)>>>
+) -> Router {
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: String| {
+            let calls = calls.clone();
+            async move {
+                let operation = headers
+                    .get("x-amz-target")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.rsplit('.').next())
+                    .unwrap_or_default()
+                    .to_string();
+                let request: Value = serde_json::from_str(&body).unwrap();
+
+                let (status, response) = match operation.as_str() {
+                    "GetItem" if asset_exists && request["TableName"] == "Assets" => {
+                        (StatusCode::OK, json!({ "Item": asset_item() }))
+                    }
+                    "PutItem" if key_in_use && request["TableName"] == "IdempotencyKeys" => {
+                        (
+                            StatusCode::BAD_REQUEST,
+                            json!({
+                                "__type": CONDITIONAL_CHECK_FAILED,
+                                "message": "The conditional request failed"
+                            }),
+                        )
+                    }
+                    "UpdateItem" if request["TableName"] == "Counters" => {
+                        (StatusCode::OK, json!({ "Attributes": { "value": { "N": "7" } } }))
+                    }
+                    _ => (StatusCode::OK, json!({})),
+                };
+                calls.lock().unwrap().push((operation, request));
+
+                (status, [("content-type", "application/x-amz-json-1.0")], response.to_string())
+            }
+        })
+    )
+}
+
+async fn execute(
+    asset_exists: bool,
+    key_in_use: bool,
+    calls: Arc<Mutex<Vec<(String, Value)>>>,
+    request: Request
+) -> Response {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = dynamodb_stub(asset_exists, key_in_use, calls);
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    let schema = create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .finish();
+
+    let response = schema.execute(request).await;
+
+    server.abort();
+    response
+}
+
+#[tokio::test]
+async fn test_create_with_missing_asset_takes_no_number() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+
+    let response = execute(false, false, calls.clone(), Request::new(CREATE_WORK_ORDER)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("Asset asset-1 not found"));
+    let calls = calls.lock().unwrap();
+    assert!(
+        calls.iter().all(|(_, request)| request["TableName"] != "Counters"),
+        "the work order counter must not move for a rejected request"
+    );
+}
+
+#[tokio::test]
+async fn test_create_with_key_in_flight_is_conflict_before_taking_a_number() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+
+    let keyed = CREATE_WORK_ORDER.replace(
+        r#"createdBy: "user-1""#,
+        r#"createdBy: "user-1" idempotencyKey: "retry-1""#
+    );
+    let response = execute(true, true, calls.clone(), Request::new(keyed)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    let code = response.errors[0].extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("code").cloned());
+    assert_eq!(code, Some(GraphQLValue::from("CONFLICT")));
+    let calls = calls.lock().unwrap();
+    assert!(calls.iter().any(|(_, request)| request["TableName"] == "IdempotencyKeys"));
+    assert!(
+        calls
+            .iter()
+            .all(|(_, request)| {
+                request["TableName"] != "Counters" && request["TableName"] != "WorkOrders"
+            }),
+        "nothing may be written while another request holds the key"
+    );
+}
@@ -0,0 +1,159 @@
+// Laying out the futures of the merged GraphQL roots nests deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::{ net::SocketAddr, time::Duration };
+
+use async_graphql::{ Request, Value as GraphQLValue };
+use aws_sdk_dynamodb::{
+    config::{ BehaviorVersion, Builder, Credentials, Region },
+    Client,
+};
+use axum::{ routing::post, Router };
+use chrono::Utc;
+use futures_util::StreamExt;
+use ore_dock_cmms_lambda::{
+    auth::{ guard::VIEW_WORK_ORDERS, AuthUser },
+    config::GraphQLConfig,
+    create_schema,
+    models::work_order::WorkOrderStatus,
+    schema::resolvers::subscription::{ WorkOrderStatusBroker, WorkOrderStatusChanged },
+    DbClient,
+    GraphQLSchema,
+};
+use serde_json::{ json, Value };
+use tokio::net::TcpListener;
+
+const SUBSCRIPTION: &str =
+    r#"subscription {
+    workOrderStatusChanged(assetId: "asset-1") { workOrderId assetId status }
+}"#;
+
+fn status_changed(work_order_id: &str, asset_id: &str) -> WorkOrderStatusChanged {
+    WorkOrderStatusChanged {
+        work_order_id: work_order_id.to_string(),
+        asset_id: asset_id.to_string(),
+        previous_status: WorkOrderStatus::Scheduled,
+        status: WorkOrderStatus::InProgress,
+        changed_at: Utc::now(),
+    }
+}
+
+/// Roles table where only `dispatcher` grants `VIEW_WORK_ORDERS`
+fn roles_stub() -> Router {
+    Router::new().route(
+        "/",
+        post(|body: String| async move {
+            let request: Value = serde_json::from_str(&body).unwrap();
+            let roles: Vec<Value> = request["RequestItems"]["Roles"]["Keys"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|key| key["id"]["S"].as_str().unwrap())
+                .map(|id| {
+                    let mut role = json!({
+                        "id": { "S": id },
+                        "name": { "S": id },
+                        "role_type": { "S": "custom" },
+                        "is_system_role": { "BOOL": false }
+                    });
+                    if id == "dispatcher" {
+                        role["permission_ids"] = json!({ "SS": [VIEW_WORK_ORDERS] });
+                    }
+                    role
+                })
+                .collect();
+            let response = json!({ "Responses": { "Roles": roles }, "UnprocessedKeys": {} });
+
+            ([("content-type", "application/x-amz-json-1.0")], response.to_string())
+        })
+    )
+}
+
+fn schema_for(address: SocketAddr, broker: &WorkOrderStatusBroker) -> GraphQLSchema {
+    let config = Builder::new()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-2"))
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .endpoint_url(format!("http://{}", address))
+        .build();
+    let db_client: DbClient = Client::from_conf(config);
+
+    create_schema(
+        &(GraphQLConfig {
+            playground: false,
+            introspection: true,
+            complexity_limit: None,
+            depth_limit: None,
+        })
+    )
+        .data(db_client)
+        .data(broker.clone())
+        .finish()
+}
+
+fn as_caller(role: &str) -> Request {
+    Request::new(SUBSCRIPTION).data(AuthUser {
+        user_id: "user-1".to_string(),
+        roles: vec![role.to_string()],
+    })
+}
+
+#[tokio::test]
+async fn test_published_status_change_reaches_subscriber_for_its_asset() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move { axum::serve(listener, roles_stub()).await });
+
+    let broker = WorkOrderStatusBroker::default();
+    let schema = schema_for(address, &broker);
+
+    let mut stream = schema.execute_stream(as_caller("dispatcher"));
+
+    // Let the resolver check the caller's roles and subscribe to the broker
+    let first_poll = tokio::time::timeout(Duration::from_millis(200), stream.next()).await;
+    assert!(first_poll.is_err(), "subscription ended early: {:?}", first_poll);
+
+    broker.publish(status_changed("wo-other", "asset-2"));
+    broker.publish(status_changed("wo-1", "asset-1"));
+
+    let response = tokio::time
+        ::timeout(Duration::from_secs(2), stream.next()).await
+        .expect("no event delivered to the subscription")
+        .unwrap();
+
+    server.abort();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap()["workOrderStatusChanged"],
+        json!({ "workOrderId": "wo-1", "assetId": "asset-1", "status": "IN_PROGRESS" })
+    );
+}
+
+#[tokio::test]
+async fn test_subscription_requires_permission() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move { axum::serve(listener, roles_stub()).await });
+
+    let broker = WorkOrderStatusBroker::default();
+    let schema = schema_for(address, &broker);
+
+    let code = |request: Request| {
+        let schema = schema.clone();
+        async move {
+            let response = schema.execute_stream(request).next().await.unwrap();
+            response.errors[0].extensions
+                .as_ref()
+                .and_then(|extensions| extensions.get("code").cloned())
+        }
+    };
+
+    let anonymous = code(Request::new(SUBSCRIPTION)).await;
+    let viewer = code(as_caller("viewer")).await;
+
+    server.abort();
+
+    assert_eq!(anonymous, Some(GraphQLValue::from("UNAUTHORIZED")));
+    assert_eq!(viewer, Some(GraphQLValue::from("FORBIDDEN")));
+}